@@ -182,6 +182,12 @@ void main() async {
         print('Action: Check file permissions and accessibility');
       case EmbeddingCancelledError():
         print('Action: Operation was cancelled - this is expected if cancel() was called');
+      case UnauthorizedError():
+        print('Action: Set a valid HuggingFace token with setHfToken()');
+      case ModelMismatchError():
+        print('Action: Rebuild the index with the current embedder');
+      case OperationTimeoutError():
+        print('Action: Allow a longer timeout or split the work');
     }
     print('');
   }
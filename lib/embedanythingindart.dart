@@ -26,3 +26,6 @@ export 'src/embedding_result.dart';
 export 'src/errors.dart';
 export 'src/model_config.dart';
 export 'src/models.dart';
+export 'src/multi_vector_embedding.dart';
+export 'src/rerank_result.dart';
+export 'src/sparse_embedding.dart';
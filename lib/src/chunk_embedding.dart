@@ -1,4 +1,5 @@
 import 'embedding_result.dart';
+import 'multi_vector_embedding.dart';
 
 /// Result of embedding a text chunk from a file
 ///
@@ -26,8 +27,16 @@ import 'embedding_result.dart';
 /// ```
 class ChunkEmbedding {
   /// The embedding vector for this chunk
+  ///
+  /// For multi-vector models this is the mean of the token vectors in
+  /// [multiVector].
   final EmbeddingResult embedding;
 
+  /// Per-token vectors, for multi-vector (ColBERT) models only
+  ///
+  /// Null for dense models, whose chunks have a single vector.
+  final MultiVectorEmbedding? multiVector;
+
   /// The text content of this chunk (may be null)
   final String? text;
 
@@ -46,10 +55,12 @@ class ChunkEmbedding {
   /// - [embedding]: The embedding vector (required)
   /// - [text]: The text content of this chunk (optional)
   /// - [metadata]: Metadata about the chunk (optional)
+  /// - [multiVector]: Per-token vectors for multi-vector models (optional)
   const ChunkEmbedding({
     required this.embedding,
     this.text,
     this.metadata,
+    this.multiVector,
   });
 
   /// Convenience getter for file path from metadata
@@ -64,6 +75,12 @@ class ChunkEmbedding {
   /// ```
   String? get filePath => metadata?['file_path'];
 
+  /// Convenience getter for the modality from metadata
+  ///
+  /// Returns "text" or "image" for directories embedded with an image
+  /// embedder, or null otherwise.
+  String? get modality => metadata?['modality'];
+
   /// Convenience getter for page number from metadata (PDFs)
   ///
   /// Returns the integer value of the `page_number` key in metadata,
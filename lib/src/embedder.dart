@@ -1,6 +1,7 @@
 import 'dart:async';
 import 'dart:convert';
 import 'dart:ffi';
+import 'dart:typed_data';
 
 import 'package:ffi/ffi.dart';
 
@@ -14,6 +15,9 @@ import 'ffi/ffi_utils.dart';
 import 'ffi/native_types.dart';
 import 'model_config.dart';
 import 'models.dart';
+import 'multi_vector_embedding.dart';
+import 'rerank_result.dart';
+import 'sparse_embedding.dart';
 
 /// High-level interface to EmbedAnything embedding models
 ///
@@ -85,16 +89,28 @@ class EmbedAnything {
     // Initialize runtime on first use
     _initializeRuntime();
 
-    final handle = withCString(config.modelId, (modelIdPtr) {
-      return withCString(config.revision, (revisionPtr) {
-        return ffi.embedderFromPretrainedHf(
-          config.modelType.value,
-          modelIdPtr,
-          revisionPtr,
-          config.dtype.value,
-        );
+    final options = allocateEmbedderOptions(
+      normalize: config.normalize,
+      batchSize: config.defaultBatchSize,
+    );
+
+    final Pointer<CEmbedder> handle;
+    try {
+      handle = withCString(config.modelId, (modelIdPtr) {
+        return withCString(config.revision, (revisionPtr) {
+          return ffi.embedderFromPretrainedHf(
+            config.modelType.value,
+            modelIdPtr,
+            revisionPtr,
+            nullptr,
+            config.dtype.value,
+            options,
+          );
+        });
       });
-    });
+    } finally {
+      calloc.free(options);
+    }
 
     if (handle == nullptr) {
       throwLastError('Failed to load model: ${config.modelId}');
@@ -148,6 +164,193 @@ class EmbedAnything {
     return EmbedAnything.fromConfig(config);
   }
 
+  /// Create an embedder from a model directory on disk
+  ///
+  /// Loads without any network access, e.g. for models shipped inside the
+  /// app bundle. The directory must contain `config.json`,
+  /// `tokenizer.json` and `model.safetensors`; the architecture is read
+  /// from `config.json`.
+  ///
+  /// Parameters:
+  /// - [modelPath]: Path to the model directory
+  /// - [dtype]: Model data type (default: f32)
+  /// - [normalize]: L2-normalize output vectors (default: true)
+  /// - [batchSize]: Batch size for embedding calls (default: 32)
+  /// - [pooling]: Pooling of token vectors (default: the checkpoint's
+  ///   `1_Pooling/config.json`, or mean)
+  /// - [maxSequenceLength]: Maximum tokens per input, capped at the
+  ///   model's limit (0 = the model's limit)
+  ///
+  /// Throws:
+  /// - [FileNotFoundError] if the directory or one of its files is missing
+  /// - [InvalidConfigError] if the architecture is not supported
+  /// - [EmbeddingFailedError] if the weights cannot be loaded
+  ///
+  /// Example:
+  /// ```dart
+  /// final embedder = EmbedAnything.fromLocalPath('assets/models/minilm');
+  /// ```
+  factory EmbedAnything.fromLocalPath(
+    String modelPath, {
+    ModelDtype dtype = ModelDtype.f32,
+    bool normalize = true,
+    int batchSize = 32,
+    PoolingStrategy pooling = PoolingStrategy.modelDefault,
+    int maxSequenceLength = 0,
+  }) {
+    _initializeRuntime();
+
+    final options = allocateEmbedderOptions(
+      normalize: normalize,
+      batchSize: batchSize,
+      maxSequenceLength: maxSequenceLength,
+      pooling: pooling.value,
+    );
+
+    final Pointer<CEmbedder> handle;
+    try {
+      handle = withCString(modelPath, (modelPathPtr) {
+        return ffi.embedderFromLocalPath(modelPathPtr, dtype.value, options);
+      });
+    } finally {
+      calloc.free(options);
+    }
+
+    if (handle == nullptr) {
+      throwLastError('Failed to load model from: $modelPath');
+    }
+
+    return EmbedAnything._(handle);
+  }
+
+  /// Create an embedder backed by ONNX Runtime
+  ///
+  /// The embedder works with every embedding method, sync and async,
+  /// exactly like one loaded from the hub. Requires a native library built
+  /// with the `ort` feature.
+  ///
+  /// Parameters:
+  /// - [modelPath]: Path to the exported `.onnx` model
+  /// - [tokenizerPath]: Path to the matching `tokenizer.json`
+  /// - [pooling]: Pooling of token vectors (default: the model's own);
+  ///   ignored when the model already outputs sentence embeddings
+  /// - [normalize]: L2-normalize output vectors (default: true)
+  /// - [batchSize]: Batch size for embedding calls (default: 32)
+  /// - [maxSequenceLength]: Maximum tokens per input (0 = 512)
+  ///
+  /// Throws:
+  /// - [FileNotFoundError] if the model or tokenizer file is missing
+  /// - [InvalidConfigError] if the library was built without `ort`
+  /// - [EmbeddingFailedError] if the ONNX session cannot be created
+  ///
+  /// Example:
+  /// ```dart
+  /// final embedder = EmbedAnything.fromOnnx(
+  ///   'assets/minilm/model.onnx',
+  ///   'assets/minilm/tokenizer.json',
+  /// );
+  /// ```
+  factory EmbedAnything.fromOnnx(
+    String modelPath,
+    String tokenizerPath, {
+    PoolingStrategy pooling = PoolingStrategy.modelDefault,
+    bool normalize = true,
+    int batchSize = 32,
+    int maxSequenceLength = 0,
+  }) {
+    _initializeRuntime();
+
+    final options = allocateEmbedderOptions(
+      normalize: normalize,
+      batchSize: batchSize,
+      maxSequenceLength: maxSequenceLength,
+      pooling: pooling.value,
+    );
+
+    final Pointer<CEmbedder> handle;
+    try {
+      handle = withCString(modelPath, (modelPathPtr) {
+        return withCString(tokenizerPath, (tokenizerPathPtr) {
+          return ffi.embedderFromOnnx(modelPathPtr, tokenizerPathPtr, options);
+        });
+      });
+    } finally {
+      calloc.free(options);
+    }
+
+    if (handle == nullptr) {
+      throwLastError('Failed to load ONNX model: $modelPath');
+    }
+
+    return EmbedAnything._(handle);
+  }
+
+  /// Create an image embedder from a CLIP or SigLIP checkpoint
+  ///
+  /// The embedder maps images ([embedImageFile], [embedImageBytes],
+  /// [embedImageFilesBatch]) and text queries ([embedImageQuery]) into the
+  /// same space, so text can be used to search images.
+  ///
+  /// Parameters:
+  /// - [modelId]: HuggingFace model identifier
+  ///   (e.g., 'openai/clip-vit-base-patch32')
+  /// - [revision]: Git revision/branch (defaults to 'main')
+  /// - [dtype]: Model data type (default: f32)
+  /// - [normalize]: L2-normalize output vectors (default: true)
+  /// - [batchSize]: Batch size for image batches (default: 32)
+  ///
+  /// Throws:
+  /// - [ModelNotFoundError] if the model doesn't exist on HuggingFace Hub
+  /// - [InvalidConfigError] if the checkpoint is not a vision model
+  /// - [EmbeddingFailedError] if the model cannot be loaded
+  ///
+  /// Example:
+  /// ```dart
+  /// final embedder = EmbedAnything.fromVisionModel(
+  ///   modelId: 'openai/clip-vit-base-patch32',
+  /// );
+  /// final image = embedder.embedImageFile('photos/cat.jpg');
+  /// final query = embedder.embedImageQuery('a photo of a cat');
+  /// print('Score: ${query.cosineSimilarity(image)}');
+  /// ```
+  factory EmbedAnything.fromVisionModel({
+    required String modelId,
+    String revision = 'main',
+    ModelDtype dtype = ModelDtype.f32,
+    bool normalize = true,
+    int batchSize = 32,
+  }) {
+    _initializeRuntime();
+
+    final options = allocateEmbedderOptions(
+      normalize: normalize,
+      batchSize: batchSize,
+    );
+
+    final Pointer<CEmbedder> handle;
+    try {
+      handle = withCString(modelId, (modelIdPtr) {
+        return withCString(revision, (revisionPtr) {
+          return ffi.visionEmbedderFromPretrainedHf(
+            modelIdPtr,
+            revisionPtr,
+            nullptr,
+            dtype.value,
+            options,
+          );
+        });
+      });
+    } finally {
+      calloc.free(options);
+    }
+
+    if (handle == nullptr) {
+      throwLastError('Failed to load vision model: $modelId');
+    }
+
+    return EmbedAnything._(handle);
+  }
+
   /// Generate embedding for a single text
   ///
   /// Converts the input text into a dense vector representation
@@ -266,254 +469,539 @@ class EmbedAnything {
     }
   }
 
-  /// Embed a single file with automatic chunking
-  ///
-  /// Processes a document file and returns all text chunks with their embeddings
-  /// and metadata. The file is automatically chunked based on the configuration
-  /// parameters.
+  /// Generate a sparse (SPLADE) embedding for a single text
   ///
-  /// Supported file formats: PDF, TXT, MD, DOCX, HTML
+  /// Requires an embedder loaded from a sparse checkpoint. Only the
+  /// non-zero vocabulary weights are returned.
   ///
   /// Parameters:
-  /// - [filePath]: Path to the file to embed
-  /// - [chunkSize]: Maximum characters per chunk (default: 1000)
-  /// - [overlapRatio]: Overlap between chunks 0.0-1.0 (default: 0.0)
-  /// - [batchSize]: Batch size for embedding generation (default: 32)
+  /// - [text]: The text to embed
   ///
-  /// Returns a [Future] that completes with a list of [ChunkEmbedding]s,
-  /// one for each chunk of the file. Each chunk includes the embedding,
-  /// text content, and metadata (file path, chunk index, page number for PDFs).
+  /// Returns a [SparseEmbedding] with the non-zero entries.
   ///
   /// Throws:
-  /// - [FileNotFoundError] if the file does not exist
-  /// - [UnsupportedFileFormatError] if the file format is not supported
-  /// - [FileReadError] if there's a permission or I/O error reading the file
+  /// - [InvalidConfigError] if the embedder is not a sparse model
   /// - [EmbeddingFailedError] if embedding generation fails
   /// - [StateError] if the embedder has been disposed
   ///
   /// Example:
   /// ```dart
-  /// final embedder = EmbedAnything.fromConfig(ModelConfig.bertMiniLML6());
-  /// try {
-  ///   final chunks = await embedder.embedFile(
-  ///     'document.pdf',
-  ///     chunkSize: 500,
-  ///     overlapRatio: 0.1,
-  ///   );
-  ///
-  ///   for (final chunk in chunks) {
-  ///     print('File: ${chunk.filePath}');
-  ///     print('Page: ${chunk.page}');
-  ///     print('Chunk ${chunk.chunkIndex}: ${chunk.text?.substring(0, 50)}...');
-  ///   }
-  /// } finally {
-  ///   embedder.dispose();
-  /// }
+  /// final sparse = embedder.embedTextSparse('Hello, world!');
+  /// print('Non-zero terms: ${sparse.length}');
   /// ```
-  Future<List<ChunkEmbedding>> embedFile(
-    String filePath, {
-    int chunkSize = 1000,
-    double overlapRatio = 0.0,
-    int batchSize = 32,
-  }) async {
+  SparseEmbedding embedTextSparse(String text) {
     _checkDisposed();
 
-    // Allocate config struct
-    final config = allocateTextEmbedConfig(
-      chunkSize: chunkSize,
-      overlapRatio: overlapRatio,
-      batchSize: batchSize,
-      bufferSize: 100, // Default buffer size
-    );
+    final embeddingPtr = withCString(text, (textPtr) {
+      return ffi.embedTextSparse(_handle, textPtr);
+    });
 
-    // Convert file path to C string
-    final filePathPtr = stringToCString(filePath);
+    if (embeddingPtr == nullptr) {
+      throwLastError('Failed to generate sparse embedding');
+    }
 
     try {
-      // Call FFI function
-      final batchPtr = ffi.embedFile(_handle, filePathPtr, config);
+      return _copySparseEmbedding(embeddingPtr.ref);
+    } finally {
+      ffi.freeSparseEmbedding(embeddingPtr);
+    }
+  }
 
-      if (batchPtr == nullptr) {
-        throwLastError('Failed to embed file: $filePath');
-      }
+  /// Generate sparse (SPLADE) embeddings for multiple texts in a batch
+  ///
+  /// Parameters:
+  /// - [texts]: List of texts to embed
+  ///
+  /// Returns a list of [SparseEmbedding]s in the same order as the input.
+  ///
+  /// Throws:
+  /// - [InvalidConfigError] if the embedder is not a sparse model
+  /// - [EmbeddingFailedError] if embedding generation fails
+  /// - [StateError] if the embedder has been disposed
+  List<SparseEmbedding> embedTextsBatchSparse(List<String> texts) {
+    _checkDisposed();
 
-      try {
-        final batch = batchPtr.ref;
-        final results = <ChunkEmbedding>[];
+    if (texts.isEmpty) {
+      return [];
+    }
 
-        // Convert each CEmbedData to ChunkEmbedding
-        for (int i = 0; i < batch.count; i++) {
-          final embedData = batch.items[i];
-          results.add(_cEmbedDataToChunkEmbedding(embedData));
-        }
+    final batchPtr = _withCStringArray(texts, (textsPtr) {
+      return ffi.embedTextsBatchSparse(_handle, textsPtr, texts.length);
+    });
 
-        return results;
-      } finally {
-        ffi.freeEmbedDataBatch(batchPtr);
-      }
+    if (batchPtr == nullptr) {
+      throwLastError('Failed to generate sparse embeddings batch');
+    }
+
+    try {
+      final batch = batchPtr.ref;
+      return [
+        for (int i = 0; i < batch.count; i++)
+          _copySparseEmbedding(batch.embeddings[i]),
+      ];
     } finally {
-      // Free allocated memory
-      freeCString(filePathPtr);
-      calloc.free(config);
+      ffi.freeSparseEmbeddingBatch(batchPtr);
     }
   }
 
-  /// Embed all files in a directory (streaming)
-  ///
-  /// Processes all files in a directory and returns a [Stream] that yields
-  /// [ChunkEmbedding]s as they are generated. This allows processing large
-  /// directories without loading all embeddings into memory at once.
+  /// Generate a multi-vector (late-interaction) embedding for a single text
   ///
-  /// Files that fail to process will emit stream errors but won't stop
-  /// the processing of other files.
-  ///
-  /// Supported file formats: PDF, TXT, MD, DOCX, HTML
+  /// Late-interaction models such as ColBERT return one vector per token;
+  /// dense models return a single row.
   ///
   /// Parameters:
-  /// - [directoryPath]: Path to the directory to embed
-  /// - [extensions]: Optional list of file extensions to include (e.g., ['.pdf', '.txt']).
-  ///   If null, all supported file types will be processed.
-  /// - [chunkSize]: Maximum characters per chunk (default: 1000)
-  /// - [overlapRatio]: Overlap between chunks 0.0-1.0 (default: 0.0)
-  /// - [batchSize]: Batch size for embedding generation (default: 32)
+  /// - [text]: The text to embed
   ///
-  /// Returns a [Stream] of [ChunkEmbedding]s that yields results incrementally
-  /// as files are processed.
+  /// Returns a [MultiVectorEmbedding] with one row per token.
   ///
   /// Throws:
-  /// - [FileNotFoundError] if the directory does not exist
-  /// - [FileReadError] if there's a permission error accessing the directory
-  ///
-  /// Stream errors:
-  /// - Individual file processing errors are emitted to the stream but don't stop processing
+  /// - [EmbeddingFailedError] if embedding generation fails
+  /// - [StateError] if the embedder has been disposed
   ///
   /// Example:
   /// ```dart
-  /// final embedder = EmbedAnything.fromConfig(ModelConfig.bertMiniLML6());
-  /// try {
-  ///   await for (final chunk in embedder.embedDirectory(
-  ///     'documents/',
-  ///     extensions: ['.pdf', '.txt'],
-  ///   )) {
-  ///     print('Processing ${chunk.filePath}: chunk ${chunk.chunkIndex}');
-  ///     // Process chunk immediately without storing all in memory
-  ///   }
-  /// } finally {
-  ///   embedder.dispose();
-  /// }
+  /// final query = embedder.embedTextMultiVector('machine learning');
+  /// final doc = embedder.embedTextMultiVector('An introduction to AI');
+  /// print('Score: ${query.maxSim(doc)}');
   /// ```
-  Stream<ChunkEmbedding> embedDirectory(
-    String directoryPath, {
-    List<String>? extensions,
-    int chunkSize = 1000,
-    double overlapRatio = 0.0,
-    int batchSize = 32,
-  }) {
+  MultiVectorEmbedding embedTextMultiVector(String text) {
     _checkDisposed();
 
-    late StreamController<ChunkEmbedding> controller;
-    NativeCallable<ffi.StreamCallbackType>? callback;
-    Pointer<CTextEmbedConfig>? config;
-    Pointer<Utf8>? directoryPathPtr;
-    Pointer<Pointer<Utf8>>? extensionsPtr;
-
-    controller = StreamController<ChunkEmbedding>(
-      onListen: () {
-        // Allocate config struct
-        config = allocateTextEmbedConfig(
-          chunkSize: chunkSize,
-          overlapRatio: overlapRatio,
-          batchSize: batchSize,
-          bufferSize: 100,
-        );
-
-        // Allocate extensions array if provided
-        if (extensions != null && extensions.isNotEmpty) {
-          extensionsPtr = allocateStringArray(extensions);
-        }
+    final embeddingPtr = withCString(text, (textPtr) {
+      return ffi.embedTextMultiVector(_handle, textPtr);
+    });
 
-        // Convert directory path to C string
-        directoryPathPtr = stringToCString(directoryPath);
-
-        // Use Completer to wait for callback before closing stream
-        final callbackCompleter = Completer<void>();
-
-        // Create callback that adds chunks to stream
-        callback = NativeCallable<ffi.StreamCallbackType>.listener(
-          (Pointer<CEmbedDataBatch> batchPtr, Pointer<Void> context) {
-            try {
-              print('DEBUG DART: Directory callback fired');
-              final batch = batchPtr.ref;
-              print('DEBUG DART: Batch count: ${batch.count}');
-
-              // Convert each CEmbedData to ChunkEmbedding and add to stream
-              for (int i = 0; i < batch.count; i++) {
-                final embedData = batch.items[i];
-                final chunk = _cEmbedDataToChunkEmbedding(embedData);
-                controller.add(chunk);
-                print('DEBUG DART: Added chunk $i to stream');
-              }
+    if (embeddingPtr == nullptr) {
+      throwLastError('Failed to generate multi-vector embedding');
+    }
 
-              // Free the batch memory (CRITICAL for preventing memory leaks)
-              ffi.freeEmbedDataBatch(batchPtr);
-              print('DEBUG DART: Freed batch memory');
-            } catch (e, stackTrace) {
-              print('DEBUG DART: Callback error: $e');
-              // Add error to stream instead of throwing
-              controller.addError(e, stackTrace);
-            } finally {
-              // Signal that callback has completed
-              if (!callbackCompleter.isCompleted) {
-                callbackCompleter.complete();
-              }
-            }
-          },
-        );
+    try {
+      return _copyMultiVectorEmbedding(embeddingPtr.ref);
+    } finally {
+      ffi.freeMultiVectorEmbedding(embeddingPtr);
+    }
+  }
 
-        // Call FFI function
-        final result = ffi.embedDirectoryStream(
-          _handle,
-          directoryPathPtr!,
-          extensionsPtr ?? nullptr,
-          extensions?.length ?? 0,
-          config!,
-          callback!.nativeFunction,
-          nullptr, // No context needed
-        );
+  /// Generate multi-vector (late-interaction) embeddings for multiple texts
+  ///
+  /// Parameters:
+  /// - [texts]: List of texts to embed
+  ///
+  /// Returns a list of [MultiVectorEmbedding]s in the same order as the
+  /// input.
+  ///
+  /// Throws:
+  /// - [EmbeddingFailedError] if embedding generation fails
+  /// - [StateError] if the embedder has been disposed
+  List<MultiVectorEmbedding> embedTextsBatchMultiVector(List<String> texts) {
+    _checkDisposed();
 
-        // Check result and handle errors
-        if (result != 0) {
-          final errorMessage = getLastErrorMessage();
-          if (errorMessage != null) {
-            controller.addError(
-              _parseErrorForDirectory(errorMessage, directoryPath),
-            );
-          } else {
-            controller.addError(
-              Exception('Failed to embed directory: $directoryPath'),
-            );
+    if (texts.isEmpty) {
+      return [];
+    }
+
+    final batchPtr = _withCStringArray(texts, (textsPtr) {
+      return ffi.embedTextsBatchMultiVector(_handle, textsPtr, texts.length);
+    });
+
+    if (batchPtr == nullptr) {
+      throwLastError('Failed to generate multi-vector embeddings batch');
+    }
+
+    try {
+      final batch = batchPtr.ref;
+      return [
+        for (int i = 0; i < batch.count; i++)
+          _copyMultiVectorEmbedding(batch.embeddings[i]),
+      ];
+    } finally {
+      ffi.freeMultiVectorEmbeddingBatch(batchPtr);
+    }
+  }
+
+  /// Embed an image file with a vision embedder
+  ///
+  /// Requires an embedder created with [EmbedAnything.fromVisionModel].
+  ///
+  /// Parameters:
+  /// - [imagePath]: Path to a PNG, JPEG, WebP, GIF or BMP file
+  ///
+  /// Throws:
+  /// - [FileNotFoundError] if the image doesn't exist
+  /// - [UnsupportedFileFormatError] if the file is not a supported image
+  /// - [InvalidConfigError] if the embedder is not a vision model
+  /// - [EmbeddingFailedError] if decoding or inference fails
+  /// - [StateError] if the embedder has been disposed
+  EmbeddingResult embedImageFile(String imagePath) {
+    _checkDisposed();
+
+    final embeddingPtr = withCString(imagePath, (imagePathPtr) {
+      return ffi.embedImageFile(_handle, imagePathPtr);
+    });
+
+    if (embeddingPtr == nullptr) {
+      throwLastError('Failed to embed image: $imagePath');
+    }
+
+    return _takeEmbedding(embeddingPtr);
+  }
+
+  /// Embed an encoded image held in memory with a vision embedder
+  ///
+  /// Parameters:
+  /// - [bytes]: Encoded image (PNG, JPEG, WebP, GIF or BMP)
+  ///
+  /// Throws:
+  /// - [UnsupportedFileFormatError] if the bytes are not a recognised image
+  /// - [InvalidConfigError] if the embedder is not a vision model
+  /// - [EmbeddingFailedError] if decoding or inference fails
+  /// - [StateError] if the embedder has been disposed
+  EmbeddingResult embedImageBytes(Uint8List bytes) {
+    _checkDisposed();
+
+    final embeddingPtr = _withNativeBytes(bytes, (dataPtr) {
+      return ffi.embedImageBytes(_handle, dataPtr, bytes.length);
+    });
+
+    if (embeddingPtr == nullptr) {
+      throwLastError('Failed to embed image bytes');
+    }
+
+    return _takeEmbedding(embeddingPtr);
+  }
+
+  /// Embed multiple image files with a vision embedder
+  ///
+  /// Parameters:
+  /// - [imagePaths]: Paths to PNG, JPEG, WebP, GIF or BMP files
+  ///
+  /// Returns a list of [EmbeddingResult]s in the same order as the input.
+  ///
+  /// Throws:
+  /// - [FileNotFoundError] if an image doesn't exist
+  /// - [UnsupportedFileFormatError] if a file is not a supported image
+  /// - [InvalidConfigError] if the embedder is not a vision model
+  /// - [EmbeddingFailedError] if decoding or inference fails
+  /// - [StateError] if the embedder has been disposed
+  List<EmbeddingResult> embedImageFilesBatch(List<String> imagePaths) {
+    _checkDisposed();
+
+    if (imagePaths.isEmpty) {
+      return [];
+    }
+
+    final batchPtr = _withCStringArray(imagePaths, (imagePathsPtr) {
+      return ffi.embedImageFilesBatch(
+        _handle,
+        imagePathsPtr,
+        imagePaths.length,
+      );
+    });
+
+    if (batchPtr == nullptr) {
+      throwLastError('Failed to embed image batch');
+    }
+
+    try {
+      final batch = batchPtr.ref;
+      final results = <EmbeddingResult>[];
+      for (int i = 0; i < batch.count; i++) {
+        final embedding = batch.embeddings[i];
+        results.add(
+          EmbeddingResult(_copyFloatArray(embedding.values, embedding.len)),
+        );
+      }
+      return results;
+    } finally {
+      ffi.freeEmbeddingBatch(batchPtr);
+    }
+  }
+
+  /// Embed a text query into the image space of a vision embedder
+  ///
+  /// The result is comparable with image embeddings from the same
+  /// embedder, for text-to-image search.
+  ///
+  /// Parameters:
+  /// - [text]: Query text
+  ///
+  /// Throws:
+  /// - [InvalidConfigError] if the embedder is not a vision model
+  /// - [EmbeddingFailedError] if inference fails
+  /// - [StateError] if the embedder has been disposed
+  EmbeddingResult embedImageQuery(String text) {
+    _checkDisposed();
+
+    final embeddingPtr = withCString(text, (textPtr) {
+      return ffi.embedImageQuery(_handle, textPtr);
+    });
+
+    if (embeddingPtr == nullptr) {
+      throwLastError('Failed to embed image query');
+    }
+
+    return _takeEmbedding(embeddingPtr);
+  }
+
+  /// Embed a single file with automatic chunking
+  ///
+  /// Processes a document file and returns all text chunks with their embeddings
+  /// and metadata. The file is automatically chunked based on the configuration
+  /// parameters.
+  ///
+  /// Supported file formats: PDF, TXT, MD, DOCX, HTML
+  ///
+  /// Parameters:
+  /// - [filePath]: Path to the file to embed
+  /// - [chunkSize]: Maximum characters per chunk (default: 1000)
+  /// - [overlapRatio]: Overlap between chunks 0.0-1.0 (default: 0.0)
+  /// - [batchSize]: Batch size for embedding generation (default: 32)
+  ///
+  /// Returns a [Future] that completes with a list of [ChunkEmbedding]s,
+  /// one for each chunk of the file. Each chunk includes the embedding,
+  /// text content, and metadata (file path, chunk index, page number for PDFs).
+  ///
+  /// Throws:
+  /// - [FileNotFoundError] if the file does not exist
+  /// - [UnsupportedFileFormatError] if the file format is not supported
+  /// - [FileReadError] if there's a permission or I/O error reading the file
+  /// - [EmbeddingFailedError] if embedding generation fails
+  /// - [StateError] if the embedder has been disposed
+  ///
+  /// Example:
+  /// ```dart
+  /// final embedder = EmbedAnything.fromConfig(ModelConfig.bertMiniLML6());
+  /// try {
+  ///   final chunks = await embedder.embedFile(
+  ///     'document.pdf',
+  ///     chunkSize: 500,
+  ///     overlapRatio: 0.1,
+  ///   );
+  ///
+  ///   for (final chunk in chunks) {
+  ///     print('File: ${chunk.filePath}');
+  ///     print('Page: ${chunk.page}');
+  ///     print('Chunk ${chunk.chunkIndex}: ${chunk.text?.substring(0, 50)}...');
+  ///   }
+  /// } finally {
+  ///   embedder.dispose();
+  /// }
+  /// ```
+  Future<List<ChunkEmbedding>> embedFile(
+    String filePath, {
+    int chunkSize = 1000,
+    double overlapRatio = 0.0,
+    int batchSize = 32,
+  }) async {
+    _checkDisposed();
+
+    // Allocate config struct
+    final config = allocateTextEmbedConfig(
+      chunkSize: chunkSize,
+      overlapRatio: overlapRatio,
+      batchSize: batchSize,
+      bufferSize: 100, // Default buffer size
+    );
+
+    // Convert file path to C string
+    final filePathPtr = stringToCString(filePath);
+
+    try {
+      // Call FFI function
+      final batchPtr = ffi.embedFile(_handle, filePathPtr, config);
+
+      if (batchPtr == nullptr) {
+        throwLastError('Failed to embed file: $filePath');
+      }
+
+      try {
+        final batch = batchPtr.ref;
+        final results = <ChunkEmbedding>[];
+
+        // Convert each CEmbedData to ChunkEmbedding
+        for (int i = 0; i < batch.count; i++) {
+          final embedData = batch.items[i];
+          results.add(_cEmbedDataToChunkEmbedding(embedData));
+        }
+
+        return results;
+      } finally {
+        ffi.freeEmbedDataBatch(batchPtr);
+      }
+    } finally {
+      // Free allocated memory
+      freeCString(filePathPtr);
+      calloc.free(config);
+    }
+  }
+
+  /// Embed all files in a directory (streaming)
+  ///
+  /// Processes all files in a directory and returns a [Stream] that yields
+  /// [ChunkEmbedding]s as they are generated. This allows processing large
+  /// directories without loading all embeddings into memory at once.
+  ///
+  /// The walk runs on native worker threads and chunks are delivered each
+  /// time a buffer of 100 fills. Pausing the subscription pauses the walk
+  /// once two buffers are waiting, and cancelling it stops the walk before
+  /// the next file.
+  ///
+  /// Supported file formats: PDF, TXT, MD, DOCX, HTML
+  ///
+  /// Parameters:
+  /// - [directoryPath]: Path to the directory to embed
+  /// - [extensions]: Optional list of file extensions to include (e.g., ['.pdf', '.txt']).
+  ///   If null, all supported file types will be processed.
+  /// - [chunkSize]: Maximum characters per chunk (default: 1000)
+  /// - [overlapRatio]: Overlap between chunks 0.0-1.0 (default: 0.0)
+  /// - [batchSize]: Batch size for embedding generation (default: 32)
+  ///
+  /// Returns a [Stream] of [ChunkEmbedding]s that yields results incrementally
+  /// as files are processed.
+  ///
+  /// Throws:
+  /// - [FileNotFoundError] if the directory does not exist
+  /// - [FileReadError] if there's a permission error accessing the directory
+  ///
+  /// Stream errors:
+  /// - A file that fails to embed ends the stream with its error; chunks
+  ///   already delivered are kept
+  ///
+  /// Example:
+  /// ```dart
+  /// final embedder = EmbedAnything.fromConfig(ModelConfig.bertMiniLML6());
+  /// try {
+  ///   await for (final chunk in embedder.embedDirectory(
+  ///     'documents/',
+  ///     extensions: ['.pdf', '.txt'],
+  ///   )) {
+  ///     print('Processing ${chunk.filePath}: chunk ${chunk.chunkIndex}');
+  ///     // Process chunk immediately without storing all in memory
+  ///   }
+  /// } finally {
+  ///   embedder.dispose();
+  /// }
+  /// ```
+  Stream<ChunkEmbedding> embedDirectory(
+    String directoryPath, {
+    List<String>? extensions,
+    int chunkSize = 1000,
+    double overlapRatio = 0.0,
+    int batchSize = 32,
+  }) {
+    _checkDisposed();
+
+    late StreamController<ChunkEmbedding> controller;
+    var opId = -1;
+    var cancelled = false;
+
+    // Drain the entries produced so far until Rust reports the outcome. The
+    // walk runs on Rust workers, so cancelling the subscription reaches it
+    // between two polls.
+    Future<void> pump() async {
+      const pollInterval = Duration(milliseconds: 10);
+      try {
+        while (true) {
+          if (!cancelled && !controller.isPaused) {
+            _drainDirectoryPartial(opId, controller);
+          }
+
+          final result = async_ffi.pollAsyncResult(opId);
+          switch (result.status) {
+            case AsyncPollStatus.success:
+              try {
+                final rest = _extractResult<List<ChunkEmbedding>>(
+                  result,
+                  AsyncResultType.fileEmbedding,
+                );
+                if (!cancelled) {
+                  rest.forEach(controller.add);
+                }
+              } finally {
+                if (result.errorMessage != nullptr) {
+                  async_ffi.freeAsyncErrorMessage(result.errorMessage);
+                }
+              }
+              return;
+
+            case AsyncPollStatus.error:
+              final errorMessage = result.errorMessage != nullptr
+                  ? result.errorMessage.toDartString()
+                  : null;
+              if (result.errorMessage != nullptr) {
+                async_ffi.freeAsyncErrorMessage(result.errorMessage);
+              }
+              controller.addError(errorMessage != null
+                  ? _parseErrorForDirectory(errorMessage, directoryPath)
+                  : Exception('Failed to embed directory: $directoryPath'));
+              return;
+
+            case AsyncPollStatus.cancelled:
+              return;
+
+            case AsyncPollStatus.pending:
+            default:
+              await Future.delayed(pollInterval);
           }
-          // Complete the completer since callback won't be called on error
-          if (!callbackCompleter.isCompleted) {
-            callbackCompleter.complete();
+        }
+      } catch (e, stackTrace) {
+        controller.addError(e, stackTrace);
+      } finally {
+        await controller.close();
+      }
+    }
+
+    controller = StreamController<ChunkEmbedding>(
+      onListen: () {
+        final config = allocateTextEmbedConfig(
+          chunkSize: chunkSize,
+          overlapRatio: overlapRatio,
+          batchSize: batchSize,
+          bufferSize: 100,
+        );
+        final directoryPathPtr = stringToCString(directoryPath);
+        Pointer<Pointer<Utf8>>? extensionsPtr;
+        if (extensions != null && extensions.isNotEmpty) {
+          extensionsPtr = allocateStringArray(extensions);
+        }
+
+        // The arguments are copied by Rust before the call returns
+        try {
+          opId = async_ffi.startEmbedDirectory(
+            _handle,
+            nullptr, // Text only
+            directoryPathPtr,
+            extensionsPtr ?? nullptr,
+            extensions?.length ?? 0,
+            config,
+            async_ffi.defaultPriority,
+          );
+        } finally {
+          freeCString(directoryPathPtr);
+          calloc.free(config);
+          if (extensionsPtr != null) {
+            freeStringArray(extensionsPtr, extensions!.length);
           }
         }
 
-        // Wait for callback to complete before closing stream
-        callbackCompleter.future.then((_) {
-          print('DEBUG DART: Callback completed, closing stream');
+        if (opId < 0) {
+          final errorMessage = getLastErrorMessage();
+          controller.addError(errorMessage != null
+              ? _parseErrorForDirectory(errorMessage, directoryPath)
+              : Exception('Failed to embed directory: $directoryPath'));
           controller.close();
-        });
+          return;
+        }
+
+        unawaited(pump());
       },
       onCancel: () {
-        // Clean up resources when stream is cancelled
-        _cleanupDirectoryResources(
-          callback,
-          config,
-          directoryPathPtr,
-          extensionsPtr,
-          extensions?.length ?? 0,
-        );
+        // pump() keeps polling until Rust acknowledges, which frees the
+        // operation and whatever it had not handed over yet
+        cancelled = true;
+        if (opId > 0) {
+          async_ffi.cancelAsyncOperation(opId);
+        }
       },
     );
 
@@ -552,34 +1040,30 @@ class EmbedAnything {
     }
   }
 
-  /// Helper to clean up directory streaming resources
-  void _cleanupDirectoryResources(
-    NativeCallable<ffi.StreamCallbackType>? callback,
-    Pointer<CTextEmbedConfig>? config,
-    Pointer<Utf8>? directoryPathPtr,
-    Pointer<Pointer<Utf8>>? extensionsPtr,
-    int extensionsCount,
+  /// Add the entries a running directory operation produced since the
+  /// last drain to [controller].
+  static void _drainDirectoryPartial(
+    int opId,
+    StreamController<ChunkEmbedding> controller,
   ) {
-    callback?.close();
-    if (config != null && config != nullptr) {
-      calloc.free(config);
+    final batchPtr = async_ffi.drainAsyncPartial(opId);
+    if (batchPtr == nullptr) {
+      return;
     }
-    if (directoryPathPtr != null && directoryPathPtr != nullptr) {
-      freeCString(directoryPathPtr);
-    }
-    if (extensionsPtr != null && extensionsPtr != nullptr) {
-      freeStringArray(extensionsPtr, extensionsCount);
+    try {
+      final batch = batchPtr.ref;
+      for (int i = 0; i < batch.count; i++) {
+        controller.add(_cEmbedDataToChunkEmbeddingStatic(batch.items[i]));
+      }
+    } finally {
+      ffi.freeEmbedDataBatch(batchPtr);
     }
   }
 
   /// Convert CEmbedData to ChunkEmbedding
   ChunkEmbedding _cEmbedDataToChunkEmbedding(CEmbedData embedData) {
-    // Copy embedding vector
-    final embeddingValues = _copyFloatArray(
-      embedData.embeddingValues,
-      embedData.embeddingLen,
-    );
-    final embedding = EmbeddingResult(embeddingValues);
+    // Copy embedding vector (split into token rows for multi-vector data)
+    final (embedding, multiVector) = _copyChunkEmbedding(embedData);
 
     // Parse combined text and metadata JSON (SurrealDB pattern)
     String? text;
@@ -623,6 +1107,7 @@ class EmbedAnything {
       embedding: embedding,
       text: text,
       metadata: metadata,
+      multiVector: multiVector,
     );
   }
 
@@ -678,6 +1163,98 @@ class EmbedAnything {
     return list;
   }
 
+  /// Copy a dense embedding from native memory and free it
+  static EmbeddingResult _takeEmbedding(Pointer<CTextEmbedding> embeddingPtr) {
+    try {
+      final embedding = embeddingPtr.ref;
+      return EmbeddingResult(_copyFloatArray(embedding.values, embedding.len));
+    } finally {
+      ffi.freeEmbedding(embeddingPtr);
+    }
+  }
+
+  /// Execute a callback with a native copy of [bytes], freeing it
+  /// afterwards
+  static T _withNativeBytes<T>(
+    Uint8List bytes,
+    T Function(Pointer<Uint8>) callback,
+  ) {
+    final dataPtr = malloc<Uint8>(bytes.isEmpty ? 1 : bytes.length);
+    try {
+      dataPtr.asTypedList(bytes.length).setAll(0, bytes);
+      return callback(dataPtr);
+    } finally {
+      malloc.free(dataPtr);
+    }
+  }
+
+  /// Copy a sparse embedding from native memory
+  static SparseEmbedding _copySparseEmbedding(CSparseEmbedding embedding) {
+    return SparseEmbedding(
+      List<int>.generate(embedding.len, (i) => embedding.indices[i]),
+      _copyFloatArray(embedding.values, embedding.len),
+    );
+  }
+
+  /// Copy a multi-vector embedding from native memory
+  static MultiVectorEmbedding _copyMultiVectorEmbedding(
+    CMultiVectorEmbedding embedding,
+  ) {
+    return MultiVectorEmbedding.fromRowMajor(
+      _copyFloatArray(embedding.values, embedding.rows * embedding.dim),
+      embedding.rows,
+    );
+  }
+
+  /// Copy rerank results from native memory
+  static List<RerankResult> _copyRerankResults(CRerankResults results) {
+    return [
+      for (int i = 0; i < results.count; i++)
+        RerankResult(results.results[i].index, results.results[i].score),
+    ];
+  }
+
+  /// Copy the embedding of a file chunk from native memory
+  ///
+  /// Multi-vector data (more than one row) is split into token rows, and
+  /// the chunk's single embedding is their mean.
+  static (EmbeddingResult, MultiVectorEmbedding?) _copyChunkEmbedding(
+    CEmbedData embedData,
+  ) {
+    final values = _copyFloatArray(
+      embedData.embeddingValues,
+      embedData.embeddingLen,
+    );
+    if (embedData.embeddingRows <= 1) {
+      return (EmbeddingResult(values), null);
+    }
+
+    final multiVector = MultiVectorEmbedding.fromRowMajor(
+      values,
+      embedData.embeddingRows,
+    );
+    return (multiVector.mean(), multiVector);
+  }
+
+  /// Execute a callback with a native array of C strings, freeing the
+  /// strings and the array afterwards
+  static T _withCStringArray<T>(
+    List<String> texts,
+    T Function(Pointer<Pointer<Utf8>>) callback,
+  ) {
+    final cStrings = texts.map(stringToCString).toList();
+    final cStringsArray = malloc<Pointer<Utf8>>(texts.length);
+    try {
+      for (int i = 0; i < texts.length; i++) {
+        cStringsArray[i] = cStrings[i];
+      }
+      return callback(cStringsArray);
+    } finally {
+      cStrings.forEach(freeCString);
+      malloc.free(cStringsArray);
+    }
+  }
+
   /// Initialize the Tokio runtime (once)
   static bool _runtimeInitialized = false;
 
@@ -728,6 +1305,28 @@ class EmbedAnything {
     return result == 0;
   }
 
+  /// Set the HuggingFace auth token used to load private or gated models.
+  ///
+  /// The token applies process-wide to every subsequent model load.
+  /// Pass `null` to clear it.
+  ///
+  /// Example:
+  /// ```dart
+  /// EmbedAnything.setHfToken(Platform.environment['HF_TOKEN']);
+  /// final embedder = EmbedAnything.fromPretrainedHf(
+  ///   model: EmbeddingModel.bert,
+  ///   modelId: 'my-org/private-minilm',
+  /// );
+  /// ```
+  static void setHfToken(String? token) {
+    final result = token == null
+        ? ffi.setHfToken(nullptr)
+        : withCString(token, (tokenPtr) => ffi.setHfToken(tokenPtr));
+    if (result != 0) {
+      throwLastError('Failed to set HuggingFace token');
+    }
+  }
+
   /// Get the current thread pool size.
   ///
   /// Returns the number of threads in the Rayon thread pool.
@@ -803,10 +1402,13 @@ class EmbedAnything {
   /// Parameters:
   /// - [modelId]: HuggingFace model identifier
   ///   (e.g., 'sentence-transformers/all-MiniLM-L6-v2')
+  /// - [model]: Architecture to load (default: detected from config.json)
   /// - [revision]: Git revision/branch (defaults to 'main')
   /// - [dtype]: Model data type (default: auto)
   ///
   /// Throws:
+  /// - [InvalidConfigError] if the checkpoint declares a different
+  ///   architecture than [model]
   /// - [ModelNotFoundError] if model doesn't exist on HuggingFace Hub
   /// - [EmbeddingCancelledError] if operation was cancelled
   /// - [FFIError] if model loading fails
@@ -826,21 +1428,166 @@ class EmbedAnything {
   /// }
   /// ```
   static Future<EmbedAnything> fromPretrainedHfAsync({
+    required String modelId,
+    EmbeddingModel model = EmbeddingModel.auto,
+    String revision = 'main',
+    ModelDtype dtype = ModelDtype.f32,
+  }) async {
+    // Initialize runtime first
+    _initializeRuntime();
+
+    // Create config for reference
+    final config = ModelConfig(
+      modelId: modelId,
+      modelType: model,
+      revision: revision,
+      dtype: dtype,
+    );
+
+    final options = allocateEmbedderOptions(
+      normalize: config.normalize,
+      batchSize: config.defaultBatchSize,
+    );
+
+    final int opId;
+    try {
+      opId = withCString(modelId, (modelIdPtr) {
+        return withCString(revision, (revisionPtr) {
+          return async_ffi.startLoadModel(
+            model.value,
+            modelIdPtr,
+            revisionPtr,
+            nullptr,
+            dtype.value,
+            options,
+          );
+        });
+      });
+    } finally {
+      calloc.free(options);
+    }
+
+    if (opId < 0) {
+      throwLastError('Failed to start model loading');
+    }
+
+    final handle = await _pollUntilComplete<Pointer<CEmbedder>>(
+      opId,
+      AsyncResultType.modelLoad,
+    );
+
+    return EmbedAnything._(handle, config);
+  }
+
+  /// Load a model from a directory on disk without blocking the UI.
+  ///
+  /// This is the async version of [EmbedAnything.fromLocalPath].
+  ///
+  /// Parameters:
+  /// - [modelPath]: Path to the model directory
+  /// - [dtype]: Model data type (default: f32)
+  /// - [normalize]: L2-normalize output vectors (default: true)
+  /// - [batchSize]: Batch size for embedding calls (default: 32)
+  /// - [pooling]: Pooling of token vectors (default: the checkpoint's
+  ///   `1_Pooling/config.json`, or mean)
+  /// - [maxSequenceLength]: Maximum tokens per input, capped at the
+  ///   model's limit (0 = the model's limit)
+  ///
+  /// Throws:
+  /// - [FileNotFoundError] if the directory or one of its files is missing
+  /// - [InvalidConfigError] if the architecture is not supported
+  /// - [EmbeddingFailedError] if the weights cannot be loaded
+  /// - [EmbeddingCancelledError] if operation was cancelled
+  ///
+  /// Example:
+  /// ```dart
+  /// final embedder =
+  ///     await EmbedAnything.fromLocalPathAsync('assets/models/minilm');
+  /// ```
+  static Future<EmbedAnything> fromLocalPathAsync(
+    String modelPath, {
+    ModelDtype dtype = ModelDtype.f32,
+    bool normalize = true,
+    int batchSize = 32,
+    PoolingStrategy pooling = PoolingStrategy.modelDefault,
+    int maxSequenceLength = 0,
+  }) async {
+    _initializeRuntime();
+
+    final options = allocateEmbedderOptions(
+      normalize: normalize,
+      batchSize: batchSize,
+      maxSequenceLength: maxSequenceLength,
+      pooling: pooling.value,
+    );
+
+    final int opId;
+    try {
+      opId = withCString(modelPath, (modelPathPtr) {
+        return async_ffi.startLoadModelFromLocalPath(
+          modelPathPtr,
+          dtype.value,
+          options,
+        );
+      });
+    } finally {
+      calloc.free(options);
+    }
+
+    if (opId < 0) {
+      throwLastError('Failed to start model loading');
+    }
+
+    final handle = await _pollUntilComplete<Pointer<CEmbedder>>(
+      opId,
+      AsyncResultType.modelLoad,
+    );
+
+    return EmbedAnything._(handle);
+  }
+
+  /// Load a CLIP or SigLIP image embedder without blocking the UI.
+  ///
+  /// This is the async version of [EmbedAnything.fromVisionModel].
+  ///
+  /// Throws:
+  /// - [ModelNotFoundError] if the model doesn't exist on HuggingFace Hub
+  /// - [InvalidConfigError] if the checkpoint is not a vision model
+  /// - [EmbeddingFailedError] if the model cannot be loaded
+  /// - [EmbeddingCancelledError] if operation was cancelled
+  static Future<EmbedAnything> fromVisionModelAsync({
     required String modelId,
     String revision = 'main',
     ModelDtype dtype = ModelDtype.f32,
+    bool normalize = true,
+    int batchSize = 32,
   }) async {
-    // Initialize runtime first
     _initializeRuntime();
 
-    final opId = withCString(modelId, (modelIdPtr) {
-      return withCString(revision, (revisionPtr) {
-        return async_ffi.startLoadModel(modelIdPtr, revisionPtr, dtype.value);
+    final options = allocateEmbedderOptions(
+      normalize: normalize,
+      batchSize: batchSize,
+    );
+
+    final int opId;
+    try {
+      opId = withCString(modelId, (modelIdPtr) {
+        return withCString(revision, (revisionPtr) {
+          return async_ffi.startLoadVisionModel(
+            modelIdPtr,
+            revisionPtr,
+            nullptr,
+            dtype.value,
+            options,
+          );
+        });
       });
-    });
+    } finally {
+      calloc.free(options);
+    }
 
     if (opId < 0) {
-      throwLastError('Failed to start model loading');
+      throwLastError('Failed to start vision model loading');
     }
 
     final handle = await _pollUntilComplete<Pointer<CEmbedder>>(
@@ -848,15 +1595,7 @@ class EmbedAnything {
       AsyncResultType.modelLoad,
     );
 
-    // Create config for reference
-    final config = ModelConfig(
-      modelId: modelId,
-      modelType: EmbeddingModel.bert, // Default, actual type determined by model
-      revision: revision,
-      dtype: dtype,
-    );
-
-    return EmbedAnything._(handle, config);
+    return EmbedAnything._(handle);
   }
 
   /// Embed text asynchronously without blocking the UI.
@@ -866,12 +1605,15 @@ class EmbedAnything {
   ///
   /// Parameters:
   /// - [text]: The text to embed
+  /// - [priority]: Scheduling priority (default: interactive)
+  /// - [timeout]: Deadline after which unfinished work is cancelled
   ///
   /// Returns a [Future] that completes with the [EmbeddingResult].
   ///
   /// Throws:
   /// - [EmbeddingFailedError] if embedding generation fails
   /// - [EmbeddingCancelledError] if operation was cancelled
+  /// - [OperationTimeoutError] if [timeout] passed before it finished
   /// - [StateError] if the embedder has been disposed
   ///
   /// Example:
@@ -879,16 +1621,25 @@ class EmbedAnything {
   /// final result = await embedder.embedTextAsync('Hello, world!');
   /// print('Dimension: ${result.dimension}');
   /// ```
-  Future<EmbeddingResult> embedTextAsync(String text) async {
+  Future<EmbeddingResult> embedTextAsync(
+    String text, {
+    OperationPriority? priority,
+    Duration? timeout,
+  }) async {
     _checkDisposed();
 
     final opId = withCString(text, (textPtr) {
-      return async_ffi.startEmbedText(_handle, textPtr);
+      return async_ffi.startEmbedText(
+        _handle,
+        textPtr,
+        _priorityValue(priority),
+      );
     });
 
     if (opId < 0) {
       throwLastError('Failed to start text embedding');
     }
+    _applyTimeout(opId, timeout);
 
     return _pollUntilComplete<EmbeddingResult>(
       opId,
@@ -912,12 +1663,16 @@ class EmbedAnything {
   ///   uses [ModelConfig.defaultBatchSize] or 32.
   /// - [onProgress]: Optional callback for progress updates. Called after
   ///   each chunk completes with (completedCount, totalCount).
+  /// - [priority]: Scheduling priority of each chunk (default: background)
+  /// - [timeout]: Deadline for the whole batch; unfinished work is
+  ///   cancelled
   ///
   /// Returns a [Future] that completes with a list of [EmbeddingResult]s.
   ///
   /// Throws:
   /// - [EmbeddingFailedError] if embedding generation fails
   /// - [EmbeddingCancelledError] if operation was cancelled
+  /// - [OperationTimeoutError] if [timeout] passed before it finished
   /// - [StateError] if the embedder has been disposed
   ///
   /// Example:
@@ -941,77 +1696,438 @@ class EmbedAnything {
     List<String> texts, {
     int? chunkSize,
     void Function(int completed, int total)? onProgress,
+    OperationPriority? priority,
+    Duration? timeout,
+  }) async {
+    _checkDisposed();
+
+    if (texts.isEmpty) {
+      return [];
+    }
+
+    // Determine effective chunk size
+    final effectiveChunkSize = chunkSize ?? _config?.defaultBatchSize ?? 32;
+
+    // If batch is small enough, process directly without chunking overhead
+    if (texts.length <= effectiveChunkSize) {
+      final results =
+          await _embedTextsBatchAsyncInternal(texts, priority, timeout);
+      onProgress?.call(texts.length, texts.length);
+      return results;
+    }
+
+    // Process in chunks for large batches; the timeout covers all of them
+    final results = <EmbeddingResult>[];
+    final stopwatch = Stopwatch()..start();
+
+    for (int i = 0; i < texts.length; i += effectiveChunkSize) {
+      final end = (i + effectiveChunkSize < texts.length)
+          ? i + effectiveChunkSize
+          : texts.length;
+      final chunk = texts.sublist(i, end);
+
+      final remaining = timeout == null ? null : timeout - stopwatch.elapsed;
+      final chunkResults =
+          await _embedTextsBatchAsyncInternal(chunk, priority, remaining);
+      results.addAll(chunkResults);
+
+      // Report progress after each chunk
+      onProgress?.call(results.length, texts.length);
+    }
+
+    return results;
+  }
+
+  /// Internal method to embed a batch of texts without chunking.
+  Future<List<EmbeddingResult>> _embedTextsBatchAsyncInternal(
+    List<String> texts,
+    OperationPriority? priority,
+    Duration? timeout,
+  ) async {
+    // Convert Dart strings to C strings
+    final cStrings = texts.map((t) => stringToCString(t)).toList();
+    final cStringsArray = malloc<Pointer<Utf8>>(texts.length);
+
+    try {
+      // Fill the array
+      for (int i = 0; i < texts.length; i++) {
+        cStringsArray[i] = cStrings[i];
+      }
+
+      final opId = async_ffi.startEmbedTextsBatch(
+        _handle,
+        cStringsArray,
+        texts.length,
+        _priorityValue(priority),
+      );
+
+      if (opId < 0) {
+        throwLastError('Failed to start batch embedding');
+      }
+      _applyTimeout(opId, timeout);
+
+      return _pollUntilComplete<List<EmbeddingResult>>(
+        opId,
+        AsyncResultType.batchEmbedding,
+      );
+    } finally {
+      // Free all C strings
+      for (final cStr in cStrings) {
+        freeCString(cStr);
+      }
+      malloc.free(cStringsArray);
+    }
+  }
+
+  /// Generate a sparse (SPLADE) embedding without blocking the UI.
+  ///
+  /// This is the async version of [embedTextSparse].
+  ///
+  /// Parameters:
+  /// - [text]: The text to embed
+  /// - [priority]: Scheduling priority (default: interactive)
+  /// - [timeout]: Deadline after which unfinished work is cancelled
+  ///
+  /// Throws:
+  /// - [InvalidConfigError] if the embedder is not a sparse model
+  /// - [EmbeddingFailedError] if embedding generation fails
+  /// - [EmbeddingCancelledError] if operation was cancelled
+  /// - [OperationTimeoutError] if [timeout] passed before it finished
+  /// - [StateError] if the embedder has been disposed
+  Future<SparseEmbedding> embedTextSparseAsync(
+    String text, {
+    OperationPriority? priority,
+    Duration? timeout,
+  }) async {
+    _checkDisposed();
+
+    final opId = withCString(text, (textPtr) {
+      return async_ffi.startEmbedTextSparse(
+        _handle,
+        textPtr,
+        _priorityValue(priority),
+      );
+    });
+
+    if (opId < 0) {
+      throwLastError('Failed to start sparse embedding');
+    }
+    _applyTimeout(opId, timeout);
+
+    return _pollUntilComplete<SparseEmbedding>(
+      opId,
+      AsyncResultType.sparseEmbedding,
+    );
+  }
+
+  /// Generate sparse (SPLADE) embeddings for multiple texts without
+  /// blocking the UI.
+  ///
+  /// This is the async version of [embedTextsBatchSparse].
+  ///
+  /// Parameters:
+  /// - [texts]: List of texts to embed
+  /// - [priority]: Scheduling priority (default: background)
+  /// - [timeout]: Deadline after which unfinished work is cancelled
+  ///
+  /// Throws:
+  /// - [InvalidConfigError] if the embedder is not a sparse model
+  /// - [EmbeddingFailedError] if embedding generation fails
+  /// - [EmbeddingCancelledError] if operation was cancelled
+  /// - [OperationTimeoutError] if [timeout] passed before it finished
+  /// - [StateError] if the embedder has been disposed
+  Future<List<SparseEmbedding>> embedTextsBatchSparseAsync(
+    List<String> texts, {
+    OperationPriority? priority,
+    Duration? timeout,
+  }) async {
+    _checkDisposed();
+
+    if (texts.isEmpty) {
+      return [];
+    }
+
+    final opId = _withCStringArray(texts, (textsPtr) {
+      return async_ffi.startEmbedTextsBatchSparse(
+        _handle,
+        textsPtr,
+        texts.length,
+        _priorityValue(priority),
+      );
+    });
+
+    if (opId < 0) {
+      throwLastError('Failed to start sparse batch embedding');
+    }
+    _applyTimeout(opId, timeout);
+
+    return _pollUntilComplete<List<SparseEmbedding>>(
+      opId,
+      AsyncResultType.sparseBatchEmbedding,
+    );
+  }
+
+  /// Generate a multi-vector (late-interaction) embedding without blocking
+  /// the UI.
+  ///
+  /// This is the async version of [embedTextMultiVector].
+  ///
+  /// Parameters:
+  /// - [text]: The text to embed
+  /// - [priority]: Scheduling priority (default: interactive)
+  /// - [timeout]: Deadline after which unfinished work is cancelled
+  ///
+  /// Throws:
+  /// - [EmbeddingFailedError] if embedding generation fails
+  /// - [EmbeddingCancelledError] if operation was cancelled
+  /// - [OperationTimeoutError] if [timeout] passed before it finished
+  /// - [StateError] if the embedder has been disposed
+  Future<MultiVectorEmbedding> embedTextMultiVectorAsync(
+    String text, {
+    OperationPriority? priority,
+    Duration? timeout,
+  }) async {
+    _checkDisposed();
+
+    final opId = withCString(text, (textPtr) {
+      return async_ffi.startEmbedTextMultiVector(
+        _handle,
+        textPtr,
+        _priorityValue(priority),
+      );
+    });
+
+    if (opId < 0) {
+      throwLastError('Failed to start multi-vector embedding');
+    }
+    _applyTimeout(opId, timeout);
+
+    return _pollUntilComplete<MultiVectorEmbedding>(
+      opId,
+      AsyncResultType.multiVectorEmbedding,
+    );
+  }
+
+  /// Generate multi-vector (late-interaction) embeddings for multiple
+  /// texts without blocking the UI.
+  ///
+  /// This is the async version of [embedTextsBatchMultiVector].
+  ///
+  /// Parameters:
+  /// - [texts]: List of texts to embed
+  /// - [priority]: Scheduling priority (default: background)
+  /// - [timeout]: Deadline after which unfinished work is cancelled
+  ///
+  /// Throws:
+  /// - [EmbeddingFailedError] if embedding generation fails
+  /// - [EmbeddingCancelledError] if operation was cancelled
+  /// - [OperationTimeoutError] if [timeout] passed before it finished
+  /// - [StateError] if the embedder has been disposed
+  Future<List<MultiVectorEmbedding>> embedTextsBatchMultiVectorAsync(
+    List<String> texts, {
+    OperationPriority? priority,
+    Duration? timeout,
+  }) async {
+    _checkDisposed();
+
+    if (texts.isEmpty) {
+      return [];
+    }
+
+    final opId = _withCStringArray(texts, (textsPtr) {
+      return async_ffi.startEmbedTextsBatchMultiVector(
+        _handle,
+        textsPtr,
+        texts.length,
+        _priorityValue(priority),
+      );
+    });
+
+    if (opId < 0) {
+      throwLastError('Failed to start multi-vector batch embedding');
+    }
+    _applyTimeout(opId, timeout);
+
+    return _pollUntilComplete<List<MultiVectorEmbedding>>(
+      opId,
+      AsyncResultType.multiVectorBatchEmbedding,
+    );
+  }
+
+  /// Embed an image file without blocking the UI.
+  ///
+  /// This is the async version of [embedImageFile].
+  ///
+  /// Parameters:
+  /// - [imagePath]: Path to a PNG, JPEG, WebP, GIF or BMP file
+  /// - [priority]: Scheduling priority (default: interactive)
+  /// - [timeout]: Deadline after which unfinished work is cancelled
+  ///
+  /// Throws:
+  /// - [FileNotFoundError] if the image doesn't exist
+  /// - [UnsupportedFileFormatError] if the file is not a supported image
+  /// - [InvalidConfigError] if the embedder is not a vision model
+  /// - [EmbeddingFailedError] if decoding or inference fails
+  /// - [EmbeddingCancelledError] if operation was cancelled
+  /// - [OperationTimeoutError] if [timeout] passed before it finished
+  /// - [StateError] if the embedder has been disposed
+  Future<EmbeddingResult> embedImageFileAsync(
+    String imagePath, {
+    OperationPriority? priority,
+    Duration? timeout,
+  }) async {
+    _checkDisposed();
+
+    final opId = withCString(imagePath, (imagePathPtr) {
+      return async_ffi.startEmbedImageFile(
+        _handle,
+        imagePathPtr,
+        _priorityValue(priority),
+      );
+    });
+
+    if (opId < 0) {
+      throwLastError('Failed to start image embedding');
+    }
+    _applyTimeout(opId, timeout);
+
+    return _pollUntilComplete<EmbeddingResult>(
+      opId,
+      AsyncResultType.imageEmbedding,
+    );
+  }
+
+  /// Embed encoded image bytes without blocking the UI.
+  ///
+  /// This is the async version of [embedImageBytes]. The bytes are copied
+  /// before this returns, so [bytes] may be reused immediately.
+  ///
+  /// Parameters:
+  /// - [bytes]: Encoded image (PNG, JPEG, WebP, GIF or BMP)
+  /// - [priority]: Scheduling priority (default: interactive)
+  /// - [timeout]: Deadline after which unfinished work is cancelled
+  ///
+  /// Throws:
+  /// - [UnsupportedFileFormatError] if the bytes are not a recognised image
+  /// - [InvalidConfigError] if the embedder is not a vision model
+  /// - [EmbeddingFailedError] if decoding or inference fails
+  /// - [EmbeddingCancelledError] if operation was cancelled
+  /// - [OperationTimeoutError] if [timeout] passed before it finished
+  /// - [StateError] if the embedder has been disposed
+  Future<EmbeddingResult> embedImageBytesAsync(
+    Uint8List bytes, {
+    OperationPriority? priority,
+    Duration? timeout,
+  }) async {
+    _checkDisposed();
+
+    final opId = _withNativeBytes(bytes, (dataPtr) {
+      return async_ffi.startEmbedImageBytes(
+        _handle,
+        dataPtr,
+        bytes.length,
+        _priorityValue(priority),
+      );
+    });
+
+    if (opId < 0) {
+      throwLastError('Failed to start image embedding');
+    }
+    _applyTimeout(opId, timeout);
+
+    return _pollUntilComplete<EmbeddingResult>(
+      opId,
+      AsyncResultType.imageEmbedding,
+    );
+  }
+
+  /// Embed multiple image files without blocking the UI.
+  ///
+  /// This is the async version of [embedImageFilesBatch].
+  ///
+  /// Parameters:
+  /// - [imagePaths]: Paths to PNG, JPEG, WebP, GIF or BMP files
+  /// - [priority]: Scheduling priority (default: background)
+  /// - [timeout]: Deadline after which unfinished work is cancelled
+  ///
+  /// Throws:
+  /// - [FileNotFoundError] if an image doesn't exist
+  /// - [UnsupportedFileFormatError] if a file is not a supported image
+  /// - [InvalidConfigError] if the embedder is not a vision model
+  /// - [EmbeddingFailedError] if decoding or inference fails
+  /// - [EmbeddingCancelledError] if operation was cancelled
+  /// - [OperationTimeoutError] if [timeout] passed before it finished
+  /// - [StateError] if the embedder has been disposed
+  Future<List<EmbeddingResult>> embedImageFilesBatchAsync(
+    List<String> imagePaths, {
+    OperationPriority? priority,
+    Duration? timeout,
   }) async {
     _checkDisposed();
 
-    if (texts.isEmpty) {
+    if (imagePaths.isEmpty) {
       return [];
     }
 
-    // Determine effective chunk size
-    final effectiveChunkSize = chunkSize ?? _config?.defaultBatchSize ?? 32;
-
-    // If batch is small enough, process directly without chunking overhead
-    if (texts.length <= effectiveChunkSize) {
-      final results = await _embedTextsBatchAsyncInternal(texts);
-      onProgress?.call(texts.length, texts.length);
-      return results;
-    }
-
-    // Process in chunks for large batches
-    final results = <EmbeddingResult>[];
-
-    for (int i = 0; i < texts.length; i += effectiveChunkSize) {
-      final end = (i + effectiveChunkSize < texts.length)
-          ? i + effectiveChunkSize
-          : texts.length;
-      final chunk = texts.sublist(i, end);
-
-      final chunkResults = await _embedTextsBatchAsyncInternal(chunk);
-      results.addAll(chunkResults);
+    final opId = _withCStringArray(imagePaths, (imagePathsPtr) {
+      return async_ffi.startEmbedImageFilesBatch(
+        _handle,
+        imagePathsPtr,
+        imagePaths.length,
+        _priorityValue(priority),
+      );
+    });
 
-      // Report progress after each chunk
-      onProgress?.call(results.length, texts.length);
+    if (opId < 0) {
+      throwLastError('Failed to start image batch embedding');
     }
+    _applyTimeout(opId, timeout);
 
-    return results;
+    return _pollUntilComplete<List<EmbeddingResult>>(
+      opId,
+      AsyncResultType.imageBatchEmbedding,
+    );
   }
 
-  /// Internal method to embed a batch of texts without chunking.
-  Future<List<EmbeddingResult>> _embedTextsBatchAsyncInternal(
-    List<String> texts,
-  ) async {
-    // Convert Dart strings to C strings
-    final cStrings = texts.map((t) => stringToCString(t)).toList();
-    final cStringsArray = malloc<Pointer<Utf8>>(texts.length);
-
-    try {
-      // Fill the array
-      for (int i = 0; i < texts.length; i++) {
-        cStringsArray[i] = cStrings[i];
-      }
+  /// Embed a text query into the image space without blocking the UI.
+  ///
+  /// This is the async version of [embedImageQuery].
+  ///
+  /// Parameters:
+  /// - [text]: Query text
+  /// - [priority]: Scheduling priority (default: interactive)
+  /// - [timeout]: Deadline after which unfinished work is cancelled
+  ///
+  /// Throws:
+  /// - [InvalidConfigError] if the embedder is not a vision model
+  /// - [EmbeddingFailedError] if inference fails
+  /// - [EmbeddingCancelledError] if operation was cancelled
+  /// - [OperationTimeoutError] if [timeout] passed before it finished
+  /// - [StateError] if the embedder has been disposed
+  Future<EmbeddingResult> embedImageQueryAsync(
+    String text, {
+    OperationPriority? priority,
+    Duration? timeout,
+  }) async {
+    _checkDisposed();
 
-      final opId = async_ffi.startEmbedTextsBatch(
+    final opId = withCString(text, (textPtr) {
+      return async_ffi.startEmbedImageQuery(
         _handle,
-        cStringsArray,
-        texts.length,
+        textPtr,
+        _priorityValue(priority),
       );
+    });
 
-      if (opId < 0) {
-        throwLastError('Failed to start batch embedding');
-      }
-
-      return _pollUntilComplete<List<EmbeddingResult>>(
-        opId,
-        AsyncResultType.batchEmbedding,
-      );
-    } finally {
-      // Free all C strings
-      for (final cStr in cStrings) {
-        freeCString(cStr);
-      }
-      malloc.free(cStringsArray);
+    if (opId < 0) {
+      throwLastError('Failed to start image query embedding');
     }
+    _applyTimeout(opId, timeout);
+
+    return _pollUntilComplete<EmbeddingResult>(
+      opId,
+      AsyncResultType.singleEmbedding,
+    );
   }
 
   /// Embed a file asynchronously without blocking the UI.
@@ -1024,6 +2140,8 @@ class EmbedAnything {
   /// - [chunkSize]: Maximum characters per chunk (default: 1000)
   /// - [overlapRatio]: Overlap between chunks 0.0-1.0 (default: 0.0)
   /// - [batchSize]: Batch size for embedding generation (default: 32)
+  /// - [priority]: Scheduling priority (default: background)
+  /// - [timeout]: Deadline after which unfinished work is cancelled
   ///
   /// Returns a [Future] that completes with a list of [ChunkEmbedding]s.
   ///
@@ -1031,6 +2149,7 @@ class EmbedAnything {
   /// - [FileNotFoundError] if the file does not exist
   /// - [UnsupportedFileFormatError] if the file format is not supported
   /// - [EmbeddingCancelledError] if operation was cancelled
+  /// - [OperationTimeoutError] if [timeout] passed before it finished
   /// - [StateError] if the embedder has been disposed
   ///
   /// Example:
@@ -1045,6 +2164,8 @@ class EmbedAnything {
     int chunkSize = 1000,
     double overlapRatio = 0.0,
     int batchSize = 32,
+    OperationPriority? priority,
+    Duration? timeout,
   }) async {
     _checkDisposed();
 
@@ -1060,11 +2181,17 @@ class EmbedAnything {
     final filePathPtr = stringToCString(filePath);
 
     try {
-      final opId = async_ffi.startEmbedFile(_handle, filePathPtr, config);
+      final opId = async_ffi.startEmbedFile(
+        _handle,
+        filePathPtr,
+        config,
+        _priorityValue(priority),
+      );
 
       if (opId < 0) {
         throwLastError('Failed to start file embedding');
       }
+      _applyTimeout(opId, timeout);
 
       return _pollUntilComplete<List<ChunkEmbedding>>(
         opId,
@@ -1087,12 +2214,15 @@ class EmbedAnything {
   /// - [chunkSize]: Maximum characters per chunk (default: 1000)
   /// - [overlapRatio]: Overlap between chunks 0.0-1.0 (default: 0.0)
   /// - [batchSize]: Batch size for embedding generation (default: 32)
+  /// - [priority]: Scheduling priority (default: background)
+  /// - [timeout]: Deadline after which unfinished work is cancelled
   ///
   /// Returns a [Future] that completes with a list of [ChunkEmbedding]s.
   ///
   /// Throws:
   /// - [FileNotFoundError] if the directory does not exist
   /// - [EmbeddingCancelledError] if operation was cancelled
+  /// - [OperationTimeoutError] if [timeout] passed before it finished
   /// - [StateError] if the embedder has been disposed
   ///
   /// Example:
@@ -1109,6 +2239,8 @@ class EmbedAnything {
     int chunkSize = 1000,
     double overlapRatio = 0.0,
     int batchSize = 32,
+    OperationPriority? priority,
+    Duration? timeout,
   }) async {
     _checkDisposed();
 
@@ -1132,15 +2264,18 @@ class EmbedAnything {
     try {
       final opId = async_ffi.startEmbedDirectory(
         _handle,
+        nullptr, // Text only
         directoryPathPtr,
         extensionsPtr ?? nullptr,
         extensions?.length ?? 0,
         config,
+        _priorityValue(priority),
       );
 
       if (opId < 0) {
         throwLastError('Failed to start directory embedding');
       }
+      _applyTimeout(opId, timeout);
 
       return _pollUntilComplete<List<ChunkEmbedding>>(
         opId,
@@ -1162,6 +2297,9 @@ class EmbedAnything {
   ///
   /// Parameters:
   /// - [text]: The text to embed
+  /// - [priority]: Scheduling priority (default: interactive); change it
+  ///   later with [AsyncEmbeddingOperation.setPriority]
+  /// - [timeout]: Deadline after which unfinished work is cancelled
   ///
   /// Returns an [AsyncEmbeddingOperation] with a [future] that completes
   /// with the result, and a [cancel] method to abort the operation.
@@ -1179,11 +2317,19 @@ class EmbedAnything {
   ///   print('Operation was cancelled');
   /// }
   /// ```
-  AsyncEmbeddingOperation<EmbeddingResult> startEmbedTextAsync(String text) {
+  AsyncEmbeddingOperation<EmbeddingResult> startEmbedTextAsync(
+    String text, {
+    OperationPriority? priority,
+    Duration? timeout,
+  }) {
     _checkDisposed();
 
     final opId = withCString(text, (textPtr) {
-      return async_ffi.startEmbedText(_handle, textPtr);
+      return async_ffi.startEmbedText(
+        _handle,
+        textPtr,
+        _priorityValue(priority),
+      );
     });
 
     if (opId < 0) {
@@ -1197,6 +2343,8 @@ class EmbedAnything {
       );
     }
 
+    _applyTimeout(opId, timeout);
+
     final future = _pollUntilComplete<EmbeddingResult>(
       opId,
       AsyncResultType.singleEmbedding,
@@ -1205,12 +2353,33 @@ class EmbedAnything {
     return AsyncEmbeddingOperation._(opId, future);
   }
 
+  /// Priority to pass to a start function; Rust records it before the
+  /// operation is queued, so it applies from the first scheduling decision.
+  static int _priorityValue(OperationPriority? priority) =>
+      priority?.value ?? async_ffi.defaultPriority;
+
+  /// Give an operation that was just started a deadline.
+  ///
+  /// Past it the operation fails with [OperationTimeoutError] and its work
+  /// is cancelled. A timeout that has already run out expires right away.
+  static void _applyTimeout(int opId, Duration? timeout) {
+    if (timeout != null) {
+      final timeoutMs =
+          timeout.inMilliseconds < 1 ? 1 : timeout.inMilliseconds;
+      async_ffi.setAsyncDeadline(opId, timeoutMs);
+    }
+  }
+
   /// Poll for async operation completion.
   ///
+  /// Waits for the native completion callback, then collects the result.
+  /// Falls back to polling if the callback cannot be registered.
   /// Returns when the operation completes (success/error/cancelled).
   static Future<T> _pollUntilComplete<T>(int opId, int expectedType) async {
     const pollInterval = Duration(milliseconds: 10);
 
+    final notifiedStatus = await _waitForCompletion(opId);
+
     while (true) {
       final result = async_ffi.pollAsyncResult(opId);
 
@@ -1232,7 +2401,12 @@ class EmbedAnything {
           if (result.errorMessage != nullptr) {
             async_ffi.freeAsyncErrorMessage(result.errorMessage);
           }
-          throwLastError(errorMsg);
+          // A discarded operation reports cancelled through the callback
+          // and is unknown to pollAsyncResult afterwards
+          if (notifiedStatus == AsyncPollStatus.cancelled) {
+            throw EmbeddingCancelledError();
+          }
+          throwErrorMessage(errorMsg);
 
         case AsyncPollStatus.cancelled:
           throw EmbeddingCancelledError();
@@ -1244,6 +2418,32 @@ class EmbedAnything {
     }
   }
 
+  /// Wait until Rust reports that the operation has finished.
+  ///
+  /// Returns the status passed to the completion callback, or null if the
+  /// callback could not be registered.
+  static Future<int?> _waitForCompletion(int opId) async {
+    final completer = Completer<int>();
+    final callable =
+        NativeCallable<async_ffi.AsyncCompletionCallbackType>.listener(
+      (int _, int status, Pointer<Void> __) => completer.complete(status),
+    );
+
+    try {
+      final registered = async_ffi.setAsyncCompletionCallback(
+        opId,
+        callable.nativeFunction,
+        nullptr,
+      );
+      if (registered == 0) {
+        return await completer.future;
+      }
+      return null;
+    } finally {
+      callable.close();
+    }
+  }
+
   /// Extract typed result from CAsyncPollResult.
   static T _extractResult<T>(CAsyncPollResult result, int expectedType) {
     if (result.resultType != expectedType) {
@@ -1255,6 +2455,7 @@ class EmbedAnything {
 
     switch (expectedType) {
       case AsyncResultType.singleEmbedding:
+      case AsyncResultType.imageEmbedding:
         final ptr = result.data.cast<CTextEmbedding>();
         final embedding = ptr.ref;
         final values = _copyFloatArray(embedding.values, embedding.len);
@@ -1263,6 +2464,7 @@ class EmbedAnything {
         return EmbeddingResult(values) as T;
 
       case AsyncResultType.batchEmbedding:
+      case AsyncResultType.imageBatchEmbedding:
         final ptr = result.data.cast<CTextEmbeddingBatch>();
         final batch = ptr.ref;
         final results = <EmbeddingResult>[];
@@ -1287,6 +2489,57 @@ class EmbedAnything {
         ffi.freeEmbedDataBatch(ptr);
         return results as T;
 
+      case AsyncResultType.sparseEmbedding:
+        final ptr = result.data.cast<CSparseEmbedding>();
+        try {
+          return _copySparseEmbedding(ptr.ref) as T;
+        } finally {
+          ffi.freeSparseEmbedding(ptr);
+        }
+
+      case AsyncResultType.sparseBatchEmbedding:
+        final ptr = result.data.cast<CSparseEmbeddingBatch>();
+        try {
+          final batch = ptr.ref;
+          return [
+            for (int i = 0; i < batch.count; i++)
+              _copySparseEmbedding(batch.embeddings[i]),
+          ] as T;
+        } finally {
+          ffi.freeSparseEmbeddingBatch(ptr);
+        }
+
+      case AsyncResultType.multiVectorEmbedding:
+        final ptr = result.data.cast<CMultiVectorEmbedding>();
+        try {
+          return _copyMultiVectorEmbedding(ptr.ref) as T;
+        } finally {
+          ffi.freeMultiVectorEmbedding(ptr);
+        }
+
+      case AsyncResultType.multiVectorBatchEmbedding:
+        final ptr = result.data.cast<CMultiVectorEmbeddingBatch>();
+        try {
+          final batch = ptr.ref;
+          return [
+            for (int i = 0; i < batch.count; i++)
+              _copyMultiVectorEmbedding(batch.embeddings[i]),
+          ] as T;
+        } finally {
+          ffi.freeMultiVectorEmbeddingBatch(ptr);
+        }
+
+      case AsyncResultType.rerankerLoad:
+        return result.data.cast<CReranker>() as T;
+
+      case AsyncResultType.rerank:
+        final ptr = result.data.cast<CRerankResults>();
+        try {
+          return _copyRerankResults(ptr.ref) as T;
+        } finally {
+          ffi.freeRerankResults(ptr);
+        }
+
       case AsyncResultType.modelLoad:
         return result.data.cast<CEmbedder>() as T;
 
@@ -1300,12 +2553,8 @@ class EmbedAnything {
 
   /// Static version of _cEmbedDataToChunkEmbedding for use in static methods.
   static ChunkEmbedding _cEmbedDataToChunkEmbeddingStatic(CEmbedData embedData) {
-    // Copy embedding vector
-    final embeddingValues = _copyFloatArray(
-      embedData.embeddingValues,
-      embedData.embeddingLen,
-    );
-    final embedding = EmbeddingResult(embeddingValues);
+    // Copy embedding vector (split into token rows for multi-vector data)
+    final (embedding, multiVector) = _copyChunkEmbedding(embedData);
 
     // Parse combined text and metadata JSON
     String? text;
@@ -1337,8 +2586,264 @@ class EmbedAnything {
       embedding: embedding,
       text: text,
       metadata: metadata,
+      multiVector: multiVector,
+    );
+  }
+}
+
+/// Cross-encoder reranker for scoring documents against a query
+///
+/// Rerankers read the query and each document together, which is slower
+/// than comparing embeddings but more accurate. Use them on the top
+/// results of an embedding search. Models must provide an ONNX export and
+/// the native library must be built with the `ort` feature.
+///
+/// Like [EmbedAnything], rerankers hold native memory and must be disposed.
+///
+/// Example:
+/// ```dart
+/// final reranker = await Reranker.fromPretrainedHfAsync(
+///   modelId: 'cross-encoder/ms-marco-MiniLM-L-6-v2',
+/// );
+/// try {
+///   final results = reranker.rerank(
+///     'What is machine learning?',
+///     ['Pasta recipes', 'Machine learning is a subset of AI'],
+///   );
+///   print('Best match: ${results.first.index}');
+/// } finally {
+///   reranker.dispose();
+/// }
+/// ```
+class Reranker {
+  final Pointer<CReranker> _handle;
+  bool _disposed = false;
+
+  Reranker._(this._handle);
+
+  /// Load a reranker from HuggingFace Hub
+  ///
+  /// Parameters:
+  /// - [modelId]: HuggingFace model identifier
+  ///   (e.g., 'cross-encoder/ms-marco-MiniLM-L-6-v2')
+  /// - [revision]: Git revision/branch (defaults to 'main')
+  ///
+  /// Throws:
+  /// - [ModelNotFoundError] if the model or its ONNX/tokenizer files
+  ///   don't exist
+  /// - [InvalidConfigError] if the library was built without `ort`
+  /// - [FFIError] if the model cannot be loaded
+  factory Reranker.fromPretrainedHf({
+    required String modelId,
+    String revision = 'main',
+  }) {
+    EmbedAnything._initializeRuntime();
+
+    final handle = withCString(modelId, (modelIdPtr) {
+      return withCString(revision, (revisionPtr) {
+        return ffi.rerankerFromPretrainedHf(modelIdPtr, revisionPtr, nullptr);
+      });
+    });
+
+    if (handle == nullptr) {
+      throwLastError('Failed to load reranker: $modelId');
+    }
+
+    return Reranker._(handle);
+  }
+
+  /// Load a reranker from a directory on disk
+  ///
+  /// The directory must contain `tokenizer.json` and `onnx/model.onnx` or
+  /// `model.onnx`.
+  ///
+  /// Parameters:
+  /// - [modelPath]: Path to the reranker directory
+  ///
+  /// Throws:
+  /// - [ModelNotFoundError] if the directory or one of its files is missing
+  /// - [InvalidConfigError] if the library was built without `ort`
+  /// - [FFIError] if the model cannot be loaded
+  factory Reranker.fromLocalPath(String modelPath) {
+    EmbedAnything._initializeRuntime();
+
+    final handle = withCString(modelPath, ffi.rerankerFromLocalPath);
+
+    if (handle == nullptr) {
+      throwLastError('Failed to load reranker: $modelPath');
+    }
+
+    return Reranker._(handle);
+  }
+
+  /// Load a reranker from HuggingFace Hub without blocking the UI.
+  ///
+  /// This is the async version of [Reranker.fromPretrainedHf].
+  ///
+  /// Throws:
+  /// - [ModelNotFoundError] if the model or its ONNX/tokenizer files
+  ///   don't exist
+  /// - [InvalidConfigError] if the library was built without `ort`
+  /// - [EmbeddingCancelledError] if operation was cancelled
+  static Future<Reranker> fromPretrainedHfAsync({
+    required String modelId,
+    String revision = 'main',
+  }) async {
+    EmbedAnything._initializeRuntime();
+
+    final opId = withCString(modelId, (modelIdPtr) {
+      return withCString(revision, (revisionPtr) {
+        return async_ffi.startLoadReranker(modelIdPtr, revisionPtr, nullptr);
+      });
+    });
+
+    if (opId < 0) {
+      throwLastError('Failed to start reranker loading');
+    }
+
+    final handle = await EmbedAnything._pollUntilComplete<Pointer<CReranker>>(
+      opId,
+      AsyncResultType.rerankerLoad,
+    );
+
+    return Reranker._(handle);
+  }
+
+  /// Load a reranker from a directory on disk without blocking the UI.
+  ///
+  /// This is the async version of [Reranker.fromLocalPath].
+  ///
+  /// Throws:
+  /// - [ModelNotFoundError] if the directory or one of its files is missing
+  /// - [InvalidConfigError] if the library was built without `ort`
+  /// - [EmbeddingCancelledError] if operation was cancelled
+  static Future<Reranker> fromLocalPathAsync(String modelPath) async {
+    EmbedAnything._initializeRuntime();
+
+    final opId = withCString(
+      modelPath,
+      async_ffi.startLoadRerankerFromLocalPath,
+    );
+
+    if (opId < 0) {
+      throwLastError('Failed to start reranker loading');
+    }
+
+    final handle = await EmbedAnything._pollUntilComplete<Pointer<CReranker>>(
+      opId,
+      AsyncResultType.rerankerLoad,
+    );
+
+    return Reranker._(handle);
+  }
+
+  /// Score documents against a query
+  ///
+  /// Parameters:
+  /// - [query]: The query text
+  /// - [documents]: Documents to score
+  /// - [topK]: Number of results to return (default: all)
+  ///
+  /// Returns [RerankResult]s sorted by score, highest first.
+  ///
+  /// Throws:
+  /// - [EmbeddingFailedError] if scoring fails
+  /// - [StateError] if the reranker has been disposed
+  List<RerankResult> rerank(
+    String query,
+    List<String> documents, {
+    int? topK,
+  }) {
+    _checkDisposed();
+
+    if (documents.isEmpty) {
+      return [];
+    }
+
+    final resultsPtr = withCString(query, (queryPtr) {
+      return EmbedAnything._withCStringArray(documents, (documentsPtr) {
+        return ffi.rerank(
+          _handle,
+          queryPtr,
+          documentsPtr,
+          documents.length,
+          topK ?? 0,
+        );
+      });
+    });
+
+    if (resultsPtr == nullptr) {
+      throwLastError('Failed to rerank documents');
+    }
+
+    try {
+      return EmbedAnything._copyRerankResults(resultsPtr.ref);
+    } finally {
+      ffi.freeRerankResults(resultsPtr);
+    }
+  }
+
+  /// Score documents against a query without blocking the UI.
+  ///
+  /// This is the async version of [rerank]. With a [timeout], scoring that
+  /// has not finished in time is cancelled.
+  ///
+  /// Throws:
+  /// - [EmbeddingFailedError] if scoring fails
+  /// - [EmbeddingCancelledError] if operation was cancelled
+  /// - [OperationTimeoutError] if [timeout] passed before scoring finished
+  /// - [StateError] if the reranker has been disposed
+  Future<List<RerankResult>> rerankAsync(
+    String query,
+    List<String> documents, {
+    int? topK,
+    Duration? timeout,
+  }) async {
+    _checkDisposed();
+
+    if (documents.isEmpty) {
+      return [];
+    }
+
+    final opId = withCString(query, (queryPtr) {
+      return EmbedAnything._withCStringArray(documents, (documentsPtr) {
+        return async_ffi.startRerank(
+          _handle,
+          queryPtr,
+          documentsPtr,
+          documents.length,
+          topK ?? 0,
+          async_ffi.defaultPriority,
+        );
+      });
+    });
+
+    if (opId < 0) {
+      throwLastError('Failed to start reranking');
+    }
+    EmbedAnything._applyTimeout(opId, timeout);
+
+    return EmbedAnything._pollUntilComplete<List<RerankResult>>(
+      opId,
+      AsyncResultType.rerank,
     );
   }
+
+  /// Release native resources
+  ///
+  /// Safe to call multiple times.
+  void dispose() {
+    if (!_disposed) {
+      ffi.rerankerFree(_handle);
+      _disposed = true;
+    }
+  }
+
+  void _checkDisposed() {
+    if (_disposed) {
+      throw StateError('Reranker instance has been disposed');
+    }
+  }
 }
 
 /// Represents an in-progress async embedding operation that can be cancelled.
@@ -1392,6 +2897,15 @@ class AsyncEmbeddingOperation<T> {
   /// Whether the operation has been cancelled.
   bool get isCancelled => _cancelled;
 
+  /// Change the priority of the operation while it waits for a slot.
+  ///
+  /// Returns `true` if the new priority was applied, `false` if the
+  /// operation is already running or has finished. A micro-batched request
+  /// whose batch is already queued can only raise the batch's priority.
+  bool setPriority(OperationPriority priority) {
+    return async_ffi.setAsyncPriority(_operationId, priority.value) == 0;
+  }
+
   /// The operation ID (for debugging).
   int get operationId => _operationId;
 }
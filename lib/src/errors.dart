@@ -30,6 +30,12 @@
 ///       print('File read error: ${e.reason}');
 ///     case EmbeddingCancelledError():
 ///       print('Embedding cancelled');
+///     case UnauthorizedError():
+///       print('Access denied: ${e.modelId}');
+///     case ModelMismatchError():
+///       print('Incompatible index: ${e.reason}');
+///     case OperationTimeoutError():
+///       print('Timed out: ${e.reason}');
 ///   }
 /// }
 /// ```
@@ -48,7 +54,9 @@ sealed class EmbedAnythingError implements Exception {
 /// - The model ID is incorrect or misspelled
 /// - The model doesn't exist on HuggingFace Hub
 /// - Network connectivity issues prevent model download
-/// - The model requires authentication but no token is provided
+///
+/// Private or gated models accessed without a valid token throw
+/// [UnauthorizedError] instead.
 ///
 /// Example:
 /// ```dart
@@ -76,6 +84,41 @@ class ModelNotFoundError extends EmbedAnythingError {
   String toString() => 'ModelNotFoundError: $message';
 }
 
+/// Error thrown when the HuggingFace Hub denies access to a model
+///
+/// This occurs when the hub answers 401 or 403:
+/// - The model is private and no token (or a wrong one) was provided
+/// - The model is gated and the token's account has not accepted its terms
+///
+/// Example:
+/// ```dart
+/// try {
+///   final embedder = EmbedAnything.fromPretrainedHf(
+///     model: EmbeddingModel.bert,
+///     modelId: 'my-org/private-minilm',
+///   );
+/// } on UnauthorizedError catch (e) {
+///   print('Access to ${e.modelId} denied');
+///   // Call EmbedAnything.setHfToken() with a valid token and retry
+/// }
+/// ```
+class UnauthorizedError extends EmbedAnythingError {
+  /// The model ID that access was denied to
+  final String modelId;
+
+  /// The reason reported by the hub
+  final String reason;
+
+  /// Creates a new UnauthorizedError
+  UnauthorizedError({required this.modelId, required this.reason});
+
+  @override
+  String get message => 'Access to $modelId denied: $reason';
+
+  @override
+  String toString() => 'UnauthorizedError: $message';
+}
+
 /// Error thrown when model or embedder configuration is invalid
 ///
 /// This occurs when:
@@ -342,3 +385,57 @@ class EmbeddingCancelledError extends EmbedAnythingError {
   @override
   String toString() => 'EmbeddingCancelledError: $message';
 }
+
+/// Error thrown when an async operation does not finish before its timeout
+///
+/// The operation's work is cancelled when the deadline passes, so no
+/// partial result is returned.
+///
+/// Example:
+/// ```dart
+/// try {
+///   final chunks = await embedder.embedFileAsync(
+///     'large.pdf',
+///     timeout: const Duration(seconds: 30),
+///   );
+/// } on OperationTimeoutError catch (e) {
+///   print('Gave up: ${e.reason}');
+/// }
+/// ```
+class OperationTimeoutError extends EmbedAnythingError {
+  /// The reason reported by the native side
+  final String reason;
+
+  /// Creates a new OperationTimeoutError
+  OperationTimeoutError(this.reason);
+
+  @override
+  String get message => 'Operation timed out: $reason';
+
+  @override
+  String toString() => 'OperationTimeoutError: $message';
+}
+
+/// Error thrown when a saved vector index does not match the embedder
+///
+/// This occurs when loading an index that was built with:
+/// - A different model, revision or dtype
+/// - Different local model files at the same path
+/// - Different output-affecting options (normalize, pooling, max length)
+///
+/// Vectors from another model live in a different space, so searching them
+/// with this embedder's queries would return meaningless results. Rebuild
+/// the index with the current embedder instead.
+class ModelMismatchError extends EmbedAnythingError {
+  /// The saved and current model identities, as reported by the native side
+  final String reason;
+
+  /// Creates a new ModelMismatchError
+  ModelMismatchError(this.reason);
+
+  @override
+  String get message => 'Index does not match the embedder: $reason';
+
+  @override
+  String toString() => 'ModelMismatchError: $message';
+}
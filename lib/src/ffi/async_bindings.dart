@@ -9,21 +9,81 @@ import 'package:ffi/ffi.dart';
 import 'async_types.dart';
 import 'native_types.dart';
 
+/// Priority passed to a start function to keep its default: interactive for
+/// single queries and reranking, background for everything else. Otherwise
+/// pass an `OperationPriority` value; it is recorded before the operation
+/// is queued.
+const int defaultPriority = -1;
+
 // ============================================================================
 // Async Model Loading
 // ============================================================================
 
 /// Start loading a model asynchronously.
 ///
+/// modelType: 0 = BERT, 1 = Jina, 2 = ModernBERT, 3 = Qwen3,
+/// 4 = XLM-RoBERTa, 255 = auto-detect from config.json
+///
 /// Returns operation ID (positive) on success, -1 on immediate failure.
-@Native<Int64 Function(Pointer<Utf8>, Pointer<Utf8>, Int32)>(
+@Native<
+    Int64 Function(
+      Uint8,
+      Pointer<Utf8>,
+      Pointer<Utf8>,
+      Pointer<Utf8>,
+      Int32,
+      Pointer<CEmbedderOptions>,
+    )>(
   symbol: 'start_load_model',
   assetId: 'package:embedanythingindart/embedanything_dart',
 )
 external int startLoadModel(
+  int modelType,
+  Pointer<Utf8> modelId,
+  Pointer<Utf8> revision,
+  Pointer<Utf8> token,
+  int dtype,
+  Pointer<CEmbedderOptions> options,
+);
+
+/// Start loading a model from a local directory asynchronously.
+///
+/// The directory must contain config.json, tokenizer.json and
+/// model.safetensors; dtype: 0 = F32, 1 = F16, -1 = default.
+///
+/// Returns operation ID (positive) on success, -1 on immediate failure.
+@Native<Int64 Function(Pointer<Utf8>, Int32, Pointer<CEmbedderOptions>)>(
+  symbol: 'start_load_model_from_local_path',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int startLoadModelFromLocalPath(
+  Pointer<Utf8> modelPath,
+  int dtype,
+  Pointer<CEmbedderOptions> options,
+);
+
+/// Start loading a CLIP or SigLIP image embedder asynchronously.
+///
+/// dtype: 0 = F32, 1 = F16, -1 = default.
+///
+/// Returns operation ID (positive) on success, -1 on immediate failure.
+@Native<
+    Int64 Function(
+      Pointer<Utf8>,
+      Pointer<Utf8>,
+      Pointer<Utf8>,
+      Int32,
+      Pointer<CEmbedderOptions>,
+    )>(
+  symbol: 'start_load_vision_model',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int startLoadVisionModel(
   Pointer<Utf8> modelId,
   Pointer<Utf8> revision,
+  Pointer<Utf8> token,
   int dtype,
+  Pointer<CEmbedderOptions> options,
 );
 
 // ============================================================================
@@ -33,16 +93,26 @@ external int startLoadModel(
 /// Start embedding a single text asynchronously.
 ///
 /// Returns operation ID (positive) on success, -1 on immediate failure.
-@Native<Int64 Function(Pointer<CEmbedder>, Pointer<Utf8>)>(
+@Native<Int64 Function(Pointer<CEmbedder>, Pointer<Utf8>, Int32)>(
   symbol: 'start_embed_text',
   assetId: 'package:embedanythingindart/embedanything_dart',
 )
-external int startEmbedText(Pointer<CEmbedder> embedder, Pointer<Utf8> text);
+external int startEmbedText(
+  Pointer<CEmbedder> embedder,
+  Pointer<Utf8> text,
+  int priority,
+);
 
 /// Start embedding multiple texts asynchronously.
 ///
 /// Returns operation ID (positive) on success, -1 on immediate failure.
-@Native<Int64 Function(Pointer<CEmbedder>, Pointer<Pointer<Utf8>>, Size)>(
+@Native<
+    Int64 Function(
+      Pointer<CEmbedder>,
+      Pointer<Pointer<Utf8>>,
+      Size,
+      Int32,
+    )>(
   symbol: 'start_embed_texts_batch',
   assetId: 'package:embedanythingindart/embedanything_dart',
 )
@@ -50,6 +120,197 @@ external int startEmbedTextsBatch(
   Pointer<CEmbedder> embedder,
   Pointer<Pointer<Utf8>> texts,
   int count,
+  int priority,
+);
+
+// ============================================================================
+// Async Sparse Embedding
+// ============================================================================
+
+/// Start embedding a single text with a sparse (SPLADE) model.
+///
+/// Returns operation ID (positive) on success, -1 on immediate failure.
+@Native<Int64 Function(Pointer<CEmbedder>, Pointer<Utf8>, Int32)>(
+  symbol: 'start_embed_text_sparse',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int startEmbedTextSparse(
+  Pointer<CEmbedder> embedder,
+  Pointer<Utf8> text,
+  int priority,
+);
+
+/// Start embedding multiple texts with a sparse (SPLADE) model.
+///
+/// Returns operation ID (positive) on success, -1 on immediate failure.
+@Native<
+    Int64 Function(
+      Pointer<CEmbedder>,
+      Pointer<Pointer<Utf8>>,
+      Size,
+      Int32,
+    )>(
+  symbol: 'start_embed_texts_batch_sparse',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int startEmbedTextsBatchSparse(
+  Pointer<CEmbedder> embedder,
+  Pointer<Pointer<Utf8>> texts,
+  int count,
+  int priority,
+);
+
+// ============================================================================
+// Async Multi-Vector Embedding
+// ============================================================================
+
+/// Start embedding a single text, keeping one vector per token.
+///
+/// Returns operation ID (positive) on success, -1 on immediate failure.
+@Native<Int64 Function(Pointer<CEmbedder>, Pointer<Utf8>, Int32)>(
+  symbol: 'start_embed_text_multi_vector',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int startEmbedTextMultiVector(
+  Pointer<CEmbedder> embedder,
+  Pointer<Utf8> text,
+  int priority,
+);
+
+/// Start embedding multiple texts, keeping one vector per token.
+///
+/// Returns operation ID (positive) on success, -1 on immediate failure.
+@Native<
+    Int64 Function(
+      Pointer<CEmbedder>,
+      Pointer<Pointer<Utf8>>,
+      Size,
+      Int32,
+    )>(
+  symbol: 'start_embed_texts_batch_multi_vector',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int startEmbedTextsBatchMultiVector(
+  Pointer<CEmbedder> embedder,
+  Pointer<Pointer<Utf8>> texts,
+  int count,
+  int priority,
+);
+
+// ============================================================================
+// Async Image Embedding
+// ============================================================================
+
+/// Start embedding an image file with a vision embedder.
+///
+/// Returns operation ID (positive) on success, -1 on immediate failure.
+@Native<Int64 Function(Pointer<CEmbedder>, Pointer<Utf8>, Int32)>(
+  symbol: 'start_embed_image_file',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int startEmbedImageFile(
+  Pointer<CEmbedder> embedder,
+  Pointer<Utf8> imagePath,
+  int priority,
+);
+
+/// Start embedding encoded image bytes with a vision embedder.
+///
+/// The bytes are copied before this returns.
+///
+/// Returns operation ID (positive) on success, -1 on immediate failure.
+@Native<Int64 Function(Pointer<CEmbedder>, Pointer<Uint8>, Size, Int32)>(
+  symbol: 'start_embed_image_bytes',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int startEmbedImageBytes(
+  Pointer<CEmbedder> embedder,
+  Pointer<Uint8> data,
+  int len,
+  int priority,
+);
+
+/// Start embedding a batch of image files with a vision embedder.
+///
+/// Returns operation ID (positive) on success, -1 on immediate failure.
+@Native<
+    Int64 Function(
+      Pointer<CEmbedder>,
+      Pointer<Pointer<Utf8>>,
+      Size,
+      Int32,
+    )>(
+  symbol: 'start_embed_image_files_batch',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int startEmbedImageFilesBatch(
+  Pointer<CEmbedder> embedder,
+  Pointer<Pointer<Utf8>> imagePaths,
+  int count,
+  int priority,
+);
+
+/// Start embedding a text query into the image space of a vision embedder.
+///
+/// Returns operation ID (positive) on success, -1 on immediate failure.
+@Native<Int64 Function(Pointer<CEmbedder>, Pointer<Utf8>, Int32)>(
+  symbol: 'start_embed_image_query',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int startEmbedImageQuery(
+  Pointer<CEmbedder> embedder,
+  Pointer<Utf8> text,
+  int priority,
+);
+
+// ============================================================================
+// Async Reranking
+// ============================================================================
+
+/// Start loading a cross-encoder reranker from HuggingFace Hub.
+///
+/// Returns operation ID (positive) on success, -1 on immediate failure.
+@Native<Int64 Function(Pointer<Utf8>, Pointer<Utf8>, Pointer<Utf8>)>(
+  symbol: 'start_load_reranker',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int startLoadReranker(
+  Pointer<Utf8> modelId,
+  Pointer<Utf8> revision,
+  Pointer<Utf8> token,
+);
+
+/// Start loading a cross-encoder reranker from a local directory.
+///
+/// Returns operation ID (positive) on success, -1 on immediate failure.
+@Native<Int64 Function(Pointer<Utf8>)>(
+  symbol: 'start_load_reranker_from_local_path',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int startLoadRerankerFromLocalPath(Pointer<Utf8> modelPath);
+
+/// Start reranking documents against a query.
+///
+/// Returns operation ID (positive) on success, -1 on immediate failure.
+@Native<
+    Int64 Function(
+      Pointer<CReranker>,
+      Pointer<Utf8>,
+      Pointer<Pointer<Utf8>>,
+      Size,
+      Size,
+      Int32,
+    )>(
+  symbol: 'start_rerank',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int startRerank(
+  Pointer<CReranker> reranker,
+  Pointer<Utf8> query,
+  Pointer<Pointer<Utf8>> documents,
+  int count,
+  int topK,
+  int priority,
 );
 
 // ============================================================================
@@ -59,7 +320,13 @@ external int startEmbedTextsBatch(
 /// Start embedding a file asynchronously.
 ///
 /// Returns operation ID (positive) on success, -1 on immediate failure.
-@Native<Int64 Function(Pointer<CEmbedder>, Pointer<Utf8>, Pointer<CTextEmbedConfig>)>(
+@Native<
+    Int64 Function(
+      Pointer<CEmbedder>,
+      Pointer<Utf8>,
+      Pointer<CTextEmbedConfig>,
+      Int32,
+    )>(
   symbol: 'start_embed_file',
   assetId: 'package:embedanythingindart/embedanything_dart',
 )
@@ -67,21 +334,36 @@ external int startEmbedFile(
   Pointer<CEmbedder> embedder,
   Pointer<Utf8> filePath,
   Pointer<CTextEmbedConfig> config,
+  int priority,
 );
 
 /// Start embedding a directory asynchronously.
 ///
+/// Pass a vision embedder as [imageEmbedder] to route image files to it,
+/// or nullptr for text only.
+///
 /// Returns operation ID (positive) on success, -1 on immediate failure.
-@Native<Int64 Function(Pointer<CEmbedder>, Pointer<Utf8>, Pointer<Pointer<Utf8>>, Size, Pointer<CTextEmbedConfig>)>(
+@Native<
+    Int64 Function(
+      Pointer<CEmbedder>,
+      Pointer<CEmbedder>,
+      Pointer<Utf8>,
+      Pointer<Pointer<Utf8>>,
+      Size,
+      Pointer<CTextEmbedConfig>,
+      Int32,
+    )>(
   symbol: 'start_embed_directory',
   assetId: 'package:embedanythingindart/embedanything_dart',
 )
 external int startEmbedDirectory(
   Pointer<CEmbedder> embedder,
+  Pointer<CEmbedder> imageEmbedder,
   Pointer<Utf8> directoryPath,
   Pointer<Pointer<Utf8>> extensions,
   int extensionsCount,
   Pointer<CTextEmbedConfig> config,
+  int priority,
 );
 
 // ============================================================================
@@ -97,6 +379,25 @@ external int startEmbedDirectory(
 )
 external CAsyncPollResult pollAsyncResult(int operationId);
 
+/// Poll the progress and queue position of an async operation.
+///
+/// Does not consume the result. Free `currentFile` with freeString.
+@Native<CAsyncProgress Function(Int64)>(
+  symbol: 'poll_async_progress',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external CAsyncProgress pollAsyncProgress(int operationId);
+
+/// Drain the entries produced so far by startEmbedDirectory.
+///
+/// Returns a CEmbedDataBatch (free with freeEmbedDataBatch), or nullptr on
+/// error. Drained entries are not repeated in the final poll result.
+@Native<Pointer<CEmbedDataBatch> Function(Int64)>(
+  symbol: 'drain_async_partial',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external Pointer<CEmbedDataBatch> drainAsyncPartial(int operationId);
+
 /// Cancel an async operation.
 ///
 /// Returns 0 on success, -1 if operation ID not found.
@@ -106,6 +407,118 @@ external CAsyncPollResult pollAsyncResult(int operationId);
 )
 external int cancelAsyncOperation(int operationId);
 
+/// Callback typedef for async completion.
+///
+/// Called once from a Rust worker thread with (operationId, status, context),
+/// where status is the value pollAsyncResult will report. Use a
+/// `NativeCallable.listener`.
+typedef AsyncCompletionCallbackType = Void Function(Int64, Int32, Pointer<Void>);
+
+/// Register a callback to be told when an async operation finishes.
+///
+/// The result is still collected with pollAsyncResult. Called immediately if
+/// the operation has already finished. Returns 0 on success, -1 on error.
+@Native<
+    Int32 Function(
+      Int64,
+      Pointer<NativeFunction<AsyncCompletionCallbackType>>,
+      Pointer<Void>,
+    )>(
+  symbol: 'set_async_completion_callback',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int setAsyncCompletionCallback(
+  int operationId,
+  Pointer<NativeFunction<AsyncCompletionCallbackType>> callback,
+  Pointer<Void> context,
+);
+
+/// Give an async operation a deadline in milliseconds.
+///
+/// Past the deadline, polling reports an error prefixed with "TIMEOUT:" and
+/// the work is cancelled. Returns 0 on success, -1 on error.
+@Native<Int32 Function(Int64, Uint64)>(
+  symbol: 'set_async_deadline',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int setAsyncDeadline(int operationId, int timeoutMs);
+
+// ============================================================================
+// Scheduling
+// ============================================================================
+
+/// Set how many operations an embedder runs at once (default 4).
+///
+/// Returns 0 on success, -1 on error.
+@Native<Int32 Function(Pointer<CEmbedder>, Size)>(
+  symbol: 'set_embedder_max_in_flight',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int setEmbedderMaxInFlight(
+  Pointer<CEmbedder> embedder,
+  int maxInFlight,
+);
+
+/// Coalesce concurrent startEmbedText calls on an embedder.
+///
+/// Requests within maxWaitMs of the first pending one are embedded in one
+/// call of at most maxBatch texts (0 or 1 turns it off).
+/// Returns 0 on success, -1 on error.
+@Native<Int32 Function(Pointer<CEmbedder>, Size, Uint64)>(
+  symbol: 'set_embedder_micro_batching',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int setEmbedderMicroBatching(
+  Pointer<CEmbedder> embedder,
+  int maxBatch,
+  int maxWaitMs,
+);
+
+/// Change the priority (see AsyncPriority) of a queued operation.
+///
+/// Returns 0 on success, -1 if the operation ID is unknown or the
+/// operation is no longer waiting for a slot.
+@Native<Int32 Function(Int64, Int32)>(
+  symbol: 'set_async_priority',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int setAsyncPriority(int operationId, int priority);
+
+// ============================================================================
+// Registry Maintenance
+// ============================================================================
+
+/// Discard an async operation and free any result it holds.
+///
+/// Cancels the work if still running and notifies a registered completion
+/// callback with -2 (cancelled). Returns 0 on success, -1 if the operation
+/// ID is unknown.
+@Native<Int32 Function(Int64)>(
+  symbol: 'discard_async_operation',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external int discardAsyncOperation(int operationId);
+
+/// Set how long finished results are kept before they are reaped
+/// (0 keeps them until polled or discarded).
+///
+/// Expired results are reaped when an operation is started or listed and
+/// by a background sweep every 30 seconds.
+@Native<Void Function(Uint64)>(
+  symbol: 'set_async_result_ttl',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external void setAsyncResultTtl(int ttlMs);
+
+/// List the operations in the async registry as a JSON array.
+///
+/// Returns a string to free with freeString, or nullptr on failure.
+@Native<Pointer<Utf8> Function()>(
+  symbol: 'list_async_operations',
+  assetId: 'package:embedanythingindart/embedanything_dart',
+)
+external Pointer<Utf8> listAsyncOperations();
+
 // ============================================================================
 // Memory Cleanup
 // ============================================================================
@@ -14,7 +14,7 @@ final class CAsyncPollResult extends Struct {
   @Int32()
   external int status;
 
-  /// Result type: 0=single, 1=batch, 2=file, 3=model
+  /// Result type: 0=single, 1=batch, 2=file, 3=model, 4=sparse, 5=sparse batch
   @Int32()
   external int resultType;
 
@@ -25,12 +25,62 @@ final class CAsyncPollResult extends Struct {
   external Pointer<Utf8> errorMessage;
 }
 
+/// Progress snapshot of an async operation.
+///
+/// Maps to CAsyncProgress in Rust.
+final class CAsyncProgress extends Struct {
+  /// Status: 0=running, 1=finished, -1=unknown operation ID
+  @Int32()
+  external int status;
+
+  /// Files found by the directory walk (1 for a single file)
+  @Size()
+  external int filesDiscovered;
+
+  /// Files fully embedded
+  @Size()
+  external int filesDone;
+
+  /// Chunks (or images) embedded so far
+  @Size()
+  external int chunksEmbedded;
+
+  /// Size of the finished files in bytes
+  @Uint64()
+  external int bytesRead;
+
+  /// File being embedded, or nullptr (free with freeString)
+  external Pointer<Utf8> currentFile;
+
+  /// Position among operations waiting for the same embedder (1 = next),
+  /// 0 once running or finished
+  @Size()
+  external int queuePosition;
+}
+
 /// Result type identifiers (matches AsyncResultType in Rust).
 abstract class AsyncResultType {
   static const int singleEmbedding = 0;
   static const int batchEmbedding = 1;
   static const int fileEmbedding = 2;
   static const int modelLoad = 3;
+  static const int sparseEmbedding = 4;
+  static const int sparseBatchEmbedding = 5;
+  static const int multiVectorEmbedding = 6;
+  static const int multiVectorBatchEmbedding = 7;
+  static const int rerankerLoad = 8;
+  static const int rerank = 9;
+  static const int imageEmbedding = 10;
+  static const int imageBatchEmbedding = 11;
+}
+
+/// Scheduling priorities for async operations (higher runs first).
+abstract class AsyncPriority {
+  /// Batches, images, files and directories.
+  static const int background = 0;
+
+  /// Single queries and reranking.
+  static const int interactive = 1;
 }
 
 /// Async poll status codes.
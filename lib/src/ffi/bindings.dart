@@ -18,6 +18,25 @@ const String _assetId = 'package:embedanythingindart/embedanything_dart';
 )
 external int initRuntime();
 
+/// Initialize the Tokio runtime with explicit sizing.
+///
+/// Must be called before initRuntime or any embedding call.
+///
+/// Parameters:
+/// - workerThreads: Async worker threads (0 = one per CPU core)
+/// - maxBlockingThreads: Threads for model loads and inference
+///   (0 = default of 32)
+///
+/// Returns:
+/// - 0 on success
+/// - -1 if the runtime was already initialized (call came too late)
+/// - -2 on other errors
+@Native<Int32 Function(Size, Size)>(
+  symbol: 'init_runtime_with_options',
+  assetId: _assetId,
+)
+external int initRuntimeWithOptions(int workerThreads, int maxBlockingThreads);
+
 // ============================================================================
 // Thread Pool Configuration
 // ============================================================================
@@ -72,14 +91,25 @@ external void freeErrorString(Pointer<Utf8> ptr);
 /// Create an embedder from a pretrained HuggingFace model
 ///
 /// Parameters:
-/// - modelType: 0 = BERT, 1 = Jina
+/// - modelType: 0 = BERT, 1 = Jina, 2 = ModernBERT, 3 = Qwen3,
+///   4 = XLM-RoBERTa, 255 = auto-detect from config.json
 /// - modelId: HuggingFace model identifier
 /// - revision: Git revision (or nullptr for default)
+/// - token: HuggingFace auth token (or nullptr to use the one set via
+///   setHfToken)
 /// - dtype: Data type for model weights (0 = F32, 1 = F16, -1 = default)
+/// - options: Embedder options (normalize, batch size), or nullptr for defaults
 ///
 /// Returns: Pointer to CEmbedder or nullptr on failure
 @Native<
-    Pointer<CEmbedder> Function(Uint8, Pointer<Utf8>, Pointer<Utf8>, Int32)>(
+    Pointer<CEmbedder> Function(
+      Uint8,
+      Pointer<Utf8>,
+      Pointer<Utf8>,
+      Pointer<Utf8>,
+      Int32,
+      Pointer<CEmbedderOptions>,
+    )>(
   symbol: 'embedder_from_pretrained_hf',
   assetId: _assetId,
 )
@@ -87,9 +117,109 @@ external Pointer<CEmbedder> embedderFromPretrainedHf(
   int modelType,
   Pointer<Utf8> modelId,
   Pointer<Utf8> revision,
+  Pointer<Utf8> token,
+  int dtype,
+  Pointer<CEmbedderOptions> options,
+);
+
+/// Create an embedder from a local model directory (no network access)
+///
+/// Parameters:
+/// - modelPath: Directory containing config.json, tokenizer.json and
+///   model.safetensors
+/// - dtype: Data type for model weights (0 = F32, 1 = F16, -1 = default)
+/// - options: Embedder options (normalize, batch size), or nullptr for defaults
+///
+/// Returns: Pointer to CEmbedder or nullptr on failure
+@Native<
+    Pointer<CEmbedder> Function(
+      Pointer<Utf8>,
+      Int32,
+      Pointer<CEmbedderOptions>,
+    )>(
+  symbol: 'embedder_from_local_path',
+  assetId: _assetId,
+)
+external Pointer<CEmbedder> embedderFromLocalPath(
+  Pointer<Utf8> modelPath,
   int dtype,
+  Pointer<CEmbedderOptions> options,
 );
 
+/// Create an embedder backed by ONNX Runtime
+///
+/// Requires a native library built with the `ort` feature.
+///
+/// Parameters:
+/// - modelPath: Path to the exported `.onnx` model
+/// - tokenizerPath: Path to the matching `tokenizer.json`
+/// - options: Embedder options (normalize, batch size, max sequence
+///   length, pooling), or nullptr for defaults
+///
+/// Returns: Pointer to CEmbedder or nullptr on failure
+@Native<
+    Pointer<CEmbedder> Function(
+      Pointer<Utf8>,
+      Pointer<Utf8>,
+      Pointer<CEmbedderOptions>,
+    )>(
+  symbol: 'embedder_from_onnx',
+  assetId: _assetId,
+)
+external Pointer<CEmbedder> embedderFromOnnx(
+  Pointer<Utf8> modelPath,
+  Pointer<Utf8> tokenizerPath,
+  Pointer<CEmbedderOptions> options,
+);
+
+/// Create an image embedder from a CLIP or SigLIP checkpoint
+///
+/// Parameters:
+/// - modelId: HuggingFace model identifier
+/// - revision: Git revision (or nullptr for default)
+/// - token: HuggingFace auth token (or nullptr to use the one set via
+///   setHfToken)
+/// - dtype: Data type for model weights (0 = F32, 1 = F16, -1 = default)
+/// - options: Embedder options (normalize, batch size), or nullptr for defaults
+///
+/// Returns: Pointer to CEmbedder or nullptr on failure
+@Native<
+    Pointer<CEmbedder> Function(
+      Pointer<Utf8>,
+      Pointer<Utf8>,
+      Pointer<Utf8>,
+      Int32,
+      Pointer<CEmbedderOptions>,
+    )>(
+  symbol: 'vision_embedder_from_pretrained_hf',
+  assetId: _assetId,
+)
+external Pointer<CEmbedder> visionEmbedderFromPretrainedHf(
+  Pointer<Utf8> modelId,
+  Pointer<Utf8> revision,
+  Pointer<Utf8> token,
+  int dtype,
+  Pointer<CEmbedderOptions> options,
+);
+
+/// Set the process-wide HuggingFace auth token (nullptr clears it)
+///
+/// Returns: 0 on success, -1 on failure
+@Native<Int32 Function(Pointer<Utf8>)>(
+  symbol: 'set_hf_token',
+  assetId: _assetId,
+)
+external int setHfToken(Pointer<Utf8> token);
+
+/// List the model architectures compiled into the native library
+///
+/// Returns: JSON array string (free with freeString) or nullptr on failure
+@Native<Pointer<Utf8> Function()>(
+  symbol: 'list_supported_architectures',
+  assetId: _assetId,
+)
+external Pointer<Utf8> listSupportedArchitectures();
+
 // ============================================================================
 // Embedding Operations - Text
 // ============================================================================
@@ -133,17 +263,550 @@ external Pointer<CTextEmbeddingBatch> embedTextsBatch(
   int count,
 );
 
+// ============================================================================
+// Embedding Operations - Sparse
+// ============================================================================
+
+/// Embed a single text with a sparse (SPLADE) model
+///
+/// Parameters:
+/// - embedder: Pointer to CEmbedder loaded from a sparse checkpoint
+/// - text: Text to embed
+///
+/// Returns: Pointer to CSparseEmbedding or nullptr on failure
+@Native<Pointer<CSparseEmbedding> Function(Pointer<CEmbedder>, Pointer<Utf8>)>(
+  symbol: 'embed_text_sparse',
+  assetId: _assetId,
+)
+external Pointer<CSparseEmbedding> embedTextSparse(
+  Pointer<CEmbedder> embedder,
+  Pointer<Utf8> text,
+);
+
+/// Embed a batch of texts with a sparse (SPLADE) model
+///
+/// Parameters:
+/// - embedder: Pointer to CEmbedder loaded from a sparse checkpoint
+/// - texts: Array of text pointers
+/// - count: Number of texts
+///
+/// Returns: Pointer to CSparseEmbeddingBatch or nullptr on failure
+@Native<
+    Pointer<CSparseEmbeddingBatch> Function(
+      Pointer<CEmbedder>,
+      Pointer<Pointer<Utf8>>,
+      Size,
+    )>(
+  symbol: 'embed_texts_batch_sparse',
+  assetId: _assetId,
+)
+external Pointer<CSparseEmbeddingBatch> embedTextsBatchSparse(
+  Pointer<CEmbedder> embedder,
+  Pointer<Pointer<Utf8>> texts,
+  int count,
+);
+
+// ============================================================================
+// Embedding Operations - Multi-Vector
+// ============================================================================
+
+/// Embed a single text, keeping one vector per token
+///
+/// Parameters:
+/// - embedder: Pointer to CEmbedder (late-interaction models such as
+///   ColBERT; dense models return a single row)
+/// - text: Text to embed
+///
+/// Returns: Pointer to CMultiVectorEmbedding or nullptr on failure
+@Native<
+    Pointer<CMultiVectorEmbedding> Function(
+      Pointer<CEmbedder>,
+      Pointer<Utf8>,
+    )>(
+  symbol: 'embed_text_multi_vector',
+  assetId: _assetId,
+)
+external Pointer<CMultiVectorEmbedding> embedTextMultiVector(
+  Pointer<CEmbedder> embedder,
+  Pointer<Utf8> text,
+);
+
+/// Embed a batch of texts, keeping one vector per token
+///
+/// Parameters:
+/// - embedder: Pointer to CEmbedder
+/// - texts: Array of text pointers
+/// - count: Number of texts
+///
+/// Returns: Pointer to CMultiVectorEmbeddingBatch or nullptr on failure
+@Native<
+    Pointer<CMultiVectorEmbeddingBatch> Function(
+      Pointer<CEmbedder>,
+      Pointer<Pointer<Utf8>>,
+      Size,
+    )>(
+  symbol: 'embed_texts_batch_multi_vector',
+  assetId: _assetId,
+)
+external Pointer<CMultiVectorEmbeddingBatch> embedTextsBatchMultiVector(
+  Pointer<CEmbedder> embedder,
+  Pointer<Pointer<Utf8>> texts,
+  int count,
+);
+
+// ============================================================================
+// Embedding Operations - Image
+// ============================================================================
+
+/// Embed an image file with a vision embedder
+///
+/// Parameters:
+/// - embedder: Pointer to CEmbedder loaded as a vision model
+/// - imagePath: Path to a PNG, JPEG, WebP, GIF or BMP file
+///
+/// Returns: Pointer to CTextEmbedding or nullptr on failure
+@Native<Pointer<CTextEmbedding> Function(Pointer<CEmbedder>, Pointer<Utf8>)>(
+  symbol: 'embed_image_file',
+  assetId: _assetId,
+)
+external Pointer<CTextEmbedding> embedImageFile(
+  Pointer<CEmbedder> embedder,
+  Pointer<Utf8> imagePath,
+);
+
+/// Embed an encoded image held in memory with a vision embedder
+///
+/// Parameters:
+/// - embedder: Pointer to CEmbedder loaded as a vision model
+/// - data: Encoded image bytes
+/// - len: Number of bytes
+///
+/// Returns: Pointer to CTextEmbedding or nullptr on failure
+@Native<
+    Pointer<CTextEmbedding> Function(
+      Pointer<CEmbedder>,
+      Pointer<Uint8>,
+      Size,
+    )>(
+  symbol: 'embed_image_bytes',
+  assetId: _assetId,
+)
+external Pointer<CTextEmbedding> embedImageBytes(
+  Pointer<CEmbedder> embedder,
+  Pointer<Uint8> data,
+  int len,
+);
+
+/// Embed a batch of image files with a vision embedder
+///
+/// Parameters:
+/// - embedder: Pointer to CEmbedder loaded as a vision model
+/// - imagePaths: Array of image path pointers
+/// - count: Number of paths
+///
+/// Returns: Pointer to CTextEmbeddingBatch or nullptr on failure
+@Native<
+    Pointer<CTextEmbeddingBatch> Function(
+      Pointer<CEmbedder>,
+      Pointer<Pointer<Utf8>>,
+      Size,
+    )>(
+  symbol: 'embed_image_files_batch',
+  assetId: _assetId,
+)
+external Pointer<CTextEmbeddingBatch> embedImageFilesBatch(
+  Pointer<CEmbedder> embedder,
+  Pointer<Pointer<Utf8>> imagePaths,
+  int count,
+);
+
+/// Embed a text query into the image space of a vision embedder
+///
+/// Parameters:
+/// - embedder: Pointer to CEmbedder loaded as a vision model
+/// - text: Query text
+///
+/// Returns: Pointer to CTextEmbedding or nullptr on failure
+@Native<Pointer<CTextEmbedding> Function(Pointer<CEmbedder>, Pointer<Utf8>)>(
+  symbol: 'embed_image_query',
+  assetId: _assetId,
+)
+external Pointer<CTextEmbedding> embedImageQuery(
+  Pointer<CEmbedder> embedder,
+  Pointer<Utf8> text,
+);
+
+// ============================================================================
+// Reranking
+// ============================================================================
+
+/// Load a cross-encoder reranker from HuggingFace Hub
+///
+/// Parameters:
+/// - modelId: HuggingFace model identifier (repo with an ONNX export)
+/// - revision: Git revision (or nullptr for default)
+/// - token: HuggingFace auth token (or nullptr to use the one set via
+///   setHfToken)
+///
+/// Returns: Pointer to CReranker or nullptr on failure
+@Native<
+    Pointer<CReranker> Function(
+      Pointer<Utf8>,
+      Pointer<Utf8>,
+      Pointer<Utf8>,
+    )>(
+  symbol: 'reranker_from_pretrained_hf',
+  assetId: _assetId,
+)
+external Pointer<CReranker> rerankerFromPretrainedHf(
+  Pointer<Utf8> modelId,
+  Pointer<Utf8> revision,
+  Pointer<Utf8> token,
+);
+
+/// Load a cross-encoder reranker from a local directory
+///
+/// Parameters:
+/// - modelPath: Directory containing tokenizer.json and an ONNX model
+///
+/// Returns: Pointer to CReranker or nullptr on failure
+@Native<Pointer<CReranker> Function(Pointer<Utf8>)>(
+  symbol: 'reranker_from_local_path',
+  assetId: _assetId,
+)
+external Pointer<CReranker> rerankerFromLocalPath(Pointer<Utf8> modelPath);
+
+/// Rerank documents against a query
+///
+/// Parameters:
+/// - reranker: Pointer to CReranker
+/// - query: Query text
+/// - documents: Array of document text pointers
+/// - count: Number of documents
+/// - topK: Number of results to return (0 = all)
+///
+/// Returns: Pointer to CRerankResults (highest score first) or nullptr on
+/// failure
+@Native<
+    Pointer<CRerankResults> Function(
+      Pointer<CReranker>,
+      Pointer<Utf8>,
+      Pointer<Pointer<Utf8>>,
+      Size,
+      Size,
+    )>(
+  symbol: 'rerank',
+  assetId: _assetId,
+)
+external Pointer<CRerankResults> rerank(
+  Pointer<CReranker> reranker,
+  Pointer<Utf8> query,
+  Pointer<Pointer<Utf8>> documents,
+  int count,
+  int topK,
+);
+
+// ============================================================================
+// Embedding Cache
+// ============================================================================
+
+/// Enable, resize or disable the in-memory embedding cache of an embedder
+///
+/// Parameters:
+/// - embedder: Pointer to CEmbedder
+/// - maxEntries: Maximum number of cached vectors (0 = no entry limit)
+/// - maxBytes: Maximum bytes of keys and vectors (0 = no byte limit);
+///   both 0 disables the cache
+///
+/// Returns: 0 on success, -1 on failure
+@Native<Int32 Function(Pointer<CEmbedder>, Size, Size)>(
+  symbol: 'embedder_set_cache',
+  assetId: _assetId,
+)
+external int embedderSetCache(
+  Pointer<CEmbedder> embedder,
+  int maxEntries,
+  int maxBytes,
+);
+
+/// Read the hit/miss counters and size of an embedder's cache
+@Native<CCacheStats Function(Pointer<CEmbedder>)>(
+  symbol: 'embedder_cache_stats',
+  assetId: _assetId,
+)
+external CCacheStats embedderCacheStats(Pointer<CEmbedder> embedder);
+
+/// Open (or create) the persistent embedding cache under a directory
+///
+/// Shared by every embedder; text and file embed calls consult it before
+/// running inference.
+///
+/// Parameters:
+/// - directory: Directory holding the cache file (created if missing)
+/// - maxBytes: Size limit of the live records (0 = 512 MiB)
+///
+/// Returns: 0 on success, -1 on failure
+@Native<Int32 Function(Pointer<Utf8>, Uint64)>(
+  symbol: 'cache_open',
+  assetId: _assetId,
+)
+external int cacheOpen(Pointer<Utf8> directory, int maxBytes);
+
+/// Close the persistent embedding cache
+@Native<Void Function()>(
+  symbol: 'cache_close',
+  assetId: _assetId,
+)
+external void cacheClose();
+
+/// Remove every record from the persistent embedding cache
+///
+/// Returns: 0 on success, -1 on failure (e.g. no cache is open)
+@Native<Int32 Function()>(
+  symbol: 'cache_clear',
+  assetId: _assetId,
+)
+external int cacheClear();
+
+/// Rewrite the persistent embedding cache without its evicted records
+///
+/// Returns: 0 on success, -1 on failure (e.g. no cache is open)
+@Native<Int32 Function()>(
+  symbol: 'cache_compact',
+  assetId: _assetId,
+)
+external int cacheCompact();
+
+// ============================================================================
+// Vector Index
+// ============================================================================
+
+/// Create an empty flat (exact) vector index
+///
+/// Parameters:
+/// - dimension: Length of every vector in the index
+/// - metric: 0 = cosine, 1 = dot product, 2 = Euclidean (L2)
+///
+/// Returns: Pointer to CVectorIndex or nullptr on failure
+@Native<Pointer<CVectorIndex> Function(Size, Int32)>(
+  symbol: 'vector_index_create',
+  assetId: _assetId,
+)
+external Pointer<CVectorIndex> vectorIndexCreate(int dimension, int metric);
+
+/// Create an empty approximate (HNSW) vector index
+///
+/// Parameters:
+/// - dimension: Length of every vector in the index
+/// - metric: 0 = cosine, 1 = dot product, 2 = Euclidean (L2)
+/// - m: Links per node (0 = default of 16)
+/// - efConstruction: Candidates considered per insert (0 = default of 200)
+/// - efSearch: Candidates considered per search (0 = default of 64)
+///
+/// Returns: Pointer to CVectorIndex or nullptr on failure
+@Native<Pointer<CVectorIndex> Function(Size, Int32, Size, Size, Size)>(
+  symbol: 'vector_index_create_hnsw',
+  assetId: _assetId,
+)
+external Pointer<CVectorIndex> vectorIndexCreateHnsw(
+  int dimension,
+  int metric,
+  int m,
+  int efConstruction,
+  int efSearch,
+);
+
+/// Change the search candidate list size of an HNSW index
+///
+/// Returns: 0 on success, -1 on failure (e.g. a flat index)
+@Native<Int32 Function(Pointer<CVectorIndex>, Size)>(
+  symbol: 'vector_index_set_ef_search',
+  assetId: _assetId,
+)
+external int vectorIndexSetEfSearch(Pointer<CVectorIndex> index, int efSearch);
+
+/// Add one vector, replacing any entry with the same id
+///
+/// Parameters:
+/// - index: Pointer to CVectorIndex
+/// - id: Id returned by searches
+/// - values: Vector values (e.g. CTextEmbedding.values)
+/// - len: Number of values; must equal the index dimension
+/// - metadataJson: JSON returned with search hits, or nullptr for none
+///
+/// Returns: 0 on success, -1 on failure
+@Native<
+    Int32 Function(
+      Pointer<CVectorIndex>,
+      Uint64,
+      Pointer<Float>,
+      Size,
+      Pointer<Utf8>,
+    )>(
+  symbol: 'vector_index_add',
+  assetId: _assetId,
+)
+external int vectorIndexAdd(
+  Pointer<CVectorIndex> index,
+  int id,
+  Pointer<Float> values,
+  int len,
+  Pointer<Utf8> metadataJson,
+);
+
+/// Add every embedding of a text batch, without metadata
+///
+/// The batch is read in place and not freed.
+///
+/// Parameters:
+/// - index: Pointer to CVectorIndex
+/// - ids: One id per embedding
+/// - batch: Pointer to CTextEmbeddingBatch
+///
+/// Returns: 0 on success, -1 on failure
+@Native<
+    Int32 Function(
+      Pointer<CVectorIndex>,
+      Pointer<Uint64>,
+      Pointer<CTextEmbeddingBatch>,
+    )>(
+  symbol: 'vector_index_add_text_batch',
+  assetId: _assetId,
+)
+external int vectorIndexAddTextBatch(
+  Pointer<CVectorIndex> index,
+  Pointer<Uint64> ids,
+  Pointer<CTextEmbeddingBatch> batch,
+);
+
+/// Add every item of an embed data batch with its text and metadata JSON
+///
+/// The batch is read in place and not freed. Nothing is added if any item is
+/// rejected (wrong dimension, multi-vector or invalid UTF-8 metadata).
+///
+/// Parameters:
+/// - index: Pointer to CVectorIndex
+/// - ids: One id per item
+/// - batch: Pointer to CEmbedDataBatch
+///
+/// Returns: 0 on success, -1 on failure
+@Native<
+    Int32 Function(
+      Pointer<CVectorIndex>,
+      Pointer<Uint64>,
+      Pointer<CEmbedDataBatch>,
+    )>(
+  symbol: 'vector_index_add_embed_data_batch',
+  assetId: _assetId,
+)
+external int vectorIndexAddEmbedDataBatch(
+  Pointer<CVectorIndex> index,
+  Pointer<Uint64> ids,
+  Pointer<CEmbedDataBatch> batch,
+);
+
+/// Remove the entry stored under an id
+///
+/// Returns: 1 if removed, 0 if not found, -1 on failure
+@Native<Int32 Function(Pointer<CVectorIndex>, Uint64)>(
+  symbol: 'vector_index_remove',
+  assetId: _assetId,
+)
+external int vectorIndexRemove(Pointer<CVectorIndex> index, int id);
+
+/// Number of entries in an index
+@Native<Size Function(Pointer<CVectorIndex>)>(
+  symbol: 'vector_index_count',
+  assetId: _assetId,
+)
+external int vectorIndexCount(Pointer<CVectorIndex> index);
+
+/// Find the entries closest to a query vector
+///
+/// Parameters:
+/// - index: Pointer to CVectorIndex
+/// - query: Query values (e.g. CTextEmbedding.values)
+/// - len: Number of values; must equal the index dimension
+/// - k: Maximum number of hits
+///
+/// Returns: Pointer to CSearchResults or nullptr on failure
+@Native<
+    Pointer<CSearchResults> Function(
+      Pointer<CVectorIndex>,
+      Pointer<Float>,
+      Size,
+      Size,
+    )>(
+  symbol: 'vector_index_search',
+  assetId: _assetId,
+)
+external Pointer<CSearchResults> vectorIndexSearch(
+  Pointer<CVectorIndex> index,
+  Pointer<Float> query,
+  int len,
+  int k,
+);
+
+/// Save an index to a single file, recording the embedder's model id,
+/// revision, dtype and output-affecting options (normalize, pooling,
+/// max sequence length)
+///
+/// Parameters:
+/// - index: Pointer to CVectorIndex
+/// - embedder: Pointer to the CEmbedder the vectors were embedded with
+/// - path: Destination file path (replaced if it exists)
+///
+/// Returns: 0 on success, -1 on failure
+@Native<
+    Int32 Function(
+      Pointer<CVectorIndex>,
+      Pointer<CEmbedder>,
+      Pointer<Utf8>,
+    )>(
+  symbol: 'vector_index_save',
+  assetId: _assetId,
+)
+external int vectorIndexSave(
+  Pointer<CVectorIndex> index,
+  Pointer<CEmbedder> embedder,
+  Pointer<Utf8> path,
+);
+
+/// Load an index saved by vectorIndexSave
+///
+/// Fails with a "MODEL_MISMATCH:" error if [embedder] was loaded with a
+/// different model id, revision, dtype or output-affecting option than the
+/// index was saved with.
+///
+/// Parameters:
+/// - path: File written by vectorIndexSave
+/// - embedder: Pointer to the CEmbedder that will produce query vectors
+///
+/// Returns: Pointer to CVectorIndex or nullptr on failure
+@Native<Pointer<CVectorIndex> Function(Pointer<Utf8>, Pointer<CEmbedder>)>(
+  symbol: 'vector_index_load',
+  assetId: _assetId,
+)
+external Pointer<CVectorIndex> vectorIndexLoad(
+  Pointer<Utf8> path,
+  Pointer<CEmbedder> embedder,
+);
+
 // ============================================================================
 // Embedding Operations - File & Directory (Phase 3)
 // ============================================================================
 
 /// Callback typedef for directory streaming
 ///
-/// Called from Rust with batches of embeddings during directory processing.
+/// Called from Rust with batches of embeddings during directory processing,
+/// on the thread that called embedDirectoryStream.
 /// Parameters:
-/// - batch: Pointer to CEmbedDataBatch containing the embeddings
+/// - batch: Pointer to CEmbedDataBatch containing the embeddings (the callee
+///   must free it)
 /// - context: User data pointer passed through from embedDirectoryStream
-typedef StreamCallbackType = Void Function(
+///
+/// Returns: 0 to continue, non-zero to stop the walk
+typedef StreamCallbackType = Int32 Function(
     Pointer<CEmbedDataBatch>, Pointer<Void>);
 
 /// Embed a single file with chunking
@@ -173,6 +836,7 @@ external Pointer<CEmbedDataBatch> embedFile(
 ///
 /// Parameters:
 /// - embedder: Pointer to CEmbedder
+/// - imageEmbedder: Vision CEmbedder for image files, or nullptr for text only
 /// - directoryPath: Path to directory to embed
 /// - extensions: NULL-terminated array of extension strings, or nullptr for all
 /// - extensionsCount: Number of extensions (0 if extensions is nullptr)
@@ -180,9 +844,10 @@ external Pointer<CEmbedDataBatch> embedFile(
 /// - callback: Function to call with each batch of embeddings
 /// - callbackContext: User data passed through to callback
 ///
-/// Returns: 0 on success, -1 on failure
+/// Returns: 0 on success, 1 if the callback stopped the walk, -1 on failure
 @Native<
     Int32 Function(
+      Pointer<CEmbedder>,
       Pointer<CEmbedder>,
       Pointer<Utf8>,
       Pointer<Pointer<Utf8>>,
@@ -196,6 +861,7 @@ external Pointer<CEmbedDataBatch> embedFile(
 )
 external int embedDirectoryStream(
   Pointer<CEmbedder> embedder,
+  Pointer<CEmbedder> imageEmbedder,
   Pointer<Utf8> directoryPath,
   Pointer<Pointer<Utf8>> extensions,
   int extensionsCount,
@@ -215,6 +881,34 @@ external int embedDirectoryStream(
 )
 external void embedderFree(Pointer<CEmbedder> embedder);
 
+/// Free a reranker instance
+@Native<Void Function(Pointer<CReranker>)>(
+  symbol: 'reranker_free',
+  assetId: _assetId,
+)
+external void rerankerFree(Pointer<CReranker> reranker);
+
+/// Free rerank results
+@Native<Void Function(Pointer<CRerankResults>)>(
+  symbol: 'free_rerank_results',
+  assetId: _assetId,
+)
+external void freeRerankResults(Pointer<CRerankResults> results);
+
+/// Free a vector index
+@Native<Void Function(Pointer<CVectorIndex>)>(
+  symbol: 'vector_index_free',
+  assetId: _assetId,
+)
+external void vectorIndexFree(Pointer<CVectorIndex> index);
+
+/// Free vector index search results
+@Native<Void Function(Pointer<CSearchResults>)>(
+  symbol: 'free_search_results',
+  assetId: _assetId,
+)
+external void freeSearchResults(Pointer<CSearchResults> results);
+
 /// Free a single text embedding
 @Native<Void Function(Pointer<CTextEmbedding>)>(
   symbol: 'free_embedding',
@@ -229,6 +923,45 @@ external void freeEmbedding(Pointer<CTextEmbedding> embedding);
 )
 external void freeEmbeddingBatch(Pointer<CTextEmbeddingBatch> batch);
 
+/// Free a single sparse embedding
+@Native<Void Function(Pointer<CSparseEmbedding>)>(
+  symbol: 'free_sparse_embedding',
+  assetId: _assetId,
+)
+external void freeSparseEmbedding(Pointer<CSparseEmbedding> embedding);
+
+/// Free a batch of sparse embeddings
+@Native<Void Function(Pointer<CSparseEmbeddingBatch>)>(
+  symbol: 'free_sparse_embedding_batch',
+  assetId: _assetId,
+)
+external void freeSparseEmbeddingBatch(Pointer<CSparseEmbeddingBatch> batch);
+
+/// Free a single multi-vector embedding
+@Native<Void Function(Pointer<CMultiVectorEmbedding>)>(
+  symbol: 'free_multi_vector_embedding',
+  assetId: _assetId,
+)
+external void freeMultiVectorEmbedding(
+  Pointer<CMultiVectorEmbedding> embedding,
+);
+
+/// Free a batch of multi-vector embeddings
+@Native<Void Function(Pointer<CMultiVectorEmbeddingBatch>)>(
+  symbol: 'free_multi_vector_embedding_batch',
+  assetId: _assetId,
+)
+external void freeMultiVectorEmbeddingBatch(
+  Pointer<CMultiVectorEmbeddingBatch> batch,
+);
+
+/// Free a string returned by Rust (e.g. listSupportedArchitectures)
+@Native<Void Function(Pointer<Utf8>)>(
+  symbol: 'free_string',
+  assetId: _assetId,
+)
+external void freeString(Pointer<Utf8> ptr);
+
 /// Free a single embed data instance (Phase 3)
 @Native<Void Function(Pointer<CEmbedData>)>(
   symbol: 'free_embed_data',
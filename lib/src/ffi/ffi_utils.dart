@@ -69,12 +69,19 @@ String? getLastErrorMessage() {
 ///
 /// Error messages from Rust are prefixed with type indicators:
 /// - "MODEL_NOT_FOUND:" -> ModelNotFoundError
+/// - "UNAUTHORIZED:" -> UnauthorizedError
 /// - "INVALID_CONFIG:" -> InvalidConfigError
 /// - "EMBEDDING_FAILED:" -> EmbeddingFailedError
 /// - "MULTI_VECTOR:" -> MultiVectorNotSupportedError
 /// - "FILE_NOT_FOUND:" -> FileNotFoundError (Phase 3)
+/// - "TOKENIZER_NOT_FOUND:", "WEIGHTS_NOT_FOUND:" -> FileNotFoundError
+/// - "UNSUPPORTED_ARCHITECTURE:" -> InvalidConfigError
+/// - "RERANKER_NOT_FOUND:" -> ModelNotFoundError
+/// - "RERANK_FAILED:" -> EmbeddingFailedError
 /// - "UNSUPPORTED_FORMAT:" -> UnsupportedFileFormatError (Phase 3)
 /// - "FILE_READ_ERROR:" -> FileReadError (Phase 3)
+/// - "MODEL_MISMATCH:" -> ModelMismatchError
+/// - "TIMEOUT:" -> OperationTimeoutError
 /// - "FFI_ERROR:" -> FFIError
 ///
 /// If no prefix is found, returns FFIError as fallback.
@@ -82,6 +89,14 @@ EmbedAnythingError _parseError(String errorMessage, String operation) {
   if (errorMessage.startsWith('MODEL_NOT_FOUND:')) {
     final modelId = errorMessage.substring('MODEL_NOT_FOUND:'.length).trim();
     return ModelNotFoundError(modelId);
+  } else if (errorMessage.startsWith('UNAUTHORIZED:')) {
+    // Expected format: "UNAUTHORIZED: Access to 'org/model' was denied; ..."
+    final reason = errorMessage.substring('UNAUTHORIZED:'.length).trim();
+    final start = reason.indexOf("'");
+    final end = start == -1 ? -1 : reason.indexOf("'", start + 1);
+    final modelId =
+        end == -1 ? 'unknown' : reason.substring(start + 1, end);
+    return UnauthorizedError(modelId: modelId, reason: reason);
   } else if (errorMessage.startsWith('INVALID_CONFIG:')) {
     final parts = errorMessage.substring('INVALID_CONFIG:'.length).trim();
     // Expected format: "field=value: reason"
@@ -101,6 +116,25 @@ EmbedAnythingError _parseError(String errorMessage, String operation) {
   } else if (errorMessage.startsWith('FILE_NOT_FOUND:')) {
     final path = errorMessage.substring('FILE_NOT_FOUND:'.length).trim();
     return FileNotFoundError(path);
+  } else if (errorMessage.startsWith('TOKENIZER_NOT_FOUND:') ||
+      errorMessage.startsWith('WEIGHTS_NOT_FOUND:')) {
+    // A local model directory is missing one of its files
+    final path = errorMessage.substring(errorMessage.indexOf(':') + 1).trim();
+    return FileNotFoundError(path);
+  } else if (errorMessage.startsWith('UNSUPPORTED_ARCHITECTURE:')) {
+    final architecture =
+        errorMessage.substring('UNSUPPORTED_ARCHITECTURE:'.length).trim();
+    return InvalidConfigError(
+      field: 'architecture',
+      reason: 'unsupported architecture $architecture',
+    );
+  } else if (errorMessage.startsWith('RERANKER_NOT_FOUND:')) {
+    final modelId =
+        errorMessage.substring('RERANKER_NOT_FOUND:'.length).trim();
+    return ModelNotFoundError(modelId);
+  } else if (errorMessage.startsWith('RERANK_FAILED:')) {
+    final reason = errorMessage.substring('RERANK_FAILED:'.length).trim();
+    return EmbeddingFailedError(reason: reason);
   } else if (errorMessage.startsWith('UNSUPPORTED_FORMAT:')) {
     // Expected format: "UNSUPPORTED_FORMAT: extension for /path/to/file"
     final parts = errorMessage.substring('UNSUPPORTED_FORMAT:'.length).trim();
@@ -124,6 +158,12 @@ EmbedAnythingError _parseError(String errorMessage, String operation) {
     } else {
       return FileReadError(path: parts, reason: 'Unknown error');
     }
+  } else if (errorMessage.startsWith('MODEL_MISMATCH:')) {
+    final reason = errorMessage.substring('MODEL_MISMATCH:'.length).trim();
+    return ModelMismatchError(reason);
+  } else if (errorMessage.startsWith('TIMEOUT:')) {
+    final reason = errorMessage.substring('TIMEOUT:'.length).trim();
+    return OperationTimeoutError(reason);
   } else if (errorMessage.startsWith('FFI_ERROR:')) {
     final nativeError =
         errorMessage.substring('FFI_ERROR:'.length).trim();
@@ -145,12 +185,15 @@ EmbedAnythingError _parseError(String errorMessage, String operation) {
 ///
 /// Throws one of:
 /// - [ModelNotFoundError] - Model not found on HuggingFace Hub
+/// - [UnauthorizedError] - HuggingFace Hub denied access (401/403)
 /// - [InvalidConfigError] - Invalid configuration parameters
 /// - [EmbeddingFailedError] - Embedding generation failed
 /// - [MultiVectorNotSupportedError] - Multi-vector embeddings not supported
 /// - [FileNotFoundError] - File or directory not found (Phase 3)
 /// - [UnsupportedFileFormatError] - File format not supported (Phase 3)
 /// - [FileReadError] - File I/O error (Phase 3)
+/// - [ModelMismatchError] - Saved index was built with another model
+/// - [OperationTimeoutError] - Async operation passed its deadline
 /// - [FFIError] - Generic FFI operation failure
 Never throwLastError(
     [String operation = 'Operation failed', String? defaultMessage]) {
@@ -166,6 +209,17 @@ Never throwLastError(
   throw _parseError(errorMessage, operation);
 }
 
+/// Throw a typed exception for an error message reported by an async
+/// operation
+///
+/// Async operations return their error through the poll result rather
+/// than the thread-local last error; the message carries the same
+/// prefixes, so it maps to the same typed errors as [throwLastError].
+Never throwErrorMessage(String errorMessage,
+    [String operation = 'Async operation failed']) {
+  throw _parseError(errorMessage, operation);
+}
+
 // ============================================================================
 // Phase 3 Helper Functions - File Embedding FFI Utilities
 // ============================================================================
@@ -202,6 +256,25 @@ Pointer<CTextEmbedConfig> allocateTextEmbedConfig({
   return config;
 }
 
+/// Allocate a CEmbedderOptions struct from Dart parameters
+///
+/// A [batchSize] or [maxSequenceLength] of 0 and a [pooling] of -1 keep the
+/// model defaults. The caller is responsible for freeing the returned
+/// pointer using calloc.free().
+Pointer<CEmbedderOptions> allocateEmbedderOptions({
+  required bool normalize,
+  int batchSize = 0,
+  int maxSequenceLength = 0,
+  int pooling = -1,
+}) {
+  final options = calloc<CEmbedderOptions>();
+  options.ref.normalize = normalize ? 1 : 0;
+  options.ref.batchSize = batchSize;
+  options.ref.maxSequenceLength = maxSequenceLength;
+  options.ref.pooling = pooling;
+  return options;
+}
+
 /// Parse metadata JSON string to `Map<String, String>`
 ///
 /// Returns null if:
@@ -28,6 +28,93 @@ final class CTextEmbeddingBatch extends Struct {
   external int count;
 }
 
+/// C representation of a sparse (SPLADE) embedding
+///
+/// Only non-zero entries are stored: `indices[i]` is the vocabulary index
+/// of `values[i]`. Both arrays have `len` elements.
+final class CSparseEmbedding extends Struct {
+  external Pointer<Uint32> indices;
+
+  external Pointer<Float> values;
+
+  @Size()
+  external int len;
+}
+
+/// C representation of a batch of sparse embeddings
+final class CSparseEmbeddingBatch extends Struct {
+  external Pointer<CSparseEmbedding> embeddings;
+
+  @Size()
+  external int count;
+}
+
+/// C representation of a multi-vector (ColBERT) embedding
+///
+/// `values` is a row-major `rows x dim` buffer: one row per token.
+/// Dense embeddings are represented as a single row.
+final class CMultiVectorEmbedding extends Struct {
+  external Pointer<Float> values;
+
+  @Size()
+  external int rows;
+
+  @Size()
+  external int dim;
+}
+
+/// C representation of a batch of multi-vector embeddings
+final class CMultiVectorEmbeddingBatch extends Struct {
+  external Pointer<CMultiVectorEmbedding> embeddings;
+
+  @Size()
+  external int count;
+}
+
+/// Opaque pointer to a Rust cross-encoder reranker
+final class CReranker extends Opaque {}
+
+/// C representation of one reranked document
+///
+/// `index` is the document's position in the input array.
+final class CRerankResult extends Struct {
+  @Size()
+  external int index;
+
+  @Float()
+  external double score;
+}
+
+/// C representation of reranked documents, highest score first
+final class CRerankResults extends Struct {
+  external Pointer<CRerankResult> results;
+
+  @Size()
+  external int count;
+}
+
+/// C representation of embedder options passed at load time
+///
+/// Memory layout must match Rust CEmbedderOptions struct.
+final class CEmbedderOptions extends Struct {
+  /// 1 to L2-normalize output vectors, 0 to return them as-is
+  @Uint8()
+  external int normalize;
+
+  /// Batch size for embedding calls (0 = model default)
+  @Size()
+  external int batchSize;
+
+  /// Maximum tokens per input (0 = model default; ONNX and local backends)
+  @Size()
+  external int maxSequenceLength;
+
+  /// Pooling override: -1 = model default, 0 = mean, 1 = CLS (ONNX and
+  /// local backends)
+  @Int32()
+  external int pooling;
+}
+
 /// C representation of text embedding configuration
 ///
 /// Configuration for chunking and embedding text from files.
@@ -59,12 +146,17 @@ final class CEmbedData extends Struct {
   /// Pointer to the embedding values array (f32)
   external Pointer<Float> embeddingValues;
 
-  /// Length of the embedding vector
+  /// Length of the embedding vector (total floats for multi-vector data)
   @Size()
   external int embeddingLen;
 
   /// Combined text and metadata as JSON: {"text": "...", "metadata": {...}}
   external Pointer<Utf8> textAndMetadataJson;
+
+  /// Number of token vectors stored row-major in [embeddingValues]
+  /// (1 for dense embeddings)
+  @Size()
+  external int embeddingRows;
 }
 
 /// C representation of a batch of embedded data
@@ -79,3 +171,65 @@ final class CEmbedDataBatch extends Struct {
   @Size()
   external int count;
 }
+
+/// Hit/miss counters of an embedder's embedding cache
+///
+/// Memory layout must match Rust CCacheStats struct.
+final class CCacheStats extends Struct {
+  /// Status: 1 = cache enabled, 0 = no cache, -1 = error
+  @Int32()
+  external int status;
+
+  @Uint64()
+  external int hits;
+
+  @Uint64()
+  external int misses;
+
+  @Uint64()
+  external int evictions;
+
+  /// Number of cached vectors
+  @Size()
+  external int entries;
+
+  /// Bytes used by cached keys and vectors
+  @Size()
+  external int bytes;
+
+  /// Configured entry limit (0 = unbounded)
+  @Size()
+  external int maxEntries;
+
+  /// Configured byte limit (0 = unbounded)
+  @Size()
+  external int maxBytes;
+}
+
+/// Opaque pointer to a Rust vector index
+final class CVectorIndex extends Opaque {}
+
+/// One vector index search hit
+///
+/// Memory layout must match Rust CSearchResult struct.
+final class CSearchResult extends Struct {
+  @Uint64()
+  external int id;
+
+  /// Similarity for cosine/dot indexes, Euclidean distance for L2 indexes
+  @Float()
+  external double score;
+
+  /// Metadata JSON stored with the vector, or nullptr if none
+  external Pointer<Utf8> metadataJson;
+}
+
+/// Vector index search hits, closest first
+///
+/// Memory layout must match Rust CSearchResults struct.
+final class CSearchResults extends Struct {
+  external Pointer<CSearchResult> results;
+
+  @Size()
+  external int count;
+}
@@ -28,9 +28,10 @@ class ModelConfig {
   /// The model will be downloaded and cached on first use.
   final String modelId;
 
-  /// Model architecture type (BERT or Jina)
+  /// Model architecture type
   ///
   /// This determines which embedding model architecture to use.
+  /// [EmbeddingModel.auto] detects it from the model's `config.json`.
   final EmbeddingModel modelType;
 
   /// Git revision (branch, tag, or commit hash)
@@ -52,12 +53,14 @@ class ModelConfig {
   /// Whether to normalize embeddings to unit length
   ///
   /// Defaults to true. Normalized embeddings are suitable for
-  /// cosine similarity comparisons.
+  /// cosine similarity comparisons. Applied by the native layer to every
+  /// embedding call, including file and directory embedding.
   final bool normalize;
 
   /// Default batch size for batch operations
   ///
-  /// Defaults to 32. Larger batch sizes are more efficient but
+  /// Passed to the native layer at load time and used by batch embedding
+  /// calls. Defaults to 32. Larger batch sizes are more efficient but
   /// require more memory. Adjust based on your hardware and use case.
   final int defaultBatchSize;
 
@@ -4,6 +4,14 @@
 /// tokenization strategies. Choose based on your use case:
 /// - [bert]: General-purpose, fast, good quality
 /// - [jina]: Optimized for semantic search, higher quality
+/// - [modernBert]: Long-context encoder, modern BERT variant
+/// - [qwen3]: Qwen3 embedding models, highest quality
+/// - [xlmRoberta]: Multilingual XLM-RoBERTa encoders
+/// - [sparseBert]: Sparse BERT models (SPLADE) for `embedTextSparse`
+/// - [auto]: Detect the architecture from the model's `config.json`
+///
+/// The selected architecture is used to load the model. Loading fails with
+/// `InvalidConfigError` if the checkpoint declares a different architecture.
 ///
 /// Example:
 /// ```dart
@@ -51,7 +59,62 @@ enum EmbeddingModel {
   /// Performance:
   /// - Model load (warm cache): ~150ms
   /// - Single embedding latency (short text): ~10-15ms
-  jina(1);
+  jina(1),
+
+  /// ModernBERT models.
+  ///
+  /// ModernBERT is a refreshed BERT architecture with rotary embeddings
+  /// and support for long inputs (up to 8192 tokens).
+  ///
+  /// Common ModernBERT models:
+  /// - `nomic-ai/modernbert-embed-base` (768 dim)
+  ///
+  /// Best for:
+  /// - Long documents
+  /// - Code and technical text
+  modernBert(2),
+
+  /// Qwen3 embedding models.
+  ///
+  /// Decoder-based embedding models with multilingual support and
+  /// state-of-the-art retrieval quality, at a higher compute cost.
+  ///
+  /// Common Qwen3 models:
+  /// - `Qwen/Qwen3-Embedding-0.6B` (1024 dim)
+  ///
+  /// Best for:
+  /// - Multilingual retrieval
+  /// - Maximum quality requirements
+  qwen3(3),
+
+  /// XLM-RoBERTa models.
+  ///
+  /// Multilingual BERT variant trained on 100 languages.
+  ///
+  /// Common XLM-RoBERTa models:
+  /// - `sentence-transformers/paraphrase-multilingual-mpnet-base-v2` (768 dim)
+  ///
+  /// Best for:
+  /// - Multilingual semantic similarity
+  xlmRoberta(4),
+
+  /// Sparse BERT models (SPLADE).
+  ///
+  /// BERT checkpoints with a masked-LM head (`BertForMaskedLM`) that
+  /// produce one weight per vocabulary token. Embedders of this type only
+  /// support the sparse embedding methods such as `embedTextSparse`.
+  ///
+  /// Common SPLADE models:
+  /// - `prithivida/Splade_PP_en_v1`
+  ///
+  /// Best for:
+  /// - Lexical / hybrid search
+  sparseBert(5),
+
+  /// Detect the architecture from the model's `config.json`.
+  ///
+  /// Use when the architecture of a checkpoint is not known in advance.
+  auto(255);
 
   const EmbeddingModel(this.value);
 
@@ -201,3 +264,50 @@ enum ComputeDevice {
     );
   }
 }
+
+/// How token vectors are pooled into one sentence embedding.
+///
+/// Only the ONNX and local-directory backends honor an override; models
+/// loaded from the hub always use the pooling of their checkpoint.
+enum PoolingStrategy {
+  /// Use the model's own pooling (mean for ONNX models).
+  modelDefault(-1),
+
+  /// Average of the token vectors, ignoring padding.
+  mean(0),
+
+  /// Vector of the first ([CLS]) token.
+  cls(1);
+
+  const PoolingStrategy(this.value);
+
+  /// Numeric value passed to Rust FFI.
+  final int value;
+}
+
+/// Scheduling priority of an async operation.
+///
+/// Operations on the same embedder wait for a free slot; higher priorities
+/// are admitted first, then earlier arrivals. By default single queries
+/// run as [interactive] and batches, files and directories as
+/// [background].
+///
+/// Example:
+/// ```dart
+/// final chunks = await embedder.embedFileAsync(
+///   'notes.md',
+///   priority: OperationPriority.interactive,
+/// );
+/// ```
+enum OperationPriority {
+  /// Bulk work that may wait behind queries.
+  background(0),
+
+  /// Latency-sensitive work such as a search query.
+  interactive(1);
+
+  const OperationPriority(this.value);
+
+  /// Numeric value passed to Rust FFI.
+  final int value;
+}
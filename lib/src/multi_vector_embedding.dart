@@ -0,0 +1,108 @@
+import 'embedding_result.dart';
+
+/// Result of a multi-vector (late-interaction) embedding operation.
+///
+/// Late-interaction models such as ColBERT keep one vector per token
+/// instead of pooling them into a single vector. Each row in [rows] is the
+/// embedding of one token; all rows share the same [dimension]. Dense
+/// models produce a single row.
+///
+/// Multi-vector embeddings are compared with [maxSim].
+///
+/// Example:
+/// ```dart
+/// final query = embedder.embedTextMultiVector('machine learning');
+/// final doc = embedder.embedTextMultiVector('An introduction to AI');
+///
+/// print('Tokens: ${query.length}, dimension: ${query.dimension}');
+/// print('Score: ${query.maxSim(doc)}');
+/// ```
+class MultiVectorEmbedding {
+  /// One embedding per token, in token order.
+  final List<EmbeddingResult> rows;
+
+  /// Creates a multi-vector embedding from its token rows.
+  const MultiVectorEmbedding(this.rows);
+
+  /// Creates a multi-vector embedding from a row-major buffer of
+  /// [rowCount] rows.
+  ///
+  /// Throws [ArgumentError] if the buffer length is not a multiple of
+  /// [rowCount].
+  factory MultiVectorEmbedding.fromRowMajor(
+    List<double> values,
+    int rowCount,
+  ) {
+    if (rowCount == 0 && values.isEmpty) {
+      return const MultiVectorEmbedding([]);
+    }
+    if (rowCount <= 0 || values.length % rowCount != 0) {
+      throw ArgumentError(
+        'Cannot split ${values.length} values into $rowCount rows',
+      );
+    }
+
+    final dim = values.length ~/ rowCount;
+    return MultiVectorEmbedding([
+      for (int r = 0; r < rowCount; r++)
+        EmbeddingResult(values.sublist(r * dim, (r + 1) * dim)),
+    ]);
+  }
+
+  /// Number of token vectors.
+  int get length => rows.length;
+
+  /// Dimensionality of each token vector (0 if there are no rows).
+  int get dimension => rows.isEmpty ? 0 : rows.first.dimension;
+
+  /// Mean of all token vectors, as a single dense embedding.
+  ///
+  /// Useful where a single vector is needed, e.g. for
+  /// [EmbeddingResult.cosineSimilarity]. Prefer [maxSim] for ranking.
+  EmbeddingResult mean() {
+    final sums = List<double>.filled(dimension, 0.0);
+    for (final row in rows) {
+      for (int i = 0; i < dimension; i++) {
+        sums[i] += row.values[i];
+      }
+    }
+    return EmbeddingResult([for (final sum in sums) sum / rows.length]);
+  }
+
+  /// Late-interaction (MaxSim) score against a document embedding.
+  ///
+  /// For each query token, takes the highest dot product with any token
+  /// of [other], and sums these maxima. The score is not symmetric: call
+  /// it on the query embedding.
+  ///
+  /// Throws [ArgumentError] if the embeddings have different dimensions.
+  double maxSim(MultiVectorEmbedding other) {
+    if (dimension != other.dimension) {
+      throw ArgumentError(
+        'Embedding dimensions must match: $dimension vs ${other.dimension}',
+      );
+    }
+
+    double score = 0.0;
+    for (final queryRow in rows) {
+      double best = double.negativeInfinity;
+      for (final docRow in other.rows) {
+        double dot = 0.0;
+        for (int i = 0; i < dimension; i++) {
+          dot += queryRow.values[i] * docRow.values[i];
+        }
+        if (dot > best) {
+          best = dot;
+        }
+      }
+      if (other.rows.isNotEmpty) {
+        score += best;
+      }
+    }
+    return score;
+  }
+
+  @override
+  String toString() =>
+      'MultiVectorEmbedding(rows: $length, dimension: $dimension)';
+}
@@ -0,0 +1,25 @@
+/// One document scored by a [Reranker].
+///
+/// [index] is the document's position in the list passed to
+/// [Reranker.rerank]; higher [score]s mean more relevant documents.
+///
+/// Example:
+/// ```dart
+/// final results = reranker.rerank(query, documents, topK: 3);
+/// for (final result in results) {
+///   print('${result.score.toStringAsFixed(3)}: ${documents[result.index]}');
+/// }
+/// ```
+class RerankResult {
+  /// Position of the document in the input list.
+  final int index;
+
+  /// Relevance score assigned by the cross-encoder.
+  final double score;
+
+  /// Creates a rerank result.
+  const RerankResult(this.index, this.score);
+
+  @override
+  String toString() => 'RerankResult(index: $index, score: $score)';
+}
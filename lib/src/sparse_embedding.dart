@@ -0,0 +1,53 @@
+/// Result of a sparse (SPLADE) embedding operation.
+///
+/// Sparse models score every vocabulary token; only the non-zero entries
+/// are kept. [indices] holds the vocabulary index of each weight in
+/// [values], in ascending order. Sparse vectors are compared with [dot],
+/// typically alongside a dense embedding in hybrid search.
+///
+/// Example:
+/// ```dart
+/// final embedder = await EmbedAnything.fromPretrainedHfAsync(
+///   modelId: 'prithivida/Splade_PP_en_v1',
+/// );
+/// final query = embedder.embedTextSparse('machine learning');
+/// final doc = embedder.embedTextSparse('An introduction to deep learning');
+///
+/// print('Non-zero terms: ${query.length}');
+/// print('Score: ${query.dot(doc)}');
+/// ```
+class SparseEmbedding {
+  /// Vocabulary indices of the non-zero entries.
+  final List<int> indices;
+
+  /// Weights of the non-zero entries, aligned with [indices].
+  final List<double> values;
+
+  /// Creates a sparse embedding from aligned index and weight lists.
+  const SparseEmbedding(this.indices, this.values);
+
+  /// Number of non-zero entries.
+  int get length => indices.length;
+
+  /// Dot product with another sparse embedding.
+  ///
+  /// Both embeddings must come from the same model so that indices refer
+  /// to the same vocabulary.
+  double dot(SparseEmbedding other) {
+    final weights = <int, double>{
+      for (int i = 0; i < other.length; i++) other.indices[i]: other.values[i],
+    };
+
+    double score = 0.0;
+    for (int i = 0; i < length; i++) {
+      final weight = weights[indices[i]];
+      if (weight != null) {
+        score += values[i] * weight;
+      }
+    }
+    return score;
+  }
+
+  @override
+  String toString() => 'SparseEmbedding(length: $length)';
+}
@@ -10,8 +10,13 @@
 //! 3. Dart polls `poll_async_result(op_id)` with 10ms delays
 //! 4. When ready, Dart gets the result and frees memory
 
+use crate::cache::{CacheKind, ModelFingerprint, TextCache};
+use crate::options::EmbedderOptions;
 use crate::{
-    clear_last_error, set_last_error, CEmbedData, CEmbedDataBatch, CEmbedder, CTextEmbedConfig,
+    clear_last_error, dense_to_c, dense_to_sparse, dense_vec_to_batch, directory, disk_cache,
+    flatten_embedding, model_loader, multi_vector_to_c, multi_vector_vec_to_batch,
+    rerank_results_to_c, reranker, set_last_error, sparse_to_c, sparse_vec_to_batch, vision,
+    CEmbedData, CEmbedDataBatch, CEmbedder, CEmbedderOptions, CReranker, CTextEmbedConfig,
     CTextEmbedding, CTextEmbeddingBatch, RUNTIME,
 };
 use embed_anything::config::TextEmbedConfig;
@@ -20,10 +25,14 @@ use embed_anything::Dtype;
 use lazy_static::lazy_static;
 use std::collections::HashMap;
 use std::ffi::{CStr, CString};
+use std::future::Future;
+use std::ops::ControlFlow;
 use std::os::raw::c_char;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{oneshot, Notify};
 use tokio_util::sync::CancellationToken;
 
 // ============================================================================
@@ -49,6 +58,29 @@ pub struct BatchEmbeddingResult {
     pub embeddings: Vec<Vec<f32>>,
 }
 
+/// Result data for single sparse embedding (non-zero entries only)
+pub struct SparseEmbeddingResult {
+    pub indices: Vec<u32>,
+    pub values: Vec<f32>,
+}
+
+/// Result data for batch sparse embedding
+pub struct SparseBatchEmbeddingResult {
+    pub embeddings: Vec<(Vec<u32>, Vec<f32>)>,
+}
+
+/// Result data for single multi-vector embedding (row-major `rows x dim`)
+pub struct MultiVectorEmbeddingResult {
+    pub values: Vec<f32>,
+    pub rows: usize,
+    pub dim: usize,
+}
+
+/// Result data for batch multi-vector embedding
+pub struct MultiVectorBatchEmbeddingResult {
+    pub embeddings: Vec<(Vec<f32>, usize, usize)>,
+}
+
 /// Result data for file/directory embedding
 pub struct FileEmbeddingResult {
     pub items: Vec<EmbedData>,
@@ -57,6 +89,20 @@ pub struct FileEmbeddingResult {
 /// Result data for model loading
 pub struct ModelLoadResult {
     pub embedder: Arc<Embedder>,
+    pub options: EmbedderOptions,
+    pub model: ModelFingerprint,
+    /// Whether the model was built as a sparse (SPLADE) model
+    pub sparse: bool,
+}
+
+/// Result data for reranker loading
+pub struct RerankerLoadResult {
+    pub reranker: Arc<reranker::Reranker>,
+}
+
+/// Result data for reranking: `(index, score)` pairs, highest score first
+pub struct RerankResult {
+    pub ranked: Vec<(usize, f32)>,
 }
 
 /// Union of all possible async results
@@ -65,6 +111,14 @@ pub enum AsyncResultData {
     BatchEmbedding(BatchEmbeddingResult),
     FileEmbedding(FileEmbeddingResult),
     ModelLoad(ModelLoadResult),
+    SparseEmbedding(SparseEmbeddingResult),
+    SparseBatchEmbedding(SparseBatchEmbeddingResult),
+    MultiVectorEmbedding(MultiVectorEmbeddingResult),
+    MultiVectorBatchEmbedding(MultiVectorBatchEmbeddingResult),
+    RerankerLoad(RerankerLoadResult),
+    Rerank(RerankResult),
+    ImageEmbedding(SingleEmbeddingResult),
+    ImageBatchEmbedding(BatchEmbeddingResult),
 }
 
 /// Entry in the async operations registry
@@ -72,6 +126,41 @@ pub struct AsyncOperation {
     pub status: AsyncOperationStatus,
     pub result: Option<AsyncResultData>,
     pub cancel_token: CancellationToken,
+    /// Fires once the operation reaches its final status or is cancelled
+    /// (child of `cancel_token`); ends its deadline timer
+    pub done: CancellationToken,
+    /// Live counters for file and directory jobs
+    pub progress: Option<Arc<directory::Progress>>,
+    /// Directory entries not yet handed out by drain_async_partial
+    pub partial: Vec<EmbedData>,
+    /// Signalled by drain_async_partial; None until the first drain
+    pub drained: Option<Arc<Notify>>,
+    /// Callback to notify when the operation finishes
+    pub on_complete: Option<CompletionCallback>,
+    /// When the operation was started
+    pub created_at: Instant,
+    /// When the operation reached its final status (TTL reaping)
+    pub finished_at: Option<Instant>,
+}
+
+/// Completion callback: (op_id, poll status, context).
+///
+/// Invoked once, from a runtime worker thread, with the status
+/// poll_async_result will report (1=success, -1=error, -2=cancelled).
+pub type AsyncCompletionCallback = extern "C" fn(i64, i32, *mut std::ffi::c_void);
+
+/// Callback registered with set_async_completion_callback.
+pub struct CompletionCallback {
+    callback: AsyncCompletionCallback,
+    /// Caller context, passed back untouched (stored as an address so the
+    /// registry stays `Send`)
+    context: usize,
+}
+
+impl CompletionCallback {
+    fn notify(&self, op_id: i64, status: i32) {
+        (self.callback)(op_id, status, self.context as *mut std::ffi::c_void);
+    }
 }
 
 // ============================================================================
@@ -88,6 +177,18 @@ lazy_static! {
 /// Atomic counter for generating unique operation IDs.
 static NEXT_OPERATION_ID: AtomicI64 = AtomicI64::new(1);
 
+/// How long finished results wait to be polled before they are reaped.
+pub const DEFAULT_RESULT_TTL_MS: u64 = 10 * 60 * 1000;
+
+/// Current result TTL in milliseconds (0 keeps results until polled).
+static RESULT_TTL_MS: AtomicU64 = AtomicU64::new(DEFAULT_RESULT_TTL_MS);
+
+/// How often the background sweep reaps expired results.
+const REAP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Starts the background sweep with the first operation.
+static START_REAPER: std::sync::Once = std::sync::Once::new();
+
 // ============================================================================
 // C-Compatible Result Types
 // ============================================================================
@@ -99,6 +200,14 @@ pub enum AsyncResultType {
     BatchEmbedding = 1,
     FileEmbedding = 2,
     ModelLoad = 3,
+    SparseEmbedding = 4,
+    SparseBatchEmbedding = 5,
+    MultiVectorEmbedding = 6,
+    MultiVectorBatchEmbedding = 7,
+    RerankerLoad = 8,
+    Rerank = 9,
+    ImageEmbedding = 10,
+    ImageBatchEmbedding = 11,
 }
 
 /// C-compatible result structure for polling async operations.
@@ -106,7 +215,9 @@ pub enum AsyncResultType {
 pub struct CAsyncPollResult {
     /// Status: 0=pending, 1=success, -1=error, -2=cancelled
     pub status: i32,
-    /// Result type: 0=single, 1=batch, 2=file, 3=model
+    /// Result type: 0=single, 1=batch, 2=file, 3=model, 4=sparse, 5=sparse batch,
+    /// 6=multi-vector, 7=multi-vector batch, 8=reranker, 9=rerank results,
+    /// 10=image, 11=image batch
     pub result_type: i32,
     /// Pointer to result data (type depends on result_type)
     pub data: *mut std::ffi::c_void,
@@ -125,55 +236,326 @@ impl Default for CAsyncPollResult {
     }
 }
 
+/// C-compatible progress snapshot of an async operation.
+#[repr(C)]
+pub struct CAsyncProgress {
+    /// Status: 0=running, 1=finished (collect with poll_async_result),
+    /// -1=unknown operation ID
+    pub status: i32,
+    /// Files found by the directory walk (1 for a single file)
+    pub files_discovered: usize,
+    /// Files fully embedded
+    pub files_done: usize,
+    /// Chunks (or images) embedded so far
+    pub chunks_embedded: usize,
+    /// Size of the finished files in bytes
+    pub bytes_read: u64,
+    /// File being embedded, or NULL (free with free_string)
+    pub current_file: *mut c_char,
+    /// Position among the operations waiting for the same embedder
+    /// (1 = next to run), 0 once running or finished
+    pub queue_position: usize,
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+/// Run synchronous work (model loads, image and rerank inference) on the
+/// runtime's blocking pool so the async workers stay responsive.
+async fn run_blocking<T, F>(work: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .unwrap_or_else(|e| Err(format!("FFI_ERROR: blocking task failed: {}", e)))
+}
+
+/// Drive upstream async inference (`embed`, `embed_query`, `embed_file`) to
+/// completion on the blocking pool. Those futures run the model inside
+/// `poll`, so awaiting them on an async worker would stall every other task
+/// scheduled there.
+///
+/// # Errors
+/// "FFI_ERROR:" if the blocking task panicked.
+async fn run_inference<T, Fut>(work: Fut) -> Result<T, String>
+where
+    Fut: Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    run_blocking(move || Ok(tokio::runtime::Handle::current().block_on(work))).await
+}
+
+/// `embed_query` on the blocking pool (see `run_inference`).
+async fn embed_query_blocking(
+    embedder: &Arc<Embedder>,
+    texts: &[&str],
+) -> anyhow::Result<Vec<EmbedData>> {
+    let embedder = embedder.clone();
+    let texts: Vec<String> = texts.iter().map(|text| text.to_string()).collect();
+    run_inference(async move {
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        embedder.embed_query(&texts, None).await
+    })
+    .await
+    .unwrap_or_else(|e| Err(anyhow::anyhow!(e)))
+}
+
+/// Move a running operation to its final status and notify its callback.
+///
+/// A no-op once the operation has finished (e.g. after its deadline passed),
+/// so the first final status wins.
+fn finish_operation(op_id: i64, status: AsyncOperationStatus, result: Option<AsyncResultData>) {
+    let callback = {
+        let mut ops = ASYNC_OPERATIONS.lock().unwrap();
+        match ops.get_mut(&op_id) {
+            Some(op) if matches!(op.status, AsyncOperationStatus::InProgress) => {
+                op.status = status;
+                op.result = result;
+                op.finished_at = Some(Instant::now());
+                // Ends the deadline timer, if any
+                op.done.cancel();
+                if !matches!(op.status, AsyncOperationStatus::Success) {
+                    // Stops leftover work, e.g. after the deadline passed
+                    op.cancel_token.cancel();
+                }
+                op.on_complete
+                    .take()
+                    .map(|callback| (callback, poll_status(&op.status)))
+            }
+            _ => None,
+        }
+    };
+    // Work that finished before getting a slot can no longer be reprioritized,
+    // unless a batch still waits under it
+    let mut scheduler = SCHEDULER.lock().unwrap();
+    if !scheduler.batch_leaders.contains_key(&op_id) {
+        scheduler.priorities.remove(&op_id);
+    }
+    drop(scheduler);
+
+    // Call outside the lock so the callback may poll right away
+    if let Some((callback, status)) = callback {
+        callback.notify(op_id, status);
+    }
+}
+
 /// Store a successful result in the registry.
 fn store_success(op_id: i64, result: AsyncResultData) {
-    let mut ops = ASYNC_OPERATIONS.lock().unwrap();
-    if let Some(op) = ops.get_mut(&op_id) {
-        op.status = AsyncOperationStatus::Success;
-        op.result = Some(result);
-    }
+    finish_operation(op_id, AsyncOperationStatus::Success, Some(result));
 }
 
 /// Store an error result in the registry.
 fn store_error(op_id: i64, error: String) {
-    let mut ops = ASYNC_OPERATIONS.lock().unwrap();
-    if let Some(op) = ops.get_mut(&op_id) {
-        op.status = AsyncOperationStatus::Error(error);
-    }
+    finish_operation(op_id, AsyncOperationStatus::Error(error), None);
 }
 
 /// Store a cancelled result in the registry.
 fn store_cancelled(op_id: i64) {
+    finish_operation(op_id, AsyncOperationStatus::Cancelled, None);
+}
+
+/// Fail a still-running operation with "TIMEOUT:" and cancel its work.
+fn store_timeout(op_id: i64, timeout_ms: u64) {
+    let error = format!(
+        "TIMEOUT: operation {} did not finish within {} ms",
+        op_id, timeout_ms
+    );
+    finish_operation(op_id, AsyncOperationStatus::Error(error), None);
+}
+
+/// Status code reported by poll_async_result for an operation status.
+fn poll_status(status: &AsyncOperationStatus) -> i32 {
+    match status {
+        AsyncOperationStatus::InProgress => 0,
+        AsyncOperationStatus::Success => 1,
+        AsyncOperationStatus::Error(_) => -1,
+        AsyncOperationStatus::Cancelled => -2,
+    }
+}
+
+/// Append entries produced so far by a running operation.
+fn append_partial(op_id: i64, items: Vec<EmbedData>) {
     let mut ops = ASYNC_OPERATIONS.lock().unwrap();
     if let Some(op) = ops.get_mut(&op_id) {
-        op.status = AsyncOperationStatus::Cancelled;
+        op.partial.extend(items);
+    }
+}
+
+/// Take the entries not yet drained, or None if the operation is unknown.
+fn take_partial(op_id: i64) -> Option<Vec<EmbedData>> {
+    let mut ops = ASYNC_OPERATIONS.lock().unwrap();
+    ops.get_mut(&op_id)
+        .map(|op| std::mem::take(&mut op.partial))
+}
+
+/// Take the entries not yet drained for a consumer streaming them, waking
+/// a walk paused in wait_for_drain.
+fn drain_partial(op_id: i64) -> Option<Vec<EmbedData>> {
+    let mut ops = ASYNC_OPERATIONS.lock().unwrap();
+    let op = ops.get_mut(&op_id)?;
+    op.drained
+        .get_or_insert_with(|| Arc::new(Notify::new()))
+        .notify_one();
+    Some(std::mem::take(&mut op.partial))
+}
+
+/// Undrained buffers a directory job may park before its walk pauses.
+const MAX_UNDRAINED_BUFFERS: usize = 2;
+
+/// Wait while an operation holds more than `limit` undrained entries.
+///
+/// Only operations that have been drained at least once wait; a caller that
+/// collects everything from poll_async_result never drains, so its walk runs
+/// to the end. Returns early on cancellation or once the operation is gone.
+async fn wait_for_drain(op_id: i64, limit: usize, cancel_token: &CancellationToken) {
+    loop {
+        let drained = {
+            let ops = ASYNC_OPERATIONS.lock().unwrap();
+            match ops.get(&op_id) {
+                Some(op) if op.partial.len() > limit => match &op.drained {
+                    Some(drained) => drained.clone(),
+                    None => return,
+                },
+                _ => return,
+            }
+        };
+
+        // notify_one keeps a permit, so a drain between the check above and
+        // this wait is not lost
+        tokio::select! {
+            _ = drained.notified() => {}
+            _ = cancel_token.cancelled() => return,
+        }
     }
 }
 
+/// Texts per upstream call between cancellation checks when the embedder
+/// has no batch size.
+const CANCEL_CHECK_BATCH_SIZE: usize = 32;
+
+/// Embed texts one batch at a time so cancellation is honoured mid-job.
+/// Runs on the blocking pool (see `run_inference`).
+///
+/// Returns `None` if the token fired between batches.
+async fn embed_in_batches(
+    embedder: &Arc<Embedder>,
+    texts: &[&str],
+    batch_size: Option<usize>,
+    cancel_token: &CancellationToken,
+) -> anyhow::Result<Option<Vec<EmbeddingResult>>> {
+    let embedder = embedder.clone();
+    let texts: Vec<String> = texts.iter().map(|text| text.to_string()).collect();
+    let cancel_token = cancel_token.clone();
+
+    run_inference(async move {
+        let chunk_size = batch_size.unwrap_or(CANCEL_CHECK_BATCH_SIZE).max(1);
+        let mut results = Vec::with_capacity(texts.len());
+
+        for chunk in texts.chunks(chunk_size) {
+            if cancel_token.is_cancelled() {
+                return Ok(None);
+            }
+            let chunk: Vec<&str> = chunk.iter().map(String::as_str).collect();
+            results.extend(embedder.embed(&chunk, batch_size, None).await?);
+        }
+        Ok(Some(results))
+    })
+    .await
+    .unwrap_or_else(|e| Err(anyhow::anyhow!(e)))
+}
+
 /// Register a new async operation and return its ID.
 fn register_operation() -> (i64, CancellationToken) {
     let op_id = NEXT_OPERATION_ID.fetch_add(1, Ordering::SeqCst);
     let cancel_token = CancellationToken::new();
+    START_REAPER.call_once(start_reaper);
 
-    {
+    let expired = {
         let mut ops = ASYNC_OPERATIONS.lock().unwrap();
+        let expired = reap_expired(&mut ops, Instant::now());
         ops.insert(
             op_id,
             AsyncOperation {
                 status: AsyncOperationStatus::InProgress,
                 result: None,
                 cancel_token: cancel_token.clone(),
+                done: cancel_token.child_token(),
+                progress: None,
+                partial: Vec::new(),
+                drained: None,
+                on_complete: None,
+                created_at: Instant::now(),
+                finished_at: None,
             },
         );
-    }
+        expired
+    };
+    // Outside the registry lock, like discard_async_operation
+    drop(expired);
 
     (op_id, cancel_token)
 }
 
+/// Drop finished operations nobody polled within the result TTL.
+///
+/// Runs whenever an operation is registered or listed, and every
+/// `REAP_INTERVAL` on the runtime, so abandoned results (including loaded
+/// models) do not pile up even when no new operations are started.
+///
+/// Returns the expired entries so the caller can drop them (and any loaded
+/// model) after releasing the registry lock.
+#[must_use]
+fn reap_expired(ops: &mut HashMap<i64, AsyncOperation>, now: Instant) -> Vec<AsyncOperation> {
+    let ttl_ms = RESULT_TTL_MS.load(Ordering::Relaxed);
+    if ttl_ms == 0 {
+        return Vec::new();
+    }
+
+    let ttl = Duration::from_millis(ttl_ms);
+    let expired: Vec<i64> = ops
+        .iter()
+        .filter(|(_, op)| {
+            op.finished_at
+                .is_some_and(|finished_at| now.duration_since(finished_at) >= ttl)
+        })
+        .map(|(&op_id, _)| op_id)
+        .collect();
+    expired
+        .iter()
+        .filter_map(|op_id| ops.remove(op_id))
+        .collect()
+}
+
+/// Spawn the periodic `reap_expired` sweep on the runtime.
+fn start_reaper() {
+    RUNTIME.spawn(async {
+        let mut interval = tokio::time::interval(REAP_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            // The guard is released at the end of the statement
+            let expired = reap_expired(&mut ASYNC_OPERATIONS.lock().unwrap(), Instant::now());
+            drop(expired);
+        }
+    });
+}
+
+/// Register a new operation that reports progress via `poll_async_progress`.
+fn register_tracked_operation() -> (i64, CancellationToken, Arc<directory::Progress>) {
+    let (op_id, cancel_token) = register_operation();
+    let progress = Arc::new(directory::Progress::default());
+
+    let mut ops = ASYNC_OPERATIONS.lock().unwrap();
+    if let Some(op) = ops.get_mut(&op_id) {
+        op.progress = Some(progress.clone());
+    }
+
+    (op_id, cancel_token, progress)
+}
+
 /// Convert Vec<f32> to C pointer with ownership transfer.
 /// The caller must free this memory.
 fn vec_to_c_ptr(vec: Vec<f32>) -> (*mut f32, usize) {
@@ -184,6 +566,468 @@ fn vec_to_c_ptr(vec: Vec<f32>) -> (*mut f32, usize) {
     (ptr, len)
 }
 
+// ============================================================================
+// Scheduler
+// ============================================================================
+
+/// Priority of background work such as batches, files and directories.
+pub const PRIORITY_BACKGROUND: i32 = 0;
+
+/// Priority of interactive single queries; runs before background work.
+pub const PRIORITY_INTERACTIVE: i32 = 1;
+
+/// Passed as a start_* `priority` to keep that call's default priority.
+pub const PRIORITY_DEFAULT: i32 = -1;
+
+/// Operations one embedder runs at once unless configured otherwise.
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 4;
+
+/// Operation waiting for a slot on its lane.
+struct Waiter {
+    op_id: i64,
+    seq: u64,
+    wake: oneshot::Sender<()>,
+}
+
+/// Running and waiting operations of one embedder (or reranker).
+#[derive(Default)]
+struct Lane {
+    running: usize,
+    waiting: Vec<Waiter>,
+}
+
+/// Per-embedder admission control. Each embedder is a lane with a maximum
+/// number of operations in flight; waiters are admitted by priority, then
+/// in arrival order.
+#[derive(Default)]
+struct Scheduler {
+    lanes: HashMap<usize, Lane>,
+    limits: HashMap<usize, usize>,
+    /// Priority of every operation that has not got its slot yet
+    priorities: HashMap<i64, i32>,
+    /// Leader of every coalesced request whose batch waits for a slot
+    /// (the batch queues under its leader's op_id)
+    batch_leaders: HashMap<i64, i64>,
+    next_seq: u64,
+}
+
+impl Scheduler {
+    fn limit(&self, lane: usize) -> usize {
+        self.limits
+            .get(&lane)
+            .copied()
+            .unwrap_or(DEFAULT_MAX_IN_FLIGHT)
+    }
+
+    /// Sort key: higher priority first, then earlier arrival.
+    fn rank(&self, waiter: &Waiter) -> (std::cmp::Reverse<i32>, u64) {
+        let priority = self
+            .priorities
+            .get(&waiter.op_id)
+            .copied()
+            .unwrap_or(PRIORITY_BACKGROUND);
+        (std::cmp::Reverse(priority), waiter.seq)
+    }
+
+    /// Hand free slots of `lane` to the best waiters still listening.
+    fn admit(&mut self, lane: usize) {
+        let limit = self.limit(lane);
+        loop {
+            let Some(entry) = self.lanes.get(&lane) else {
+                return;
+            };
+            if entry.running >= limit || entry.waiting.is_empty() {
+                break;
+            }
+
+            let best = (0..entry.waiting.len())
+                .min_by_key(|&i| self.rank(&entry.waiting[i]))
+                .unwrap();
+            let entry = self.lanes.get_mut(&lane).unwrap();
+            let waiter = entry.waiting.remove(best);
+            if waiter.wake.send(()).is_ok() {
+                entry.running += 1;
+            }
+            self.priorities.remove(&waiter.op_id);
+        }
+
+        if let Some(entry) = self.lanes.get(&lane) {
+            if entry.running == 0 && entry.waiting.is_empty() {
+                self.lanes.remove(&lane);
+            }
+        }
+    }
+
+    /// 1-based position of a waiting operation, 0 if it is not waiting.
+    fn queue_position(&self, op_id: i64) -> usize {
+        let op_id = self.batch_leaders.get(&op_id).copied().unwrap_or(op_id);
+        for entry in self.lanes.values() {
+            if let Some(waiter) = entry.waiting.iter().find(|w| w.op_id == op_id) {
+                let rank = self.rank(waiter);
+                return 1 + entry
+                    .waiting
+                    .iter()
+                    .filter(|other| self.rank(other) < rank)
+                    .count();
+            }
+        }
+        0
+    }
+}
+
+lazy_static! {
+    static ref SCHEDULER: Mutex<Scheduler> = Mutex::new(Scheduler::default());
+}
+
+/// A running slot on a lane; dropping it admits the next waiter.
+pub(crate) struct Slot {
+    lane: usize,
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        let mut scheduler = SCHEDULER.lock().unwrap();
+        if let Some(entry) = scheduler.lanes.get_mut(&self.lane) {
+            entry.running = entry.running.saturating_sub(1);
+        }
+        scheduler.admit(self.lane);
+    }
+}
+
+/// Lane of an embedder or reranker: the address of its shared instance.
+pub fn lane_of<T>(instance: &Arc<T>) -> usize {
+    Arc::as_ptr(instance) as *const () as usize
+}
+
+/// Priority a start_* call asked for, or its `default` for PRIORITY_DEFAULT.
+fn priority_or(priority: i32, default: i32) -> i32 {
+    if priority == PRIORITY_DEFAULT {
+        default
+    } else {
+        priority
+    }
+}
+
+/// Record the priority of an operation before its task is spawned.
+fn queue_operation(op_id: i64, priority: i32) {
+    SCHEDULER.lock().unwrap().priorities.insert(op_id, priority);
+}
+
+/// Wait for a slot on `lane`, or `None` if the operation is cancelled first.
+async fn acquire_slot(lane: usize, op_id: i64, cancel_token: &CancellationToken) -> Option<Slot> {
+    let woken = {
+        let mut scheduler = SCHEDULER.lock().unwrap();
+        if cancel_token.is_cancelled() {
+            scheduler.priorities.remove(&op_id);
+            return None;
+        }
+
+        let seq = scheduler.next_seq;
+        scheduler.next_seq += 1;
+        let (wake, woken) = oneshot::channel();
+        scheduler
+            .lanes
+            .entry(lane)
+            .or_default()
+            .waiting
+            .push(Waiter { op_id, seq, wake });
+        scheduler.admit(lane);
+        woken
+    };
+
+    // Kept alive across the select, so a slot handed over while the
+    // cancellation is handled is never lost or counted twice
+    tokio::pin!(woken);
+    tokio::select! {
+        result = &mut woken => result.ok().map(|_| Slot { lane }),
+        _ = cancel_token.cancelled() => {
+            let mut scheduler = SCHEDULER.lock().unwrap();
+            scheduler.priorities.remove(&op_id);
+            let waiting = scheduler
+                .lanes
+                .get_mut(&lane)
+                .and_then(|entry| {
+                    let index = entry.waiting.iter().position(|w| w.op_id == op_id)?;
+                    Some(entry.waiting.remove(index))
+                });
+            if waiting.is_some() {
+                scheduler.admit(lane);
+            } else if woken.try_recv().is_ok() {
+                // Admitted while being cancelled: give the slot back
+                drop(scheduler);
+                drop(Slot { lane });
+            }
+            None
+        }
+    }
+}
+
+/// Wait for a slot on another embedder's lane for an operation that already
+/// holds one on its own (the image batches of a routed directory job).
+pub(crate) async fn acquire_lane_slot(
+    slot: directory::LaneSlot,
+    cancel_token: &CancellationToken,
+) -> Option<Slot> {
+    queue_operation(slot.op_id, slot.priority);
+    acquire_slot(slot.lane, slot.op_id, cancel_token).await
+}
+
+// ============================================================================
+// Micro-Batching
+// ============================================================================
+
+/// Micro-batching settings of one embedder.
+#[derive(Debug, Clone, Copy)]
+struct MicroBatchConfig {
+    max_batch: usize,
+    max_wait: Duration,
+}
+
+/// start_embed_text request waiting to be coalesced.
+struct PendingText {
+    op_id: i64,
+    priority: i32,
+    text: String,
+    cancel_token: CancellationToken,
+    cache: TextCache,
+}
+
+/// Requests collected for one embedder since its window opened.
+struct PendingBatch {
+    generation: u64,
+    items: Vec<PendingText>,
+}
+
+/// What the caller of `MicroBatcher::push` has to do next.
+enum Push {
+    /// Micro-batching is off for this lane; run the request on its own.
+    Disabled(PendingText),
+    /// The request opened a window; flush `generation` after `max_wait`.
+    OpenWindow { generation: u64, max_wait: Duration },
+    /// The request joined an open window.
+    Queued,
+    /// The batch is full; run it now.
+    Flush(Vec<PendingText>),
+}
+
+/// Coalesces concurrent single-text requests per embedder lane.
+#[derive(Default)]
+struct MicroBatcher {
+    configs: HashMap<usize, MicroBatchConfig>,
+    pending: HashMap<usize, PendingBatch>,
+    next_generation: u64,
+}
+
+impl MicroBatcher {
+    fn push(&mut self, lane: usize, item: PendingText) -> Push {
+        let Some(config) = self.configs.get(&lane).copied() else {
+            return Push::Disabled(item);
+        };
+
+        let opened = !self.pending.contains_key(&lane);
+        let generation = self.next_generation;
+        let batch = self.pending.entry(lane).or_insert_with(|| PendingBatch {
+            generation,
+            items: Vec::new(),
+        });
+        batch.items.push(item);
+
+        if batch.items.len() >= config.max_batch {
+            return Push::Flush(self.pending.remove(&lane).unwrap().items);
+        }
+        if opened {
+            self.next_generation += 1;
+            return Push::OpenWindow {
+                generation,
+                max_wait: config.max_wait,
+            };
+        }
+        Push::Queued
+    }
+
+    /// Change the priority of a request still waiting in a window; false if
+    /// no window holds it.
+    fn set_priority(&mut self, op_id: i64, priority: i32) -> bool {
+        let item = self
+            .pending
+            .values_mut()
+            .flat_map(|batch| batch.items.iter_mut())
+            .find(|item| item.op_id == op_id);
+        match item {
+            Some(item) => {
+                item.priority = priority;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Take the batch of a window whose wait elapsed, unless it was
+    /// already flushed because it filled up.
+    fn take(&mut self, lane: usize, generation: u64) -> Vec<PendingText> {
+        match self.pending.get(&lane) {
+            Some(batch) if batch.generation == generation => {
+                self.pending.remove(&lane).unwrap().items
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+lazy_static! {
+    static ref MICRO_BATCHER: Mutex<MicroBatcher> = Mutex::new(MicroBatcher::default());
+}
+
+/// Hand a start_embed_text request to the micro-batcher of its lane.
+///
+/// Gives the request back if micro-batching is off for the embedder.
+fn enqueue_text(
+    lane: usize,
+    embedder: &Arc<Embedder>,
+    options: EmbedderOptions,
+    item: PendingText,
+) -> Result<(), PendingText> {
+    let push = MICRO_BATCHER.lock().unwrap().push(lane, item);
+    match push {
+        Push::Disabled(item) => return Err(item),
+        Push::OpenWindow {
+            generation,
+            max_wait,
+        } => {
+            let embedder = embedder.clone();
+            RUNTIME.spawn(async move {
+                tokio::time::sleep(max_wait).await;
+                let items = MICRO_BATCHER.lock().unwrap().take(lane, generation);
+                run_text_batch(lane, embedder, options, items).await;
+            });
+        }
+        Push::Queued => {}
+        Push::Flush(items) => {
+            RUNTIME.spawn(run_text_batch(lane, embedder.clone(), options, items));
+        }
+    }
+    Ok(())
+}
+
+/// Wait for one slot for a whole batch, queued under its first request
+/// with the highest priority of its requests.
+///
+/// Gives up (`None`) only once every request is cancelled or past its
+/// deadline; meanwhile each request reports the batch's queue position.
+async fn acquire_batch_slot(lane: usize, items: &[PendingText]) -> Option<Slot> {
+    let leader = items[0].op_id;
+    {
+        let mut scheduler = SCHEDULER.lock().unwrap();
+        for item in items {
+            scheduler.batch_leaders.insert(item.op_id, leader);
+        }
+        let priority = items.iter().map(|item| item.priority).max().unwrap();
+        scheduler.priorities.insert(leader, priority);
+    }
+
+    // Deadlines cancel their operation's token, so this covers both
+    let all_cancelled = CancellationToken::new();
+    let watcher = tokio::spawn({
+        let tokens: Vec<CancellationToken> =
+            items.iter().map(|item| item.cancel_token.clone()).collect();
+        let all_cancelled = all_cancelled.clone();
+        async move {
+            for token in &tokens {
+                token.cancelled().await;
+            }
+            all_cancelled.cancel();
+        }
+    });
+
+    let slot = acquire_slot(lane, leader, &all_cancelled).await;
+    watcher.abort();
+
+    // acquire_slot already dropped the leader's priority
+    let mut scheduler = SCHEDULER.lock().unwrap();
+    for item in items {
+        scheduler.batch_leaders.remove(&item.op_id);
+    }
+    slot
+}
+
+/// Embed coalesced requests with one `embed_query` call and fan the
+/// vectors back out to their operations.
+async fn run_text_batch(
+    lane: usize,
+    embedder: Arc<Embedder>,
+    options: EmbedderOptions,
+    items: Vec<PendingText>,
+) {
+    // Requests cancelled while waiting never reach the model
+    let (cancelled, items): (Vec<_>, Vec<_>) = items
+        .into_iter()
+        .partition(|item| item.cancel_token.is_cancelled());
+    for item in cancelled {
+        store_cancelled(item.op_id);
+    }
+    if items.is_empty() {
+        return;
+    }
+
+    let Some(_slot) = acquire_batch_slot(lane, &items).await else {
+        for item in &items {
+            store_cancelled(item.op_id);
+        }
+        return;
+    };
+
+    // Requests cancelled (or past their deadline) while the batch waited
+    let (cancelled, items): (Vec<_>, Vec<_>) = items
+        .into_iter()
+        .partition(|item| item.cancel_token.is_cancelled());
+    for item in cancelled {
+        store_cancelled(item.op_id);
+    }
+    if items.is_empty() {
+        return;
+    }
+
+    let texts: Vec<&str> = items.iter().map(|item| item.text.as_str()).collect();
+    let result = embed_query_blocking(&embedder, &texts)
+        .await
+        .map(|data| options.apply_to_data(data));
+
+    match result {
+        Ok(embed_data_vec) if embed_data_vec.len() == items.len() => {
+            for (item, embed_data) in items.iter().zip(embed_data_vec) {
+                if item.cancel_token.is_cancelled() {
+                    store_cancelled(item.op_id);
+                } else {
+                    store_query_embedding(item.op_id, Some(embed_data), &item.cache, &item.text);
+                }
+            }
+        }
+        Ok(embed_data_vec) => {
+            let error = format!(
+                "EMBEDDING_FAILED: embed_query returned {} results for {} texts",
+                embed_data_vec.len(),
+                items.len()
+            );
+            for item in &items {
+                store_error(item.op_id, error.clone());
+            }
+        }
+        Err(e) => {
+            let error = format!("EMBEDDING_FAILED: Text embedding generation failed: {}", e);
+            for item in &items {
+                store_error(item.op_id, error.clone());
+            }
+        }
+    }
+}
+
+/// Forget the scheduling and micro-batching settings of a lane (when its
+/// embedder is freed).
+pub fn forget_lane(lane: usize) {
+    SCHEDULER.lock().unwrap().limits.remove(&lane);
+    MICRO_BATCHER.lock().unwrap().configs.remove(&lane);
+}
+
 // ============================================================================
 // Async Model Loading
 // ============================================================================
@@ -191,18 +1035,26 @@ fn vec_to_c_ptr(vec: Vec<f32>) -> (*mut f32, usize) {
 /// Start loading a model asynchronously.
 ///
 /// # Parameters
+/// - model_type: Architecture to load (0=BERT, 1=Jina, 2=ModernBERT, 3=Qwen3,
+///   4=XLM-RoBERTa, 255=auto-detect from config.json)
 /// - model_id: Model identifier (e.g., "sentence-transformers/all-MiniLM-L6-v2")
 /// - revision: Git revision (e.g., "main"), or NULL for default
+/// - token: HuggingFace auth token, or NULL to use the token set via
+///   set_hf_token (if any)
 /// - dtype: Data type for model weights (0=F32, 1=F16, -1=default)
+/// - options: Pointer to CEmbedderOptions, or NULL for defaults
 ///
 /// # Returns
 /// Operation ID (positive) on success, -1 on immediate failure.
 #[no_mangle]
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn start_load_model(
+    model_type: u8,
     model_id: *const c_char,
     revision: *const c_char,
+    token: *const c_char,
     dtype: i32,
+    options: *const CEmbedderOptions,
 ) -> i64 {
     clear_last_error();
 
@@ -237,6 +1089,28 @@ pub extern "C" fn start_load_model(
         }
     };
 
+    let token_opt = if token.is_null() {
+        None
+    } else {
+        unsafe {
+            match CStr::from_ptr(token).to_str() {
+                Ok(s) => Some(s.to_string()),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: token: invalid UTF-8 encoding");
+                    return -1;
+                }
+            }
+        }
+    };
+
+    let architecture = match model_loader::ModelArchitecture::from_model_type(model_type) {
+        Ok(arch) => arch,
+        Err(e) => {
+            set_last_error(&e);
+            return -1;
+        }
+    };
+
     // Map dtype parameter to Dtype enum
     let dtype_opt = match dtype {
         0 => Some(Dtype::F32),
@@ -248,7 +1122,19 @@ pub extern "C" fn start_load_model(
         }
     };
 
-    // Register operation
+    let options = match EmbedderOptions::from_c(unsafe { options.as_ref() })
+        .and_then(|o| o.ensure_model_defaults().map(|_| o))
+    {
+        Ok(o) => o,
+        Err(e) => {
+            set_last_error(&e);
+            return -1;
+        }
+    };
+
+    let model = ModelFingerprint::new(&model_id_str, revision_opt.as_deref(), dtype);
+
+    // Register operation
     let (op_id, cancel_token) = register_operation();
 
     // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
@@ -259,14 +1145,17 @@ pub extern "C" fn start_load_model(
             return;
         }
 
-        // Load model (synchronous in EmbedAnything, but wrapped in spawn_blocking would be better)
-        // For now, run in async context - model loading is I/O bound (downloading)
-        let result = Embedder::from_pretrained_hf(
-            &model_id_str,
-            revision_opt.as_deref(),
-            None, // token
-            dtype_opt,
-        );
+        // Load model (synchronous in EmbedAnything) on the blocking pool
+        let result = run_blocking(move || {
+            model_loader::load_from_hf(
+                &model_id_str,
+                revision_opt.as_deref(),
+                token_opt.as_deref(),
+                architecture,
+                dtype_opt,
+            )
+        })
+        .await;
 
         // Check cancellation after loading
         if cancel_token.is_cancelled() {
@@ -276,28 +1165,127 @@ pub extern "C" fn start_load_model(
 
         // Store result
         match result {
-            Ok(embedder) => {
+            Ok(loaded) => {
                 store_success(
                     op_id,
                     AsyncResultData::ModelLoad(ModelLoadResult {
-                        embedder: Arc::new(embedder),
+                        embedder: Arc::new(loaded.embedder),
+                        options,
+                        model,
+                        sparse: loaded.sparse,
                     }),
                 );
             }
-            Err(e) => {
-                let error_str = e.to_string().to_lowercase();
-                if error_str.contains("404") {
-                    store_error(op_id, format!("MODEL_NOT_FOUND: {}", model_id_str));
-                } else {
-                    store_error(
-                        op_id,
-                        format!(
-                            "EMBEDDING_FAILED: Failed to load model '{}': {}",
-                            model_id_str, e
-                        ),
-                    );
-                }
+            Err(e) => store_error(op_id, e),
+        }
+    });
+
+    op_id
+}
+
+/// Start loading a model from a local directory asynchronously.
+///
+/// See `embedder_from_local_path` for the expected directory layout and
+/// error prefixes.
+///
+/// # Parameters
+/// - model_path: Path to the model directory
+/// - dtype: Data type for model weights (0=F32, 1=F16, -1=default)
+/// - options: Pointer to CEmbedderOptions, or NULL for defaults
+///
+/// # Returns
+/// Operation ID (positive) on success, -1 on immediate failure.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn start_load_model_from_local_path(
+    model_path: *const c_char,
+    dtype: i32,
+    options: *const CEmbedderOptions,
+) -> i64 {
+    clear_last_error();
+
+    // Validate inputs
+    if model_path.is_null() {
+        set_last_error("INVALID_CONFIG: model_path: cannot be null");
+        return -1;
+    }
+
+    let model_path_buf = unsafe {
+        match CStr::from_ptr(model_path).to_str() {
+            Ok(s) => PathBuf::from(s),
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: model_path: invalid UTF-8 encoding");
+                return -1;
+            }
+        }
+    };
+
+    // Map dtype parameter to Dtype enum
+    let dtype_opt = match dtype {
+        0 => Some(Dtype::F32),
+        1 => Some(Dtype::F16),
+        -1 => None,
+        _ => {
+            set_last_error(&format!("INVALID_CONFIG: dtype: invalid value {}", dtype));
+            return -1;
+        }
+    };
+
+    let options = match EmbedderOptions::from_c(unsafe { options.as_ref() }) {
+        Ok(o) => o,
+        Err(e) => {
+            set_last_error(&e);
+            return -1;
+        }
+    };
+
+    let model = ModelFingerprint::new(&model_path_buf.to_string_lossy(), None, dtype);
+
+    // Register operation
+    let (op_id, cancel_token) = register_operation();
+
+    // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
+    RUNTIME.spawn(async move {
+        // Check cancellation before starting
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        let result = run_blocking(move || {
+            model_loader::load_from_local_dir(
+                &model_path_buf,
+                dtype_opt,
+                options.pooling,
+                options.max_sequence_length,
+            )
+            .map(|embedder| {
+                let content_hash = model_loader::local_dir_content_hash(&model_path_buf);
+                (embedder, model.with_content_hash(content_hash))
+            })
+        })
+        .await;
+
+        // Check cancellation after loading
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        // Store result
+        match result {
+            Ok((embedder, model)) => {
+                store_success(
+                    op_id,
+                    AsyncResultData::ModelLoad(ModelLoadResult {
+                        embedder: Arc::new(embedder),
+                        options,
+                        model,
+                        sparse: false,
+                    }),
+                );
             }
+            Err(e) => store_error(op_id, e),
         }
     });
 
@@ -313,12 +1301,17 @@ pub extern "C" fn start_load_model(
 /// # Parameters
 /// - embedder: Pointer to CEmbedder
 /// - text: Text to embed
+/// - priority: Scheduling priority, or PRIORITY_DEFAULT (-1) for PRIORITY_INTERACTIVE
 ///
 /// # Returns
 /// Operation ID (positive) on success, -1 on immediate failure.
 #[no_mangle]
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
-pub extern "C" fn start_embed_text(embedder: *const CEmbedder, text: *const c_char) -> i64 {
+pub extern "C" fn start_embed_text(
+    embedder: *const CEmbedder,
+    text: *const c_char,
+    priority: i32,
+) -> i64 {
     clear_last_error();
 
     // Validate inputs
@@ -333,6 +1326,7 @@ pub extern "C" fn start_embed_text(embedder: *const CEmbedder, text: *const c_ch
 
     // Clone Arc<Embedder> for thread
     let embedder_arc = unsafe { &*embedder }.inner.clone();
+    let options = unsafe { &*embedder }.options;
 
     // Convert C string to Rust string
     let text_str = unsafe {
@@ -347,59 +1341,59 @@ pub extern "C" fn start_embed_text(embedder: *const CEmbedder, text: *const c_ch
 
     // Register operation
     let (op_id, cancel_token) = register_operation();
+    let lane = lane_of(&embedder_arc);
+
+    // Repeated texts finish immediately from the cache
+    let cache = unsafe { &*embedder }.text_cache();
+    if let Some(values) = cache.get(CacheKind::Query, &text_str) {
+        store_success(
+            op_id,
+            AsyncResultData::SingleEmbedding(SingleEmbeddingResult { values }),
+        );
+        return op_id;
+    }
+
+    // Coalesce with other pending requests if micro-batching is enabled
+    let priority = priority_or(priority, PRIORITY_INTERACTIVE);
+    let pending = PendingText {
+        op_id,
+        priority,
+        text: text_str,
+        cancel_token,
+        cache,
+    };
+    let pending = match enqueue_text(lane, &embedder_arc, options, pending) {
+        Ok(()) => return op_id,
+        Err(pending) => pending,
+    };
+    queue_operation(op_id, priority);
 
     // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
     RUNTIME.spawn(async move {
-        // Check cancellation
-        if cancel_token.is_cancelled() {
+        // Wait for a slot on this embedder; cancellation ends the wait
+        let Some(_slot) = acquire_slot(lane, op_id, &pending.cancel_token).await else {
             store_cancelled(op_id);
             return;
-        }
+        };
 
-        // Run embedding directly in async context
-        let result = embedder_arc.embed_query(&[&text_str], None).await;
+        let result = embed_query_blocking(&embedder_arc, &[&pending.text])
+            .await
+            .map(|data| options.apply_to_data(data));
 
         // Check cancellation
-        if cancel_token.is_cancelled() {
+        if pending.cancel_token.is_cancelled() {
             store_cancelled(op_id);
             return;
         }
 
         // Process result
         match result {
-            Ok(embed_data_vec) => {
-                if embed_data_vec.is_empty() {
-                    store_error(op_id, "EMBEDDING_FAILED: embed_query returned empty result".to_string());
-                    return;
-                }
-
-                let embed_data = &embed_data_vec[0];
-
-                // Extract vector from EmbeddingResult enum
-                match &embed_data.embedding {
-                    EmbeddingResult::DenseVector(vec) => {
-                        if vec.is_empty() {
-                            store_error(
-                                op_id,
-                                "EMBEDDING_FAILED: Generated embedding vector is empty".to_string(),
-                            );
-                            return;
-                        }
-                        store_success(
-                            op_id,
-                            AsyncResultData::SingleEmbedding(SingleEmbeddingResult {
-                                values: vec.clone(),
-                            }),
-                        );
-                    }
-                    EmbeddingResult::MultiVector(_) => {
-                        store_error(
-                            op_id,
-                            "MULTI_VECTOR: Multi-vector embeddings are not supported".to_string(),
-                        );
-                    }
-                }
-            }
+            Ok(embed_data_vec) => store_query_embedding(
+                op_id,
+                embed_data_vec.into_iter().next(),
+                &pending.cache,
+                &pending.text,
+            ),
             Err(e) => {
                 store_error(
                     op_id,
@@ -412,6 +1406,42 @@ pub extern "C" fn start_embed_text(embedder: *const CEmbedder, text: *const c_ch
     op_id
 }
 
+/// Store the dense vector of a single-text query as the operation result,
+/// and in the embedder's caches under `text`.
+fn store_query_embedding(op_id: i64, embed_data: Option<EmbedData>, cache: &TextCache, text: &str) {
+    let Some(embed_data) = embed_data else {
+        store_error(
+            op_id,
+            "EMBEDDING_FAILED: embed_query returned empty result".to_string(),
+        );
+        return;
+    };
+
+    // Extract vector from EmbeddingResult enum
+    match embed_data.embedding {
+        EmbeddingResult::DenseVector(vec) => {
+            if vec.is_empty() {
+                store_error(
+                    op_id,
+                    "EMBEDDING_FAILED: Generated embedding vector is empty".to_string(),
+                );
+                return;
+            }
+            cache.insert(CacheKind::Query, text, &vec);
+            store_success(
+                op_id,
+                AsyncResultData::SingleEmbedding(SingleEmbeddingResult { values: vec }),
+            );
+        }
+        EmbeddingResult::MultiVector(_) => {
+            store_error(
+                op_id,
+                "MULTI_VECTOR: Multi-vector embeddings are not supported".to_string(),
+            );
+        }
+    }
+}
+
 // ============================================================================
 // Async Batch Text Embedding
 // ============================================================================
@@ -422,6 +1452,7 @@ pub extern "C" fn start_embed_text(embedder: *const CEmbedder, text: *const c_ch
 /// - embedder: Pointer to CEmbedder
 /// - texts: Array of text pointers
 /// - count: Number of texts
+/// - priority: Scheduling priority, or PRIORITY_DEFAULT (-1) for PRIORITY_BACKGROUND
 ///
 /// # Returns
 /// Operation ID (positive) on success, -1 on immediate failure.
@@ -431,6 +1462,7 @@ pub extern "C" fn start_embed_texts_batch(
     embedder: *const CEmbedder,
     texts: *const *const c_char,
     count: usize,
+    priority: i32,
 ) -> i64 {
     clear_last_error();
 
@@ -450,6 +1482,7 @@ pub extern "C" fn start_embed_texts_batch(
 
     // Clone Arc<Embedder> for thread
     let embedder_arc = unsafe { &*embedder }.inner.clone();
+    let options = unsafe { &*embedder }.options;
 
     // Convert C string array to Rust Vec<String>
     let texts_slice = unsafe { std::slice::from_raw_parts(texts, count) };
@@ -473,22 +1506,52 @@ pub extern "C" fn start_embed_texts_batch(
         text_strings.push(text_str);
     }
 
+    // Only texts missing from the cache go to the model
+    let cache = unsafe { &*embedder }.text_cache();
+    let lookup = {
+        let text_refs: Vec<&str> = text_strings.iter().map(|s| s.as_str()).collect();
+        cache.lookup_batch(CacheKind::Document, &text_refs)
+    };
+
     // Register operation
     let (op_id, cancel_token) = register_operation();
+    let lane = lane_of(&embedder_arc);
+
+    // A fully cached batch finishes immediately
+    if lookup.miss_count() == 0 {
+        if let Some(embeddings) = lookup.into_cached() {
+            store_success(
+                op_id,
+                AsyncResultData::BatchEmbedding(BatchEmbeddingResult { embeddings }),
+            );
+        }
+        return op_id;
+    }
+    queue_operation(op_id, priority_or(priority, PRIORITY_BACKGROUND));
 
     // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
     RUNTIME.spawn(async move {
-        // Check cancellation
-        if cancel_token.is_cancelled() {
+        // Wait for a slot on this embedder; cancellation ends the wait
+        let Some(_slot) = acquire_slot(lane, op_id, &cancel_token).await else {
             store_cancelled(op_id);
             return;
-        }
+        };
 
         // Convert to Vec<&str> for embed function
         let text_refs: Vec<&str> = text_strings.iter().map(|s| s.as_str()).collect();
+        let miss_refs = lookup.miss_texts(&text_refs);
 
-        // Run embedding directly in async context
-        let result = embedder_arc.embed(&text_refs, None, None).await;
+        // Run embedding one batch at a time, checking cancellation in between
+        let batches =
+            embed_in_batches(&embedder_arc, &miss_refs, options.batch_size, &cancel_token).await;
+        let result = match batches {
+            Ok(Some(results)) => Ok(options.apply_to_results(results)),
+            Ok(None) => {
+                store_cancelled(op_id);
+                return;
+            }
+            Err(e) => Err(e),
+        };
 
         // Check cancellation
         if cancel_token.is_cancelled() {
@@ -525,10 +1588,21 @@ pub extern "C" fn start_embed_texts_batch(
                     }
                 }
 
-                store_success(
-                    op_id,
-                    AsyncResultData::BatchEmbedding(BatchEmbeddingResult { embeddings }),
-                );
+                let returned = embeddings.len();
+                match lookup.fill(&cache, CacheKind::Document, &text_refs, embeddings) {
+                    Some(embeddings) => store_success(
+                        op_id,
+                        AsyncResultData::BatchEmbedding(BatchEmbeddingResult { embeddings }),
+                    ),
+                    None => store_error(
+                        op_id,
+                        format!(
+                            "EMBEDDING_FAILED: embed returned {} results for {} texts",
+                            returned,
+                            miss_refs.len()
+                        ),
+                    ),
+                }
             }
             Err(e) => {
                 store_error(
@@ -546,86 +1620,75 @@ pub extern "C" fn start_embed_texts_batch(
 }
 
 // ============================================================================
-// Async File Embedding
+// Async Sparse Embedding
 // ============================================================================
 
-/// Start embedding a file asynchronously.
+/// Start embedding a single text with a sparse (SPLADE) model asynchronously.
 ///
 /// # Parameters
-/// - embedder: Embedder handle
-/// - file_path: Path to file (C string)
-/// - config: Pointer to CTextEmbedConfig
+/// - embedder: Pointer to CEmbedder (loaded from a sparse checkpoint)
+/// - text: Text to embed
+/// - priority: Scheduling priority, or PRIORITY_DEFAULT (-1) for PRIORITY_INTERACTIVE
 ///
 /// # Returns
 /// Operation ID (positive) on success, -1 on immediate failure.
+///
+/// # Error Prefixes
+/// - "INVALID_CONFIG: embedder:" - The embedder is not a sparse model
 #[no_mangle]
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
-pub extern "C" fn start_embed_file(
+pub extern "C" fn start_embed_text_sparse(
     embedder: *const CEmbedder,
-    file_path: *const c_char,
-    config: *const CTextEmbedConfig,
+    text: *const c_char,
+    priority: i32,
 ) -> i64 {
     clear_last_error();
 
-    // Validate pointers
+    // Validate inputs
     if embedder.is_null() {
         set_last_error("FFI_ERROR: embedder pointer is null");
         return -1;
     }
-    if file_path.is_null() {
-        set_last_error("INVALID_CONFIG: file_path: cannot be null");
+    if text.is_null() {
+        set_last_error("INVALID_CONFIG: text: cannot be null");
         return -1;
     }
-    if config.is_null() {
-        set_last_error("INVALID_CONFIG: config: cannot be null");
+
+    if let Err(e) = unsafe { &*embedder }.ensure_sparse() {
+        set_last_error(&e);
         return -1;
     }
 
+    // Clone Arc<Embedder> for thread
     let embedder_arc = unsafe { &*embedder }.inner.clone();
-    let config_ref = unsafe { &*config };
+    let options = unsafe { &*embedder }.options;
 
-    // Convert C string to Rust Path
-    let file_path_str = unsafe {
-        match CStr::from_ptr(file_path).to_str() {
+    let text_str = unsafe {
+        match CStr::from_ptr(text).to_str() {
             Ok(s) => s.to_string(),
             Err(_) => {
-                set_last_error("INVALID_CONFIG: file_path: invalid UTF-8 encoding");
+                set_last_error("INVALID_CONFIG: text: invalid UTF-8 encoding");
                 return -1;
             }
         }
     };
 
-    // Check if file exists before spawning thread
-    let path = PathBuf::from(&file_path_str);
-    if !path.exists() {
-        set_last_error(&format!("FILE_NOT_FOUND: {}", file_path_str));
-        return -1;
-    }
-
-    // Build TextEmbedConfig from CTextEmbedConfig
-    let text_config = TextEmbedConfig {
-        chunk_size: Some(config_ref.chunk_size),
-        overlap_ratio: Some(config_ref.overlap_ratio),
-        batch_size: Some(config_ref.batch_size),
-        buffer_size: Some(config_ref.buffer_size),
-        ..Default::default()
-    };
-
     // Register operation
     let (op_id, cancel_token) = register_operation();
+    let lane = lane_of(&embedder_arc);
+    queue_operation(op_id, priority_or(priority, PRIORITY_INTERACTIVE));
 
     // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
     RUNTIME.spawn(async move {
-        // Check cancellation
-        if cancel_token.is_cancelled() {
+        // Wait for a slot on this embedder; cancellation ends the wait
+        let Some(_slot) = acquire_slot(lane, op_id, &cancel_token).await else {
             store_cancelled(op_id);
             return;
-        }
+        };
 
-        // Run embedding directly in async context
-        let result = embedder_arc
-            .embed_file(path.clone(), Some(&text_config), None)
-            .await;
+        let result = embed_query_blocking(&embedder_arc, &[&text_str])
+            .await
+            .map(|data| options.apply_to_data(data));
 
         // Check cancellation
         if cancel_token.is_cancelled() {
@@ -635,62 +1698,1370 @@ pub extern "C" fn start_embed_file(
 
         // Process result
         match result {
-            Ok(Some(embed_data_vec)) => {
-                store_success(
+            Ok(embed_data_vec) => match embed_data_vec.first().map(|data| &data.embedding) {
+                Some(EmbeddingResult::DenseVector(vec)) => {
+                    let (indices, values) = dense_to_sparse(vec);
+                    store_success(
+                        op_id,
+                        AsyncResultData::SparseEmbedding(SparseEmbeddingResult { indices, values }),
+                    );
+                }
+                Some(EmbeddingResult::MultiVector(_)) => {
+                    store_error(
+                        op_id,
+                        "MULTI_VECTOR: Multi-vector embeddings cannot be returned as sparse"
+                            .to_string(),
+                    );
+                }
+                None => {
+                    store_error(
+                        op_id,
+                        "EMBEDDING_FAILED: embed_query returned empty result".to_string(),
+                    );
+                }
+            },
+            Err(e) => {
+                store_error(
                     op_id,
-                    AsyncResultData::FileEmbedding(FileEmbeddingResult {
-                        items: embed_data_vec,
-                    }),
+                    format!(
+                        "EMBEDDING_FAILED: Sparse embedding generation failed: {}",
+                        e
+                    ),
                 );
             }
-            Ok(None) => {
-                store_error(op_id, "EMBEDDING_FAILED: embed_file returned None".to_string());
-            }
-            Err(e) => {
-                let error_str = e.to_string().to_lowercase();
-                if error_str.contains("not found") || error_str.contains("no such file") {
-                    store_error(op_id, format!("FILE_NOT_FOUND: {}", file_path_str));
-                } else if error_str.contains("unsupported") || error_str.contains("format") {
-                    store_error(op_id, format!("UNSUPPORTED_FORMAT: {}", file_path_str));
-                } else if error_str.contains("permission") || error_str.contains("access denied") {
-                    store_error(op_id, format!("FILE_READ_ERROR: {}", e));
-                } else {
-                    store_error(op_id, format!("EMBEDDING_FAILED: {}", e));
-                }
-            }
         }
     });
 
     op_id
 }
 
-// ============================================================================
-// Async Directory Embedding
-// ============================================================================
-
-/// Start embedding a directory asynchronously.
+/// Start embedding multiple texts with a sparse (SPLADE) model asynchronously.
 ///
 /// # Parameters
-/// - embedder: Embedder handle
-/// - directory_path: Path to directory (C string)
-/// - extensions: NULL-terminated array of extension strings, or NULL for all files
-/// - extensions_count: Number of extensions (0 if extensions is NULL)
-/// - config: Pointer to CTextEmbedConfig
+/// - embedder: Pointer to CEmbedder (loaded from a sparse checkpoint)
+/// - texts: Array of text pointers
+/// - count: Number of texts
+/// - priority: Scheduling priority, or PRIORITY_DEFAULT (-1) for PRIORITY_BACKGROUND
 ///
 /// # Returns
 /// Operation ID (positive) on success, -1 on immediate failure.
+///
+/// # Error Prefixes
+/// - "INVALID_CONFIG: embedder:" - The embedder is not a sparse model
 #[no_mangle]
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
-pub extern "C" fn start_embed_directory(
+pub extern "C" fn start_embed_texts_batch_sparse(
     embedder: *const CEmbedder,
-    directory_path: *const c_char,
-    extensions: *const *const c_char,
-    extensions_count: usize,
-    config: *const CTextEmbedConfig,
+    texts: *const *const c_char,
+    count: usize,
+    priority: i32,
 ) -> i64 {
     clear_last_error();
 
-    // Validate pointers
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return -1;
+    }
+    if texts.is_null() {
+        set_last_error("INVALID_CONFIG: texts: cannot be null");
+        return -1;
+    }
+    if count == 0 {
+        set_last_error("INVALID_CONFIG: count: must be greater than 0");
+        return -1;
+    }
+    if let Err(e) = unsafe { &*embedder }.ensure_sparse() {
+        set_last_error(&e);
+        return -1;
+    }
+
+    // Clone Arc<Embedder> for thread
+    let embedder_arc = unsafe { &*embedder }.inner.clone();
+    let options = unsafe { &*embedder }.options;
+
+    // Convert C string array to Rust Vec<String>
+    let texts_slice = unsafe { std::slice::from_raw_parts(texts, count) };
+    let mut text_strings = Vec::with_capacity(count);
+
+    for &text_ptr in texts_slice {
+        if text_ptr.is_null() {
+            set_last_error("INVALID_CONFIG: texts: array contains null pointer");
+            return -1;
+        }
+
+        let text_str = unsafe {
+            match CStr::from_ptr(text_ptr).to_str() {
+                Ok(s) => s.to_string(),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: texts: array contains invalid UTF-8");
+                    return -1;
+                }
+            }
+        };
+        text_strings.push(text_str);
+    }
+
+    // Register operation
+    let (op_id, cancel_token) = register_operation();
+    let lane = lane_of(&embedder_arc);
+    queue_operation(op_id, priority_or(priority, PRIORITY_BACKGROUND));
+
+    // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
+    RUNTIME.spawn(async move {
+        // Wait for a slot on this embedder; cancellation ends the wait
+        let Some(_slot) = acquire_slot(lane, op_id, &cancel_token).await else {
+            store_cancelled(op_id);
+            return;
+        };
+
+        let text_refs: Vec<&str> = text_strings.iter().map(|s| s.as_str()).collect();
+        let batches =
+            embed_in_batches(&embedder_arc, &text_refs, options.batch_size, &cancel_token).await;
+        let result = match batches {
+            Ok(Some(results)) => Ok(options.apply_to_results(results)),
+            Ok(None) => {
+                store_cancelled(op_id);
+                return;
+            }
+            Err(e) => Err(e),
+        };
+
+        // Check cancellation
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        // Process result
+        match result {
+            Ok(embedding_results) => {
+                let mut embeddings = Vec::with_capacity(embedding_results.len());
+
+                for embedding_result in embedding_results {
+                    match embedding_result {
+                        EmbeddingResult::DenseVector(vec) => embeddings.push(dense_to_sparse(&vec)),
+                        EmbeddingResult::MultiVector(_) => {
+                            store_error(
+                                op_id,
+                                "MULTI_VECTOR: Multi-vector embeddings cannot be returned as sparse"
+                                    .to_string(),
+                            );
+                            return;
+                        }
+                    }
+                }
+
+                store_success(
+                    op_id,
+                    AsyncResultData::SparseBatchEmbedding(SparseBatchEmbeddingResult {
+                        embeddings,
+                    }),
+                );
+            }
+            Err(e) => {
+                store_error(
+                    op_id,
+                    format!(
+                        "EMBEDDING_FAILED: Sparse batch embedding generation failed for {} texts: {}",
+                        count, e
+                    ),
+                );
+            }
+        }
+    });
+
+    op_id
+}
+
+// ============================================================================
+// Async Multi-Vector Embedding
+// ============================================================================
+
+/// Start embedding a single text asynchronously, keeping one vector per token.
+///
+/// # Parameters
+/// - embedder: Pointer to CEmbedder
+/// - text: Text to embed
+/// - priority: Scheduling priority, or PRIORITY_DEFAULT (-1) for PRIORITY_INTERACTIVE
+///
+/// # Returns
+/// Operation ID (positive) on success, -1 on immediate failure.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn start_embed_text_multi_vector(
+    embedder: *const CEmbedder,
+    text: *const c_char,
+    priority: i32,
+) -> i64 {
+    clear_last_error();
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return -1;
+    }
+    if text.is_null() {
+        set_last_error("INVALID_CONFIG: text: cannot be null");
+        return -1;
+    }
+
+    // Clone Arc<Embedder> for thread
+    let embedder_arc = unsafe { &*embedder }.inner.clone();
+    let options = unsafe { &*embedder }.options;
+
+    let text_str = unsafe {
+        match CStr::from_ptr(text).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: text: invalid UTF-8 encoding");
+                return -1;
+            }
+        }
+    };
+
+    // Register operation
+    let (op_id, cancel_token) = register_operation();
+    let lane = lane_of(&embedder_arc);
+    queue_operation(op_id, priority_or(priority, PRIORITY_INTERACTIVE));
+
+    // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
+    RUNTIME.spawn(async move {
+        // Wait for a slot on this embedder; cancellation ends the wait
+        let Some(_slot) = acquire_slot(lane, op_id, &cancel_token).await else {
+            store_cancelled(op_id);
+            return;
+        };
+
+        let result = embed_query_blocking(&embedder_arc, &[&text_str])
+            .await
+            .map(|data| options.apply_to_data(data));
+
+        // Check cancellation
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        // Process result
+        match result {
+            Ok(mut embed_data_vec) => {
+                if embed_data_vec.is_empty() {
+                    store_error(
+                        op_id,
+                        "EMBEDDING_FAILED: embed_query returned empty result".to_string(),
+                    );
+                    return;
+                }
+
+                match flatten_embedding(embed_data_vec.swap_remove(0).embedding) {
+                    Ok((values, rows, dim)) => store_success(
+                        op_id,
+                        AsyncResultData::MultiVectorEmbedding(MultiVectorEmbeddingResult {
+                            values,
+                            rows,
+                            dim,
+                        }),
+                    ),
+                    Err(e) => store_error(op_id, e),
+                }
+            }
+            Err(e) => {
+                store_error(
+                    op_id,
+                    format!(
+                        "EMBEDDING_FAILED: Multi-vector embedding generation failed: {}",
+                        e
+                    ),
+                );
+            }
+        }
+    });
+
+    op_id
+}
+
+/// Start embedding multiple texts asynchronously, keeping one vector per token.
+///
+/// # Parameters
+/// - embedder: Pointer to CEmbedder
+/// - texts: Array of text pointers
+/// - count: Number of texts
+/// - priority: Scheduling priority, or PRIORITY_DEFAULT (-1) for PRIORITY_BACKGROUND
+///
+/// # Returns
+/// Operation ID (positive) on success, -1 on immediate failure.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn start_embed_texts_batch_multi_vector(
+    embedder: *const CEmbedder,
+    texts: *const *const c_char,
+    count: usize,
+    priority: i32,
+) -> i64 {
+    clear_last_error();
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return -1;
+    }
+    if texts.is_null() {
+        set_last_error("INVALID_CONFIG: texts: cannot be null");
+        return -1;
+    }
+    if count == 0 {
+        set_last_error("INVALID_CONFIG: count: must be greater than 0");
+        return -1;
+    }
+
+    // Clone Arc<Embedder> for thread
+    let embedder_arc = unsafe { &*embedder }.inner.clone();
+    let options = unsafe { &*embedder }.options;
+
+    // Convert C string array to Rust Vec<String>
+    let texts_slice = unsafe { std::slice::from_raw_parts(texts, count) };
+    let mut text_strings = Vec::with_capacity(count);
+
+    for &text_ptr in texts_slice {
+        if text_ptr.is_null() {
+            set_last_error("INVALID_CONFIG: texts: array contains null pointer");
+            return -1;
+        }
+
+        let text_str = unsafe {
+            match CStr::from_ptr(text_ptr).to_str() {
+                Ok(s) => s.to_string(),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: texts: array contains invalid UTF-8");
+                    return -1;
+                }
+            }
+        };
+        text_strings.push(text_str);
+    }
+
+    // Register operation
+    let (op_id, cancel_token) = register_operation();
+    let lane = lane_of(&embedder_arc);
+    queue_operation(op_id, priority_or(priority, PRIORITY_BACKGROUND));
+
+    // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
+    RUNTIME.spawn(async move {
+        // Wait for a slot on this embedder; cancellation ends the wait
+        let Some(_slot) = acquire_slot(lane, op_id, &cancel_token).await else {
+            store_cancelled(op_id);
+            return;
+        };
+
+        let text_refs: Vec<&str> = text_strings.iter().map(|s| s.as_str()).collect();
+        let batches =
+            embed_in_batches(&embedder_arc, &text_refs, options.batch_size, &cancel_token).await;
+        let result = match batches {
+            Ok(Some(results)) => Ok(options.apply_to_results(results)),
+            Ok(None) => {
+                store_cancelled(op_id);
+                return;
+            }
+            Err(e) => Err(e),
+        };
+
+        // Check cancellation
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        // Process result
+        match result {
+            Ok(embedding_results) => {
+                let flattened: Result<Vec<_>, String> = embedding_results
+                    .into_iter()
+                    .map(flatten_embedding)
+                    .collect();
+
+                match flattened {
+                    Ok(embeddings) => store_success(
+                        op_id,
+                        AsyncResultData::MultiVectorBatchEmbedding(
+                            MultiVectorBatchEmbeddingResult { embeddings },
+                        ),
+                    ),
+                    Err(e) => store_error(op_id, e),
+                }
+            }
+            Err(e) => {
+                store_error(
+                    op_id,
+                    format!(
+                        "EMBEDDING_FAILED: Multi-vector batch embedding generation failed for {} texts: {}",
+                        count, e
+                    ),
+                );
+            }
+        }
+    });
+
+    op_id
+}
+
+// ============================================================================
+// Async Reranking
+// ============================================================================
+
+/// Start loading a cross-encoder reranker asynchronously.
+///
+/// See `reranker_from_pretrained_hf` for the expected repo layout and
+/// error prefixes.
+///
+/// # Parameters
+/// - model_id: Model identifier (e.g., "cross-encoder/ms-marco-MiniLM-L-6-v2")
+/// - revision: Git revision (e.g., "main"), or NULL for default
+/// - token: HuggingFace auth token, or NULL to use the token set via
+///   set_hf_token (if any)
+///
+/// # Returns
+/// Operation ID (positive) on success, -1 on immediate failure.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn start_load_reranker(
+    model_id: *const c_char,
+    revision: *const c_char,
+    token: *const c_char,
+) -> i64 {
+    clear_last_error();
+
+    // Validate inputs
+    if model_id.is_null() {
+        set_last_error("INVALID_CONFIG: model_id: cannot be null");
+        return -1;
+    }
+
+    // Convert C strings to Rust strings
+    let model_id_str = unsafe {
+        match CStr::from_ptr(model_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: model_id: invalid UTF-8 encoding");
+                return -1;
+            }
+        }
+    };
+
+    let revision_opt = if revision.is_null() {
+        None
+    } else {
+        unsafe {
+            match CStr::from_ptr(revision).to_str() {
+                Ok(s) => Some(s.to_string()),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: revision: invalid UTF-8 encoding");
+                    return -1;
+                }
+            }
+        }
+    };
+
+    let token_opt = if token.is_null() {
+        None
+    } else {
+        unsafe {
+            match CStr::from_ptr(token).to_str() {
+                Ok(s) => Some(s.to_string()),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: token: invalid UTF-8 encoding");
+                    return -1;
+                }
+            }
+        }
+    };
+
+    // Register operation
+    let (op_id, cancel_token) = register_operation();
+
+    // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
+    RUNTIME.spawn(async move {
+        // Check cancellation before starting
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        let result = run_blocking(move || {
+            reranker::Reranker::from_pretrained_hf(
+                &model_id_str,
+                revision_opt.as_deref(),
+                token_opt.as_deref(),
+            )
+        })
+        .await;
+
+        // Check cancellation after loading
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        // Store result
+        match result {
+            Ok(reranker) => {
+                store_success(
+                    op_id,
+                    AsyncResultData::RerankerLoad(RerankerLoadResult {
+                        reranker: Arc::new(reranker),
+                    }),
+                );
+            }
+            Err(e) => store_error(op_id, e),
+        }
+    });
+
+    op_id
+}
+
+/// Start loading a cross-encoder reranker from a local directory asynchronously.
+///
+/// See `reranker_from_local_path` for the expected directory layout and
+/// error prefixes.
+///
+/// # Parameters
+/// - model_path: Path to the reranker directory
+///
+/// # Returns
+/// Operation ID (positive) on success, -1 on immediate failure.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn start_load_reranker_from_local_path(model_path: *const c_char) -> i64 {
+    clear_last_error();
+
+    // Validate inputs
+    if model_path.is_null() {
+        set_last_error("INVALID_CONFIG: model_path: cannot be null");
+        return -1;
+    }
+
+    let model_path_buf = unsafe {
+        match CStr::from_ptr(model_path).to_str() {
+            Ok(s) => PathBuf::from(s),
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: model_path: invalid UTF-8 encoding");
+                return -1;
+            }
+        }
+    };
+
+    // Register operation
+    let (op_id, cancel_token) = register_operation();
+
+    // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
+    RUNTIME.spawn(async move {
+        // Check cancellation before starting
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        let result =
+            run_blocking(move || reranker::Reranker::from_local_dir(&model_path_buf)).await;
+
+        // Check cancellation after loading
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        // Store result
+        match result {
+            Ok(reranker) => {
+                store_success(
+                    op_id,
+                    AsyncResultData::RerankerLoad(RerankerLoadResult {
+                        reranker: Arc::new(reranker),
+                    }),
+                );
+            }
+            Err(e) => store_error(op_id, e),
+        }
+    });
+
+    op_id
+}
+
+/// Start reranking documents against a query asynchronously.
+///
+/// # Parameters
+/// - reranker: Pointer to CReranker
+/// - query: Query text
+/// - documents: Array of document text pointers
+/// - count: Number of documents
+/// - top_k: Number of results to return (0 = all)
+/// - priority: Scheduling priority, or PRIORITY_DEFAULT (-1) for PRIORITY_INTERACTIVE
+///
+/// # Returns
+/// Operation ID (positive) on success, -1 on immediate failure.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn start_rerank(
+    reranker: *const CReranker,
+    query: *const c_char,
+    documents: *const *const c_char,
+    count: usize,
+    top_k: usize,
+    priority: i32,
+) -> i64 {
+    clear_last_error();
+
+    // Validate inputs
+    if reranker.is_null() {
+        set_last_error("FFI_ERROR: reranker pointer is null");
+        return -1;
+    }
+    if query.is_null() {
+        set_last_error("INVALID_CONFIG: query: cannot be null");
+        return -1;
+    }
+    if documents.is_null() {
+        set_last_error("INVALID_CONFIG: documents: cannot be null");
+        return -1;
+    }
+    if count == 0 {
+        set_last_error("INVALID_CONFIG: count: must be greater than 0");
+        return -1;
+    }
+
+    // Clone Arc<Reranker> for thread
+    let reranker_arc = unsafe { &*reranker }.inner.clone();
+
+    let query_str = unsafe {
+        match CStr::from_ptr(query).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: query: invalid UTF-8 encoding");
+                return -1;
+            }
+        }
+    };
+
+    // Convert C string array to Rust Vec<String>
+    let documents_slice = unsafe { std::slice::from_raw_parts(documents, count) };
+    let mut document_strings = Vec::with_capacity(count);
+
+    for &document_ptr in documents_slice {
+        if document_ptr.is_null() {
+            set_last_error("INVALID_CONFIG: documents: array contains null pointer");
+            return -1;
+        }
+
+        let document_str = unsafe {
+            match CStr::from_ptr(document_ptr).to_str() {
+                Ok(s) => s.to_string(),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: documents: array contains invalid UTF-8");
+                    return -1;
+                }
+            }
+        };
+        document_strings.push(document_str);
+    }
+
+    // Register operation
+    let (op_id, cancel_token) = register_operation();
+    let lane = lane_of(&reranker_arc);
+    queue_operation(op_id, priority_or(priority, PRIORITY_INTERACTIVE));
+
+    // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
+    RUNTIME.spawn(async move {
+        // Wait for a slot on this embedder; cancellation ends the wait
+        let Some(_slot) = acquire_slot(lane, op_id, &cancel_token).await else {
+            store_cancelled(op_id);
+            return;
+        };
+
+        let result = run_blocking(move || {
+            let document_refs: Vec<&str> = document_strings.iter().map(|s| s.as_str()).collect();
+            reranker_arc.rerank(&query_str, &document_refs, top_k)
+        })
+        .await;
+
+        // Check cancellation
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        // Store result
+        match result {
+            Ok(ranked) => store_success(op_id, AsyncResultData::Rerank(RerankResult { ranked })),
+            Err(e) => store_error(op_id, e),
+        }
+    });
+
+    op_id
+}
+
+// ============================================================================
+// Async Image Embedding
+// ============================================================================
+
+/// Start loading a CLIP/SigLIP vision model asynchronously.
+///
+/// See `vision_embedder_from_pretrained_hf` for error prefixes. The result
+/// is a CEmbedder (result_type 3).
+///
+/// # Parameters
+/// - model_id: Model identifier (e.g., "openai/clip-vit-base-patch32")
+/// - revision: Git revision (e.g., "main"), or NULL for default
+/// - token: HuggingFace auth token, or NULL to use the token set via
+///   set_hf_token (if any)
+/// - dtype: Data type for model weights (0=F32, 1=F16, -1=default)
+/// - options: Pointer to CEmbedderOptions, or NULL for defaults
+///
+/// # Returns
+/// Operation ID (positive) on success, -1 on immediate failure.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn start_load_vision_model(
+    model_id: *const c_char,
+    revision: *const c_char,
+    token: *const c_char,
+    dtype: i32,
+    options: *const CEmbedderOptions,
+) -> i64 {
+    clear_last_error();
+
+    // Validate inputs
+    if model_id.is_null() {
+        set_last_error("INVALID_CONFIG: model_id: cannot be null");
+        return -1;
+    }
+
+    // Convert C strings to Rust strings
+    let model_id_str = unsafe {
+        match CStr::from_ptr(model_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: model_id: invalid UTF-8 encoding");
+                return -1;
+            }
+        }
+    };
+
+    let revision_opt = if revision.is_null() {
+        None
+    } else {
+        unsafe {
+            match CStr::from_ptr(revision).to_str() {
+                Ok(s) => Some(s.to_string()),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: revision: invalid UTF-8 encoding");
+                    return -1;
+                }
+            }
+        }
+    };
+
+    let token_opt = if token.is_null() {
+        None
+    } else {
+        unsafe {
+            match CStr::from_ptr(token).to_str() {
+                Ok(s) => Some(s.to_string()),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: token: invalid UTF-8 encoding");
+                    return -1;
+                }
+            }
+        }
+    };
+
+    // Map dtype parameter to Dtype enum
+    let dtype_opt = match dtype {
+        0 => Some(Dtype::F32),
+        1 => Some(Dtype::F16),
+        -1 => None,
+        _ => {
+            set_last_error(&format!("INVALID_CONFIG: dtype: invalid value {}", dtype));
+            return -1;
+        }
+    };
+
+    let options = match EmbedderOptions::from_c(unsafe { options.as_ref() })
+        .and_then(|o| o.ensure_model_defaults().map(|_| o))
+    {
+        Ok(o) => o,
+        Err(e) => {
+            set_last_error(&e);
+            return -1;
+        }
+    };
+
+    let model = ModelFingerprint::new(&model_id_str, revision_opt.as_deref(), dtype);
+
+    // Register operation
+    let (op_id, cancel_token) = register_operation();
+
+    // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
+    RUNTIME.spawn(async move {
+        // Check cancellation before starting
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        let result = run_blocking(move || {
+            vision::load_from_hf(
+                &model_id_str,
+                revision_opt.as_deref(),
+                token_opt.as_deref(),
+                dtype_opt,
+            )
+        })
+        .await;
+
+        // Check cancellation after loading
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        // Store result
+        match result {
+            Ok(embedder) => {
+                store_success(
+                    op_id,
+                    AsyncResultData::ModelLoad(ModelLoadResult {
+                        embedder: Arc::new(embedder),
+                        options,
+                        model,
+                        sparse: false,
+                    }),
+                );
+            }
+            Err(e) => store_error(op_id, e),
+        }
+    });
+
+    op_id
+}
+
+/// Start embedding an image file asynchronously.
+///
+/// # Parameters
+/// - embedder: Pointer to a vision CEmbedder
+/// - image_path: Path to a PNG, JPEG, WebP, GIF or BMP file
+/// - priority: Scheduling priority, or PRIORITY_DEFAULT (-1) for PRIORITY_BACKGROUND
+///
+/// # Returns
+/// Operation ID (positive) on success, -1 on immediate failure.
+/// The result is a CTextEmbedding (result_type 10).
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn start_embed_image_file(
+    embedder: *const CEmbedder,
+    image_path: *const c_char,
+    priority: i32,
+) -> i64 {
+    clear_last_error();
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return -1;
+    }
+    if image_path.is_null() {
+        set_last_error("INVALID_CONFIG: image_path: cannot be null");
+        return -1;
+    }
+
+    // Clone Arc<Embedder> for thread
+    let embedder_arc = unsafe { &*embedder }.inner.clone();
+    let options = unsafe { &*embedder }.options;
+
+    let path = unsafe {
+        match CStr::from_ptr(image_path).to_str() {
+            Ok(s) => PathBuf::from(s),
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: image_path: invalid UTF-8 encoding");
+                return -1;
+            }
+        }
+    };
+
+    // Register operation
+    let (op_id, cancel_token) = register_operation();
+    let lane = lane_of(&embedder_arc);
+    queue_operation(op_id, priority_or(priority, PRIORITY_BACKGROUND));
+
+    // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
+    RUNTIME.spawn(async move {
+        // Wait for a slot on this embedder; cancellation ends the wait
+        let Some(_slot) = acquire_slot(lane, op_id, &cancel_token).await else {
+            store_cancelled(op_id);
+            return;
+        };
+
+        let result = run_blocking(move || vision::embed_image_files(&embedder_arc, &[path])).await;
+
+        // Check cancellation
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        // Store result
+        match result {
+            Ok(mut vectors) => store_success(
+                op_id,
+                AsyncResultData::ImageEmbedding(SingleEmbeddingResult {
+                    values: options.apply_to_vector(vectors.swap_remove(0)),
+                }),
+            ),
+            Err(e) => store_error(op_id, e),
+        }
+    });
+
+    op_id
+}
+
+/// Start embedding an encoded image held in memory asynchronously.
+///
+/// The bytes are copied before this function returns, so the caller may
+/// free `data` immediately.
+///
+/// # Parameters
+/// - embedder: Pointer to a vision CEmbedder
+/// - data: Encoded image bytes (PNG, JPEG, WebP, GIF or BMP)
+/// - len: Number of bytes
+/// - priority: Scheduling priority, or PRIORITY_DEFAULT (-1) for PRIORITY_BACKGROUND
+///
+/// # Returns
+/// Operation ID (positive) on success, -1 on immediate failure.
+/// The result is a CTextEmbedding (result_type 10).
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn start_embed_image_bytes(
+    embedder: *const CEmbedder,
+    data: *const u8,
+    len: usize,
+    priority: i32,
+) -> i64 {
+    clear_last_error();
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return -1;
+    }
+    if data.is_null() {
+        set_last_error("INVALID_CONFIG: data: cannot be null");
+        return -1;
+    }
+    if len == 0 {
+        set_last_error("INVALID_CONFIG: len: must be greater than 0");
+        return -1;
+    }
+
+    // Clone Arc<Embedder> for thread
+    let embedder_arc = unsafe { &*embedder }.inner.clone();
+    let options = unsafe { &*embedder }.options;
+
+    let bytes = unsafe { std::slice::from_raw_parts(data, len) }.to_vec();
+
+    // Register operation
+    let (op_id, cancel_token) = register_operation();
+    let lane = lane_of(&embedder_arc);
+    queue_operation(op_id, priority_or(priority, PRIORITY_BACKGROUND));
+
+    // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
+    RUNTIME.spawn(async move {
+        // Wait for a slot on this embedder; cancellation ends the wait
+        let Some(_slot) = acquire_slot(lane, op_id, &cancel_token).await else {
+            store_cancelled(op_id);
+            return;
+        };
+
+        let result = run_blocking(move || vision::embed_image_bytes(&embedder_arc, &bytes)).await;
+
+        // Check cancellation
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        // Store result
+        match result {
+            Ok(vector) => store_success(
+                op_id,
+                AsyncResultData::ImageEmbedding(SingleEmbeddingResult {
+                    values: options.apply_to_vector(vector),
+                }),
+            ),
+            Err(e) => store_error(op_id, e),
+        }
+    });
+
+    op_id
+}
+
+/// Start embedding a batch of image files asynchronously.
+///
+/// # Parameters
+/// - embedder: Pointer to a vision CEmbedder
+/// - image_paths: Array of image path pointers
+/// - count: Number of paths
+/// - priority: Scheduling priority, or PRIORITY_DEFAULT (-1) for PRIORITY_BACKGROUND
+///
+/// # Returns
+/// Operation ID (positive) on success, -1 on immediate failure.
+/// The result is a CTextEmbeddingBatch (result_type 11).
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn start_embed_image_files_batch(
+    embedder: *const CEmbedder,
+    image_paths: *const *const c_char,
+    count: usize,
+    priority: i32,
+) -> i64 {
+    clear_last_error();
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return -1;
+    }
+    if image_paths.is_null() {
+        set_last_error("INVALID_CONFIG: image_paths: cannot be null");
+        return -1;
+    }
+    if count == 0 {
+        set_last_error("INVALID_CONFIG: count: must be greater than 0");
+        return -1;
+    }
+
+    // Clone Arc<Embedder> for thread
+    let embedder_arc = unsafe { &*embedder }.inner.clone();
+    let options = unsafe { &*embedder }.options;
+
+    // Convert C string array to Rust Vec<PathBuf>
+    let paths_slice = unsafe { std::slice::from_raw_parts(image_paths, count) };
+    let mut paths = Vec::with_capacity(count);
+
+    for &path_ptr in paths_slice {
+        if path_ptr.is_null() {
+            set_last_error("INVALID_CONFIG: image_paths: array contains null pointer");
+            return -1;
+        }
+
+        let path = unsafe {
+            match CStr::from_ptr(path_ptr).to_str() {
+                Ok(s) => PathBuf::from(s),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: image_paths: array contains invalid UTF-8");
+                    return -1;
+                }
+            }
+        };
+        paths.push(path);
+    }
+
+    // Register operation
+    let (op_id, cancel_token) = register_operation();
+    let lane = lane_of(&embedder_arc);
+    queue_operation(op_id, priority_or(priority, PRIORITY_BACKGROUND));
+
+    // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
+    RUNTIME.spawn(async move {
+        // Wait for a slot on this embedder; cancellation ends the wait
+        let Some(_slot) = acquire_slot(lane, op_id, &cancel_token).await else {
+            store_cancelled(op_id);
+            return;
+        };
+
+        let result = run_blocking(move || vision::embed_image_files(&embedder_arc, &paths)).await;
+
+        // Check cancellation
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        // Store result
+        match result {
+            Ok(vectors) => store_success(
+                op_id,
+                AsyncResultData::ImageBatchEmbedding(BatchEmbeddingResult {
+                    embeddings: vectors
+                        .into_iter()
+                        .map(|v| options.apply_to_vector(v))
+                        .collect(),
+                }),
+            ),
+            Err(e) => store_error(op_id, e),
+        }
+    });
+
+    op_id
+}
+
+/// Start embedding a text query into a vision model's image space asynchronously.
+///
+/// # Parameters
+/// - embedder: Pointer to a vision CEmbedder
+/// - text: Query text
+/// - priority: Scheduling priority, or PRIORITY_DEFAULT (-1) for PRIORITY_INTERACTIVE
+///
+/// # Returns
+/// Operation ID (positive) on success, -1 on immediate failure.
+/// The result is a CTextEmbedding (result_type 0).
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn start_embed_image_query(
+    embedder: *const CEmbedder,
+    text: *const c_char,
+    priority: i32,
+) -> i64 {
+    clear_last_error();
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return -1;
+    }
+    if text.is_null() {
+        set_last_error("INVALID_CONFIG: text: cannot be null");
+        return -1;
+    }
+
+    // Clone Arc<Embedder> for thread
+    let embedder_arc = unsafe { &*embedder }.inner.clone();
+    let options = unsafe { &*embedder }.options;
+
+    let text_str = unsafe {
+        match CStr::from_ptr(text).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: text: invalid UTF-8 encoding");
+                return -1;
+            }
+        }
+    };
+
+    // Register operation
+    let (op_id, cancel_token) = register_operation();
+    let lane = lane_of(&embedder_arc);
+    queue_operation(op_id, priority_or(priority, PRIORITY_INTERACTIVE));
+
+    // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
+    RUNTIME.spawn(async move {
+        // Wait for a slot on this embedder; cancellation ends the wait
+        let Some(_slot) = acquire_slot(lane, op_id, &cancel_token).await else {
+            store_cancelled(op_id);
+            return;
+        };
+
+        let result = vision::embed_text_query(&embedder_arc, &text_str).await;
+
+        // Check cancellation
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        // Store result
+        match result {
+            Ok(vector) => store_success(
+                op_id,
+                AsyncResultData::SingleEmbedding(SingleEmbeddingResult {
+                    values: options.apply_to_vector(vector),
+                }),
+            ),
+            Err(e) => store_error(op_id, e),
+        }
+    });
+
+    op_id
+}
+
+// ============================================================================
+// Async File Embedding
+// ============================================================================
+
+/// Start embedding a file asynchronously.
+///
+/// # Parameters
+/// - embedder: Embedder handle
+/// - file_path: Path to file (C string)
+/// - config: Pointer to CTextEmbedConfig
+/// - priority: Scheduling priority, or PRIORITY_DEFAULT (-1) for PRIORITY_BACKGROUND
+///
+/// # Returns
+/// Operation ID (positive) on success, -1 on immediate failure.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn start_embed_file(
+    embedder: *const CEmbedder,
+    file_path: *const c_char,
+    config: *const CTextEmbedConfig,
+    priority: i32,
+) -> i64 {
+    clear_last_error();
+
+    // Validate pointers
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return -1;
+    }
+    if file_path.is_null() {
+        set_last_error("INVALID_CONFIG: file_path: cannot be null");
+        return -1;
+    }
+    if config.is_null() {
+        set_last_error("INVALID_CONFIG: config: cannot be null");
+        return -1;
+    }
+
+    let embedder_arc = unsafe { &*embedder }.inner.clone();
+    let options = unsafe { &*embedder }.options;
+    let config_ref = unsafe { &*config };
+
+    // Convert C string to Rust Path
+    let file_path_str = unsafe {
+        match CStr::from_ptr(file_path).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: file_path: invalid UTF-8 encoding");
+                return -1;
+            }
+        }
+    };
+
+    // Check if file exists before spawning thread
+    let path = PathBuf::from(&file_path_str);
+    if !path.exists() {
+        set_last_error(&format!("FILE_NOT_FOUND: {}", file_path_str));
+        return -1;
+    }
+
+    // Build TextEmbedConfig from CTextEmbedConfig
+    let text_config = TextEmbedConfig {
+        chunk_size: Some(config_ref.chunk_size),
+        overlap_ratio: Some(config_ref.overlap_ratio),
+        batch_size: options.resolve_batch_size(config_ref.batch_size),
+        buffer_size: Some(config_ref.buffer_size),
+        ..Default::default()
+    };
+
+    let file_cache = unsafe { &*embedder }.file_cache();
+
+    // Register operation
+    let (op_id, cancel_token, progress) = register_tracked_operation();
+    let lane = lane_of(&embedder_arc);
+    queue_operation(op_id, priority_or(priority, PRIORITY_BACKGROUND));
+    progress.discover(1);
+
+    // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
+    RUNTIME.spawn(async move {
+        // Wait for a slot on this embedder; cancellation ends the wait
+        let Some(_slot) = acquire_slot(lane, op_id, &cancel_token).await else {
+            store_cancelled(op_id);
+            return;
+        };
+
+        // Run embedding on the blocking pool, unless the disk cache already
+        // holds this content
+        progress.start_file(&path);
+        let file_path = path.clone();
+        let result = run_inference(async move {
+            let embed = embedder_arc.embed_file(file_path.clone(), Some(&text_config), None);
+            disk_cache::embed_file_cached(file_cache.as_ref(), &file_path, &text_config, embed)
+                .await
+        })
+        .await
+        .unwrap_or_else(|e| Err(anyhow::anyhow!(e)));
+
+        // Check cancellation
+        if cancel_token.is_cancelled() {
+            store_cancelled(op_id);
+            return;
+        }
+
+        // Process result
+        match result {
+            Ok(Some(embed_data_vec)) => {
+                progress.finish_file(&path, embed_data_vec.len());
+                store_success(
+                    op_id,
+                    AsyncResultData::FileEmbedding(FileEmbeddingResult {
+                        items: options.apply_to_data(embed_data_vec),
+                    }),
+                );
+            }
+            Ok(None) => {
+                store_error(op_id, "EMBEDDING_FAILED: embed_file returned None".to_string());
+            }
+            Err(e) => {
+                let error_str = e.to_string().to_lowercase();
+                if error_str.contains("not found") || error_str.contains("no such file") {
+                    store_error(op_id, format!("FILE_NOT_FOUND: {}", file_path_str));
+                } else if error_str.contains("unsupported") || error_str.contains("format") {
+                    store_error(op_id, format!("UNSUPPORTED_FORMAT: {}", file_path_str));
+                } else if error_str.contains("permission") || error_str.contains("access denied") {
+                    store_error(op_id, format!("FILE_READ_ERROR: {}", e));
+                } else {
+                    store_error(op_id, format!("EMBEDDING_FAILED: {}", e));
+                }
+            }
+        }
+    });
+
+    op_id
+}
+
+// ============================================================================
+// Async Directory Embedding
+// ============================================================================
+
+/// Start embedding a directory asynchronously.
+///
+/// Entries become available to drain_async_partial as each buffer fills;
+/// poll_async_result returns whatever was not drained. Once the caller has
+/// drained at least once, the walk pauses before the next file or image
+/// batch while more than two buffers (2 × buffer_size entries) wait to be
+/// drained, so a slow consumer bounds the job's memory; cancellation ends
+/// the pause. A caller that never drains gets the whole tree from
+/// poll_async_result.
+///
+/// With an `image_embedder`, image files are routed to it and every entry's
+/// metadata carries a `modality` field (see `embed_directory_stream`). The
+/// image batches also wait for a slot on the vision embedder, so they count
+/// against its set_embedder_max_in_flight limit like start_embed_image_*.
+///
+/// # Parameters
+/// - embedder: Embedder handle
+/// - image_embedder: Vision embedder handle for images, or NULL for text only
+/// - directory_path: Path to directory (C string)
+/// - extensions: NULL-terminated array of extension strings, or NULL for the
+///   default text formats (PDF, TXT, MD, DOCX, HTML)
+/// - extensions_count: Number of extensions (0 if extensions is NULL)
+/// - config: Pointer to CTextEmbedConfig
+/// - priority: Scheduling priority, or PRIORITY_DEFAULT (-1) for PRIORITY_BACKGROUND
+///
+/// # Returns
+/// Operation ID (positive) on success, -1 on immediate failure.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn start_embed_directory(
+    embedder: *const CEmbedder,
+    image_embedder: *const CEmbedder,
+    directory_path: *const c_char,
+    extensions: *const *const c_char,
+    extensions_count: usize,
+    config: *const CTextEmbedConfig,
+    priority: i32,
+) -> i64 {
+    clear_last_error();
+
+    // Validate pointers
     if embedder.is_null() {
         set_last_error("FFI_ERROR: embedder pointer is null");
         return -1;
@@ -705,8 +3076,19 @@ pub extern "C" fn start_embed_directory(
     }
 
     let embedder_arc = unsafe { &*embedder }.inner.clone();
+    let options = unsafe { &*embedder }.options;
     let config_ref = unsafe { &*config };
 
+    // Clone the vision embedder for the routed mode
+    let image_embedder_opt = match unsafe { image_embedder.as_ref() } {
+        Some(image_ref) if !vision::is_vision(&image_ref.inner) => {
+            set_last_error("INVALID_CONFIG: image_embedder: not a vision model");
+            return -1;
+        }
+        Some(image_ref) => Some((image_ref.inner.clone(), image_ref.options)),
+        None => None,
+    };
+
     // Convert C string to Rust PathBuf
     let dir_path_str = unsafe {
         match CStr::from_ptr(directory_path).to_str() {
@@ -758,26 +3140,76 @@ pub extern "C" fn start_embed_directory(
     let text_config = TextEmbedConfig {
         chunk_size: Some(config_ref.chunk_size),
         overlap_ratio: Some(config_ref.overlap_ratio),
-        batch_size: Some(config_ref.batch_size),
+        batch_size: options.resolve_batch_size(config_ref.batch_size),
         buffer_size: Some(config_ref.buffer_size),
         ..Default::default()
     };
+    let image_batch_size = image_embedder_opt
+        .as_ref()
+        .and_then(|(_, image_options)| image_options.resolve_batch_size(config_ref.batch_size));
+    let buffer_size = config_ref.buffer_size;
+    let file_cache = unsafe { &*embedder }.file_cache();
 
     // Register operation
-    let (op_id, cancel_token) = register_operation();
+    let (op_id, cancel_token, progress) = register_tracked_operation();
+    let lane = lane_of(&embedder_arc);
+    let priority = priority_or(priority, PRIORITY_BACKGROUND);
+    queue_operation(op_id, priority);
 
     // Spawn Tokio task (uses bounded thread pool, not unbounded OS threads)
     RUNTIME.spawn(async move {
-        // Check cancellation
-        if cancel_token.is_cancelled() {
+        // Wait for a slot on this embedder; cancellation ends the wait
+        let Some(_slot) = acquire_slot(lane, op_id, &cancel_token).await else {
             store_cancelled(op_id);
             return;
-        }
+        };
 
-        // Run embedding directly in async context
-        let result = embedder_arc
-            .embed_directory_stream(dir_path.clone(), extensions_opt, Some(&text_config), None)
-            .await;
+        let route_images = image_embedder_opt.is_some();
+        let plan = match directory::plan_directory(&dir_path, extensions_opt, route_images) {
+            Ok(plan) => plan,
+            Err(e) => {
+                store_error(op_id, e);
+                return;
+            }
+        };
+
+        // Walk on the blocking pool, parking every buffer in the registry so
+        // drain_async_partial can take it
+        let walk_cancel = cancel_token.clone();
+        let undrained_limit = MAX_UNDRAINED_BUFFERS * buffer_size;
+        let result = run_inference(async move {
+            let image_route = image_embedder_opt
+                .as_ref()
+                .map(|(image_arc, image_options)| directory::ImageRoute {
+                    embedder: image_arc,
+                    options: *image_options,
+                    batch_size: image_batch_size,
+                    slot: Some(directory::LaneSlot {
+                        lane: lane_of(image_arc),
+                        op_id,
+                        priority,
+                    }),
+                });
+            directory::stream_directory(
+                &plan,
+                &embedder_arc,
+                options,
+                &text_config,
+                file_cache.as_ref(),
+                image_route,
+                buffer_size,
+                &progress,
+                &walk_cancel,
+                || wait_for_drain(op_id, undrained_limit, &walk_cancel),
+                |batch| {
+                    append_partial(op_id, batch);
+                    Ok(ControlFlow::Continue(()))
+                },
+            )
+            .await
+        })
+        .await
+        .and_then(|result| result);
 
         // Check cancellation
         if cancel_token.is_cancelled() {
@@ -785,35 +3217,16 @@ pub extern "C" fn start_embed_directory(
             return;
         }
 
-        // Process result
+        // Store result (only the entries that were not drained)
         match result {
-            Ok(Some(embed_data_vec)) => {
+            Ok(_) => {
+                let items = take_partial(op_id).unwrap_or_default();
                 store_success(
                     op_id,
-                    AsyncResultData::FileEmbedding(FileEmbeddingResult {
-                        items: embed_data_vec,
-                    }),
-                );
-            }
-            Ok(None) => {
-                store_error(
-                    op_id,
-                    "EMBEDDING_FAILED: embed_directory_stream returned None".to_string(),
+                    AsyncResultData::FileEmbedding(FileEmbeddingResult { items }),
                 );
             }
-            Err(e) => {
-                let error_str = e.to_string().to_lowercase();
-                if error_str.contains("not found") || error_str.contains("no such file") {
-                    store_error(op_id, format!("FILE_NOT_FOUND: {}", dir_path_str));
-                } else if error_str.contains("permission") || error_str.contains("access denied") {
-                    store_error(op_id, format!("FILE_READ_ERROR: {}", e));
-                } else {
-                    store_error(
-                        op_id,
-                        format!("EMBEDDING_FAILED: Directory embedding failed - {}", e),
-                    );
-                }
-            }
+            Err(e) => store_error(op_id, e),
         }
     });
 
@@ -832,7 +3245,9 @@ pub extern "C" fn start_embed_directory(
 /// # Returns
 /// CAsyncPollResult with:
 /// - status: 0=pending, 1=success, -1=error, -2=cancelled
-/// - result_type: 0=single, 1=batch, 2=file, 3=model
+/// - result_type: 0=single, 1=batch, 2=file, 3=model, 4=sparse, 5=sparse batch,
+///   6=multi-vector, 7=multi-vector batch, 8=reranker, 9=rerank results,
+///   10=image, 11=image batch
 /// - data: Pointer to result data (caller must free)
 /// - error_message: Error message if status == -1
 #[no_mangle]
@@ -903,11 +3318,67 @@ pub extern "C" fn poll_async_result(op_id: i64) -> CAsyncPollResult {
                                 result.result_type = AsyncResultType::ModelLoad as i32;
 
                                 // Create CEmbedder and return pointer
-                                let c_embedder = Box::new(CEmbedder {
-                                    inner: model_result.embedder,
-                                });
+                                let c_embedder = Box::new(
+                                    CEmbedder::new(
+                                        model_result.embedder,
+                                        model_result.options,
+                                        model_result.model,
+                                    )
+                                    .with_sparse(model_result.sparse),
+                                );
                                 result.data = Box::into_raw(c_embedder) as *mut std::ffi::c_void;
                             }
+                            AsyncResultData::SparseEmbedding(sparse) => {
+                                result.result_type = AsyncResultType::SparseEmbedding as i32;
+
+                                let c_sparse = Box::new(sparse_to_c(sparse.indices, sparse.values));
+                                result.data = Box::into_raw(c_sparse) as *mut std::ffi::c_void;
+                            }
+                            AsyncResultData::SparseBatchEmbedding(batch) => {
+                                result.result_type = AsyncResultType::SparseBatchEmbedding as i32;
+                                result.data =
+                                    sparse_vec_to_batch(batch.embeddings) as *mut std::ffi::c_void;
+                            }
+                            AsyncResultData::MultiVectorEmbedding(multi) => {
+                                result.result_type = AsyncResultType::MultiVectorEmbedding as i32;
+
+                                let c_multi = Box::new(multi_vector_to_c(
+                                    multi.values,
+                                    multi.rows,
+                                    multi.dim,
+                                ));
+                                result.data = Box::into_raw(c_multi) as *mut std::ffi::c_void;
+                            }
+                            AsyncResultData::MultiVectorBatchEmbedding(batch) => {
+                                result.result_type =
+                                    AsyncResultType::MultiVectorBatchEmbedding as i32;
+                                result.data = multi_vector_vec_to_batch(batch.embeddings)
+                                    as *mut std::ffi::c_void;
+                            }
+                            AsyncResultData::RerankerLoad(reranker_result) => {
+                                result.result_type = AsyncResultType::RerankerLoad as i32;
+
+                                let c_reranker = Box::new(CReranker {
+                                    inner: reranker_result.reranker,
+                                });
+                                result.data = Box::into_raw(c_reranker) as *mut std::ffi::c_void;
+                            }
+                            AsyncResultData::Rerank(rerank_result) => {
+                                result.result_type = AsyncResultType::Rerank as i32;
+                                result.data = rerank_results_to_c(rerank_result.ranked)
+                                    as *mut std::ffi::c_void;
+                            }
+                            AsyncResultData::ImageEmbedding(single) => {
+                                result.result_type = AsyncResultType::ImageEmbedding as i32;
+
+                                let c_embedding = Box::new(dense_to_c(single.values));
+                                result.data = Box::into_raw(c_embedding) as *mut std::ffi::c_void;
+                            }
+                            AsyncResultData::ImageBatchEmbedding(batch) => {
+                                result.result_type = AsyncResultType::ImageBatchEmbedding as i32;
+                                result.data =
+                                    dense_vec_to_batch(batch.embeddings) as *mut std::ffi::c_void;
+                            }
                         }
                     }
 
@@ -943,21 +3414,102 @@ pub extern "C" fn poll_async_result(op_id: i64) -> CAsyncPollResult {
     result
 }
 
+/// Poll the progress of an async operation.
+///
+/// Does not consume the result; keep calling poll_async_result to collect
+/// it. Operations without file progress report zero counters, but every
+/// operation reports its queue position.
+///
+/// # Parameters
+/// - op_id: The operation ID returned by a start_* function
+///
+/// # Returns
+/// CAsyncProgress with status 0=running, 1=finished, -1=unknown operation ID.
+/// `current_file` must be freed with free_string.
+#[no_mangle]
+pub extern "C" fn poll_async_progress(op_id: i64) -> CAsyncProgress {
+    let mut result = CAsyncProgress {
+        status: -1,
+        files_discovered: 0,
+        files_done: 0,
+        chunks_embedded: 0,
+        bytes_read: 0,
+        current_file: std::ptr::null_mut(),
+        queue_position: 0,
+    };
+
+    let ops = ASYNC_OPERATIONS.lock().unwrap();
+    let Some(op) = ops.get(&op_id) else {
+        return result;
+    };
+
+    result.status = match op.status {
+        AsyncOperationStatus::InProgress => 0,
+        _ => 1,
+    };
+
+    if let Some(progress) = &op.progress {
+        result.files_discovered = progress.files_discovered.load(Ordering::Relaxed);
+        result.files_done = progress.files_done.load(Ordering::Relaxed);
+        result.chunks_embedded = progress.chunks_embedded.load(Ordering::Relaxed);
+        result.bytes_read = progress.bytes_read.load(Ordering::Relaxed);
+        result.current_file = progress
+            .current_file()
+            .and_then(|path| CString::new(path.display().to_string()).ok())
+            .map(CString::into_raw)
+            .unwrap_or(std::ptr::null_mut());
+    }
+    drop(ops);
+
+    result.queue_position = SCHEDULER.lock().unwrap().queue_position(op_id);
+    result
+}
+
+/// Drain the entries produced so far by a running start_embed_directory.
+///
+/// The returned entries are removed from the operation, so the final
+/// poll_async_result only carries what was produced after the last drain.
+/// After the first call the walk pauses while more than two buffers are
+/// undrained, so keep calling it until the operation finishes.
+///
+/// # Parameters
+/// - op_id: The operation ID returned by start_embed_directory
+///
+/// # Returns
+/// Pointer to CEmbedDataBatch (possibly with count 0, free with
+/// free_embed_data_batch), or NULL on error.
+///
+/// # Error Prefixes
+/// - INVALID_CONFIG: Unknown operation ID
+/// - MULTI_VECTOR: Inconsistent multi-vector shape
+#[no_mangle]
+pub extern "C" fn drain_async_partial(op_id: i64) -> *mut CEmbedDataBatch {
+    clear_last_error();
+
+    let Some(items) = drain_partial(op_id) else {
+        set_last_error(&format!(
+            "INVALID_CONFIG: op_id: unknown operation {}",
+            op_id
+        ));
+        return std::ptr::null_mut();
+    };
+
+    match convert_file_result_to_c(items) {
+        Ok(batch_ptr) => batch_ptr,
+        Err(e) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
 /// Convert file embedding result to C-compatible batch.
 fn convert_file_result_to_c(items: Vec<EmbedData>) -> Result<*mut CEmbedDataBatch, String> {
     let mut c_items = Vec::with_capacity(items.len());
 
     for data in items {
-        // Extract Vec<f32> from EmbeddingResult::DenseVector
-        let embedding_vec = match data.embedding {
-            EmbeddingResult::DenseVector(vec) => vec,
-            EmbeddingResult::MultiVector(_) => {
-                return Err(
-                    "MULTI_VECTOR_NOT_SUPPORTED: Multi-vector embeddings are not supported"
-                        .to_string(),
-                );
-            }
-        };
+        // Flatten DenseVector (1 row) or MultiVector (row-major) into one buffer
+        let (embedding_vec, embedding_rows, _dim) = flatten_embedding(data.embedding)?;
 
         // Convert embedding vector
         let embedding_len = embedding_vec.len();
@@ -987,6 +3539,7 @@ fn convert_file_result_to_c(items: Vec<EmbedData>) -> Result<*mut CEmbedDataBatc
             embedding_values,
             embedding_len,
             text_and_metadata_json,
+            embedding_rows,
         });
     }
 
@@ -995,31 +3548,407 @@ fn convert_file_result_to_c(items: Vec<EmbedData>) -> Result<*mut CEmbedDataBatc
     let items = boxed_items.as_mut_ptr();
     std::mem::forget(boxed_items);
 
-    let batch = Box::new(CEmbedDataBatch { items, count });
-    Ok(Box::into_raw(batch))
+    let batch = Box::new(CEmbedDataBatch { items, count });
+    Ok(Box::into_raw(batch))
+}
+
+// ============================================================================
+// Cancellation
+// ============================================================================
+
+/// Cancel an async operation.
+///
+/// Cancellation is cooperative: batch embeddings stop before their next
+/// batch and directory jobs before their next file or image batch. Entries a
+/// cancelled start_embed_directory already produced stay available to
+/// drain_async_partial until poll_async_result reports the cancellation,
+/// which discards them.
+///
+/// # Parameters
+/// - op_id: The operation ID to cancel
+///
+/// # Returns
+/// 0 on success, -1 if operation ID not found
+#[no_mangle]
+pub extern "C" fn cancel_async_operation(op_id: i64) -> i32 {
+    let ops = ASYNC_OPERATIONS.lock().unwrap();
+
+    if let Some(op) = ops.get(&op_id) {
+        op.cancel_token.cancel();
+        0 // Success
+    } else {
+        set_last_error(&format!("Invalid operation ID: {}", op_id));
+        -1 // Not found
+    }
+}
+
+/// Register a callback to be told when an async operation finishes.
+///
+/// Lets callers wait for completion instead of polling every few
+/// milliseconds; the result is still collected with poll_async_result. The
+/// callback runs once, on a runtime worker thread (from Dart, use a
+/// `NativeCallable.listener`). If the operation has already finished it is
+/// called right away. Registering again replaces the previous callback.
+///
+/// # Parameters
+/// - op_id: The operation ID returned by a start_* function
+/// - callback: Called with (op_id, poll status, context)
+/// - context: Opaque pointer passed back to the callback
+///
+/// # Returns
+/// 0 on success, -1 on error
+///
+/// # Error Prefixes
+/// - INVALID_CONFIG: Null callback or unknown operation ID
+#[no_mangle]
+pub extern "C" fn set_async_completion_callback(
+    op_id: i64,
+    callback: Option<AsyncCompletionCallback>,
+    context: *mut std::ffi::c_void,
+) -> i32 {
+    clear_last_error();
+
+    let Some(callback) = callback else {
+        set_last_error("INVALID_CONFIG: callback: cannot be null");
+        return -1;
+    };
+    let callback = CompletionCallback {
+        callback,
+        context: context as usize,
+    };
+
+    let status = {
+        let mut ops = ASYNC_OPERATIONS.lock().unwrap();
+        let Some(op) = ops.get_mut(&op_id) else {
+            set_last_error(&format!(
+                "INVALID_CONFIG: op_id: unknown operation {}",
+                op_id
+            ));
+            return -1;
+        };
+
+        if matches!(op.status, AsyncOperationStatus::InProgress) {
+            op.on_complete = Some(callback);
+            return 0;
+        }
+        poll_status(&op.status)
+    };
+
+    // Already finished: notify outside the lock
+    callback.notify(op_id, status);
+    0
+}
+
+/// Give an async operation a deadline.
+///
+/// If the operation is still running `timeout_ms` after this call, polling
+/// reports an error with the "TIMEOUT:" prefix and the work is cancelled
+/// (cooperatively, see cancel_async_operation). A blocking step such as a
+/// model download cannot be interrupted; it runs to completion in the
+/// background and its result is discarded.
+///
+/// # Parameters
+/// - op_id: The operation ID returned by a start_* function
+/// - timeout_ms: Deadline in milliseconds from now
+///
+/// # Returns
+/// 0 on success, -1 on error
+///
+/// # Error Prefixes
+/// - INVALID_CONFIG: Unknown operation ID or zero timeout
+#[no_mangle]
+pub extern "C" fn set_async_deadline(op_id: i64, timeout_ms: u64) -> i32 {
+    clear_last_error();
+
+    if timeout_ms == 0 {
+        set_last_error("INVALID_CONFIG: timeout_ms: must be greater than 0");
+        return -1;
+    }
+
+    let done = match ASYNC_OPERATIONS.lock().unwrap().get(&op_id) {
+        Some(op) => op.done.clone(),
+        None => {
+            set_last_error(&format!(
+                "INVALID_CONFIG: op_id: unknown operation {}",
+                op_id
+            ));
+            return -1;
+        }
+    };
+
+    // The timer ends early once the operation finishes or is cancelled
+    RUNTIME.spawn(async move {
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(timeout_ms)) => {
+                store_timeout(op_id, timeout_ms);
+            }
+            _ = done.cancelled() => {}
+        }
+    });
+
+    0
+}
+
+// ============================================================================
+// Scheduling
+// ============================================================================
+
+/// Set how many operations an embedder runs at once.
+///
+/// Further start_* calls on the embedder wait in a queue, interactive
+/// queries ahead of background work. Raising the limit admits waiting
+/// operations right away.
+///
+/// # Parameters
+/// - embedder: Embedder handle
+/// - max_in_flight: Maximum concurrent operations (default 4)
+///
+/// # Returns
+/// 0 on success, -1 on error
+///
+/// # Error Prefixes
+/// - FFI_ERROR: Null embedder pointer
+/// - INVALID_CONFIG: Zero max_in_flight
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn set_embedder_max_in_flight(
+    embedder: *const CEmbedder,
+    max_in_flight: usize,
+) -> i32 {
+    clear_last_error();
+
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return -1;
+    }
+    if max_in_flight == 0 {
+        set_last_error("INVALID_CONFIG: max_in_flight: must be greater than 0");
+        return -1;
+    }
+
+    let lane = lane_of(&unsafe { &*embedder }.inner);
+    let mut scheduler = SCHEDULER.lock().unwrap();
+    scheduler.limits.insert(lane, max_in_flight);
+    scheduler.admit(lane);
+    0
+}
+
+/// Coalesce concurrent start_embed_text calls on an embedder.
+///
+/// Requests arriving within `max_wait_ms` of the first pending one are
+/// embedded together with a single `embed_query` call (at most `max_batch`
+/// texts; a full batch runs at once) and each operation gets its own
+/// result. A batch waits for one slot under its first request; every
+/// request in it reports the batch's queue position.
+///
+/// # Parameters
+/// - embedder: Embedder handle
+/// - max_batch: Largest batch to build (0 or 1 turns micro-batching off)
+/// - max_wait_ms: How long the first request waits for company
+///
+/// # Returns
+/// 0 on success, -1 on error
+///
+/// # Error Prefixes
+/// - FFI_ERROR: Null embedder pointer
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn set_embedder_micro_batching(
+    embedder: *const CEmbedder,
+    max_batch: usize,
+    max_wait_ms: u64,
+) -> i32 {
+    clear_last_error();
+
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return -1;
+    }
+
+    let lane = lane_of(&unsafe { &*embedder }.inner);
+    let mut batcher = MICRO_BATCHER.lock().unwrap();
+    if max_batch <= 1 {
+        // Requests already waiting still run when their window closes
+        batcher.configs.remove(&lane);
+    } else {
+        let config = MicroBatchConfig {
+            max_batch,
+            max_wait: Duration::from_millis(max_wait_ms),
+        };
+        batcher.configs.insert(lane, config);
+    }
+    0
+}
+
+/// Change the priority of an operation that is still waiting for a slot.
+///
+/// start_* calls default to PRIORITY_INTERACTIVE (1) for single queries and
+/// reranking and PRIORITY_BACKGROUND (0) for batches, images, files and
+/// directories. Higher values run first. To start with a priority, pass it
+/// to the start_* function instead: by the time this runs the operation may
+/// already hold its slot. Once the operation has its slot (or has finished)
+/// the change is rejected instead of being silently dropped.
+///
+/// A micro-batched start_embed_text request shares its batch's slot: once
+/// the batch is queued, a change can only raise the batch's priority.
+///
+/// # Parameters
+/// - op_id: The operation ID returned by a start_* function
+/// - priority: New priority
+///
+/// # Returns
+/// 0 on success, -1 if the operation ID is unknown or no longer waiting
+///
+/// # Error Prefixes
+/// - "INVALID_CONFIG: op_id:" for an unknown operation, or one that is
+///   already running or finished
+#[no_mangle]
+pub extern "C" fn set_async_priority(op_id: i64, priority: i32) -> i32 {
+    clear_last_error();
+
+    if !ASYNC_OPERATIONS.lock().unwrap().contains_key(&op_id) {
+        set_last_error(&format!(
+            "INVALID_CONFIG: op_id: unknown operation {}",
+            op_id
+        ));
+        return -1;
+    }
+
+    // Requests still collecting in a micro-batch window
+    if MICRO_BATCHER.lock().unwrap().set_priority(op_id, priority) {
+        return 0;
+    }
+
+    let mut scheduler = SCHEDULER.lock().unwrap();
+    let leader = scheduler.batch_leaders.get(&op_id).copied();
+    match scheduler.priorities.get_mut(&leader.unwrap_or(op_id)) {
+        // Other requests of the batch keep what they asked for
+        Some(current) if leader.is_some() => {
+            *current = (*current).max(priority);
+            0
+        }
+        Some(current) => {
+            *current = priority;
+            0
+        }
+        None => {
+            set_last_error(&format!(
+                "INVALID_CONFIG: op_id: operation {} is no longer waiting for a slot",
+                op_id
+            ));
+            -1
+        }
+    }
 }
 
 // ============================================================================
-// Cancellation
+// Registry Maintenance
 // ============================================================================
 
-/// Cancel an async operation.
+/// Discard an async operation and free any result it holds.
+///
+/// Cancels the work if it is still running. A completion callback that is
+/// still waiting is notified with -2 (cancelled) first, so waiters never
+/// hang; polling the discarded ID afterwards reports an unknown operation.
+/// Use this instead of polling when the result is no longer wanted (e.g. a
+/// dropped Dart future).
 ///
 /// # Parameters
-/// - op_id: The operation ID to cancel
+/// - op_id: The operation ID returned by a start_* function
 ///
 /// # Returns
-/// 0 on success, -1 if operation ID not found
+/// 0 on success, -1 if the operation ID is unknown
 #[no_mangle]
-pub extern "C" fn cancel_async_operation(op_id: i64) -> i32 {
-    let ops = ASYNC_OPERATIONS.lock().unwrap();
+pub extern "C" fn discard_async_operation(op_id: i64) -> i32 {
+    clear_last_error();
 
-    if let Some(op) = ops.get(&op_id) {
-        op.cancel_token.cancel();
-        0 // Success
-    } else {
-        set_last_error(&format!("Invalid operation ID: {}", op_id));
-        -1 // Not found
+    // Drop the entry (and any loaded model) outside the registry lock
+    let removed = ASYNC_OPERATIONS.lock().unwrap().remove(&op_id);
+    match removed {
+        Some(mut op) => {
+            op.cancel_token.cancel();
+            if let Some(callback) = op.on_complete.take() {
+                callback.notify(op_id, poll_status(&AsyncOperationStatus::Cancelled));
+            }
+            0
+        }
+        None => {
+            set_last_error(&format!(
+                "INVALID_CONFIG: op_id: unknown operation {}",
+                op_id
+            ));
+            -1
+        }
+    }
+}
+
+/// Set how long finished results are kept before they are reaped.
+///
+/// Expired results are reaped when an operation is started or listed, and
+/// by a background sweep every 30 seconds, so a result may outlive its TTL
+/// by up to that interval.
+///
+/// # Parameters
+/// - ttl_ms: Time to live in milliseconds after an operation finishes
+///   (0 keeps results until they are polled or discarded)
+#[no_mangle]
+pub extern "C" fn set_async_result_ttl(ttl_ms: u64) {
+    RESULT_TTL_MS.store(ttl_ms, Ordering::Relaxed);
+}
+
+/// List the operations in the async registry (for diagnostics).
+///
+/// Expired results are reaped first.
+///
+/// # Returns
+/// - JSON string: `[{"op_id": 1, "status": "in_progress", "age_ms": 120,
+///   "finished_ms_ago": null}, ...]` sorted by op_id
+///   (caller must free with free_string)
+/// - NULL on failure (check get_last_error)
+#[no_mangle]
+pub extern "C" fn list_async_operations() -> *mut c_char {
+    clear_last_error();
+
+    let now = Instant::now();
+    let mut ops = ASYNC_OPERATIONS.lock().unwrap();
+    let expired = reap_expired(&mut ops, now);
+
+    let mut op_ids: Vec<i64> = ops.keys().copied().collect();
+    op_ids.sort_unstable();
+
+    let list: Vec<serde_json::Value> = op_ids
+        .iter()
+        .map(|op_id| {
+            let op = &ops[op_id];
+            serde_json::json!({
+                "op_id": op_id,
+                "status": status_name(&op.status),
+                "age_ms": now.duration_since(op.created_at).as_millis() as u64,
+                "finished_ms_ago": op
+                    .finished_at
+                    .map(|finished_at| now.duration_since(finished_at).as_millis() as u64),
+            })
+        })
+        .collect();
+    drop(ops);
+    drop(expired);
+
+    match CString::new(serde_json::Value::Array(list).to_string()) {
+        Ok(cstr) => cstr.into_raw(),
+        Err(_) => {
+            set_last_error("FFI_ERROR: Failed to encode operation list");
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Name of an operation status in list_async_operations.
+fn status_name(status: &AsyncOperationStatus) -> &'static str {
+    match status {
+        AsyncOperationStatus::InProgress => "in_progress",
+        AsyncOperationStatus::Success => "success",
+        AsyncOperationStatus::Error(_) => "error",
+        AsyncOperationStatus::Cancelled => "cancelled",
     }
 }
 
@@ -1082,6 +4011,607 @@ mod tests {
         assert!(matches!(op.status, AsyncOperationStatus::Error(_)));
     }
 
+    #[test]
+    fn test_store_timeout_wins_over_late_results() {
+        // Arrange
+        let (op_id, token) = register_operation();
+
+        // Act
+        store_timeout(op_id, 50);
+        store_cancelled(op_id);
+        store_error(op_id, "late error".to_string());
+
+        // Assert
+        assert!(token.is_cancelled());
+        let ops = ASYNC_OPERATIONS.lock().unwrap();
+        let op = ops.get(&op_id).unwrap();
+        match &op.status {
+            AsyncOperationStatus::Error(msg) => assert!(msg.starts_with("TIMEOUT:")),
+            other => panic!("expected TIMEOUT error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_set_async_deadline_times_out() {
+        // Arrange
+        let (op_id, token) = register_operation();
+
+        // Act
+        let status = set_async_deadline(op_id, 1);
+        std::thread::sleep(Duration::from_millis(200));
+        let result = poll_async_result(op_id);
+
+        // Assert
+        assert_eq!(status, 0);
+        assert!(token.is_cancelled());
+        assert_eq!(result.status, -1);
+        let message = unsafe { CStr::from_ptr(result.error_message) };
+        assert!(message.to_str().unwrap().starts_with("TIMEOUT:"));
+
+        free_async_error_message(result.error_message);
+    }
+
+    #[test]
+    fn test_finished_operation_stops_its_deadline_timer() {
+        // Arrange
+        let (op_id, token) = register_operation();
+        let done = ASYNC_OPERATIONS.lock().unwrap()[&op_id].done.clone();
+        assert_eq!(set_async_deadline(op_id, 60_000), 0);
+
+        // Act
+        store_success(
+            op_id,
+            AsyncResultData::SingleEmbedding(SingleEmbeddingResult { values: vec![1.0] }),
+        );
+
+        // Assert: the timer's token fired, the (finished) work was not cancelled
+        assert!(done.is_cancelled());
+        assert!(!token.is_cancelled());
+    }
+
+    #[test]
+    fn test_set_async_deadline_rejects_invalid_input() {
+        let (op_id, _token) = register_operation();
+
+        assert_eq!(set_async_deadline(op_id, 0), -1);
+        assert_eq!(set_async_deadline(i64::MAX, 100), -1);
+    }
+
+    extern "C" fn record_completion(_op_id: i64, status: i32, context: *mut std::ffi::c_void) {
+        let seen = unsafe { &*(context as *const AtomicI64) };
+        seen.store(status as i64, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_completion_callback_fires_once_finished() {
+        // Arrange
+        let (op_id, _token) = register_operation();
+        let seen = AtomicI64::new(0);
+        let context = &seen as *const AtomicI64 as *mut std::ffi::c_void;
+
+        // Act
+        let status = set_async_completion_callback(op_id, Some(record_completion), context);
+        let before = seen.load(Ordering::SeqCst);
+        store_error(op_id, "Test error".to_string());
+
+        // Assert
+        assert_eq!(status, 0);
+        assert_eq!(before, 0);
+        assert_eq!(seen.load(Ordering::SeqCst), -1);
+        ASYNC_OPERATIONS.lock().unwrap().remove(&op_id);
+    }
+
+    #[test]
+    fn test_completion_callback_on_finished_operation_fires_immediately() {
+        // Arrange
+        let (op_id, _token) = register_operation();
+        let seen = AtomicI64::new(0);
+        let context = &seen as *const AtomicI64 as *mut std::ffi::c_void;
+        store_cancelled(op_id);
+
+        // Act
+        let status = set_async_completion_callback(op_id, Some(record_completion), context);
+
+        // Assert
+        assert_eq!(status, 0);
+        assert_eq!(seen.load(Ordering::SeqCst), -2);
+        ASYNC_OPERATIONS.lock().unwrap().remove(&op_id);
+    }
+
+    #[test]
+    fn test_completion_callback_rejects_invalid_input() {
+        let (op_id, _token) = register_operation();
+
+        assert_eq!(
+            set_async_completion_callback(op_id, None, std::ptr::null_mut()),
+            -1
+        );
+        assert_eq!(
+            set_async_completion_callback(i64::MAX, Some(record_completion), std::ptr::null_mut()),
+            -1
+        );
+    }
+
+    fn operation_finished_at(finished_at: Option<Instant>) -> AsyncOperation {
+        AsyncOperation {
+            status: AsyncOperationStatus::Cancelled,
+            result: None,
+            cancel_token: CancellationToken::new(),
+            done: CancellationToken::new(),
+            progress: None,
+            partial: Vec::new(),
+            drained: None,
+            on_complete: None,
+            created_at: Instant::now(),
+            finished_at,
+        }
+    }
+
+    #[test]
+    fn test_reap_expired_drops_only_stale_finished_operations() {
+        // Arrange
+        let now = Instant::now();
+        let ttl = Duration::from_millis(DEFAULT_RESULT_TTL_MS);
+        let mut ops = HashMap::from([
+            (1, operation_finished_at(None)),
+            (2, operation_finished_at(Some(now))),
+            (3, operation_finished_at(Some(now + ttl))),
+        ]);
+
+        // Act
+        let expired = reap_expired(&mut ops, now + ttl + Duration::from_millis(1));
+
+        // Assert
+        assert_eq!(expired.len(), 1);
+        assert!(ops.contains_key(&1));
+        assert!(!ops.contains_key(&2));
+        assert!(ops.contains_key(&3));
+    }
+
+    #[test]
+    fn test_discard_async_operation() {
+        // Arrange
+        let (op_id, token) = register_operation();
+
+        // Act
+        let first = discard_async_operation(op_id);
+        let second = discard_async_operation(op_id);
+
+        // Assert
+        assert_eq!(first, 0);
+        assert_eq!(second, -1);
+        assert!(token.is_cancelled());
+        assert!(!ASYNC_OPERATIONS.lock().unwrap().contains_key(&op_id));
+    }
+
+    #[test]
+    fn test_discard_notifies_waiting_callback() {
+        // Arrange
+        let (op_id, _token) = register_operation();
+        let seen = AtomicI64::new(0);
+        let context = &seen as *const AtomicI64 as *mut std::ffi::c_void;
+        set_async_completion_callback(op_id, Some(record_completion), context);
+
+        // Act
+        let status = discard_async_operation(op_id);
+
+        // Assert
+        assert_eq!(status, 0);
+        assert_eq!(seen.load(Ordering::SeqCst), -2);
+    }
+
+    #[test]
+    fn test_list_async_operations() {
+        // Arrange
+        let (op_id, _token) = register_operation();
+
+        // Act
+        let ptr = list_async_operations();
+        let json = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string();
+        crate::free_string(ptr);
+        discard_async_operation(op_id);
+
+        // Assert
+        let list: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap();
+        let entry = list.iter().find(|op| op["op_id"] == op_id).unwrap();
+        assert_eq!(entry["status"], "in_progress");
+        assert!(entry["finished_ms_ago"].is_null());
+    }
+
+    async fn acquire_uncancelled(lane: usize, op_id: i64) -> Option<Slot> {
+        acquire_slot(lane, op_id, &CancellationToken::new()).await
+    }
+
+    async fn wait_until_queued(op_id: i64) {
+        while SCHEDULER.lock().unwrap().queue_position(op_id) == 0 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[test]
+    fn test_scheduler_admits_by_priority_then_arrival() {
+        crate::RUNTIME.block_on(async {
+            // Arrange: a private lane with one slot, held by the first op
+            let lane = usize::MAX - 1;
+            SCHEDULER.lock().unwrap().limits.insert(lane, 1);
+            let ops: Vec<i64> = (0..3).map(|_| register_operation().0).collect();
+            queue_operation(ops[0], PRIORITY_BACKGROUND);
+            queue_operation(ops[1], PRIORITY_BACKGROUND);
+            queue_operation(ops[2], PRIORITY_INTERACTIVE);
+            let first = acquire_uncancelled(lane, ops[0]).await.unwrap();
+
+            // Act
+            let background = tokio::spawn(acquire_uncancelled(lane, ops[1]));
+            wait_until_queued(ops[1]).await;
+            let interactive = tokio::spawn(acquire_uncancelled(lane, ops[2]));
+            wait_until_queued(ops[2]).await;
+
+            // Assert
+            assert_eq!(SCHEDULER.lock().unwrap().queue_position(ops[2]), 1);
+            assert_eq!(SCHEDULER.lock().unwrap().queue_position(ops[1]), 2);
+            drop(first);
+            let interactive = interactive.await.unwrap().unwrap();
+            assert_eq!(SCHEDULER.lock().unwrap().queue_position(ops[1]), 1);
+            drop(interactive);
+            drop(background.await.unwrap().unwrap());
+            assert!(!SCHEDULER.lock().unwrap().lanes.contains_key(&lane));
+
+            SCHEDULER.lock().unwrap().limits.remove(&lane);
+            for op_id in ops {
+                discard_async_operation(op_id);
+            }
+        });
+    }
+
+    #[test]
+    fn test_acquire_slot_returns_none_when_cancelled() {
+        crate::RUNTIME.block_on(async {
+            // Arrange
+            let lane = usize::MAX - 2;
+            SCHEDULER.lock().unwrap().limits.insert(lane, 1);
+            let (holder, _t1) = register_operation();
+            let (waiter, token) = register_operation();
+            queue_operation(waiter, PRIORITY_BACKGROUND);
+            let slot = acquire_uncancelled(lane, holder).await.unwrap();
+
+            // Act
+            let waiting = tokio::spawn({
+                let token = token.clone();
+                async move { acquire_slot(lane, waiter, &token).await }
+            });
+            wait_until_queued(waiter).await;
+            token.cancel();
+
+            // Assert
+            assert!(waiting.await.unwrap().is_none());
+            assert_eq!(SCHEDULER.lock().unwrap().queue_position(waiter), 0);
+            drop(slot);
+            assert!(!SCHEDULER.lock().unwrap().lanes.contains_key(&lane));
+
+            SCHEDULER.lock().unwrap().limits.remove(&lane);
+            discard_async_operation(holder);
+            discard_async_operation(waiter);
+        });
+    }
+
+    #[test]
+    fn test_cancel_racing_slot_release_keeps_running_count() {
+        crate::RUNTIME.block_on(async {
+            // Arrange: a lane with two slots, one held for the whole test
+            let lane = usize::MAX - 5;
+            let limit = 2;
+            SCHEDULER.lock().unwrap().limits.insert(lane, limit);
+            let (steady, _t1) = register_operation();
+            let steady_slot = acquire_uncancelled(lane, steady).await.unwrap();
+            let running = || {
+                SCHEDULER
+                    .lock()
+                    .unwrap()
+                    .lanes
+                    .get(&lane)
+                    .map_or(0, |e| e.running)
+            };
+
+            for _ in 0..200 {
+                let (holder, _t2) = register_operation();
+                let (waiter, token) = register_operation();
+                let holder_slot = acquire_uncancelled(lane, holder).await.unwrap();
+                let waiting = tokio::spawn(async move { acquire_slot(lane, waiter, &token).await });
+                wait_until_queued(waiter).await;
+
+                // Act: release a slot while the waiter is being cancelled
+                let release = tokio::task::spawn_blocking(move || drop(holder_slot));
+                cancel_async_operation(waiter);
+                release.await.unwrap();
+                let waiter_slot = waiting.await.unwrap();
+
+                // Assert
+                let held = 1 + waiter_slot.is_some() as usize;
+                assert_eq!(running(), held);
+                assert!(running() <= limit);
+                drop(waiter_slot);
+                assert_eq!(running(), 1);
+
+                discard_async_operation(holder);
+                discard_async_operation(waiter);
+            }
+
+            drop(steady_slot);
+            assert!(!SCHEDULER.lock().unwrap().lanes.contains_key(&lane));
+            SCHEDULER.lock().unwrap().limits.remove(&lane);
+            discard_async_operation(steady);
+        });
+    }
+
+    #[test]
+    fn test_lane_slot_waits_for_the_other_lane() {
+        crate::RUNTIME.block_on(async {
+            // Arrange: the other lane's only slot is taken
+            let lane = usize::MAX - 6;
+            SCHEDULER.lock().unwrap().limits.insert(lane, 1);
+            let (holder, _t1) = register_operation();
+            let (op_id, token) = register_operation();
+            let slot = acquire_uncancelled(lane, holder).await.unwrap();
+            let lane_slot = directory::LaneSlot {
+                lane,
+                op_id,
+                priority: PRIORITY_BACKGROUND,
+            };
+
+            // Act
+            let waiting = tokio::spawn({
+                let token = token.clone();
+                async move { acquire_lane_slot(lane_slot, &token).await }
+            });
+            wait_until_queued(op_id).await;
+            drop(slot);
+
+            // Assert
+            let admitted = waiting.await.unwrap();
+            assert!(admitted.is_some());
+            drop(admitted);
+            assert!(!SCHEDULER.lock().unwrap().lanes.contains_key(&lane));
+
+            SCHEDULER.lock().unwrap().limits.remove(&lane);
+            discard_async_operation(holder);
+            discard_async_operation(op_id);
+        });
+    }
+
+    #[test]
+    fn test_set_priority_rejected_once_admitted() {
+        crate::RUNTIME.block_on(async {
+            // Arrange
+            let lane = usize::MAX - 3;
+            let (op_id, _token) = register_operation();
+            queue_operation(op_id, PRIORITY_BACKGROUND);
+            let waiting = set_async_priority(op_id, PRIORITY_INTERACTIVE);
+
+            // Act
+            let slot = acquire_uncancelled(lane, op_id).await.unwrap();
+            let running = set_async_priority(op_id, PRIORITY_BACKGROUND);
+
+            // Assert
+            assert_eq!(waiting, 0);
+            assert_eq!(running, -1);
+            let error_ptr = crate::get_last_error();
+            let error = unsafe { CStr::from_ptr(error_ptr) }.to_str().unwrap();
+            assert!(error.starts_with("INVALID_CONFIG: op_id:"));
+            crate::free_error_string(error_ptr);
+
+            drop(slot);
+            discard_async_operation(op_id);
+        });
+    }
+
+    #[test]
+    fn test_priority_or_keeps_default_only_when_asked() {
+        assert_eq!(
+            priority_or(PRIORITY_DEFAULT, PRIORITY_INTERACTIVE),
+            PRIORITY_INTERACTIVE
+        );
+        assert_eq!(
+            priority_or(PRIORITY_BACKGROUND, PRIORITY_INTERACTIVE),
+            PRIORITY_BACKGROUND
+        );
+        assert_eq!(priority_or(7, PRIORITY_BACKGROUND), 7);
+    }
+
+    #[test]
+    fn test_finished_operation_drops_its_priority() {
+        // Arrange: work that never asked for a slot (e.g. a cache hit)
+        let (op_id, _token) = register_operation();
+        queue_operation(op_id, PRIORITY_INTERACTIVE);
+
+        // Act
+        store_error(op_id, "EMBEDDING_FAILED: boom".to_string());
+
+        // Assert
+        assert!(!SCHEDULER.lock().unwrap().priorities.contains_key(&op_id));
+        assert_eq!(set_async_priority(op_id, PRIORITY_BACKGROUND), -1);
+
+        discard_async_operation(op_id);
+    }
+
+    fn pending_text(op_id: i64) -> PendingText {
+        PendingText {
+            op_id,
+            priority: PRIORITY_INTERACTIVE,
+            text: format!("text {}", op_id),
+            cancel_token: CancellationToken::new(),
+            cache: TextCache::default(),
+        }
+    }
+
+    #[test]
+    fn test_batch_slot_waits_until_every_request_is_cancelled() {
+        crate::RUNTIME.block_on(async {
+            // Arrange: a private lane with one slot, held by another op
+            let lane = usize::MAX - 4;
+            SCHEDULER.lock().unwrap().limits.insert(lane, 1);
+            let (holder, _token) = register_operation();
+            let slot = acquire_uncancelled(lane, holder).await.unwrap();
+            let items: Vec<PendingText> = (0..2)
+                .map(|_| {
+                    let (op_id, cancel_token) = register_operation();
+                    PendingText {
+                        cancel_token,
+                        ..pending_text(op_id)
+                    }
+                })
+                .collect();
+            let ops: Vec<i64> = items.iter().map(|item| item.op_id).collect();
+            let tokens: Vec<CancellationToken> =
+                items.iter().map(|item| item.cancel_token.clone()).collect();
+
+            // Act
+            let waiting = tokio::spawn(async move { acquire_batch_slot(lane, &items).await });
+            wait_until_queued(ops[1]).await;
+            cancel_async_operation(ops[0]);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let still_waiting = !waiting.is_finished();
+            let member_position = SCHEDULER.lock().unwrap().queue_position(ops[1]);
+            tokens[1].cancel();
+
+            // Assert
+            assert!(still_waiting);
+            assert_eq!(member_position, 1);
+            assert!(waiting.await.unwrap().is_none());
+            assert_eq!(SCHEDULER.lock().unwrap().queue_position(ops[1]), 0);
+            drop(slot);
+            assert!(!SCHEDULER.lock().unwrap().lanes.contains_key(&lane));
+
+            SCHEDULER.lock().unwrap().limits.remove(&lane);
+            discard_async_operation(holder);
+            for op_id in ops {
+                discard_async_operation(op_id);
+            }
+        });
+    }
+
+    #[test]
+    fn test_set_priority_of_coalesced_request_raises_its_batch() {
+        crate::RUNTIME.block_on(async {
+            // Arrange: a batch queued behind a held slot
+            let lane = usize::MAX - 7;
+            SCHEDULER.lock().unwrap().limits.insert(lane, 1);
+            let (holder, _token) = register_operation();
+            let slot = acquire_uncancelled(lane, holder).await.unwrap();
+            let items: Vec<PendingText> = (0..2)
+                .map(|_| {
+                    let (op_id, cancel_token) = register_operation();
+                    PendingText {
+                        cancel_token,
+                        priority: PRIORITY_BACKGROUND,
+                        ..pending_text(op_id)
+                    }
+                })
+                .collect();
+            let ops: Vec<i64> = items.iter().map(|item| item.op_id).collect();
+            let waiting = tokio::spawn(async move { acquire_batch_slot(lane, &items).await });
+            wait_until_queued(ops[1]).await;
+
+            // Act
+            let raised = set_async_priority(ops[1], 5);
+            let lowered = set_async_priority(ops[1], PRIORITY_BACKGROUND);
+
+            // Assert
+            assert_eq!((raised, lowered), (0, 0));
+            assert_eq!(SCHEDULER.lock().unwrap().priorities.get(&ops[0]), Some(&5));
+            drop(slot);
+            drop(waiting.await.unwrap().unwrap());
+            assert_eq!(set_async_priority(ops[1], 5), -1);
+
+            SCHEDULER.lock().unwrap().limits.remove(&lane);
+            discard_async_operation(holder);
+            for op_id in ops {
+                discard_async_operation(op_id);
+            }
+        });
+    }
+
+    #[test]
+    fn test_micro_batcher_set_priority_in_window() {
+        // Arrange
+        let mut batcher = MicroBatcher::default();
+        let config = MicroBatchConfig {
+            max_batch: 4,
+            max_wait: Duration::from_millis(5),
+        };
+        batcher.configs.insert(1, config);
+        batcher.push(1, pending_text(10));
+
+        // Act
+        let found = batcher.set_priority(10, 7);
+        let missing = batcher.set_priority(11, 7);
+
+        // Assert
+        assert!(found);
+        assert!(!missing);
+        assert_eq!(batcher.take(1, 0)[0].priority, 7);
+    }
+
+    #[test]
+    fn test_micro_batcher_disabled_returns_request() {
+        let mut batcher = MicroBatcher::default();
+
+        let push = batcher.push(1, pending_text(10));
+
+        assert!(matches!(push, Push::Disabled(item) if item.op_id == 10));
+    }
+
+    #[test]
+    fn test_micro_batcher_flushes_full_batch() {
+        // Arrange
+        let mut batcher = MicroBatcher::default();
+        let config = MicroBatchConfig {
+            max_batch: 3,
+            max_wait: Duration::from_millis(5),
+        };
+        batcher.configs.insert(1, config);
+
+        // Act
+        let first = batcher.push(1, pending_text(10));
+        let second = batcher.push(1, pending_text(11));
+        let third = batcher.push(1, pending_text(12));
+
+        // Assert
+        let Push::OpenWindow { generation, .. } = first else {
+            panic!("first request should open a window");
+        };
+        assert!(matches!(second, Push::Queued));
+        let Push::Flush(items) = third else {
+            panic!("third request should fill the batch");
+        };
+        let op_ids: Vec<i64> = items.iter().map(|item| item.op_id).collect();
+        assert_eq!(op_ids, vec![10, 11, 12]);
+        // The window timer finds nothing left to flush
+        assert!(batcher.take(1, generation).is_empty());
+    }
+
+    #[test]
+    fn test_micro_batcher_window_flushes_partial_batch() {
+        // Arrange
+        let mut batcher = MicroBatcher::default();
+        let config = MicroBatchConfig {
+            max_batch: 8,
+            max_wait: Duration::from_millis(5),
+        };
+        batcher.configs.insert(1, config);
+        let Push::OpenWindow { generation, .. } = batcher.push(1, pending_text(10)) else {
+            panic!("first request should open a window");
+        };
+        batcher.push(1, pending_text(11));
+
+        // Act
+        let items = batcher.take(1, generation);
+        let next = batcher.push(1, pending_text(12));
+
+        // Assert
+        assert_eq!(items.len(), 2);
+        assert!(matches!(next, Push::OpenWindow { generation: g, .. } if g != generation));
+    }
+
     #[test]
     fn test_cancellation() {
         let (op_id, token) = register_operation();
@@ -1097,6 +4627,154 @@ mod tests {
         assert!(matches!(op.status, AsyncOperationStatus::Cancelled));
     }
 
+    #[test]
+    fn test_poll_async_progress() {
+        // Arrange
+        let (op_id, _token, progress) = register_tracked_operation();
+        progress.discover(4);
+        progress.start_file(std::path::Path::new("/docs/b.txt"));
+        progress.chunks_embedded.store(7, Ordering::Relaxed);
+
+        // Act
+        let running = poll_async_progress(op_id);
+        store_error(op_id, "Test error".to_string());
+        let finished = poll_async_progress(op_id);
+
+        // Assert
+        assert_eq!(running.status, 0);
+        assert_eq!(running.files_discovered, 4);
+        assert_eq!(running.chunks_embedded, 7);
+        let current = unsafe { CStr::from_ptr(running.current_file) };
+        assert_eq!(current.to_str().unwrap(), "/docs/b.txt");
+        assert_eq!(finished.status, 1);
+
+        crate::free_string(running.current_file);
+        crate::free_string(finished.current_file);
+    }
+
+    #[test]
+    fn test_poll_async_progress_unknown_operation() {
+        let progress = poll_async_progress(i64::MAX);
+
+        assert_eq!(progress.status, -1);
+        assert!(progress.current_file.is_null());
+    }
+
+    #[test]
+    fn test_drain_async_partial_takes_produced_entries() {
+        // Arrange
+        let (op_id, _token) = register_operation();
+        let entry = || EmbedData::new(EmbeddingResult::DenseVector(vec![1.0, 0.0]), None, None);
+        append_partial(op_id, vec![entry(), entry()]);
+
+        // Act
+        let first = drain_async_partial(op_id);
+        let second = drain_async_partial(op_id);
+
+        // Assert
+        assert_eq!(unsafe { (*first).count }, 2);
+        assert_eq!(unsafe { (*second).count }, 0);
+
+        crate::free_embed_data_batch(first);
+        crate::free_embed_data_batch(second);
+        ASYNC_OPERATIONS.lock().unwrap().remove(&op_id);
+    }
+
+    #[test]
+    fn test_undrained_directory_walk_stops_advancing() {
+        crate::RUNTIME.block_on(async {
+            // Arrange: a drained operation that may hold two entries, and a
+            // walk producing one entry per file
+            let (op_id, token) = register_operation();
+            crate::free_embed_data_batch(drain_async_partial(op_id));
+            let files: Vec<PathBuf> = (0..5)
+                .map(|i| PathBuf::from(format!("{}.txt", i)))
+                .collect();
+            let progress = Arc::new(directory::Progress::default());
+            let walk = {
+                let progress = progress.clone();
+                let token = token.clone();
+                tokio::spawn(async move {
+                    let entry =
+                        || EmbedData::new(EmbeddingResult::DenseVector(vec![1.0, 0.0]), None, None);
+                    directory::walk_files(
+                        &files,
+                        &progress,
+                        &token,
+                        &|| wait_for_drain(op_id, 2, &token),
+                        |_| async move { Ok(vec![entry()]) },
+                        |_, items| {
+                            append_partial(op_id, items);
+                            Ok(ControlFlow::Continue(()))
+                        },
+                    )
+                    .await
+                })
+            };
+
+            // Act: let the walk run into the limit
+            while progress.files_done.load(Ordering::Relaxed) < 3 {
+                tokio::task::yield_now().await;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+
+            // Assert: paused until drained, then runs to the end
+            assert_eq!(progress.files_done.load(Ordering::Relaxed), 3);
+            assert!(!walk.is_finished());
+
+            let drained = drain_async_partial(op_id);
+            assert_eq!(unsafe { (*drained).count }, 3);
+            crate::free_embed_data_batch(drained);
+
+            assert_eq!(walk.await.unwrap(), Ok(ControlFlow::Continue(())));
+            assert_eq!(progress.files_done.load(Ordering::Relaxed), 5);
+            ASYNC_OPERATIONS.lock().unwrap().remove(&op_id);
+        });
+    }
+
+    #[test]
+    fn test_wait_for_drain_returns_on_cancel() {
+        crate::RUNTIME.block_on(async {
+            // Arrange: a drained operation over its limit
+            let (op_id, token) = register_operation();
+            crate::free_embed_data_batch(drain_async_partial(op_id));
+            let entry = || EmbedData::new(EmbeddingResult::DenseVector(vec![1.0, 0.0]), None, None);
+            append_partial(op_id, vec![entry(), entry()]);
+            let waiter = {
+                let token = token.clone();
+                tokio::spawn(async move { wait_for_drain(op_id, 1, &token).await })
+            };
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert!(!waiter.is_finished());
+
+            // Act
+            token.cancel();
+
+            // Assert
+            waiter.await.unwrap();
+            ASYNC_OPERATIONS.lock().unwrap().remove(&op_id);
+        });
+    }
+
+    #[test]
+    fn test_wait_for_drain_ignores_operations_never_drained() {
+        crate::RUNTIME.block_on(async {
+            // Arrange: poll_async_result-only callers never drain
+            let (op_id, token) = register_operation();
+            let entry = || EmbedData::new(EmbeddingResult::DenseVector(vec![1.0, 0.0]), None, None);
+            append_partial(op_id, vec![entry(), entry()]);
+
+            // Act / Assert: returns without waiting
+            wait_for_drain(op_id, 1, &token).await;
+            ASYNC_OPERATIONS.lock().unwrap().remove(&op_id);
+        });
+    }
+
+    #[test]
+    fn test_drain_async_partial_unknown_operation() {
+        assert!(drain_async_partial(i64::MAX).is_null());
+    }
+
     #[test]
     fn test_vec_to_c_ptr() {
         let vec = vec![1.0f32, 2.0, 3.0];
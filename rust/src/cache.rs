@@ -0,0 +1,526 @@
+//! In-memory LRU cache of dense text embeddings.
+//!
+//! A cache is optional and belongs to one `CEmbedder`. Entries are keyed by
+//! the model fingerprint (id, revision, dtype), the kind of call (query or
+//! document) and the whitespace-normalized text, so repeated texts skip the
+//! model. Capacity is bounded by entry count, by bytes, or both.
+//!
+//! Text calls consult it through `TextCache`, which falls back to the
+//! process-wide disk cache (see `disk_cache`) on a miss.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+
+use sha2::{Digest, Sha256};
+
+use crate::disk_cache::DiskCache;
+
+/// Identity of a loaded model, recorded on `CEmbedder` at load time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModelFingerprint {
+    /// Hub model id, or the local model path
+    pub model_id: String,
+    /// Git revision (None = default branch), or the content hash of a
+    /// model built from local files (see `with_content_hash`)
+    pub revision: Option<String>,
+    /// Weight dtype: "f32", "f16" or "default"
+    pub dtype: String,
+}
+
+impl ModelFingerprint {
+    /// Build a fingerprint from load arguments. `dtype` is the FFI code
+    /// (0=F32, 1=F16, -1=default).
+    pub fn new(model_id: &str, revision: Option<&str>, dtype: i32) -> Self {
+        let dtype = match dtype {
+            0 => "f32",
+            1 => "f16",
+            _ => "default",
+        };
+        Self {
+            model_id: model_id.to_string(),
+            revision: revision.map(str::to_string),
+            dtype: dtype.to_string(),
+        }
+    }
+
+    /// Record the content of the files a local model was built from.
+    ///
+    /// Local models have no revision; the hash takes its place, so
+    /// replacing the files at the same path changes the fingerprint.
+    pub fn with_content_hash(mut self, hash: String) -> Self {
+        self.revision = Some(hash);
+        self
+    }
+
+    /// Stable string form used as a key prefix.
+    pub fn key(&self) -> String {
+        format!(
+            "{}@{}#{}",
+            self.model_id,
+            self.revision.as_deref().unwrap_or(""),
+            self.dtype
+        )
+    }
+}
+
+/// Content hash of the files a local model is built from.
+///
+/// Files in `hashed` (configs, tokenizers) are hashed in full; files in
+/// `stamped` (weights) contribute only their size and modification time,
+/// so fingerprinting stays cheap for multi-gigabyte checkpoints. Files that
+/// cannot be read contribute a marker instead.
+pub fn content_hash<P: AsRef<Path>>(hashed: &[P], stamped: &[P]) -> String {
+    let mut hasher = Sha256::new();
+    for path in hashed {
+        match fs::read(path.as_ref()) {
+            Ok(contents) => {
+                hasher.update((contents.len() as u64).to_le_bytes());
+                hasher.update(&contents);
+            }
+            Err(_) => hasher.update(b"missing"),
+        }
+    }
+    for path in stamped {
+        match fs::metadata(path.as_ref()) {
+            Ok(metadata) => {
+                let mtime = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map_or(0, |d| d.as_nanos());
+                hasher.update(metadata.len().to_le_bytes());
+                hasher.update(mtime.to_le_bytes());
+            }
+            Err(_) => hasher.update(b"missing"),
+        }
+    }
+    let digest = hasher.finalize();
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256:{}", hex)
+}
+
+/// Which upstream call produced a vector. Some models prefix queries and
+/// documents differently, so the two never share entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheKind {
+    /// `embed_query` (single-text calls)
+    Query,
+    /// `embed` (batch calls)
+    Document,
+}
+
+impl CacheKind {
+    /// Short tag used in cache keys.
+    pub fn tag(self) -> &'static str {
+        match self {
+            CacheKind::Query => "q",
+            CacheKind::Document => "d",
+        }
+    }
+}
+
+/// Hit/miss counters and current size of a cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+    pub bytes: usize,
+}
+
+struct Entry {
+    vector: Vec<f32>,
+    tick: u64,
+    bytes: usize,
+}
+
+#[derive(Default)]
+struct LruState {
+    entries: HashMap<String, Entry>,
+    /// Last-use tick -> key, oldest first
+    order: BTreeMap<u64, String>,
+    next_tick: u64,
+    stats: CacheStats,
+}
+
+impl LruState {
+    fn touch(&mut self, key: &str) -> Option<Vec<f32>> {
+        let tick = self.next_tick;
+        let entry = self.entries.get_mut(key)?;
+        self.order.remove(&entry.tick);
+        self.order.insert(tick, key.to_string());
+        entry.tick = tick;
+        self.next_tick += 1;
+        Some(entry.vector.clone())
+    }
+
+    fn remove_oldest(&mut self) {
+        if let Some((_, key)) = self.order.pop_first() {
+            if let Some(entry) = self.entries.remove(&key) {
+                self.stats.bytes -= entry.bytes;
+                self.stats.entries -= 1;
+                self.stats.evictions += 1;
+            }
+        }
+    }
+}
+
+/// LRU cache of dense vectors for one embedder.
+pub struct EmbeddingCache {
+    model_key: String,
+    max_entries: usize,
+    max_bytes: usize,
+    state: Mutex<LruState>,
+}
+
+impl EmbeddingCache {
+    /// Create an empty cache. A limit of 0 leaves that dimension unbounded;
+    /// callers must set at least one of them.
+    pub fn new(model: &ModelFingerprint, max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            model_key: model.key(),
+            max_entries,
+            max_bytes,
+            state: Mutex::new(LruState::default()),
+        }
+    }
+
+    pub fn max_entries(&self) -> usize {
+        self.max_entries
+    }
+
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    /// Look up one text, counting a hit or a miss.
+    pub fn get(&self, kind: CacheKind, text: &str) -> Option<Vec<f32>> {
+        let key = self.key(kind, text);
+        let mut state = self.state.lock().unwrap();
+        let vector = state.touch(&key);
+        match vector {
+            Some(_) => state.stats.hits += 1,
+            None => state.stats.misses += 1,
+        }
+        vector
+    }
+
+    /// Store a vector, evicting least recently used entries to stay within
+    /// the limits. A vector larger than the byte limit is not stored.
+    pub fn insert(&self, kind: CacheKind, text: &str, vector: &[f32]) {
+        let key = self.key(kind, text);
+        let bytes = key.len() + std::mem::size_of_val(vector);
+        if self.max_bytes > 0 && bytes > self.max_bytes {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if state.touch(&key).is_some() {
+            return;
+        }
+        let tick = state.next_tick;
+        state.next_tick += 1;
+        state.order.insert(tick, key.clone());
+        state.entries.insert(
+            key,
+            Entry {
+                vector: vector.to_vec(),
+                tick,
+                bytes,
+            },
+        );
+        state.stats.entries += 1;
+        state.stats.bytes += bytes;
+
+        while (self.max_entries > 0 && state.stats.entries > self.max_entries)
+            || (self.max_bytes > 0 && state.stats.bytes > self.max_bytes)
+        {
+            state.remove_oldest();
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.state.lock().unwrap().stats
+    }
+
+    fn key(&self, kind: CacheKind, text: &str) -> String {
+        format!(
+            "{}\n{}\n{}",
+            self.model_key,
+            kind.tag(),
+            normalize_text(text)
+        )
+    }
+}
+
+/// The caches a text call consults, captured when the call starts: the
+/// embedder's memory cache, then the disk cache.
+#[derive(Clone, Default)]
+pub struct TextCache {
+    memory: Option<Arc<EmbeddingCache>>,
+    /// Disk store and the embedder's `disk_cache::model_key`
+    disk: Option<(Arc<DiskCache>, String)>,
+}
+
+impl TextCache {
+    pub fn new(
+        memory: Option<Arc<EmbeddingCache>>,
+        disk: Option<(Arc<DiskCache>, String)>,
+    ) -> Self {
+        Self { memory, disk }
+    }
+
+    /// Look up one text. Disk hits are copied into the memory cache.
+    pub fn get(&self, kind: CacheKind, text: &str) -> Option<Vec<f32>> {
+        if let Some(vector) = self.memory.as_ref().and_then(|m| m.get(kind, text)) {
+            return Some(vector);
+        }
+        let (disk, model_key) = self.disk.as_ref()?;
+        let vector = disk.get_text(model_key, kind, text)?;
+        if let Some(memory) = &self.memory {
+            memory.insert(kind, text, &vector);
+        }
+        Some(vector)
+    }
+
+    /// Store a freshly embedded vector in every enabled cache.
+    pub fn insert(&self, kind: CacheKind, text: &str, vector: &[f32]) {
+        if let Some(memory) = &self.memory {
+            memory.insert(kind, text, vector);
+        }
+        if let Some((disk, model_key)) = &self.disk {
+            disk.put_text(model_key, kind, text, vector);
+        }
+    }
+
+    /// Look up every text of a batch.
+    pub fn lookup_batch(&self, kind: CacheKind, texts: &[&str]) -> BatchLookup {
+        let vectors: Vec<Option<Vec<f32>>> =
+            texts.iter().map(|text| self.get(kind, text)).collect();
+        let misses = (0..texts.len()).filter(|&i| vectors[i].is_none()).collect();
+        BatchLookup { vectors, misses }
+    }
+}
+
+/// A batch split into cached vectors and the texts the model still has to
+/// embed.
+#[derive(Debug)]
+pub struct BatchLookup {
+    vectors: Vec<Option<Vec<f32>>>,
+    misses: Vec<usize>,
+}
+
+impl BatchLookup {
+    /// Texts that were not cached, in batch order.
+    pub fn miss_texts<'a>(&self, texts: &[&'a str]) -> Vec<&'a str> {
+        self.misses.iter().map(|&i| texts[i]).collect()
+    }
+
+    /// Number of texts that were not cached.
+    pub fn miss_count(&self) -> usize {
+        self.misses.len()
+    }
+
+    /// All vectors in batch order, if every text was cached.
+    pub fn into_cached(self) -> Option<Vec<Vec<f32>>> {
+        self.vectors.into_iter().collect()
+    }
+
+    /// Merge the vectors embedded for the misses (in `miss_texts` order)
+    /// back into the batch, storing each in `cache`.
+    ///
+    /// # Returns
+    /// All vectors in batch order, or None if `embedded` does not have one
+    /// vector per miss.
+    pub fn fill(
+        mut self,
+        cache: &TextCache,
+        kind: CacheKind,
+        texts: &[&str],
+        embedded: Vec<Vec<f32>>,
+    ) -> Option<Vec<Vec<f32>>> {
+        if embedded.len() != self.misses.len() {
+            return None;
+        }
+        for (&i, vector) in self.misses.iter().zip(embedded) {
+            cache.insert(kind, texts[i], &vector);
+            self.vectors[i] = Some(vector);
+        }
+        self.vectors.into_iter().collect()
+    }
+}
+
+/// Trim and collapse runs of whitespace to a single space.
+pub fn normalize_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> ModelFingerprint {
+        ModelFingerprint::new("org/model", Some("main"), 0)
+    }
+
+    #[test]
+    fn test_normalize_text() {
+        assert_eq!(normalize_text("  hello \n\t world  "), "hello world");
+        assert_eq!(normalize_text(""), "");
+    }
+
+    #[test]
+    fn test_fingerprint_key() {
+        assert_eq!(model().key(), "org/model@main#f32");
+        assert_eq!(
+            ModelFingerprint::new("/models/bert", None, -1).key(),
+            "/models/bert@#default"
+        );
+    }
+
+    #[test]
+    fn test_content_hash_tracks_file_changes() {
+        let dir = std::env::temp_dir()
+            .join("embedanything_dart_tests")
+            .join(format!("content_hash_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("config.json");
+        let weights = dir.join("model.safetensors");
+        fs::write(&config, r#"{"hidden_size": 384}"#).unwrap();
+        fs::write(&weights, [0u8; 8]).unwrap();
+
+        let hash = content_hash(&[&config], &[&weights]);
+        assert!(hash.starts_with("sha256:"));
+        assert_eq!(hash, content_hash(&[&config], &[&weights]));
+
+        fs::write(&config, r#"{"hidden_size": 768}"#).unwrap();
+        let new_config = content_hash(&[&config], &[&weights]);
+        assert_ne!(hash, new_config);
+
+        fs::write(&weights, [0u8; 16]).unwrap();
+        assert_ne!(new_config, content_hash(&[&config], &[&weights]));
+
+        let fingerprint = ModelFingerprint::new("/models/bert", None, 0).with_content_hash(hash);
+        assert!(fingerprint.key().starts_with("/models/bert@sha256:"));
+    }
+
+    #[test]
+    fn test_get_counts_hits_and_misses() {
+        // Arrange
+        let cache = EmbeddingCache::new(&model(), 10, 0);
+        cache.insert(CacheKind::Query, "hello world", &[1.0, 2.0]);
+
+        // Act
+        let hit = cache.get(CacheKind::Query, "  hello   world ");
+        let other_kind = cache.get(CacheKind::Document, "hello world");
+
+        // Assert
+        assert_eq!(hit, Some(vec![1.0, 2.0]));
+        assert_eq!(other_kind, None);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+    }
+
+    #[test]
+    fn test_entry_limit_evicts_least_recently_used() {
+        // Arrange
+        let cache = EmbeddingCache::new(&model(), 2, 0);
+        cache.insert(CacheKind::Query, "a", &[1.0]);
+        cache.insert(CacheKind::Query, "b", &[2.0]);
+
+        // Act: touching "a" makes "b" the oldest entry
+        cache.get(CacheKind::Query, "a");
+        cache.insert(CacheKind::Query, "c", &[3.0]);
+
+        // Assert
+        assert!(cache.get(CacheKind::Query, "a").is_some());
+        assert!(cache.get(CacheKind::Query, "b").is_none());
+        assert!(cache.get(CacheKind::Query, "c").is_some());
+        assert_eq!(cache.stats().evictions, 1);
+        assert_eq!(cache.stats().entries, 2);
+    }
+
+    #[test]
+    fn test_byte_limit_evicts_and_skips_oversized() {
+        // Arrange: each entry is its key plus 4 floats
+        let one_entry = cache_key_len("a") + 16;
+        let cache = EmbeddingCache::new(&model(), 0, one_entry + 8);
+
+        // Act
+        cache.insert(CacheKind::Query, "a", &[0.0; 4]);
+        cache.insert(CacheKind::Query, "b", &[0.0; 4]);
+        cache.insert(CacheKind::Query, "huge", &[0.0; 64]);
+
+        // Assert
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.bytes, one_entry);
+        assert!(cache.get(CacheKind::Query, "b").is_some());
+        assert!(cache.get(CacheKind::Query, "huge").is_none());
+    }
+
+    #[test]
+    fn test_batch_lookup_only_returns_misses() {
+        // Arrange
+        let memory = Arc::new(EmbeddingCache::new(&model(), 10, 0));
+        memory.insert(CacheKind::Document, "b", &[2.0]);
+        let cache = TextCache::new(Some(memory.clone()), None);
+        let texts = ["a", "b", "c"];
+
+        // Act
+        let lookup = cache.lookup_batch(CacheKind::Document, &texts);
+        let misses = lookup.miss_texts(&texts);
+        let vectors = lookup.fill(
+            &cache,
+            CacheKind::Document,
+            &texts,
+            vec![vec![1.0], vec![3.0]],
+        );
+
+        // Assert
+        assert_eq!(misses, vec!["a", "c"]);
+        assert_eq!(vectors, Some(vec![vec![1.0], vec![2.0], vec![3.0]]));
+        assert!(memory.get(CacheKind::Document, "c").is_some());
+    }
+
+    #[test]
+    fn test_batch_fill_rejects_wrong_count() {
+        let cache = TextCache::default();
+        let lookup = cache.lookup_batch(CacheKind::Document, &["a", "b"]);
+
+        let vectors = lookup.fill(&cache, CacheKind::Document, &["a", "b"], vec![vec![1.0]]);
+
+        assert_eq!(vectors, None);
+    }
+
+    #[test]
+    fn test_text_cache_promotes_disk_hits() {
+        // Arrange
+        let dir = std::env::temp_dir().join(format!("text_cache_disk_{}", std::process::id()));
+        let disk = Arc::new(DiskCache::open(&dir, 4096).unwrap());
+        disk.put_text("model", CacheKind::Query, "hello", &[1.0, 2.0]);
+        let memory = Arc::new(EmbeddingCache::new(&model(), 10, 0));
+        let cache = TextCache::new(Some(memory.clone()), Some((disk, "model".to_string())));
+
+        // Act
+        let vector = cache.get(CacheKind::Query, "hello");
+
+        // Assert
+        assert_eq!(vector, Some(vec![1.0, 2.0]));
+        assert_eq!(memory.get(CacheKind::Query, "hello"), Some(vec![1.0, 2.0]));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn cache_key_len(text: &str) -> usize {
+        format!("{}\nq\n{}", model().key(), text).len()
+    }
+}
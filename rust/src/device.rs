@@ -3,8 +3,6 @@
 //! This module provides runtime detection of available compute devices
 //! and exposes functions to query which device is currently active.
 
-// Device import is only used when GPU features are enabled
-#[cfg(any(feature = "cuda", feature = "metal"))]
 use candle_core::Device;
 
 /// Compute device types exposed to Dart via FFI.
@@ -55,6 +53,28 @@ pub fn get_active_device_type() -> ComputeDevice {
     ComputeDevice::Cpu
 }
 
+/// Device for models built by this crate (see `local_model`).
+///
+/// Uses the same priority order as `get_active_device_type`.
+pub fn select_device() -> Device {
+    #[cfg(feature = "metal")]
+    {
+        if let Ok(device) = Device::new_metal(0) {
+            return device;
+        }
+    }
+
+    #[cfg(feature = "cuda")]
+    {
+        // cuda_if_available returns Device::Cpu if CUDA is not available
+        if let Ok(device) = Device::cuda_if_available(0) {
+            return device;
+        }
+    }
+
+    Device::Cpu
+}
+
 /// Check if a specific device type is available.
 ///
 /// Returns `true` if the device can be used for computation, `false` otherwise.
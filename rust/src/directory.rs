@@ -0,0 +1,645 @@
+//! Directory walking for streamed directory embedding.
+//!
+//! Files are discovered up front (recursive, sorted) and embedded one at a
+//! time, so results reach the sink in `buffer_size` batches instead of the
+//! whole tree being held in memory until the walk finishes. Upstream
+//! `embed_file` returns a file's chunks in one piece, so the walk itself
+//! holds at most the largest file's embedded chunks plus one partial
+//! buffer. Buffers handed to the sink are the sink's to bound: the async
+//! job parks them until they are drained and pauses the walk (through
+//! `ready`) while too many wait.
+
+use embed_anything::config::TextEmbedConfig;
+use embed_anything::embeddings::embed::{EmbedData, Embedder};
+use std::collections::HashMap;
+use std::future::Future;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::async_embed;
+use crate::disk_cache::{self, FileCache};
+use crate::options::EmbedderOptions;
+use crate::vision;
+
+/// Text formats walked when no extension filter is given.
+pub const DEFAULT_TEXT_EXTENSIONS: [&str; 5] = ["pdf", "txt", "md", "docx", "html"];
+
+/// Files discovered under a directory, split by pipeline.
+#[derive(Debug, Default, PartialEq)]
+pub struct DirectoryPlan {
+    pub text_files: Vec<PathBuf>,
+    pub image_files: Vec<PathBuf>,
+}
+
+/// Vision embedder used for the image files of a routed walk.
+pub struct ImageRoute<'a> {
+    pub embedder: &'a Embedder,
+    pub options: EmbedderOptions,
+    pub batch_size: Option<usize>,
+    /// Scheduler slot to hold on the vision embedder's lane while the
+    /// images are embedded (async jobs); None runs them unscheduled
+    pub slot: Option<LaneSlot>,
+}
+
+/// Operation that waits for a slot on another embedder's lane.
+#[derive(Debug, Clone, Copy)]
+pub struct LaneSlot {
+    pub lane: usize,
+    pub op_id: i64,
+    pub priority: i32,
+}
+
+/// Live counters of a file or directory job, read by `poll_async_progress`.
+#[derive(Debug, Default)]
+pub struct Progress {
+    pub files_discovered: AtomicUsize,
+    pub files_done: AtomicUsize,
+    pub chunks_embedded: AtomicUsize,
+    pub bytes_read: AtomicU64,
+    current_file: Mutex<Option<PathBuf>>,
+}
+
+impl Progress {
+    /// Record the number of files the job will embed.
+    pub fn discover(&self, files: usize) {
+        self.files_discovered.store(files, Ordering::Relaxed);
+    }
+
+    /// Record the file that is being embedded now.
+    pub fn start_file(&self, path: &Path) {
+        *self.current_file.lock().unwrap() = Some(path.to_path_buf());
+    }
+
+    /// Record a finished file and the number of chunks it produced.
+    pub fn finish_file(&self, path: &Path, chunks: usize) {
+        let bytes = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        self.bytes_read.fetch_add(bytes, Ordering::Relaxed);
+        self.chunks_embedded.fetch_add(chunks, Ordering::Relaxed);
+        self.files_done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// File being embedded, if any.
+    pub fn current_file(&self) -> Option<PathBuf> {
+        self.current_file.lock().unwrap().clone()
+    }
+}
+
+/// Discover the files to embed under `dir`.
+///
+/// With `route_images`, image extensions are collected for the vision
+/// pipeline (see `vision::route_extensions`); otherwise every matching file
+/// goes to the text pipeline.
+///
+/// # Errors
+/// - "FILE_READ_ERROR:" if the directory cannot be read
+pub fn plan_directory(
+    dir: &Path,
+    extensions: Option<Vec<String>>,
+    route_images: bool,
+) -> Result<DirectoryPlan, String> {
+    let (text_extensions, image_extensions) = if route_images {
+        let routing = vision::route_extensions(extensions);
+        let text = match (routing.run_text, routing.text) {
+            (false, _) => Vec::new(),
+            (true, Some(text)) => text.iter().map(|ext| normalize_extension(ext)).collect(),
+            (true, None) => default_text_extensions(),
+        };
+        (text, routing.images)
+    } else {
+        let text = match extensions {
+            Some(exts) => exts.iter().map(|ext| normalize_extension(ext)).collect(),
+            None => default_text_extensions(),
+        };
+        (text, Vec::new())
+    };
+
+    let mut files = Vec::new();
+    collect_files(
+        dir,
+        &[text_extensions, image_extensions.clone()].concat(),
+        &mut files,
+    )
+    .map_err(|e| format!("FILE_READ_ERROR: {}: {}", dir.display(), e))?;
+    files.sort();
+
+    let (image_files, text_files) = files.into_iter().partition(|path| {
+        extension_of(path)
+            .map(|ext| image_extensions.contains(&ext))
+            .unwrap_or(false)
+    });
+
+    Ok(DirectoryPlan {
+        text_files,
+        image_files,
+    })
+}
+
+/// Embed the planned files, handing results to `sink` in buffers.
+///
+/// `sink` receives exactly `buffer_size` chunks at a time (0 flushes
+/// whatever each file produced) plus a final partial buffer, and stops the
+/// walk by returning `ControlFlow::Break`. A file producing more chunks than
+/// `buffer_size` is split across several batches; it is still embedded in
+/// one piece, so memory peaks at the largest file's output. Text entries
+/// get `file_path` metadata; when an image route is given every entry also
+/// carries `modality`.
+///
+/// With a `slot` on the image route, the image batches wait for a slot on
+/// the vision embedder's lane first; cancellation ends that wait.
+///
+/// `ready` is awaited before every file and image batch, so the caller can
+/// pause the walk while its consumer catches up.
+///
+/// `cancel` is checked before every file and image batch; entries already
+/// handed to the sink are kept, the rest of the walk is skipped. Text files
+/// whose content is in `file_cache` skip inference.
+///
+/// # Returns
+/// `ControlFlow::Break` if the sink stopped the walk or it was cancelled.
+///
+/// # Errors
+/// Prefixed error of the first file that failed, or of the sink.
+#[allow(clippy::too_many_arguments)]
+pub async fn stream_directory<S, R, RF>(
+    plan: &DirectoryPlan,
+    embedder: &Embedder,
+    options: EmbedderOptions,
+    config: &TextEmbedConfig,
+    file_cache: Option<&FileCache>,
+    image_route: Option<ImageRoute<'_>>,
+    buffer_size: usize,
+    progress: &Progress,
+    cancel: &CancellationToken,
+    ready: R,
+    mut sink: S,
+) -> Result<ControlFlow<()>, String>
+where
+    S: FnMut(Vec<EmbedData>) -> Result<ControlFlow<()>, String>,
+    R: Fn() -> RF,
+    RF: Future<Output = ()>,
+{
+    let tag_modality = image_route.is_some();
+    let mut buffer: Vec<EmbedData> = Vec::new();
+
+    progress.discover(plan.text_files.len() + plan.image_files.len());
+
+    let embed_text = |path: PathBuf| async move {
+        let embed = embedder.embed_file(path.clone(), Some(config), None);
+        disk_cache::embed_file_cached(file_cache, &path, config, embed)
+            .await
+            .map(Option::unwrap_or_default)
+            .map_err(|e| file_error_message(&path, &e))
+    };
+    let on_file = |path: &Path, items: Vec<EmbedData>| {
+        for mut item in options.apply_to_data(items) {
+            // Upstream reports the source as "file_name"; the Dart API reads "file_path"
+            let metadata = item.metadata.get_or_insert_with(HashMap::new);
+            let file_path = metadata
+                .remove("file_name")
+                .unwrap_or_else(|| path.display().to_string());
+            metadata.insert("file_path".to_string(), file_path);
+            if tag_modality {
+                metadata.insert("modality".to_string(), "text".to_string());
+            }
+            buffer.push(item);
+        }
+        flush_full_buffers(&mut buffer, buffer_size, &mut sink)
+    };
+    if walk_files(
+        &plan.text_files,
+        progress,
+        cancel,
+        &ready,
+        embed_text,
+        on_file,
+    )
+    .await?
+    .is_break()
+    {
+        return Ok(ControlFlow::Break(()));
+    }
+
+    if let Some(route) = image_route {
+        // Counts against the vision embedder's in-flight limit
+        let _slot = match route.slot {
+            Some(slot) if !plan.image_files.is_empty() => {
+                match async_embed::acquire_lane_slot(slot, cancel).await {
+                    Some(slot) => Some(slot),
+                    None => return Ok(ControlFlow::Break(())),
+                }
+            }
+            _ => None,
+        };
+        let batch_size = route.batch_size.unwrap_or(vision::DEFAULT_IMAGE_BATCH_SIZE);
+        for chunk in plan.image_files.chunks(batch_size.max(1)) {
+            ready().await;
+            if cancel.is_cancelled() {
+                return Ok(ControlFlow::Break(()));
+            }
+            progress.start_file(&chunk[0]);
+            buffer.extend(vision::embed_images_as_data(
+                route.embedder,
+                route.options,
+                chunk,
+            )?);
+            for path in chunk {
+                progress.finish_file(path, 1);
+            }
+
+            if flush_full_buffers(&mut buffer, buffer_size, &mut sink)?.is_break() {
+                return Ok(ControlFlow::Break(()));
+            }
+        }
+    }
+
+    if !buffer.is_empty() {
+        return sink(buffer);
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+/// Hand `buffer` to `sink` in batches of `buffer_size` entries, keeping the
+/// remainder. With a `buffer_size` of 0 everything is handed over at once.
+fn flush_full_buffers<S>(
+    buffer: &mut Vec<EmbedData>,
+    buffer_size: usize,
+    sink: &mut S,
+) -> Result<ControlFlow<()>, String>
+where
+    S: FnMut(Vec<EmbedData>) -> Result<ControlFlow<()>, String>,
+{
+    if buffer_size == 0 {
+        if buffer.is_empty() {
+            return Ok(ControlFlow::Continue(()));
+        }
+        return sink(std::mem::take(buffer));
+    }
+    while buffer.len() >= buffer_size {
+        let rest = buffer.split_off(buffer_size);
+        if sink(std::mem::replace(buffer, rest))?.is_break() {
+            return Ok(ControlFlow::Break(()));
+        }
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+/// Embed `files` one at a time, handing each file's entries to `on_file`.
+///
+/// Awaits `ready` before each file. Stops before the next file once
+/// `cancel` fires or `on_file` breaks.
+pub(crate) async fn walk_files<R, RF, E, F, H>(
+    files: &[PathBuf],
+    progress: &Progress,
+    cancel: &CancellationToken,
+    ready: &R,
+    embed: E,
+    mut on_file: H,
+) -> Result<ControlFlow<()>, String>
+where
+    R: Fn() -> RF,
+    RF: Future<Output = ()>,
+    E: Fn(PathBuf) -> F,
+    F: Future<Output = Result<Vec<EmbedData>, String>>,
+    H: FnMut(&Path, Vec<EmbedData>) -> Result<ControlFlow<()>, String>,
+{
+    for path in files {
+        ready().await;
+        if cancel.is_cancelled() {
+            return Ok(ControlFlow::Break(()));
+        }
+
+        progress.start_file(path);
+        let items = embed(path.clone()).await?;
+        progress.finish_file(path, items.len());
+
+        if on_file(path, items)?.is_break() {
+            return Ok(ControlFlow::Break(()));
+        }
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+/// Map an upstream `embed_file` failure to a prefixed error message.
+pub fn file_error_message(path: &Path, error: &dyn std::fmt::Display) -> String {
+    let error_str = error.to_string().to_lowercase();
+    if error_str.contains("not found") || error_str.contains("no such file") {
+        format!("FILE_NOT_FOUND: {}", path.display())
+    } else if error_str.contains("unsupported") || error_str.contains("format") {
+        format!(
+            "UNSUPPORTED_FORMAT: {} for {}",
+            extension_of(path).unwrap_or_else(|| "unknown".to_string()),
+            path.display()
+        )
+    } else if error_str.contains("permission") || error_str.contains("access denied") {
+        format!("FILE_READ_ERROR: {}: {}", path.display(), error)
+    } else {
+        format!(
+            "EMBEDDING_FAILED: Failed to embed '{}': {}",
+            path.display(),
+            error
+        )
+    }
+}
+
+/// Lowercase extension without the leading dot (".PDF" -> "pdf").
+pub fn normalize_extension(ext: &str) -> String {
+    ext.trim_start_matches('.').to_lowercase()
+}
+
+/// Recursively collect files whose normalized extension is in `extensions`.
+pub fn collect_files(
+    dir: &Path,
+    extensions: &[String],
+    out: &mut Vec<PathBuf>,
+) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_files(&path, extensions, out)?;
+        } else if path.is_file() {
+            let matches = extension_of(&path)
+                .map(|ext| extensions.contains(&ext))
+                .unwrap_or(false);
+            if matches {
+                out.push(path);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn extension_of(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(normalize_extension)
+}
+
+fn default_text_extensions() -> Vec<String> {
+    DEFAULT_TEXT_EXTENSIONS
+        .iter()
+        .map(|ext| ext.to_string())
+        .collect()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a scratch tree:
+    /// root/{a.txt, b.png, skip.bin, nested/{c.md, d.JPG}}
+    fn scratch_tree(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("{}_{}", name, std::process::id()));
+        let nested = root.join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        for file in ["a.txt", "b.png", "skip.bin"] {
+            std::fs::write(root.join(file), b"x").unwrap();
+        }
+        for file in ["c.md", "d.JPG"] {
+            std::fs::write(nested.join(file), b"x").unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_normalize_extension() {
+        assert_eq!(normalize_extension(".PDF"), "pdf");
+        assert_eq!(normalize_extension("md"), "md");
+    }
+
+    #[test]
+    fn test_plan_directory_text_only_defaults() {
+        // Arrange
+        let root = scratch_tree("plan_text_only");
+
+        // Act
+        let plan = plan_directory(&root, None, false).unwrap();
+
+        // Assert
+        assert_eq!(
+            plan.text_files,
+            vec![root.join("a.txt"), root.join("nested").join("c.md")]
+        );
+        assert!(plan.image_files.is_empty());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_plan_directory_routes_images() {
+        // Arrange
+        let root = scratch_tree("plan_routed");
+
+        // Act
+        let plan = plan_directory(&root, None, true).unwrap();
+
+        // Assert
+        assert_eq!(
+            plan.text_files,
+            vec![root.join("a.txt"), root.join("nested").join("c.md")]
+        );
+        assert_eq!(
+            plan.image_files,
+            vec![root.join("b.png"), root.join("nested").join("d.JPG")]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_plan_directory_respects_filter() {
+        // Arrange
+        let root = scratch_tree("plan_filtered");
+
+        // Act
+        let plan = plan_directory(
+            &root,
+            Some(vec![".md".to_string(), ".jpg".to_string()]),
+            true,
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(plan.text_files, vec![root.join("nested").join("c.md")]);
+        assert_eq!(plan.image_files, vec![root.join("nested").join("d.JPG")]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_plan_directory_missing_dir() {
+        let result = plan_directory(Path::new("/nonexistent/plan_dir"), None, false);
+
+        assert!(result.unwrap_err().starts_with("FILE_READ_ERROR:"));
+    }
+
+    #[test]
+    fn test_progress_counts_finished_files() {
+        // Arrange
+        let root = scratch_tree("progress_counts");
+        let progress = Progress::default();
+
+        // Act
+        progress.start_file(&root.join("a.txt"));
+        progress.finish_file(&root.join("a.txt"), 3);
+        progress.finish_file(&root.join("b.png"), 1);
+
+        // Assert
+        assert_eq!(progress.files_done.load(Ordering::Relaxed), 2);
+        assert_eq!(progress.chunks_embedded.load(Ordering::Relaxed), 4);
+        assert_eq!(progress.bytes_read.load(Ordering::Relaxed), 2);
+        assert_eq!(progress.current_file(), Some(root.join("a.txt")));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_walk_files_stops_after_cancel() {
+        // Arrange
+        let files: Vec<PathBuf> = ["a.txt", "b.txt", "c.txt"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        let progress = Progress::default();
+        let cancel = CancellationToken::new();
+        let embedded = Mutex::new(Vec::new());
+        let embed = |path: PathBuf| {
+            // Cancel while the first file is being embedded
+            embedded.lock().unwrap().push(path);
+            cancel.cancel();
+            async { Ok(Vec::new()) }
+        };
+
+        // Act
+        let result = crate::RUNTIME.block_on(walk_files(
+            &files,
+            &progress,
+            &cancel,
+            &|| async {},
+            embed,
+            |_, _| Ok(ControlFlow::Continue(())),
+        ));
+
+        // Assert
+        assert_eq!(result, Ok(ControlFlow::Break(())));
+        assert_eq!(*embedded.lock().unwrap(), vec![PathBuf::from("a.txt")]);
+        assert_eq!(progress.files_done.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_walk_files_stops_when_sink_breaks() {
+        // Arrange
+        let files = vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")];
+        let progress = Progress::default();
+        let mut seen = Vec::new();
+
+        // Act
+        let result = crate::RUNTIME.block_on(walk_files(
+            &files,
+            &progress,
+            &CancellationToken::new(),
+            &|| async {},
+            |_| async { Ok(Vec::new()) },
+            |path, _| {
+                seen.push(path.to_path_buf());
+                Ok(ControlFlow::Break(()))
+            },
+        ));
+
+        // Assert
+        assert_eq!(result, Ok(ControlFlow::Break(())));
+        assert_eq!(seen, vec![PathBuf::from("a.txt")]);
+    }
+
+    fn text_items(count: usize) -> Vec<EmbedData> {
+        (0..count)
+            .map(|i| EmbedData {
+                embedding: embed_anything::embeddings::embed::EmbeddingResult::DenseVector(vec![
+                    i as f32,
+                ]),
+                text: Some(i.to_string()),
+                metadata: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_flush_splits_large_files_into_buffers() {
+        // Arrange: one file produced 7 chunks, buffers hold 3
+        let mut buffer = text_items(7);
+        let mut batches = Vec::new();
+
+        // Act
+        let result = flush_full_buffers(&mut buffer, 3, &mut |batch: Vec<EmbedData>| {
+            batches.push(batch.len());
+            Ok(ControlFlow::Continue(()))
+        });
+
+        // Assert
+        assert_eq!(result, Ok(ControlFlow::Continue(())));
+        assert_eq!(batches, vec![3, 3]);
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer[0].text.as_deref(), Some("6"));
+    }
+
+    #[test]
+    fn test_flush_stops_when_sink_breaks() {
+        // Arrange
+        let mut buffer = text_items(7);
+        let mut calls = 0;
+
+        // Act
+        let result = flush_full_buffers(&mut buffer, 2, &mut |_| {
+            calls += 1;
+            Ok(ControlFlow::Break(()))
+        });
+
+        // Assert
+        assert_eq!(result, Ok(ControlFlow::Break(())));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_flush_zero_buffer_hands_over_everything() {
+        // Arrange
+        let mut buffer = text_items(5);
+        let mut batches = Vec::new();
+
+        let mut sink = |batch: Vec<EmbedData>| {
+            batches.push(batch.len());
+            Ok(ControlFlow::Continue(()))
+        };
+
+        // Act: the second flush has nothing left to hand over
+        let first = flush_full_buffers(&mut buffer, 0, &mut sink);
+        let second = flush_full_buffers(&mut buffer, 0, &mut sink);
+
+        // Assert
+        assert_eq!(first, Ok(ControlFlow::Continue(())));
+        assert_eq!(second, Ok(ControlFlow::Continue(())));
+        assert_eq!(batches, vec![5]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_file_error_message_prefixes() {
+        let path = Path::new("/docs/report.xyz");
+
+        assert!(
+            file_error_message(path, &"No such file or directory").starts_with("FILE_NOT_FOUND:")
+        );
+        assert_eq!(
+            file_error_message(path, &"Unsupported file type"),
+            "UNSUPPORTED_FORMAT: xyz for /docs/report.xyz"
+        );
+        assert!(file_error_message(path, &"Permission denied").starts_with("FILE_READ_ERROR:"));
+        assert!(file_error_message(path, &"tokenizer exploded").starts_with("EMBEDDING_FAILED:"));
+    }
+}
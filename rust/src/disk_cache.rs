@@ -0,0 +1,1098 @@
+//! Persistent embedding cache shared by every embedder of the process.
+//!
+//! Opened with `cache_open`, the store is a single append-only file
+//! (`embeddings.cache`) under the chosen directory. Records are keyed by a
+//! SHA-256 over the model fingerprint, the options that change the output
+//! and the content (normalized text, or the file bytes plus chunking
+//! settings), so entries survive restarts and are never shared between
+//! incompatible models. Each record carries a checksum; a record that fails
+//! it (torn or overwritten bytes) is dropped instead of decoded.
+//!
+//! Beyond the size limit the least recently used records are dropped from
+//! the index; their bytes stay in the file until compaction rewrites it with
+//! the live records only. Compaction runs on its own once dead bytes
+//! outweigh live ones, or on demand through `cache_compact`.
+//!
+//! An open store holds an advisory lock on `embeddings.cache.lock`, so a
+//! second process pointed at the same directory fails to open it instead
+//! of appending to the same file.
+
+use embed_anything::config::TextEmbedConfig;
+use embed_anything::embeddings::embed::{EmbedData, EmbeddingResult};
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::future::Future;
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::cache::{normalize_text, CacheKind, ModelFingerprint};
+use crate::options::EmbedderOptions;
+
+/// Name of the store file inside the cache directory.
+pub const CACHE_FILE_NAME: &str = "embeddings.cache";
+
+/// Name of the lock file held while a store is open.
+pub const LOCK_FILE_NAME: &str = "embeddings.cache.lock";
+
+/// Size limit used when `cache_open` is given 0 (512 MiB).
+pub const DEFAULT_MAX_BYTES: u64 = 512 * 1024 * 1024;
+
+const MAGIC: &[u8; 8] = b"EADCACHE";
+const VERSION: u32 = 2;
+const HEADER_LEN: u64 = 12;
+
+/// Key (32 bytes) + payload length (u32) + checksum (u64)
+const RECORD_HEADER_LEN: u64 = 44;
+
+/// Files smaller than this are never compacted automatically
+const MIN_COMPACT_BYTES: u64 = 1024 * 1024;
+
+/// SHA-256 of a record's key material.
+pub type Key = [u8; 32];
+
+static DISK_CACHE: Lazy<RwLock<Option<Arc<DiskCache>>>> = Lazy::new(|| RwLock::new(None));
+
+/// The open disk cache, if any.
+pub fn current() -> Option<Arc<DiskCache>> {
+    DISK_CACHE.read().unwrap().clone()
+}
+
+/// Replace (or with None, close) the process-wide disk cache.
+///
+/// The previous store is closed before the swap: embedders still holding
+/// it stop reading and writing, so a store reopened on the same directory
+/// is the only writer of its file.
+pub fn install(cache: Option<Arc<DiskCache>>) {
+    let mut slot = DISK_CACHE.write().unwrap();
+    if let Some(previous) = slot.take() {
+        previous.close();
+    }
+    *slot = cache;
+}
+
+/// Disk-cache identity of an embedder: its model fingerprint plus the
+/// options that change the vectors it returns.
+pub fn model_key(model: &ModelFingerprint, options: &EmbedderOptions) -> String {
+    format!(
+        "{}|normalize={}|pooling={:?}|max_len={:?}",
+        model.key(),
+        options.normalize,
+        options.pooling,
+        options.max_sequence_length
+    )
+}
+
+/// Size and occupancy of a disk cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiskCacheStats {
+    pub entries: usize,
+    /// Bytes of the records still in the index
+    pub live_bytes: u64,
+    /// Size of the store file, dead records included
+    pub file_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Record {
+    offset: u64,
+    len: u32,
+    tick: u64,
+}
+
+impl Record {
+    fn size(&self) -> u64 {
+        RECORD_HEADER_LEN + self.len as u64
+    }
+}
+
+struct DiskState {
+    file: File,
+    file_len: u64,
+    index: HashMap<Key, Record>,
+    /// Last-use tick -> key, oldest first
+    order: BTreeMap<u64, Key>,
+    next_tick: u64,
+    live_bytes: u64,
+    /// Set once the store is replaced or closed; every access is a no-op
+    closed: bool,
+    /// Advisory lock of the directory, released on close
+    lock: Option<File>,
+}
+
+impl DiskState {
+    /// Index the records of an existing store. A file with a foreign header
+    /// is started over; a torn record at the end (interrupted write) is cut.
+    fn load(mut file: File) -> std::io::Result<Self> {
+        let file_len = file.metadata()?.len();
+        let mut records = Vec::new();
+        let mut end = HEADER_LEN;
+
+        let mut reader = BufReader::new(&mut file);
+        let mut header = [0u8; HEADER_LEN as usize];
+        let valid = file_len >= HEADER_LEN
+            && reader.read_exact(&mut header).is_ok()
+            && &header[..8] == MAGIC
+            && u32::from_le_bytes(header[8..12].try_into().unwrap()) == VERSION;
+        if valid {
+            let mut head = [0u8; RECORD_HEADER_LEN as usize];
+            while end + RECORD_HEADER_LEN <= file_len && reader.read_exact(&mut head).is_ok() {
+                let len = u32::from_le_bytes(head[32..36].try_into().unwrap());
+                if end + RECORD_HEADER_LEN + len as u64 > file_len {
+                    break;
+                }
+                reader.seek_relative(len as i64)?;
+                let key: Key = head[..32].try_into().unwrap();
+                records.push((key, end, len));
+                end += RECORD_HEADER_LEN + len as u64;
+            }
+        }
+        drop(reader);
+
+        let mut state = Self {
+            file,
+            file_len: end,
+            index: HashMap::new(),
+            order: BTreeMap::new(),
+            next_tick: 0,
+            live_bytes: 0,
+            closed: false,
+            lock: None,
+        };
+        if !valid {
+            state.reset()?;
+            return Ok(state);
+        }
+        if end < file_len {
+            state.file.set_len(end)?;
+        }
+        for (key, offset, len) in records {
+            state.add(key, offset, len);
+        }
+        Ok(state)
+    }
+
+    /// Truncate the store to an empty header.
+    fn reset(&mut self) -> std::io::Result<()> {
+        self.file.set_len(0)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.write_all(MAGIC)?;
+        self.file.write_all(&VERSION.to_le_bytes())?;
+        self.file.flush()?;
+        self.file_len = HEADER_LEN;
+        self.index.clear();
+        self.order.clear();
+        self.live_bytes = 0;
+        Ok(())
+    }
+
+    fn add(&mut self, key: Key, offset: u64, len: u32) {
+        self.remove(&key);
+        let record = Record {
+            offset,
+            len,
+            tick: self.next_tick,
+        };
+        self.next_tick += 1;
+        self.order.insert(record.tick, key);
+        self.live_bytes += record.size();
+        self.index.insert(key, record);
+    }
+
+    fn remove(&mut self, key: &Key) {
+        if let Some(record) = self.index.remove(key) {
+            self.order.remove(&record.tick);
+            self.live_bytes -= record.size();
+        }
+    }
+
+    fn touch(&mut self, key: &Key) -> Option<Record> {
+        let record = self.index.get_mut(key)?;
+        self.order.remove(&record.tick);
+        record.tick = self.next_tick;
+        self.next_tick += 1;
+        self.order.insert(record.tick, *key);
+        Some(*record)
+    }
+
+    /// Read and verify the record stored for `key`. A record whose header
+    /// or checksum does not match (torn or overwritten bytes) is an error.
+    fn read_payload(&mut self, key: &Key, record: Record) -> std::io::Result<Vec<u8>> {
+        let mut bytes = vec![0u8; record.size() as usize];
+        self.file.seek(SeekFrom::Start(record.offset))?;
+        self.file.read_exact(&mut bytes)?;
+
+        let payload = bytes.split_off(RECORD_HEADER_LEN as usize);
+        if bytes != encode_record_header(key, &payload) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "cache record checksum mismatch",
+            ));
+        }
+        Ok(payload)
+    }
+
+    fn append(&mut self, key: Key, payload: &[u8]) -> std::io::Result<()> {
+        let mut record = encode_record_header(&key, payload);
+        record.extend_from_slice(payload);
+
+        self.file.seek(SeekFrom::Start(self.file_len))?;
+        self.file.write_all(&record)?;
+        self.add(key, self.file_len, payload.len() as u32);
+        self.file_len += record.len() as u64;
+        Ok(())
+    }
+
+    fn evict_until(&mut self, max_bytes: u64) {
+        while self.live_bytes > max_bytes {
+            let Some((_, key)) = self.order.pop_first() else {
+                break;
+            };
+            if let Some(record) = self.index.remove(&key) {
+                self.live_bytes -= record.size();
+            }
+        }
+    }
+
+    /// Rewrite the store with the live records only, oldest first so the
+    /// recency order survives a restart. Records failing verification are
+    /// dropped. The index keeps pointing at the old file until the new one
+    /// has replaced it.
+    fn compact(&mut self, path: &Path) -> std::io::Result<()> {
+        let tmp_path = path.with_extension("cache.tmp");
+        // Declared before the file so the handle is closed before removal
+        let mut tmp_guard = TmpFile {
+            path: &tmp_path,
+            renamed: false,
+        };
+        let mut tmp = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&tmp_path)?;
+        tmp.write_all(MAGIC)?;
+        tmp.write_all(&VERSION.to_le_bytes())?;
+
+        let keys: Vec<Key> = self.order.values().copied().collect();
+        let mut offsets = Vec::with_capacity(keys.len());
+        let mut corrupt = Vec::new();
+        let mut end = HEADER_LEN;
+        for key in &keys {
+            let record = self.index[key];
+            let payload = match self.read_payload(key, record) {
+                Ok(payload) => payload,
+                Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                    corrupt.push(*key);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            tmp.write_all(&encode_record_header(key, &payload))?;
+            tmp.write_all(&payload)?;
+            offsets.push((*key, end));
+            end += record.size();
+        }
+        tmp.sync_all()?;
+
+        // Close the old handle before the file is replaced (required on
+        // Windows). If the rename fails the old file is still intact, so
+        // reopen it and keep the current offsets.
+        drop(std::mem::replace(&mut self.file, tmp));
+        if let Err(e) = std::fs::rename(&tmp_path, path) {
+            match OpenOptions::new().read(true).write(true).open(path) {
+                Ok(file) => self.file = file,
+                Err(_) => self.closed = true,
+            }
+            return Err(e);
+        }
+        tmp_guard.renamed = true;
+
+        self.file_len = end;
+        for key in &corrupt {
+            self.remove(key);
+        }
+        for (key, offset) in offsets {
+            if let Some(record) = self.index.get_mut(&key) {
+                record.offset = offset;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Temporary file of a compaction, removed on every path that does not
+/// rename it into place.
+struct TmpFile<'a> {
+    path: &'a Path,
+    renamed: bool,
+}
+
+impl Drop for TmpFile<'_> {
+    fn drop(&mut self) {
+        if !self.renamed {
+            let _ = std::fs::remove_file(self.path);
+        }
+    }
+}
+
+/// Take the advisory lock of a cache directory.
+fn lock_dir(dir: &Path) -> Result<File, String> {
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(dir.join(LOCK_FILE_NAME))
+        .map_err(|e| format!("FILE_READ_ERROR: {}: {}", dir.display(), e))?;
+    match file.try_lock() {
+        Ok(()) => Ok(file),
+        Err(TryLockError::WouldBlock) => Err(format!(
+            "FILE_READ_ERROR: {}: cache is already open in another process",
+            dir.display()
+        )),
+        Err(TryLockError::Error(e)) => Err(format!("FILE_READ_ERROR: {}: {}", dir.display(), e)),
+    }
+}
+
+/// Single-file store of cached embeddings.
+pub struct DiskCache {
+    path: PathBuf,
+    max_bytes: u64,
+    state: Mutex<DiskState>,
+}
+
+impl DiskCache {
+    /// Open (or create) the store under `dir`, trimming it to `max_bytes`.
+    ///
+    /// # Errors
+    /// - "FILE_READ_ERROR:" if the directory or store cannot be opened, or
+    ///   another open store holds the directory's lock
+    pub fn open(dir: &Path, max_bytes: u64) -> Result<Self, String> {
+        let io_error = |e: std::io::Error| format!("FILE_READ_ERROR: {}: {}", dir.display(), e);
+
+        std::fs::create_dir_all(dir).map_err(io_error)?;
+        let lock = lock_dir(dir)?;
+        let path = dir.join(CACHE_FILE_NAME);
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(io_error)?;
+        let mut state = DiskState::load(file).map_err(io_error)?;
+        state.lock = Some(lock);
+
+        if state.live_bytes > max_bytes {
+            state.evict_until(max_bytes);
+            state.compact(&path).map_err(io_error)?;
+        }
+
+        Ok(Self {
+            path,
+            max_bytes,
+            state: Mutex::new(state),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Stop serving this store. Takes the state lock, so a write in progress
+    /// completes first and none starts afterwards.
+    pub fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        state.lock = None;
+    }
+
+    /// Payload stored under `key`, if any.
+    pub fn get(&self, key: &Key) -> Option<Vec<u8>> {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return None;
+        }
+        let record = state.touch(key)?;
+        match state.read_payload(key, record) {
+            Ok(payload) => Some(payload),
+            Err(_) => {
+                state.remove(key);
+                None
+            }
+        }
+    }
+
+    /// Store a payload, evicting least recently used records beyond the size
+    /// limit. Write failures are ignored: the cache is best effort.
+    pub fn put(&self, key: &Key, payload: &[u8]) {
+        if RECORD_HEADER_LEN + payload.len() as u64 > self.max_bytes {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if state.closed || state.touch(key).is_some() || state.append(*key, payload).is_err() {
+            return;
+        }
+        state.evict_until(self.max_bytes);
+
+        let dead_bytes = state.file_len - HEADER_LEN - state.live_bytes;
+        if state.file_len > MIN_COMPACT_BYTES && dead_bytes > state.live_bytes {
+            let _ = state.compact(&self.path);
+        }
+    }
+
+    /// Drop every record.
+    ///
+    /// # Errors
+    /// - "FILE_READ_ERROR:" if the store cannot be truncated
+    pub fn clear(&self) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return Ok(());
+        }
+        state
+            .reset()
+            .map_err(|e| format!("FILE_READ_ERROR: {}: {}", self.path.display(), e))
+    }
+
+    /// Reclaim the space of evicted records.
+    ///
+    /// # Errors
+    /// - "FILE_READ_ERROR:" if the store cannot be rewritten
+    pub fn compact(&self) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return Ok(());
+        }
+        state
+            .compact(&self.path)
+            .map_err(|e| format!("FILE_READ_ERROR: {}: {}", self.path.display(), e))
+    }
+
+    pub fn stats(&self) -> DiskCacheStats {
+        let state = self.state.lock().unwrap();
+        DiskCacheStats {
+            entries: state.index.len(),
+            live_bytes: state.live_bytes,
+            file_bytes: state.file_len,
+        }
+    }
+
+    /// Cached vector of a text embedded by the model behind `model_key`.
+    pub fn get_text(&self, model_key: &str, kind: CacheKind, text: &str) -> Option<Vec<f32>> {
+        decode_vector(&self.get(&text_key(model_key, kind, text))?)
+    }
+
+    pub fn put_text(&self, model_key: &str, kind: CacheKind, text: &str, vector: &[f32]) {
+        self.put(&text_key(model_key, kind, text), &encode_vector(vector));
+    }
+}
+
+/// Disk-cache handle for the `embed_file` results of one embedder.
+pub struct FileCache {
+    disk: Arc<DiskCache>,
+    model_key: String,
+}
+
+impl FileCache {
+    pub fn new(disk: Arc<DiskCache>, model_key: String) -> Self {
+        Self { disk, model_key }
+    }
+
+    /// Key of a file's current contents, or None if it cannot be read.
+    ///
+    /// Covers the extension, which selects the upstream reader, and every
+    /// config field that changes the chunks; `batch_size` and `buffer_size`
+    /// only change how they are scheduled.
+    pub fn key(&self, path: &Path, config: &TextEmbedConfig) -> Option<Key> {
+        let mut hasher = Sha256::new();
+        hasher.update(format!(
+            "file\n{}\n{:?}\n{:?}\n{:?}\n{:?}\n{:?}\n{}\n{:?}\n{:?}\n",
+            self.model_key,
+            path.extension(),
+            config.chunk_size,
+            config.overlap_ratio,
+            config.late_chunking,
+            config.splitting_strategy,
+            config.semantic_encoder.is_some(),
+            config.use_ocr,
+            config.tesseract_path,
+        ));
+        std::io::copy(&mut File::open(path).ok()?, &mut hasher).ok()?;
+        Some(hasher.finalize().into())
+    }
+
+    /// Cached upstream result for a file. The "file_name" metadata is
+    /// rewritten to `path`, as the same content may have been cached from
+    /// another location.
+    pub fn get(&self, key: &Key, path: &Path) -> Option<Vec<EmbedData>> {
+        let mut items = decode_items(&self.disk.get(key)?)?;
+        for item in &mut items {
+            if let Some(file_name) = item
+                .metadata
+                .as_mut()
+                .and_then(|metadata| metadata.get_mut("file_name"))
+            {
+                *file_name = path.display().to_string();
+            }
+        }
+        Some(items)
+    }
+
+    pub fn put(&self, key: &Key, items: &[EmbedData]) {
+        self.disk.put(key, &encode_items(items));
+    }
+}
+
+/// Await `embed` for `path` unless its result is cached, caching a fresh
+/// non-empty result.
+pub async fn embed_file_cached<E, F>(
+    cache: Option<&FileCache>,
+    path: &Path,
+    config: &TextEmbedConfig,
+    embed: F,
+) -> Result<Option<Vec<EmbedData>>, E>
+where
+    F: Future<Output = Result<Option<Vec<EmbedData>>, E>>,
+{
+    let keyed = cache.and_then(|cache| Some((cache, cache.key(path, config)?)));
+    if let Some(items) = keyed.and_then(|(cache, key)| cache.get(&key, path)) {
+        return Ok(Some(items));
+    }
+
+    let result = embed.await;
+    if let (Some((cache, key)), Ok(Some(items))) = (keyed, &result) {
+        cache.put(&key, items);
+    }
+    result
+}
+
+/// Record header: key, payload length and the first 8 bytes of a SHA-256
+/// over both, so a payload is only returned under the key it was written for.
+fn encode_record_header(key: &Key, payload: &[u8]) -> Vec<u8> {
+    let len = (payload.len() as u32).to_le_bytes();
+    let digest = Sha256::new()
+        .chain_update(key)
+        .chain_update(len)
+        .chain_update(payload)
+        .finalize();
+
+    let mut header = Vec::with_capacity(RECORD_HEADER_LEN as usize + payload.len());
+    header.extend_from_slice(key);
+    header.extend_from_slice(&len);
+    header.extend_from_slice(&digest[..8]);
+    header
+}
+
+fn text_key(model_key: &str, kind: CacheKind, text: &str) -> Key {
+    Sha256::digest(format!(
+        "text\n{}\n{}\n{}",
+        model_key,
+        kind.tag(),
+        normalize_text(text)
+    ))
+    .into()
+}
+
+// ============================================================================
+// Payload Encoding
+// ============================================================================
+
+pub(crate) fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+pub(crate) fn decode_vector(bytes: &[u8]) -> Option<Vec<f32>> {
+    if !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+    Some(
+        bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect(),
+    )
+}
+
+/// Encode `embed_file` output: per item the shape (rows, dim), the values,
+/// then text and metadata JSON as optional length-prefixed strings.
+fn encode_items(items: &[EmbedData]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(items.len() as u32).to_le_bytes());
+    for item in items {
+        let (rows, values): (usize, Vec<f32>) = match &item.embedding {
+            EmbeddingResult::DenseVector(vec) => (1, vec.clone()),
+            EmbeddingResult::MultiVector(token_vecs) => (token_vecs.len(), token_vecs.concat()),
+        };
+        let dim = if rows == 0 { 0 } else { values.len() / rows };
+        out.push(matches!(item.embedding, EmbeddingResult::MultiVector(_)) as u8);
+        out.extend_from_slice(&(rows as u32).to_le_bytes());
+        out.extend_from_slice(&(dim as u32).to_le_bytes());
+        out.extend_from_slice(&encode_vector(&values));
+
+        let metadata = item
+            .metadata
+            .as_ref()
+            .and_then(|metadata| serde_json::to_string(metadata).ok());
+        for field in [item.text.as_deref(), metadata.as_deref()] {
+            match field {
+                Some(s) => {
+                    out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+                    out.extend_from_slice(s.as_bytes());
+                }
+                None => out.extend_from_slice(&u32::MAX.to_le_bytes()),
+            }
+        }
+    }
+    out
+}
+
+fn decode_items(bytes: &[u8]) -> Option<Vec<EmbedData>> {
+    let mut reader = ByteReader::new(bytes);
+    let count = reader.u32()?;
+    let mut items = Vec::new();
+    for _ in 0..count {
+        let multi = reader.take(1)?[0] == 1;
+        let rows = reader.u32()? as usize;
+        let dim = reader.u32()? as usize;
+        let values = decode_vector(reader.take(rows.checked_mul(dim)?.checked_mul(4)?)?)?;
+        let embedding = if multi {
+            EmbeddingResult::MultiVector(values.chunks(dim.max(1)).map(<[f32]>::to_vec).collect())
+        } else {
+            EmbeddingResult::DenseVector(values)
+        };
+        let text = reader.string()?;
+        let metadata = match reader.string()? {
+            Some(json) => Some(serde_json::from_str(&json).ok()?),
+            None => None,
+        };
+        items.push(EmbedData {
+            embedding,
+            text,
+            metadata,
+        });
+    }
+    Some(items)
+}
+
+/// Bounds-checked little-endian reader; every method returns None past the
+/// end of the input.
+pub(crate) struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    pub(crate) fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.bytes.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(slice)
+    }
+
+    /// Whether every byte has been read.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    pub(crate) fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Length-prefixed UTF-8 string; `u32::MAX` encodes None.
+    pub(crate) fn string(&mut self) -> Option<Option<String>> {
+        match self.u32()? {
+            u32::MAX => Some(None),
+            len => Some(Some(
+                String::from_utf8(self.take(len as usize)?.to_vec()).ok()?,
+            )),
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn key(n: u8) -> Key {
+        [n; 32]
+    }
+
+    #[test]
+    fn test_records_survive_reopen() {
+        // Arrange
+        let dir = scratch_dir("disk_cache_reopen");
+        let cache = DiskCache::open(&dir, 1024).unwrap();
+        cache.put(&key(1), b"first");
+        cache.put(&key(2), b"second");
+        drop(cache);
+
+        // Act
+        let reopened = DiskCache::open(&dir, 1024).unwrap();
+
+        // Assert
+        assert_eq!(reopened.get(&key(1)), Some(b"first".to_vec()));
+        assert_eq!(reopened.get(&key(2)), Some(b"second".to_vec()));
+        assert_eq!(reopened.get(&key(3)), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_closed_store_stops_writing() {
+        // Arrange: an embedder still holds the store while it is reopened
+        let dir = scratch_dir("disk_cache_closed");
+        let old = DiskCache::open(&dir, 1024).unwrap();
+        old.put(&key(1), b"first");
+        old.close();
+        let reopened = DiskCache::open(&dir, 1024).unwrap();
+
+        // Act
+        old.put(&key(2), b"stale");
+        reopened.put(&key(3), b"third");
+
+        // Assert
+        assert_eq!(old.get(&key(1)), None);
+        assert_eq!(reopened.get(&key(1)), Some(b"first".to_vec()));
+        assert_eq!(reopened.get(&key(2)), None);
+        assert_eq!(reopened.get(&key(3)), Some(b"third".to_vec()));
+        drop(reopened);
+        let again = DiskCache::open(&dir, 1024).unwrap();
+        assert_eq!(again.stats().entries, 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_size_limit_evicts_least_recently_used() {
+        // Arrange: room for two 10-byte records
+        let dir = scratch_dir("disk_cache_evict");
+        let cache = DiskCache::open(&dir, 2 * (RECORD_HEADER_LEN + 10)).unwrap();
+        cache.put(&key(1), &[1; 10]);
+        cache.put(&key(2), &[2; 10]);
+
+        // Act: reading key 1 makes key 2 the oldest record
+        cache.get(&key(1));
+        cache.put(&key(3), &[3; 10]);
+
+        // Assert
+        assert!(cache.get(&key(1)).is_some());
+        assert!(cache.get(&key(2)).is_none());
+        assert!(cache.get(&key(3)).is_some());
+        assert_eq!(cache.stats().entries, 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_compact_reclaims_evicted_records() {
+        // Arrange
+        let dir = scratch_dir("disk_cache_compact");
+        let cache = DiskCache::open(&dir, RECORD_HEADER_LEN + 10).unwrap();
+        cache.put(&key(1), &[1; 10]);
+        cache.put(&key(2), &[2; 10]);
+        assert_eq!(
+            cache.stats().file_bytes,
+            HEADER_LEN + 2 * (RECORD_HEADER_LEN + 10)
+        );
+
+        // Act
+        cache.compact().unwrap();
+
+        // Assert
+        let stats = cache.stats();
+        assert_eq!(stats.file_bytes, HEADER_LEN + RECORD_HEADER_LEN + 10);
+        assert_eq!(
+            std::fs::metadata(cache.path()).unwrap().len(),
+            stats.file_bytes
+        );
+        assert_eq!(cache.get(&key(2)), Some(vec![2; 10]));
+        drop(cache);
+        let reopened = DiskCache::open(&dir, 1024).unwrap();
+        assert_eq!(reopened.stats().entries, 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_compact_removes_temporary_file() {
+        // Arrange: a directory now sits where the store file is renamed to
+        let dir = scratch_dir("disk_cache_compact_failed");
+        let cache = DiskCache::open(&dir, 1024).unwrap();
+        cache.put(&key(1), b"value");
+        std::fs::remove_file(cache.path()).unwrap();
+        std::fs::create_dir_all(cache.path().join("blocker")).unwrap();
+
+        // Act
+        let result = cache.compact();
+
+        // Assert
+        assert!(result.unwrap_err().starts_with("FILE_READ_ERROR:"));
+        assert!(!cache.path().with_extension("cache.tmp").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_open_store_locks_its_directory() {
+        // Arrange
+        let dir = scratch_dir("disk_cache_lock");
+        let cache = DiskCache::open(&dir, 1024).unwrap();
+
+        // Act
+        let second = DiskCache::open(&dir, 1024);
+        cache.close();
+        let after_close = DiskCache::open(&dir, 1024);
+
+        // Assert
+        assert!(second.err().unwrap().starts_with("FILE_READ_ERROR:"));
+        assert!(after_close.is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupted_payload_is_rejected() {
+        // Arrange: flip one payload byte of the second record on disk
+        let dir = scratch_dir("disk_cache_checksum");
+        let cache = DiskCache::open(&dir, 1024).unwrap();
+        cache.put(&key(1), b"intact");
+        cache.put(&key(2), b"damaged");
+        let mut file = OpenOptions::new().write(true).open(cache.path()).unwrap();
+        let offset = HEADER_LEN + 2 * RECORD_HEADER_LEN + 6;
+        file.seek(SeekFrom::Start(offset)).unwrap();
+        file.write_all(b"X").unwrap();
+        drop(file);
+
+        // Act
+        let damaged = cache.get(&key(2));
+
+        // Assert
+        assert_eq!(damaged, None);
+        assert_eq!(cache.get(&key(1)), Some(b"intact".to_vec()));
+        assert_eq!(cache.stats().entries, 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_compact_drops_corrupted_records() {
+        // Arrange
+        let dir = scratch_dir("disk_cache_compact_checksum");
+        let cache = DiskCache::open(&dir, 1024).unwrap();
+        cache.put(&key(1), b"damaged");
+        cache.put(&key(2), b"intact");
+        let mut file = OpenOptions::new().write(true).open(cache.path()).unwrap();
+        file.seek(SeekFrom::Start(HEADER_LEN + RECORD_HEADER_LEN))
+            .unwrap();
+        file.write_all(b"X").unwrap();
+        drop(file);
+
+        // Act
+        cache.compact().unwrap();
+
+        // Assert
+        assert_eq!(cache.stats().entries, 1);
+        assert_eq!(cache.get(&key(2)), Some(b"intact".to_vec()));
+        assert_eq!(cache.stats().file_bytes, HEADER_LEN + RECORD_HEADER_LEN + 6);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_clear_empties_store() {
+        // Arrange
+        let dir = scratch_dir("disk_cache_clear");
+        let cache = DiskCache::open(&dir, 1024).unwrap();
+        cache.put(&key(1), b"value");
+
+        // Act
+        cache.clear().unwrap();
+
+        // Assert
+        assert_eq!(cache.get(&key(1)), None);
+        assert_eq!(cache.stats().file_bytes, HEADER_LEN);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_torn_tail_and_foreign_file_are_recovered() {
+        // Arrange: a store with one record plus half of another
+        let dir = scratch_dir("disk_cache_torn");
+        let cache = DiskCache::open(&dir, 1024).unwrap();
+        cache.put(&key(1), b"kept");
+        let path = cache.path().to_path_buf();
+        drop(cache);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[9; 20]).unwrap();
+        drop(file);
+
+        // Act
+        let recovered = DiskCache::open(&dir, 1024).unwrap();
+
+        // Assert
+        assert_eq!(recovered.get(&key(1)), Some(b"kept".to_vec()));
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().len(),
+            HEADER_LEN + RECORD_HEADER_LEN + 4
+        );
+        drop(recovered);
+
+        std::fs::write(&path, b"not a cache file").unwrap();
+        let fresh = DiskCache::open(&dir, 1024).unwrap();
+        assert_eq!(fresh.stats().entries, 0);
+        assert_eq!(fresh.stats().file_bytes, HEADER_LEN);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_text_entries_keyed_by_model_and_kind() {
+        // Arrange
+        let dir = scratch_dir("disk_cache_text");
+        let cache = DiskCache::open(&dir, 1024).unwrap();
+        let model = model_key(
+            &ModelFingerprint::new("org/model", None, 0),
+            &EmbedderOptions::default(),
+        );
+
+        // Act
+        cache.put_text(&model, CacheKind::Query, "hello  world", &[0.5, -1.0]);
+
+        // Assert
+        assert_eq!(
+            cache.get_text(&model, CacheKind::Query, "hello world"),
+            Some(vec![0.5, -1.0])
+        );
+        assert_eq!(
+            cache.get_text(&model, CacheKind::Document, "hello world"),
+            None
+        );
+        assert_eq!(
+            cache.get_text("other", CacheKind::Query, "hello world"),
+            None
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_items_round_trip() {
+        // Arrange
+        let mut metadata = HashMap::new();
+        metadata.insert("file_name".to_string(), "/docs/a.txt".to_string());
+        let items = vec![
+            EmbedData {
+                embedding: EmbeddingResult::DenseVector(vec![0.1, 0.2]),
+                text: Some("chunk".to_string()),
+                metadata: Some(metadata),
+            },
+            EmbedData {
+                embedding: EmbeddingResult::MultiVector(vec![vec![1.0, 2.0], vec![3.0, 4.0]]),
+                text: None,
+                metadata: None,
+            },
+        ];
+
+        // Act
+        let decoded = decode_items(&encode_items(&items)).unwrap();
+
+        // Assert
+        assert_eq!(decoded.len(), 2);
+        let EmbeddingResult::DenseVector(dense) = &decoded[0].embedding else {
+            panic!("expected a dense vector");
+        };
+        assert_eq!(dense, &[0.1, 0.2]);
+        assert_eq!(decoded[0].text.as_deref(), Some("chunk"));
+        assert_eq!(decoded[0].metadata, items[0].metadata);
+        let EmbeddingResult::MultiVector(rows) = &decoded[1].embedding else {
+            panic!("expected a multi-vector");
+        };
+        assert_eq!(rows, &[vec![1.0, 2.0], vec![3.0, 4.0]]);
+        assert!(decoded[1].text.is_none() && decoded[1].metadata.is_none());
+        assert!(decode_items(&[1, 0, 0]).is_none());
+    }
+
+    #[test]
+    fn test_file_key_follows_content() {
+        // Arrange
+        let dir = scratch_dir("disk_cache_file_key");
+        let cache = Arc::new(DiskCache::open(&dir.join("store"), 1024).unwrap());
+        let files = FileCache::new(cache, "model".to_string());
+        let config = TextEmbedConfig::default();
+        let (a, b) = (dir.join("a.txt"), dir.join("b.txt"));
+        std::fs::write(&a, b"same").unwrap();
+        std::fs::write(&b, b"same").unwrap();
+
+        // Act
+        let key_a = files.key(&a, &config).unwrap();
+        let key_b = files.key(&b, &config).unwrap();
+        std::fs::write(&b, b"changed").unwrap();
+        let key_changed = files.key(&b, &config).unwrap();
+
+        // Assert
+        assert_eq!(key_a, key_b);
+        assert_ne!(key_a, key_changed);
+        assert!(files.key(&dir.join("missing.txt"), &config).is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_file_key_follows_extension_and_config() {
+        // Arrange
+        let dir = scratch_dir("disk_cache_file_key_config");
+        let cache = Arc::new(DiskCache::open(&dir.join("store"), 1024).unwrap());
+        let files = FileCache::new(cache, "model".to_string());
+        let (txt, md) = (dir.join("a.txt"), dir.join("a.md"));
+        std::fs::write(&txt, b"# same").unwrap();
+        std::fs::write(&md, b"# same").unwrap();
+        let config = TextEmbedConfig::default();
+        let late = TextEmbedConfig {
+            late_chunking: Some(true),
+            ..Default::default()
+        };
+        let ocr = TextEmbedConfig {
+            use_ocr: Some(true),
+            ..Default::default()
+        };
+        let batched = TextEmbedConfig {
+            batch_size: Some(8),
+            buffer_size: Some(4),
+            ..Default::default()
+        };
+
+        // Act
+        let key_txt = files.key(&txt, &config).unwrap();
+
+        // Assert
+        assert_ne!(key_txt, files.key(&md, &config).unwrap());
+        assert_ne!(key_txt, files.key(&txt, &late).unwrap());
+        assert_ne!(key_txt, files.key(&txt, &ocr).unwrap());
+        assert_eq!(key_txt, files.key(&txt, &batched).unwrap());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
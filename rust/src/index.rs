@@ -0,0 +1,1421 @@
+//! Native vector indexes for nearest-neighbour search over embeddings.
+//!
+//! Vectors are added straight from embed results (by pointer, so they never
+//! pass through Dart memory) under caller-chosen `u64` ids, each with
+//! optional JSON metadata that is handed back with every hit.
+//!
+//! Two index types share one interface (`VectorIndex`): `FlatIndex` compares
+//! the query with every vector and is exact; `HnswIndex` walks a
+//! Hierarchical Navigable Small World graph and trades a little recall for
+//! search times that grow roughly logarithmically with the collection.
+//!
+//! Indexes are saved to a single versioned binary file together with the
+//! fingerprint (model id, revision or local content hash, dtype) and the
+//! output-affecting options (normalization, pooling, maximum sequence
+//! length) of the embedder that produced the vectors, and only load for an
+//! embedder that matches both.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+
+use crate::cache::ModelFingerprint;
+use crate::disk_cache::{decode_vector, encode_vector, ByteReader};
+use crate::options::{EmbedderOptions, Pooling};
+
+/// Similarity measure of an index.
+///
+/// The numeric values correspond to the `metric` argument of
+/// `vector_index_create`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum Metric {
+    /// Cosine similarity (vectors are normalized on insert)
+    Cosine = 0,
+    /// Raw dot product
+    Dot = 1,
+    /// Euclidean distance
+    L2 = 2,
+}
+
+impl Metric {
+    /// Map the `metric` FFI argument to a metric.
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Metric::Cosine),
+            1 => Some(Metric::Dot),
+            2 => Some(Metric::L2),
+            _ => None,
+        }
+    }
+
+    /// Bring a vector into the form it is stored and compared in.
+    pub fn prepare(self, vector: &[f32]) -> Vec<f32> {
+        let mut vector = vector.to_vec();
+        if self == Metric::Cosine {
+            let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                vector.iter_mut().for_each(|v| *v /= norm);
+            }
+        }
+        vector
+    }
+
+    /// Distance between two prepared vectors; lower is closer.
+    pub fn distance(self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            Metric::Cosine => 1.0 - dot(a, b),
+            Metric::Dot => -dot(a, b),
+            Metric::L2 => a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum(),
+        }
+    }
+
+    /// Score reported to callers for a distance: the similarity for cosine
+    /// and dot, the Euclidean distance for L2.
+    pub fn score(self, distance: f32) -> f32 {
+        match self {
+            Metric::Cosine => 1.0 - distance,
+            Metric::Dot => -distance,
+            Metric::L2 => distance.sqrt(),
+        }
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// One search result, closest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub id: u64,
+    pub score: f32,
+    pub metadata: Option<String>,
+}
+
+/// Exact index: every query is compared with every stored vector.
+#[derive(Debug, Clone)]
+pub struct FlatIndex {
+    dimension: usize,
+    metric: Metric,
+    ids: Vec<u64>,
+    /// Prepared vectors, row-major
+    vectors: Vec<f32>,
+    metadata: Vec<Option<String>>,
+    positions: HashMap<u64, usize>,
+}
+
+impl FlatIndex {
+    pub fn new(dimension: usize, metric: Metric) -> Self {
+        Self {
+            dimension,
+            metric,
+            ids: Vec::new(),
+            vectors: Vec::new(),
+            metadata: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Add a vector, replacing any entry stored under the same id.
+    ///
+    /// # Errors
+    /// - "INVALID_CONFIG:" if the vector does not have the index dimension
+    pub fn add(&mut self, id: u64, vector: &[f32], metadata: Option<String>) -> Result<(), String> {
+        check_dimension(self.dimension, vector)?;
+        let vector = self.metric.prepare(vector);
+
+        match self.positions.get(&id) {
+            Some(&pos) => {
+                let row = pos * self.dimension;
+                self.vectors[row..row + self.dimension].copy_from_slice(&vector);
+                self.metadata[pos] = metadata;
+            }
+            None => {
+                self.positions.insert(id, self.ids.len());
+                self.ids.push(id);
+                self.vectors.extend_from_slice(&vector);
+                self.metadata.push(metadata);
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the entry stored under `id`. Returns false if there is none.
+    pub fn remove(&mut self, id: u64) -> bool {
+        let Some(pos) = self.positions.remove(&id) else {
+            return false;
+        };
+
+        // Move the last entry into the freed slot
+        let last = self.ids.len() - 1;
+        self.ids.swap_remove(pos);
+        self.metadata.swap_remove(pos);
+        if pos != last {
+            let (dim, from) = (self.dimension, last * self.dimension);
+            self.vectors.copy_within(from..from + dim, pos * dim);
+            self.positions.insert(self.ids[pos], pos);
+        }
+        self.vectors.truncate(last * self.dimension);
+        true
+    }
+
+    /// The `k` entries closest to `query`, closest first.
+    ///
+    /// # Errors
+    /// - "INVALID_CONFIG:" if the query does not have the index dimension
+    pub fn search(&self, query: &[f32], k: usize) -> Result<Vec<SearchHit>, String> {
+        check_dimension(self.dimension, query)?;
+        let query = self.metric.prepare(query);
+
+        let mut scored: Vec<(f32, usize)> = self
+            .vectors
+            .chunks_exact(self.dimension)
+            .map(|vector| self.metric.distance(&query, vector))
+            .zip(0..)
+            .collect();
+        let k = k.min(scored.len());
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+        scored.select_nth_unstable_by(k - 1, |a, b| a.0.total_cmp(&b.0));
+        scored.truncate(k);
+        scored.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Ok(scored
+            .into_iter()
+            .map(|(distance, pos)| SearchHit {
+                id: self.ids[pos],
+                score: self.metric.score(distance),
+                metadata: self.metadata[pos].clone(),
+            })
+            .collect())
+    }
+}
+
+/// Tuning parameters of an `HnswIndex`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HnswParams {
+    /// Links per node on upper layers (twice as many on layer 0)
+    pub m: usize,
+    /// Candidate list size while inserting; higher builds a better graph
+    pub ef_construction: usize,
+    /// Candidate list size while searching; higher trades speed for recall
+    pub ef_search: usize,
+}
+
+impl Default for HnswParams {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 200,
+            ef_search: 64,
+        }
+    }
+}
+
+/// Graph node. Removed nodes stay in the graph as tombstones so searches can
+/// still route through them; they are never returned.
+#[derive(Debug, Clone)]
+struct Node {
+    id: u64,
+    vector: Vec<f32>,
+    metadata: Option<String>,
+    /// Neighbour node indexes, one list per layer (0 = bottom)
+    links: Vec<Vec<u32>>,
+    deleted: bool,
+}
+
+/// Node index ordered by distance to a query.
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance: f32,
+    node: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.node.cmp(&other.node))
+    }
+}
+
+/// Highest layer a node can be assigned to.
+const MAX_LEVEL: usize = 16;
+
+/// Approximate index over an HNSW graph.
+///
+/// Inserts link the new node into the graph incrementally. Removals leave a
+/// tombstone; once tombstones outnumber live entries the graph is rebuilt
+/// from the live ones.
+#[derive(Debug, Clone)]
+pub struct HnswIndex {
+    dimension: usize,
+    metric: Metric,
+    params: HnswParams,
+    nodes: Vec<Node>,
+    /// Id -> node index of live entries
+    positions: HashMap<u64, usize>,
+    entry_point: Option<usize>,
+    /// State of the level generator (splitmix64)
+    rng: u64,
+}
+
+impl HnswIndex {
+    /// Create an empty index.
+    ///
+    /// # Errors
+    /// - "INVALID_CONFIG:" if `m` is below 2 or an `ef` value is 0
+    pub fn new(dimension: usize, metric: Metric, params: HnswParams) -> Result<Self, String> {
+        if params.m < 2 {
+            return Err("INVALID_CONFIG: m: must be at least 2".to_string());
+        }
+        if params.ef_construction == 0 {
+            return Err("INVALID_CONFIG: ef_construction: must be greater than 0".to_string());
+        }
+        if params.ef_search == 0 {
+            return Err("INVALID_CONFIG: ef_search: must be greater than 0".to_string());
+        }
+        Ok(Self {
+            dimension,
+            metric,
+            params,
+            nodes: Vec::new(),
+            positions: HashMap::new(),
+            entry_point: None,
+            rng: 0x5EED,
+        })
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    pub fn params(&self) -> HnswParams {
+        self.params
+    }
+
+    /// Change the search candidate list size; takes effect on the next search.
+    pub fn set_ef_search(&mut self, ef_search: usize) {
+        self.params.ef_search = ef_search.max(1);
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Add a vector, replacing any entry stored under the same id.
+    ///
+    /// # Errors
+    /// - "INVALID_CONFIG:" if the vector does not have the index dimension
+    pub fn add(&mut self, id: u64, vector: &[f32], metadata: Option<String>) -> Result<(), String> {
+        check_dimension(self.dimension, vector)?;
+        self.remove(id);
+        let vector = self.metric.prepare(vector);
+        self.insert(id, vector, metadata);
+        Ok(())
+    }
+
+    /// Remove the entry stored under `id`. Returns false if there is none.
+    pub fn remove(&mut self, id: u64) -> bool {
+        let Some(node) = self.positions.remove(&id) else {
+            return false;
+        };
+        self.nodes[node].deleted = true;
+
+        if self.nodes.len() - self.positions.len() > self.positions.len() {
+            self.rebuild();
+        }
+        true
+    }
+
+    /// The `k` entries closest to `query`, closest first.
+    ///
+    /// # Errors
+    /// - "INVALID_CONFIG:" if the query does not have the index dimension
+    pub fn search(&self, query: &[f32], k: usize) -> Result<Vec<SearchHit>, String> {
+        check_dimension(self.dimension, query)?;
+        let query = self.metric.prepare(query);
+        let Some(entry_point) = self.entry_point else {
+            return Ok(Vec::new());
+        };
+        if k == 0 || self.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut entry = self.candidate(&query, entry_point);
+        for level in (1..self.nodes[entry_point].links.len()).rev() {
+            entry = self.search_layer(&query, &[entry], 1, level, false)[0];
+        }
+        let ef = self.params.ef_search.max(k);
+        let found = self.search_layer(&query, &[entry], ef, 0, true);
+
+        Ok(found
+            .into_iter()
+            .take(k)
+            .map(|candidate| {
+                let node = &self.nodes[candidate.node];
+                SearchHit {
+                    id: node.id,
+                    score: self.metric.score(candidate.distance),
+                    metadata: node.metadata.clone(),
+                }
+            })
+            .collect())
+    }
+
+    fn insert(&mut self, id: u64, vector: Vec<f32>, metadata: Option<String>) {
+        let level = self.random_level();
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            id,
+            vector,
+            metadata,
+            links: vec![Vec::new(); level + 1],
+            deleted: false,
+        });
+        self.positions.insert(id, node);
+
+        let Some(entry_point) = self.entry_point else {
+            self.entry_point = Some(node);
+            return;
+        };
+
+        let query = self.nodes[node].vector.clone();
+        let top_level = self.nodes[entry_point].links.len() - 1;
+        let mut entries = vec![self.candidate(&query, entry_point)];
+
+        // Greedy descent through the layers above the new node
+        for layer in (level + 1..=top_level).rev() {
+            entries = self.search_layer(&query, &entries, 1, layer, false);
+        }
+
+        for layer in (0..=level.min(top_level)).rev() {
+            let found =
+                self.search_layer(&query, &entries, self.params.ef_construction, layer, false);
+            let neighbours = self.select_neighbours(&found, self.params.m);
+            self.nodes[node].links[layer] = neighbours.iter().map(|c| c.node as u32).collect();
+
+            for neighbour in neighbours {
+                self.link(neighbour.node, node, layer);
+            }
+            entries = found;
+        }
+
+        if level > top_level {
+            self.entry_point = Some(node);
+        }
+    }
+
+    /// Add a link `from -> to`, pruning `from`'s list if it is over capacity.
+    fn link(&mut self, from: usize, to: usize, layer: usize) {
+        let max_links = self.max_links(layer);
+        let links = &mut self.nodes[from].links[layer];
+        links.push(to as u32);
+        if links.len() <= max_links {
+            return;
+        }
+
+        let base = &self.nodes[from].vector;
+        let mut candidates: Vec<Candidate> = self.nodes[from].links[layer]
+            .iter()
+            .map(|&n| Candidate {
+                distance: self.metric.distance(base, &self.nodes[n as usize].vector),
+                node: n as usize,
+            })
+            .collect();
+        candidates.sort();
+        let kept = self.select_neighbours(&candidates, max_links);
+        self.nodes[from].links[layer] = kept.iter().map(|c| c.node as u32).collect();
+    }
+
+    /// Pick up to `m` neighbours from `candidates` (sorted closest first),
+    /// skipping candidates closer to an already picked neighbour than to the
+    /// query so the links spread out in different directions. Skipped
+    /// candidates fill any remaining slots.
+    fn select_neighbours(&self, candidates: &[Candidate], m: usize) -> Vec<Candidate> {
+        let mut selected: Vec<Candidate> = Vec::with_capacity(m);
+        let mut skipped = Vec::new();
+        for &candidate in candidates {
+            if selected.len() == m {
+                break;
+            }
+            let vector = &self.nodes[candidate.node].vector;
+            let diverse = selected.iter().all(|picked| {
+                self.metric
+                    .distance(vector, &self.nodes[picked.node].vector)
+                    > candidate.distance
+            });
+            if diverse {
+                selected.push(candidate);
+            } else {
+                skipped.push(candidate);
+            }
+        }
+        let missing = m - selected.len();
+        selected.extend(skipped.into_iter().take(missing));
+        selected
+    }
+
+    /// Best-first search of one layer, returning up to `ef` nodes closest
+    /// first. With `live_only`, tombstones are traversed but not returned.
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entries: &[Candidate],
+        ef: usize,
+        layer: usize,
+        live_only: bool,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<usize> = entries.iter().map(|c| c.node).collect();
+        let mut candidates: BinaryHeap<Reverse<Candidate>> =
+            entries.iter().copied().map(Reverse).collect();
+        let mut found: BinaryHeap<Candidate> = entries
+            .iter()
+            .copied()
+            .filter(|c| !live_only || !self.nodes[c.node].deleted)
+            .collect();
+        while found.len() > ef {
+            found.pop();
+        }
+
+        while let Some(Reverse(current)) = candidates.pop() {
+            let furthest = found.peek().map_or(f32::INFINITY, |c| c.distance);
+            if found.len() >= ef && current.distance > furthest {
+                break;
+            }
+
+            for &next in &self.nodes[current.node].links[layer] {
+                let next = next as usize;
+                if !visited.insert(next) {
+                    continue;
+                }
+                let candidate = self.candidate(query, next);
+                let furthest = found.peek().map_or(f32::INFINITY, |c| c.distance);
+                if found.len() < ef || candidate.distance < furthest {
+                    candidates.push(Reverse(candidate));
+                    if !live_only || !self.nodes[next].deleted {
+                        found.push(candidate);
+                        if found.len() > ef {
+                            found.pop();
+                        }
+                    }
+                }
+            }
+        }
+
+        found.into_sorted_vec()
+    }
+
+    fn candidate(&self, query: &[f32], node: usize) -> Candidate {
+        Candidate {
+            distance: self.metric.distance(query, &self.nodes[node].vector),
+            node,
+        }
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.params.m * 2
+        } else {
+            self.params.m
+        }
+    }
+
+    /// Draw a layer from the exponential distribution with scale 1/ln(m).
+    fn random_level(&mut self) -> usize {
+        // splitmix64
+        self.rng = self.rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        // Uniform in (0, 1]
+        let uniform = ((z >> 11) + 1) as f64 / (1u64 << 53) as f64;
+        let level = -uniform.ln() / (self.params.m as f64).ln();
+        (level as usize).min(MAX_LEVEL)
+    }
+
+    /// Rebuild the graph from the live entries, dropping tombstones.
+    fn rebuild(&mut self) {
+        let nodes = std::mem::take(&mut self.nodes);
+        self.positions.clear();
+        self.entry_point = None;
+        for node in nodes.into_iter().filter(|node| !node.deleted) {
+            self.insert(node.id, node.vector, node.metadata);
+        }
+    }
+}
+
+/// A flat or HNSW index behind one interface.
+#[derive(Debug, Clone)]
+pub enum VectorIndex {
+    Flat(FlatIndex),
+    Hnsw(HnswIndex),
+}
+
+impl VectorIndex {
+    pub fn dimension(&self) -> usize {
+        match self {
+            VectorIndex::Flat(index) => index.dimension(),
+            VectorIndex::Hnsw(index) => index.dimension(),
+        }
+    }
+
+    pub fn metric(&self) -> Metric {
+        match self {
+            VectorIndex::Flat(index) => index.metric(),
+            VectorIndex::Hnsw(index) => index.metric(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            VectorIndex::Flat(index) => index.len(),
+            VectorIndex::Hnsw(index) => index.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn add(&mut self, id: u64, vector: &[f32], metadata: Option<String>) -> Result<(), String> {
+        match self {
+            VectorIndex::Flat(index) => index.add(id, vector, metadata),
+            VectorIndex::Hnsw(index) => index.add(id, vector, metadata),
+        }
+    }
+
+    pub fn remove(&mut self, id: u64) -> bool {
+        match self {
+            VectorIndex::Flat(index) => index.remove(id),
+            VectorIndex::Hnsw(index) => index.remove(id),
+        }
+    }
+
+    pub fn search(&self, query: &[f32], k: usize) -> Result<Vec<SearchHit>, String> {
+        match self {
+            VectorIndex::Flat(index) => index.search(query, k),
+            VectorIndex::Hnsw(index) => index.search(query, k),
+        }
+    }
+}
+
+// ============================================================================
+// Persistence
+// ============================================================================
+
+const MAGIC: &[u8; 8] = b"EADINDEX";
+const VERSION: u32 = 2;
+
+/// Suffix counter so concurrent saves never share a temporary file.
+static SAVE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Index kind tags in the file header.
+const KIND_FLAT: u8 = 0;
+const KIND_HNSW: u8 = 1;
+
+impl VectorIndex {
+    /// Write the index to `path`, replacing any existing file.
+    ///
+    /// The file starts with the magic bytes, the format version and a
+    /// header holding `model`, the output-affecting `options`, the
+    /// dimension, the metric and the index kind, followed by the entries
+    /// (and, for HNSW, the graph, so loading does not rebuild it). It is
+    /// written to a temporary file unique to this call and renamed into
+    /// place, so concurrent saves to one path never interleave.
+    ///
+    /// # Errors
+    /// - "FILE_READ_ERROR:" if the file cannot be written
+    pub fn save(
+        &self,
+        path: &Path,
+        model: &ModelFingerprint,
+        options: &EmbedderOptions,
+    ) -> Result<(), String> {
+        let io_error = |e: std::io::Error| format!("FILE_READ_ERROR: {}: {}", path.display(), e);
+
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(format!(
+            ".{}-{}.tmp",
+            std::process::id(),
+            SAVE_COUNTER.fetch_add(1, AtomicOrdering::Relaxed)
+        ));
+        let result = std::fs::File::create(&tmp_path).and_then(|mut file| {
+            file.write_all(&self.encode(model, options))?;
+            file.sync_all()?;
+            drop(file);
+            std::fs::rename(&tmp_path, path)
+        });
+        if result.is_err() {
+            let _ = std::fs::remove_file(&tmp_path);
+        }
+        result.map_err(io_error)
+    }
+
+    /// Read an index written by `save`.
+    ///
+    /// # Errors
+    /// - "FILE_NOT_FOUND:" if `path` does not exist
+    /// - "FILE_READ_ERROR:" if the file cannot be read, is not an index
+    ///   file, has an unsupported version or is corrupt
+    /// - "MODEL_MISMATCH:" if the index was built with a different model or
+    ///   with options that change the vectors
+    pub fn load(
+        path: &Path,
+        model: &ModelFingerprint,
+        options: &EmbedderOptions,
+    ) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => format!("FILE_NOT_FOUND: {}", path.display()),
+            _ => format!("FILE_READ_ERROR: {}: {}", path.display(), e),
+        })?;
+        let corrupt = || {
+            format!(
+                "FILE_READ_ERROR: {}: truncated or corrupt index file",
+                path.display()
+            )
+        };
+
+        if bytes.len() < 12 || &bytes[..8] != MAGIC {
+            return Err(format!(
+                "FILE_READ_ERROR: {}: not a vector index file",
+                path.display()
+            ));
+        }
+        let mut reader = ByteReader::new(&bytes[8..]);
+        let version = reader.u32().ok_or_else(corrupt)?;
+        if version != VERSION {
+            return Err(format!(
+                "FILE_READ_ERROR: {}: unsupported index format version {} (expected {})",
+                path.display(),
+                version,
+                VERSION
+            ));
+        }
+
+        let saved = read_fingerprint(&mut reader).ok_or_else(corrupt)?;
+        let saved_options = read_options(&mut reader).ok_or_else(corrupt)?;
+        if &saved != model || saved_options != OutputOptions::of(options) {
+            return Err(format!(
+                "MODEL_MISMATCH: index was built with {}, embedder is {}",
+                describe(&saved, saved_options),
+                describe(model, OutputOptions::of(options))
+            ));
+        }
+
+        let index = Self::decode_body(&mut reader).ok_or_else(corrupt)?;
+        // Bytes past the body mean the file was not written by save
+        if !reader.is_empty() {
+            return Err(corrupt());
+        }
+        Ok(index)
+    }
+
+    fn encode(&self, model: &ModelFingerprint, options: &EmbedderOptions) -> Vec<u8> {
+        let options = OutputOptions::of(options);
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        put_string(&mut out, Some(&model.model_id));
+        put_string(&mut out, model.revision.as_deref());
+        put_string(&mut out, Some(&model.dtype));
+        out.push(options.normalize as u8);
+        out.extend_from_slice(&options.pooling.map_or(-1, |p| p as i32).to_le_bytes());
+        out.extend_from_slice(&options.max_sequence_length.unwrap_or(0).to_le_bytes());
+        out.extend_from_slice(&(self.dimension() as u64).to_le_bytes());
+        out.push(self.metric() as u8);
+
+        match self {
+            VectorIndex::Flat(index) => {
+                out.push(KIND_FLAT);
+                out.extend_from_slice(&(index.len() as u64).to_le_bytes());
+                for (pos, vector) in index.vectors.chunks_exact(index.dimension).enumerate() {
+                    out.extend_from_slice(&index.ids[pos].to_le_bytes());
+                    put_string(&mut out, index.metadata[pos].as_deref());
+                    out.extend_from_slice(&encode_vector(vector));
+                }
+            }
+            VectorIndex::Hnsw(index) => {
+                out.push(KIND_HNSW);
+                for value in [
+                    index.params.m as u64,
+                    index.params.ef_construction as u64,
+                    index.params.ef_search as u64,
+                    index.rng,
+                    index.entry_point.map_or(u64::MAX, |node| node as u64),
+                    index.nodes.len() as u64,
+                ] {
+                    out.extend_from_slice(&value.to_le_bytes());
+                }
+                for node in &index.nodes {
+                    out.extend_from_slice(&node.id.to_le_bytes());
+                    out.push(node.deleted as u8);
+                    put_string(&mut out, node.metadata.as_deref());
+                    out.extend_from_slice(&encode_vector(&node.vector));
+                    out.extend_from_slice(&(node.links.len() as u32).to_le_bytes());
+                    for links in &node.links {
+                        out.extend_from_slice(&(links.len() as u32).to_le_bytes());
+                        for link in links {
+                            out.extend_from_slice(&link.to_le_bytes());
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Decode everything after the fingerprint and options. Returns None if
+    /// the data is truncated or inconsistent.
+    fn decode_body(reader: &mut ByteReader) -> Option<Self> {
+        let dimension = usize::try_from(reader.u64()?).ok().filter(|&d| d > 0)?;
+        let metric = Metric::from_i32(reader.take(1)?[0] as i32)?;
+        let vector_bytes = dimension.checked_mul(4)?;
+
+        match reader.take(1)?[0] {
+            KIND_FLAT => {
+                let count = reader.u64()?;
+                let mut index = FlatIndex::new(dimension, metric);
+                for pos in 0..count as usize {
+                    let id = reader.u64()?;
+                    let metadata = reader.string()?;
+                    let vector = decode_vector(reader.take(vector_bytes)?)?;
+                    if index.positions.insert(id, pos).is_some() {
+                        return None;
+                    }
+                    index.ids.push(id);
+                    index.metadata.push(metadata);
+                    index.vectors.extend_from_slice(&vector);
+                }
+                Some(VectorIndex::Flat(index))
+            }
+            KIND_HNSW => {
+                let params = HnswParams {
+                    m: reader.u64()? as usize,
+                    ef_construction: reader.u64()? as usize,
+                    ef_search: reader.u64()? as usize,
+                };
+                let mut index = HnswIndex::new(dimension, metric, params).ok()?;
+                index.rng = reader.u64()?;
+                let entry_point = reader.u64()?;
+                let count = reader.u64()?;
+
+                for node in 0..count as usize {
+                    let id = reader.u64()?;
+                    let deleted = reader.take(1)?[0] == 1;
+                    let metadata = reader.string()?;
+                    let vector = decode_vector(reader.take(vector_bytes)?)?;
+                    let levels = reader.u32()? as usize;
+                    if levels == 0 || levels > MAX_LEVEL + 1 {
+                        return None;
+                    }
+                    let mut links = Vec::with_capacity(levels);
+                    for _ in 0..levels {
+                        let len = reader.u32()? as usize;
+                        let layer: Vec<u32> =
+                            (0..len).map(|_| reader.u32()).collect::<Option<_>>()?;
+                        if layer.iter().any(|&link| link as u64 >= count) {
+                            return None;
+                        }
+                        links.push(layer);
+                    }
+                    if !deleted && index.positions.insert(id, node).is_some() {
+                        return None;
+                    }
+                    index.nodes.push(Node {
+                        id,
+                        vector,
+                        metadata,
+                        links,
+                        deleted,
+                    });
+                }
+
+                // Links must point at layers the target node exists on
+                for node in &index.nodes {
+                    for (layer, links) in node.links.iter().enumerate() {
+                        if links
+                            .iter()
+                            .any(|&link| index.nodes[link as usize].links.len() <= layer)
+                        {
+                            return None;
+                        }
+                    }
+                }
+                index.entry_point = match entry_point {
+                    u64::MAX if index.nodes.is_empty() => None,
+                    node if node < count => Some(node as usize),
+                    _ => return None,
+                };
+                Some(VectorIndex::Hnsw(index))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn read_fingerprint(reader: &mut ByteReader) -> Option<ModelFingerprint> {
+    Some(ModelFingerprint {
+        model_id: reader.string()??,
+        revision: reader.string()?,
+        dtype: reader.string()??,
+    })
+}
+
+/// The embedder options that change the vectors, as stored in the header
+/// (the same set as `disk_cache::model_key`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct OutputOptions {
+    normalize: bool,
+    pooling: Option<Pooling>,
+    max_sequence_length: Option<u64>,
+}
+
+impl OutputOptions {
+    fn of(options: &EmbedderOptions) -> Self {
+        Self {
+            normalize: options.normalize,
+            pooling: options.pooling,
+            max_sequence_length: options.max_sequence_length.map(|n| n as u64),
+        }
+    }
+}
+
+fn read_options(reader: &mut ByteReader) -> Option<OutputOptions> {
+    let normalize = match reader.take(1)?[0] {
+        0 => false,
+        1 => true,
+        _ => return None,
+    };
+    let pooling = match reader.u32()? as i32 {
+        -1 => None,
+        value => Some(Pooling::from_i32(value)?),
+    };
+    let max_sequence_length = Some(reader.u64()?).filter(|&n| n > 0);
+    Some(OutputOptions {
+        normalize,
+        pooling,
+        max_sequence_length,
+    })
+}
+
+/// Human-readable model identity for MODEL_MISMATCH errors.
+fn describe(model: &ModelFingerprint, options: OutputOptions) -> String {
+    format!(
+        "{} (revision {}, dtype {}, normalize {}, pooling {}, max_sequence_length {})",
+        model.model_id,
+        model.revision.as_deref().unwrap_or("default"),
+        model.dtype,
+        options.normalize,
+        options
+            .pooling
+            .map_or("default".to_string(), |p| format!("{:?}", p).to_lowercase()),
+        options
+            .max_sequence_length
+            .map_or("default".to_string(), |n| n.to_string())
+    )
+}
+
+/// Length-prefixed UTF-8 string; `u32::MAX` encodes None (as read by
+/// `ByteReader::string`).
+fn put_string(out: &mut Vec<u8>, value: Option<&str>) {
+    match value {
+        Some(s) => {
+            out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            out.extend_from_slice(s.as_bytes());
+        }
+        None => out.extend_from_slice(&u32::MAX.to_le_bytes()),
+    }
+}
+
+fn check_dimension(dimension: usize, vector: &[f32]) -> Result<(), String> {
+    if vector.len() != dimension {
+        return Err(format!(
+            "INVALID_CONFIG: vector: expected dimension {}, got {}",
+            dimension,
+            vector.len()
+        ));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_with(metric: Metric) -> FlatIndex {
+        let mut index = FlatIndex::new(2, metric);
+        index
+            .add(1, &[1.0, 0.0], Some("{\"n\":1}".to_string()))
+            .unwrap();
+        index.add(2, &[0.0, 2.0], None).unwrap();
+        index.add(3, &[3.0, 3.0], None).unwrap();
+        index
+    }
+
+    fn ids(hits: &[SearchHit]) -> Vec<u64> {
+        hits.iter().map(|hit| hit.id).collect()
+    }
+
+    #[test]
+    fn test_metric_from_i32() {
+        assert_eq!(Metric::from_i32(0), Some(Metric::Cosine));
+        assert_eq!(Metric::from_i32(2), Some(Metric::L2));
+        assert_eq!(Metric::from_i32(3), None);
+    }
+
+    #[test]
+    fn test_search_orders_by_metric() {
+        let query = [1.0, 0.1];
+
+        let cosine = index_with(Metric::Cosine).search(&query, 3).unwrap();
+        let dot = index_with(Metric::Dot).search(&query, 3).unwrap();
+        let l2 = index_with(Metric::L2).search(&query, 3).unwrap();
+
+        assert_eq!(ids(&cosine), vec![1, 3, 2]);
+        assert_eq!(ids(&dot), vec![3, 1, 2]);
+        assert_eq!(ids(&l2), vec![1, 2, 3]);
+        assert!((cosine[0].score - 0.995).abs() < 1e-3);
+        assert!((dot[0].score - 3.3).abs() < 1e-5);
+        assert!((l2[0].score - 0.1).abs() < 1e-5);
+        assert_eq!(cosine[0].metadata.as_deref(), Some("{\"n\":1}"));
+    }
+
+    #[test]
+    fn test_search_truncates_to_k() {
+        let index = index_with(Metric::L2);
+
+        assert_eq!(index.search(&[0.0, 0.0], 2).unwrap().len(), 2);
+        assert_eq!(index.search(&[0.0, 0.0], 10).unwrap().len(), 3);
+        assert!(index.search(&[0.0, 0.0], 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_add_replaces_existing_id() {
+        // Arrange
+        let mut index = index_with(Metric::L2);
+
+        // Act
+        index.add(2, &[1.0, 0.0], Some("{}".to_string())).unwrap();
+
+        // Assert
+        assert_eq!(index.len(), 3);
+        let hits = index.search(&[1.0, 0.0], 2).unwrap();
+        assert_eq!(hits[0].score, 0.0);
+        assert_eq!(hits[1].score, 0.0);
+        assert!(hits.iter().any(|hit| hit.id == 2 && hit.metadata.is_some()));
+    }
+
+    #[test]
+    fn test_remove_keeps_other_entries() {
+        // Arrange
+        let mut index = index_with(Metric::L2);
+
+        // Act
+        let removed = index.remove(1);
+        let removed_again = index.remove(1);
+
+        // Assert
+        assert!(removed);
+        assert!(!removed_again);
+        assert_eq!(index.len(), 2);
+        assert_eq!(ids(&index.search(&[3.0, 3.0], 3).unwrap()), vec![3, 2]);
+        assert_eq!(ids(&index.search(&[0.0, 2.0], 1).unwrap()), vec![2]);
+    }
+
+    #[test]
+    fn test_dimension_mismatch() {
+        let mut index = FlatIndex::new(3, Metric::Cosine);
+
+        let add = index.add(1, &[1.0, 2.0], None);
+        let search = index.search(&[1.0], 1);
+
+        assert!(add.unwrap_err().starts_with("INVALID_CONFIG:"));
+        assert!(search.unwrap_err().starts_with("INVALID_CONFIG:"));
+    }
+
+    /// Deterministic pseudo-random vectors in [-1, 1).
+    fn random_vectors(count: usize, dimension: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut state = seed;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+        };
+        (0..count)
+            .map(|_| (0..dimension).map(|_| next()).collect())
+            .collect()
+    }
+
+    /// Fraction of the exact top-k ids the approximate index also returns.
+    fn recall(flat: &FlatIndex, hnsw: &HnswIndex, queries: &[Vec<f32>], k: usize) -> f64 {
+        let mut matched = 0;
+        for query in queries {
+            let exact: HashSet<u64> = flat
+                .search(query, k)
+                .unwrap()
+                .iter()
+                .map(|hit| hit.id)
+                .collect();
+            let approx = hnsw.search(query, k).unwrap();
+            matched += approx.iter().filter(|hit| exact.contains(&hit.id)).count();
+        }
+        matched as f64 / (queries.len() * k) as f64
+    }
+
+    #[test]
+    fn test_hnsw_rejects_invalid_params() {
+        let params = HnswParams {
+            m: 1,
+            ..HnswParams::default()
+        };
+
+        let result = HnswIndex::new(4, Metric::L2, params);
+
+        assert!(result.unwrap_err().starts_with("INVALID_CONFIG: m:"));
+    }
+
+    #[test]
+    fn test_hnsw_recall_against_flat() {
+        // Arrange
+        let (dimension, k) = (32, 10);
+        let vectors = random_vectors(1500, dimension, 1);
+        let queries = random_vectors(50, dimension, 2);
+        let params = HnswParams {
+            m: 12,
+            ef_construction: 64,
+            ef_search: 64,
+        };
+
+        for metric in [Metric::Cosine, Metric::L2] {
+            let mut flat = FlatIndex::new(dimension, metric);
+            let mut hnsw = HnswIndex::new(dimension, metric, params).unwrap();
+
+            // Act
+            for (id, vector) in vectors.iter().enumerate() {
+                flat.add(id as u64, vector, None).unwrap();
+                hnsw.add(id as u64, vector, None).unwrap();
+            }
+
+            // Assert
+            let recall = recall(&flat, &hnsw, &queries, k);
+            assert!(recall >= 0.95, "{:?} recall {} below 0.95", metric, recall);
+        }
+    }
+
+    #[test]
+    fn test_hnsw_recall_after_removals() {
+        // Arrange
+        let (dimension, k) = (16, 10);
+        let vectors = random_vectors(1200, dimension, 3);
+        let queries = random_vectors(50, dimension, 4);
+        let params = HnswParams {
+            m: 8,
+            ef_construction: 64,
+            ef_search: 48,
+        };
+        let mut flat = FlatIndex::new(dimension, Metric::Cosine);
+        let mut hnsw = HnswIndex::new(dimension, Metric::Cosine, params).unwrap();
+        for (id, vector) in vectors.iter().enumerate() {
+            flat.add(id as u64, vector, None).unwrap();
+            hnsw.add(id as u64, vector, None).unwrap();
+        }
+
+        // Act: remove a third of the entries, then re-add some of them
+        for id in (0..1200u64).filter(|id| id % 3 == 0) {
+            assert!(flat.remove(id));
+            assert!(hnsw.remove(id));
+        }
+        for id in (0..300u64).filter(|id| id % 3 == 0) {
+            flat.add(id, &vectors[id as usize], None).unwrap();
+            hnsw.add(id, &vectors[id as usize], None).unwrap();
+        }
+
+        // Assert
+        assert_eq!(hnsw.len(), flat.len());
+        let recall = recall(&flat, &hnsw, &queries, k);
+        assert!(recall >= 0.95, "recall {} below 0.95", recall);
+        for query in &queries {
+            let hits = hnsw.search(query, k).unwrap();
+            assert!(hits.iter().all(|hit| hit.id % 3 != 0 || hit.id < 300));
+        }
+    }
+
+    #[test]
+    fn test_hnsw_rebuilds_when_mostly_removed() {
+        // Arrange
+        let vectors = random_vectors(100, 8, 5);
+        let mut hnsw = HnswIndex::new(8, Metric::L2, HnswParams::default()).unwrap();
+        for (id, vector) in vectors.iter().enumerate() {
+            hnsw.add(id as u64, vector, Some(id.to_string())).unwrap();
+        }
+
+        // Act
+        for id in 0..90 {
+            hnsw.remove(id);
+        }
+
+        // Assert: tombstones were dropped and every live entry is reachable
+        assert!(hnsw.nodes.len() < 100);
+        let hits = hnsw.search(&vectors[95], 10).unwrap();
+        assert_eq!(hits.len(), 10);
+        assert_eq!(hits[0].id, 95);
+        assert_eq!(hits[0].metadata.as_deref(), Some("95"));
+    }
+
+    #[test]
+    fn test_vector_index_dispatch() {
+        let mut index =
+            VectorIndex::Hnsw(HnswIndex::new(2, Metric::Dot, HnswParams::default()).unwrap());
+
+        index.add(1, &[1.0, 0.0], None).unwrap();
+        index.add(2, &[0.0, 1.0], None).unwrap();
+
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.search(&[0.2, 0.9], 1).unwrap()[0].id, 2);
+        assert!(index.remove(2));
+        assert_eq!(index.search(&[0.2, 0.9], 5).unwrap().len(), 1);
+    }
+
+    fn scratch_file(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("{}_{}.index", name, std::process::id()))
+    }
+
+    fn fingerprint() -> ModelFingerprint {
+        ModelFingerprint::new("org/model", Some("main"), 0)
+    }
+
+    fn options() -> EmbedderOptions {
+        EmbedderOptions {
+            normalize: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let vectors = random_vectors(200, 8, 6);
+        let params = HnswParams {
+            m: 6,
+            ef_construction: 32,
+            ef_search: 16,
+        };
+        for (name, mut index) in [
+            ("flat", VectorIndex::Flat(FlatIndex::new(8, Metric::Cosine))),
+            (
+                "hnsw",
+                VectorIndex::Hnsw(HnswIndex::new(8, Metric::L2, params).unwrap()),
+            ),
+        ] {
+            // Arrange
+            let path = scratch_file(&format!("index_roundtrip_{}", name));
+            for (id, vector) in vectors.iter().enumerate() {
+                index
+                    .add(id as u64 * 10, vector, Some(format!("{{\"n\":{}}}", id)))
+                    .unwrap();
+            }
+            index.remove(30);
+
+            // Act
+            index.save(&path, &fingerprint(), &options()).unwrap();
+            let loaded = VectorIndex::load(&path, &fingerprint(), &options()).unwrap();
+
+            // Assert
+            assert_eq!(loaded.len(), 199);
+            assert_eq!(loaded.dimension(), 8);
+            assert_eq!(loaded.metric(), index.metric());
+            for query in &vectors[..20] {
+                assert_eq!(
+                    loaded.search(query, 5).unwrap(),
+                    index.search(query, 5).unwrap()
+                );
+            }
+
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_load_rejects_other_model() {
+        // Arrange
+        let path = scratch_file("index_mismatch");
+        let mut index = VectorIndex::Flat(FlatIndex::new(2, Metric::Dot));
+        index.add(1, &[1.0, 2.0], None).unwrap();
+        index.save(&path, &fingerprint(), &options()).unwrap();
+
+        // Act
+        let other_dtype = VectorIndex::load(
+            &path,
+            &ModelFingerprint::new("org/model", Some("main"), 1),
+            &options(),
+        );
+        let other_revision = VectorIndex::load(
+            &path,
+            &ModelFingerprint::new("org/model", None, 0),
+            &options(),
+        );
+
+        // Assert
+        assert!(other_dtype.unwrap_err().starts_with("MODEL_MISMATCH:"));
+        assert!(other_revision.unwrap_err().starts_with("MODEL_MISMATCH:"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_load_rejects_other_options() {
+        // Arrange
+        let path = scratch_file("index_options_mismatch");
+        let mut index = VectorIndex::Flat(FlatIndex::new(2, Metric::Dot));
+        index.add(1, &[1.0, 2.0], None).unwrap();
+        index.save(&path, &fingerprint(), &options()).unwrap();
+        let load_with = |options: EmbedderOptions| {
+            VectorIndex::load(&path, &fingerprint(), &options).map(|_| ())
+        };
+
+        // Act
+        let unnormalized = load_with(EmbedderOptions::default());
+        let cls = load_with(EmbedderOptions {
+            pooling: Some(Pooling::Cls),
+            ..options()
+        });
+        let truncated = load_with(EmbedderOptions {
+            max_sequence_length: Some(128),
+            ..options()
+        });
+        let other_batch_size = load_with(EmbedderOptions {
+            batch_size: Some(8),
+            ..options()
+        });
+
+        // Assert
+        let error = unnormalized.unwrap_err();
+        assert!(error.starts_with("MODEL_MISMATCH:"));
+        assert!(error.contains("normalize true"));
+        assert!(cls.unwrap_err().starts_with("MODEL_MISMATCH:"));
+        assert!(truncated.unwrap_err().starts_with("MODEL_MISMATCH:"));
+        // Batch size does not change the vectors
+        assert!(other_batch_size.is_ok());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_saves_use_separate_temp_files() {
+        // Arrange
+        let path = scratch_file("index_concurrent_save");
+        let mut index = VectorIndex::Flat(FlatIndex::new(2, Metric::Dot));
+        for id in 0..500 {
+            index.add(id, &[id as f32, 1.0], None).unwrap();
+        }
+
+        // Act
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| index.save(&path, &fingerprint(), &options()).unwrap());
+            }
+        });
+
+        // Assert
+        let loaded = VectorIndex::load(&path, &fingerprint(), &options()).unwrap();
+        assert_eq!(loaded.len(), 500);
+        let dir = path.parent().unwrap();
+        let name = path.file_name().unwrap().to_str().unwrap();
+        let leftovers = std::fs::read_dir(dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                let entry_name = entry.file_name();
+                let entry_name = entry_name.to_string_lossy();
+                entry_name.starts_with(name) && entry_name.ends_with(".tmp")
+            })
+            .count();
+        assert_eq!(leftovers, 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_load_rejects_bad_files() {
+        // Arrange
+        let path = scratch_file("index_bad");
+        let mut index = VectorIndex::Flat(FlatIndex::new(2, Metric::Dot));
+        index.add(1, &[1.0, 2.0], None).unwrap();
+        index.save(&path, &fingerprint(), &options()).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+
+        // Act
+        std::fs::write(&path, &bytes[..bytes.len() - 3]).unwrap();
+        let truncated = VectorIndex::load(&path, &fingerprint(), &options());
+        let mut trailing = bytes.clone();
+        trailing.extend_from_slice(&[0; 5]);
+        std::fs::write(&path, &trailing).unwrap();
+        let trailing = VectorIndex::load(&path, &fingerprint(), &options());
+        let mut future_version = bytes.clone();
+        future_version[8..12].copy_from_slice(&3u32.to_le_bytes());
+        std::fs::write(&path, &future_version).unwrap();
+        let wrong_version = VectorIndex::load(&path, &fingerprint(), &options());
+        std::fs::write(&path, b"not an index").unwrap();
+        let not_index = VectorIndex::load(&path, &fingerprint(), &options());
+        std::fs::remove_file(&path).unwrap();
+        let missing = VectorIndex::load(&path, &fingerprint(), &options());
+
+        // Assert
+        assert!(truncated.unwrap_err().contains("corrupt"));
+        assert!(trailing.unwrap_err().starts_with("FILE_READ_ERROR:"));
+        assert!(wrong_version
+            .unwrap_err()
+            .contains("unsupported index format version 3"));
+        assert!(not_index.unwrap_err().contains("not a vector index file"));
+        assert!(missing.unwrap_err().starts_with("FILE_NOT_FOUND:"));
+    }
+}
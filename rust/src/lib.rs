@@ -1,24 +1,60 @@
 use std::cell::RefCell;
 use std::ffi::{CStr, CString};
+use std::ops::ControlFlow;
 use std::os::raw::{c_char, c_void};
 use std::panic;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 
 use embed_anything::config::TextEmbedConfig;
 use embed_anything::embeddings::embed::{EmbedData, Embedder, EmbeddingResult};
 use embed_anything::text_loader::TextLoader;
 use embed_anything::Dtype;
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use tokio::runtime::Runtime;
+use tokio_util::sync::CancellationToken;
 
 // Async embedding module - provides non-blocking operations
 pub mod async_embed;
 pub use async_embed::*;
 
+// In-memory LRU cache of text embeddings, optional per embedder
+pub mod cache;
+use cache::{CacheKind, EmbeddingCache, ModelFingerprint, TextCache};
+
 // Device detection module - provides runtime device queries
 pub mod device;
 
+// Directory walking for streamed directory embedding
+pub mod directory;
+
+// Persistent single-file embedding cache shared by all embedders
+pub mod disk_cache;
+use disk_cache::{DiskCache, FileCache};
+
+// Nearest-neighbour indexes over embeddings
+pub mod index;
+
+// Candle embedder built directly from a local model directory
+pub mod local_model;
+
+// Model loading helpers - hub models and local directories
+pub mod model_loader;
+
+// Per-embedder options - normalization, batch size, backend overrides
+pub mod options;
+use options::EmbedderOptions;
+
+// Cross-encoder reranking (inference requires the `ort` feature)
+pub mod reranker;
+
+// Image embedding with CLIP-style vision models
+pub mod vision;
+
+// ONNX Runtime backend - optional, enabled by the `ort` feature
+#[cfg(feature = "ort")]
+pub mod onnx;
+
 // ============================================================================
 // Thread-Local Error Storage
 // ============================================================================
@@ -60,13 +96,48 @@ pub extern "C" fn free_error_string(ptr: *mut c_char) {
 // Tokio Runtime Initialization
 // ============================================================================
 
+/// Default size of the blocking pool, which runs model loads and all async
+/// inference. Leaves room for several embedders each running
+/// `async_embed::DEFAULT_MAX_IN_FLIGHT` operations without queueing model
+/// loads behind them.
+pub const DEFAULT_MAX_BLOCKING_THREADS: usize = 32;
+
+/// Sizing chosen by `init_runtime_with_options` before the runtime starts.
+static RUNTIME_OPTIONS: OnceCell<(usize, usize)> = OnceCell::new();
+
 static RUNTIME: Lazy<Runtime> = Lazy::new(|| {
-    tokio::runtime::Builder::new_multi_thread()
+    let (worker_threads, max_blocking_threads) = RUNTIME_OPTIONS
+        .get()
+        .copied()
+        .unwrap_or((0, DEFAULT_MAX_BLOCKING_THREADS));
+
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    if worker_threads > 0 {
+        builder.worker_threads(worker_threads);
+    }
+    builder
+        .max_blocking_threads(max_blocking_threads)
+        .thread_name("embedanything-worker")
         .enable_all()
         .build()
         .expect("Failed to create Tokio runtime")
 });
 
+/// Whether a synchronous export may block the calling thread on the runtime.
+///
+/// Blocking from a runtime thread (e.g. inside a completion callback or a
+/// directory stream callback) would panic across the FFI boundary, so the
+/// call fails with "FFI_ERROR:" instead; use the start_* variant there.
+fn ensure_outside_runtime() -> bool {
+    if tokio::runtime::Handle::try_current().is_ok() {
+        set_last_error(
+            "FFI_ERROR: synchronous call made from inside the async runtime; use the start_* variant",
+        );
+        return false;
+    }
+    true
+}
+
 #[no_mangle]
 pub extern "C" fn init_runtime() -> i32 {
     match panic::catch_unwind(|| {
@@ -81,6 +152,46 @@ pub extern "C" fn init_runtime() -> i32 {
     }
 }
 
+/// Initialize the Tokio runtime with explicit sizing.
+///
+/// Must be called before any other call that uses the runtime (including
+/// init_runtime); afterwards the sizing can no longer change.
+///
+/// # Parameters
+/// - worker_threads: Async worker threads (0 = one per CPU core)
+/// - max_blocking_threads: Threads for model loads and inference
+///   (0 = default of 32)
+///
+/// # Returns
+/// - 0 on success
+/// - -1 if the runtime was already initialized (call came too late)
+/// - -2 on other errors
+#[no_mangle]
+pub extern "C" fn init_runtime_with_options(
+    worker_threads: usize,
+    max_blocking_threads: usize,
+) -> i32 {
+    clear_last_error();
+
+    let max_blocking_threads = match max_blocking_threads {
+        0 => DEFAULT_MAX_BLOCKING_THREADS,
+        n => n,
+    };
+    if Lazy::get(&RUNTIME).is_some()
+        || RUNTIME_OPTIONS
+            .set((worker_threads, max_blocking_threads))
+            .is_err()
+    {
+        set_last_error("INVALID_CONFIG: runtime: already initialized");
+        return -1;
+    }
+
+    match init_runtime() {
+        0 => 0,
+        _ => -2,
+    }
+}
+
 // ============================================================================
 // Rayon Thread Pool Configuration
 // ============================================================================
@@ -138,6 +249,75 @@ pub extern "C" fn get_thread_pool_size() -> i32 {
 
 pub struct CEmbedder {
     inner: Arc<Embedder>,
+    options: EmbedderOptions,
+    model: ModelFingerprint,
+    /// Whether the model was built as a sparse (SPLADE) model
+    sparse: bool,
+    cache: RwLock<Option<Arc<EmbeddingCache>>>,
+}
+
+impl CEmbedder {
+    fn new(inner: Arc<Embedder>, options: EmbedderOptions, model: ModelFingerprint) -> Self {
+        Self {
+            inner,
+            options,
+            model,
+            sparse: false,
+            cache: RwLock::new(None),
+        }
+    }
+
+    /// Mark the embedder as a sparse (SPLADE) model.
+    fn with_sparse(mut self, sparse: bool) -> Self {
+        self.sparse = sparse;
+        self
+    }
+
+    /// Fail unless the embedder was loaded as a sparse model.
+    ///
+    /// # Errors
+    /// - "INVALID_CONFIG: embedder:" for dense models
+    fn ensure_sparse(&self) -> Result<(), String> {
+        if self.sparse {
+            Ok(())
+        } else {
+            Err(
+                "INVALID_CONFIG: embedder: not a sparse (SPLADE) model; use embed_text for dense models"
+                    .to_string(),
+            )
+        }
+    }
+
+    /// The embedding cache, if one is enabled.
+    fn cache(&self) -> Option<Arc<EmbeddingCache>> {
+        self.cache.read().unwrap().clone()
+    }
+
+    /// Caches consulted by dense text calls (memory, then disk).
+    fn text_cache(&self) -> TextCache {
+        let disk = disk_cache::current()
+            .map(|disk| (disk, disk_cache::model_key(&self.model, &self.options)));
+        TextCache::new(self.cache(), disk)
+    }
+
+    /// Disk cache for embed_file results, if one is open.
+    fn file_cache(&self) -> Option<FileCache> {
+        disk_cache::current()
+            .map(|disk| FileCache::new(disk, disk_cache::model_key(&self.model, &self.options)))
+    }
+}
+
+/// C-compatible embedder options, passed at load time (NULL = defaults)
+#[repr(C)]
+pub struct CEmbedderOptions {
+    /// 1 to L2-normalize output vectors, 0 to return them as-is
+    pub normalize: u8,
+    /// Batch size for embedding calls (0 = model default)
+    pub batch_size: usize,
+    /// Maximum tokens per input (0 = model default; ONNX and local backends)
+    pub max_sequence_length: usize,
+    /// Pooling override: -1=model default, 0=mean, 1=CLS (ONNX and local backends)
+    pub pooling: i32,
 }
 
 // ============================================================================
@@ -156,6 +336,98 @@ pub struct CTextEmbeddingBatch {
     pub count: usize,
 }
 
+// ============================================================================
+// FFI Types for Sparse Embeddings
+// ============================================================================
+
+/// C-compatible sparse embedding (e.g. SPLADE)
+///
+/// Only non-zero entries are stored: `indices[i]` is the vocabulary index of
+/// `values[i]`. Both arrays have `len` elements.
+#[repr(C)]
+pub struct CSparseEmbedding {
+    pub indices: *mut u32,
+    pub values: *mut f32,
+    pub len: usize,
+}
+
+#[repr(C)]
+pub struct CSparseEmbeddingBatch {
+    pub embeddings: *mut CSparseEmbedding,
+    pub count: usize,
+}
+
+// ============================================================================
+// FFI Types for Multi-Vector Embeddings
+// ============================================================================
+
+/// C-compatible multi-vector embedding (e.g. ColBERT late interaction)
+///
+/// `values` is a row-major `rows x dim` buffer: one row per token.
+/// Dense embeddings are represented as a single row.
+#[repr(C)]
+pub struct CMultiVectorEmbedding {
+    pub values: *mut f32,
+    pub rows: usize,
+    pub dim: usize,
+}
+
+#[repr(C)]
+pub struct CMultiVectorEmbeddingBatch {
+    pub embeddings: *mut CMultiVectorEmbedding,
+    pub count: usize,
+}
+
+// ============================================================================
+// FFI Types for Reranking
+// ============================================================================
+
+/// Opaque handle for a cross-encoder reranker
+pub struct CReranker {
+    inner: Arc<reranker::Reranker>,
+}
+
+/// One reranked document: its index in the input array and its score
+#[repr(C)]
+pub struct CRerankResult {
+    pub index: usize,
+    pub score: f32,
+}
+
+/// Reranked documents, highest score first
+#[repr(C)]
+pub struct CRerankResults {
+    pub results: *mut CRerankResult,
+    pub count: usize,
+}
+
+// ============================================================================
+// FFI Types for Vector Indexes
+// ============================================================================
+
+/// Opaque handle for a vector index
+pub struct CVectorIndex {
+    inner: RwLock<index::VectorIndex>,
+}
+
+/// One search hit: its id, score and metadata JSON (NULL if none was stored)
+///
+/// The score is the similarity for cosine and dot indexes and the Euclidean
+/// distance for L2 indexes.
+#[repr(C)]
+pub struct CSearchResult {
+    pub id: u64,
+    pub score: f32,
+    pub metadata_json: *mut c_char,
+}
+
+/// Search hits, closest first
+#[repr(C)]
+pub struct CSearchResults {
+    pub results: *mut CSearchResult,
+    pub count: usize,
+}
+
 // ============================================================================
 // FFI Types for File/Directory Embeddings (Phase 3)
 // ============================================================================
@@ -173,11 +445,16 @@ pub struct CTextEmbedConfig {
 ///
 /// Combines text and metadata into single JSON field to avoid FFI alignment issues
 /// with multiple pointer fields (following SurrealDB pattern)
+///
+/// Multi-vector embeddings are stored row-major in `embedding_values`:
+/// `embedding_len` is the total number of floats and `embedding_rows` the
+/// number of token vectors (1 for dense embeddings).
 #[repr(C)]
 pub struct CEmbedData {
     pub embedding_values: *mut f32,
     pub embedding_len: usize,
     pub text_and_metadata_json: *mut c_char,  // Combined JSON: {"text": "...", "metadata": {...}}
+    pub embedding_rows: usize,
 }
 
 /// Batch of CEmbedData
@@ -188,8 +465,9 @@ pub struct CEmbedDataBatch {
 }
 
 /// Type alias for streaming callback
-/// Called from Rust with batches of embeddings
-type StreamCallback = extern "C" fn(*mut CEmbedDataBatch, *mut c_void);
+/// Called from Rust with batches of embeddings; returns 0 to continue the
+/// walk or non-zero to stop it
+type StreamCallback = extern "C" fn(*mut CEmbedDataBatch, *mut c_void) -> i32;
 
 // ============================================================================
 // Helper Functions
@@ -201,13 +479,8 @@ type StreamCallback = extern "C" fn(*mut CEmbedDataBatch, *mut c_void);
 /// This function uses std::mem::forget() to transfer ownership to Dart.
 /// The caller MUST call free_embed_data() to reclaim memory.
 fn embed_data_to_c(data: EmbedData) -> Result<CEmbedData, String> {
-    // Extract Vec<f32> from EmbeddingResult::DenseVector
-    let embedding_vec = match data.embedding {
-        EmbeddingResult::DenseVector(vec) => vec,
-        EmbeddingResult::MultiVector(_) => {
-            return Err("MULTI_VECTOR_NOT_SUPPORTED: Multi-vector embeddings are not supported in this version".to_string());
-        }
-    };
+    // Flatten DenseVector (1 row) or MultiVector (row-major) into one buffer
+    let (embedding_vec, embedding_rows, _dim) = flatten_embedding(data.embedding)?;
 
     // Convert embedding vector
     let embedding_len = embedding_vec.len();
@@ -253,6 +526,7 @@ fn embed_data_to_c(data: EmbedData) -> Result<CEmbedData, String> {
         embedding_values,
         embedding_len,
         text_and_metadata_json,
+        embedding_rows,
     })
 }
 
@@ -302,6 +576,233 @@ unsafe fn free_embed_data_single(data: CEmbedData) {
     }
 }
 
+/// Flatten an EmbeddingResult into a row-major buffer
+///
+/// Returns `(values, rows, dim)`. Dense vectors become a single row.
+///
+/// # Errors
+/// - "EMBEDDING_FAILED:" if multi-vector rows have different lengths
+fn flatten_embedding(embedding: EmbeddingResult) -> Result<(Vec<f32>, usize, usize), String> {
+    match embedding {
+        EmbeddingResult::DenseVector(vec) => {
+            let dim = vec.len();
+            Ok((vec, 1, dim))
+        }
+        EmbeddingResult::MultiVector(token_vecs) => {
+            let rows = token_vecs.len();
+            let dim = token_vecs.first().map(|row| row.len()).unwrap_or(0);
+            let mut values = Vec::with_capacity(rows * dim);
+            for row in token_vecs {
+                if row.len() != dim {
+                    return Err(format!(
+                        "EMBEDDING_FAILED: Multi-vector rows have inconsistent dimensions ({} vs {})",
+                        row.len(),
+                        dim
+                    ));
+                }
+                values.extend(row);
+            }
+            Ok((values, rows, dim))
+        }
+    }
+}
+
+/// Convert a flattened multi-vector buffer to CMultiVectorEmbedding
+///
+/// # Safety
+/// This function uses std::mem::forget() to transfer ownership to Dart.
+/// The caller MUST call free_multi_vector_embedding() (or the batch variant).
+fn multi_vector_to_c(values: Vec<f32>, rows: usize, dim: usize) -> CMultiVectorEmbedding {
+    let mut boxed = values.into_boxed_slice();
+    let values_ptr = boxed.as_mut_ptr();
+    std::mem::forget(boxed);
+
+    CMultiVectorEmbedding {
+        values: values_ptr,
+        rows,
+        dim,
+    }
+}
+
+/// Convert a list of flattened multi-vector buffers to CMultiVectorEmbeddingBatch
+fn multi_vector_vec_to_batch(
+    multi: Vec<(Vec<f32>, usize, usize)>,
+) -> *mut CMultiVectorEmbeddingBatch {
+    let c_embeddings: Vec<CMultiVectorEmbedding> = multi
+        .into_iter()
+        .map(|(values, rows, dim)| multi_vector_to_c(values, rows, dim))
+        .collect();
+
+    let count = c_embeddings.len();
+    let mut boxed_embeddings = c_embeddings.into_boxed_slice();
+    let embeddings = boxed_embeddings.as_mut_ptr();
+    std::mem::forget(boxed_embeddings);
+
+    Box::into_raw(Box::new(CMultiVectorEmbeddingBatch { embeddings, count }))
+}
+
+/// Free a single CMultiVectorEmbedding (helper for cleanup)
+unsafe fn free_multi_vector_embedding_single(embedding: CMultiVectorEmbedding) {
+    if !embedding.values.is_null() {
+        let len = embedding.rows * embedding.dim;
+        drop(Vec::from_raw_parts(embedding.values, len, len));
+    }
+}
+
+/// Convert ranked `(index, score)` pairs to CRerankResults
+///
+/// # Safety
+/// This function uses std::mem::forget() to transfer ownership to Dart.
+/// The caller MUST call free_rerank_results() to prevent memory leaks.
+fn rerank_results_to_c(ranked: Vec<(usize, f32)>) -> *mut CRerankResults {
+    let c_results: Vec<CRerankResult> = ranked
+        .into_iter()
+        .map(|(index, score)| CRerankResult { index, score })
+        .collect();
+
+    let count = c_results.len();
+    let mut boxed_results = c_results.into_boxed_slice();
+    let results = boxed_results.as_mut_ptr();
+    std::mem::forget(boxed_results);
+
+    Box::into_raw(Box::new(CRerankResults { results, count }))
+}
+
+/// Convert search hits to CSearchResults
+///
+/// # Safety
+/// This function uses std::mem::forget() to transfer ownership to Dart.
+/// The caller MUST call free_search_results() to prevent memory leaks.
+fn search_hits_to_c(hits: Vec<index::SearchHit>) -> *mut CSearchResults {
+    let c_results: Vec<CSearchResult> = hits
+        .into_iter()
+        .map(|hit| CSearchResult {
+            id: hit.id,
+            score: hit.score,
+            metadata_json: hit
+                .metadata
+                .and_then(|json| CString::new(json).ok())
+                .map_or(std::ptr::null_mut(), CString::into_raw),
+        })
+        .collect();
+
+    let count = c_results.len();
+    let mut boxed_results = c_results.into_boxed_slice();
+    let results = boxed_results.as_mut_ptr();
+    std::mem::forget(boxed_results);
+
+    Box::into_raw(Box::new(CSearchResults { results, count }))
+}
+
+/// Validate the dimension and metric arguments of the vector_index_create
+/// functions
+fn index_metric(dimension: usize, metric: i32) -> Result<index::Metric, String> {
+    if dimension == 0 {
+        return Err("INVALID_CONFIG: dimension: must be greater than 0".to_string());
+    }
+    index::Metric::from_i32(metric).ok_or_else(|| {
+        format!(
+            "INVALID_CONFIG: metric: unknown value {} (expected 0, 1 or 2)",
+            metric
+        )
+    })
+}
+
+/// Convert a dense vector to CTextEmbedding
+///
+/// # Safety
+/// This function uses std::mem::forget() to transfer ownership to Dart.
+/// The caller MUST call free_embedding() (or the batch variant).
+fn dense_to_c(values: Vec<f32>) -> CTextEmbedding {
+    let len = values.len();
+    let mut boxed = values.into_boxed_slice();
+    let ptr = boxed.as_mut_ptr();
+    std::mem::forget(boxed);
+
+    CTextEmbedding { values: ptr, len }
+}
+
+/// Convert a list of dense vectors to CTextEmbeddingBatch
+fn dense_vec_to_batch(vectors: Vec<Vec<f32>>) -> *mut CTextEmbeddingBatch {
+    let c_embeddings: Vec<CTextEmbedding> = vectors.into_iter().map(dense_to_c).collect();
+
+    let count = c_embeddings.len();
+    let mut boxed_embeddings = c_embeddings.into_boxed_slice();
+    let embeddings = boxed_embeddings.as_mut_ptr();
+    std::mem::forget(boxed_embeddings);
+
+    Box::into_raw(Box::new(CTextEmbeddingBatch { embeddings, count }))
+}
+
+/// Extract the non-zero entries of a vocabulary-sized sparse vector
+///
+/// Upstream sparse models (SPLADE) emit a dense vector with one slot per
+/// vocabulary token, almost all of them zero.
+fn dense_to_sparse(dense: &[f32]) -> (Vec<u32>, Vec<f32>) {
+    dense
+        .iter()
+        .enumerate()
+        .filter(|(_, &v)| v != 0.0)
+        .map(|(i, &v)| (i as u32, v))
+        .unzip()
+}
+
+/// Convert sparse entries to CSparseEmbedding
+///
+/// # Safety
+/// This function uses std::mem::forget() to transfer ownership to Dart.
+/// The caller MUST call free_sparse_embedding() (or the batch variant).
+fn sparse_to_c(indices: Vec<u32>, values: Vec<f32>) -> CSparseEmbedding {
+    let len = indices.len();
+
+    let mut boxed_indices = indices.into_boxed_slice();
+    let indices_ptr = boxed_indices.as_mut_ptr();
+    std::mem::forget(boxed_indices);
+
+    let mut boxed_values = values.into_boxed_slice();
+    let values_ptr = boxed_values.as_mut_ptr();
+    std::mem::forget(boxed_values);
+
+    CSparseEmbedding {
+        indices: indices_ptr,
+        values: values_ptr,
+        len,
+    }
+}
+
+/// Convert a list of sparse entries to CSparseEmbeddingBatch
+fn sparse_vec_to_batch(sparse: Vec<(Vec<u32>, Vec<f32>)>) -> *mut CSparseEmbeddingBatch {
+    let c_embeddings: Vec<CSparseEmbedding> = sparse
+        .into_iter()
+        .map(|(indices, values)| sparse_to_c(indices, values))
+        .collect();
+
+    let count = c_embeddings.len();
+    let mut boxed_embeddings = c_embeddings.into_boxed_slice();
+    let embeddings = boxed_embeddings.as_mut_ptr();
+    std::mem::forget(boxed_embeddings);
+
+    Box::into_raw(Box::new(CSparseEmbeddingBatch { embeddings, count }))
+}
+
+/// Free a single CSparseEmbedding (helper for cleanup)
+unsafe fn free_sparse_embedding_single(embedding: CSparseEmbedding) {
+    if !embedding.indices.is_null() {
+        drop(Vec::from_raw_parts(
+            embedding.indices,
+            embedding.len,
+            embedding.len,
+        ));
+    }
+    if !embedding.values.is_null() {
+        drop(Vec::from_raw_parts(
+            embedding.values,
+            embedding.len,
+            embedding.len,
+        ));
+    }
+}
+
 // ============================================================================
 // Model Loading Functions
 // ============================================================================
@@ -309,21 +810,35 @@ unsafe fn free_embed_data_single(data: CEmbedData) {
 /// Creates an embedder from a pretrained HuggingFace model
 ///
 /// # Parameters
-/// - model_type: Currently unused (for backward compatibility)
+/// - model_type: Architecture to load (0=BERT, 1=Jina, 2=ModernBERT, 3=Qwen3,
+///   4=XLM-RoBERTa, 255=auto-detect from config.json)
 /// - model_id: Model identifier (e.g., "sentence-transformers/all-MiniLM-L6-v2")
 /// - revision: Git revision (e.g., "main"), or NULL for default
+/// - token: HuggingFace auth token, or NULL to use the token set via
+///   set_hf_token (if any)
 /// - dtype: Data type for model weights (0=F32, 1=F16, -1=default)
+/// - options: Pointer to CEmbedderOptions, or NULL for defaults
 ///
 /// # Returns
 /// - Pointer to CEmbedder on success
 /// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "INVALID_CONFIG:" - Invalid argument, an option the backend cannot
+///   honor, or the checkpoint declares an architecture that contradicts
+///   model_type
+/// - "UNAUTHORIZED:" - Hub returned 401/403 (private or gated model)
+/// - "MODEL_NOT_FOUND:" - Model does not exist on the Hub
+/// - "EMBEDDING_FAILED:" - Model could not be loaded
 #[no_mangle]
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn embedder_from_pretrained_hf(
-    _model_type: u8,
+    model_type: u8,
     model_id: *const c_char,
     revision: *const c_char,
+    token: *const c_char,
     dtype: i32,
+    options: *const CEmbedderOptions,
 ) -> *mut CEmbedder {
     clear_last_error();
 
@@ -358,6 +873,28 @@ pub extern "C" fn embedder_from_pretrained_hf(
         }
     };
 
+    let token_opt = if token.is_null() {
+        None
+    } else {
+        unsafe {
+            match CStr::from_ptr(token).to_str() {
+                Ok(s) => Some(s),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: token: invalid UTF-8 encoding");
+                    return std::ptr::null_mut();
+                }
+            }
+        }
+    };
+
+    let architecture = match model_loader::ModelArchitecture::from_model_type(model_type) {
+        Ok(arch) => arch,
+        Err(e) => {
+            set_last_error(&e);
+            return std::ptr::null_mut();
+        }
+    };
+
     // Map dtype parameter to Dtype enum
     let dtype_opt = match dtype {
         0 => Some(Dtype::F32),
@@ -369,96 +906,478 @@ pub extern "C" fn embedder_from_pretrained_hf(
         }
     };
 
-    // Create embedder (synchronous - EmbedAnything auto-detects architecture)
-    let embedder_result = Embedder::from_pretrained_hf(
+    let options = match EmbedderOptions::from_c(unsafe { options.as_ref() })
+        .and_then(|o| o.ensure_model_defaults().map(|_| o))
+    {
+        Ok(o) => o,
+        Err(e) => {
+            set_last_error(&e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    // Create embedder (synchronous)
+    match model_loader::load_from_hf(
         model_id_str,
         revision_opt,
-        None, // token - use default
+        token_opt,
+        architecture,
         dtype_opt,
-    );
-
-    match embedder_result {
-        Ok(embedder) => {
-            let boxed = Box::new(CEmbedder {
-                inner: Arc::new(embedder),
-            });
+    ) {
+        Ok(loaded) => {
+            let boxed = Box::new(
+                CEmbedder::new(
+                    Arc::new(loaded.embedder),
+                    options,
+                    ModelFingerprint::new(model_id_str, revision_opt, dtype),
+                )
+                .with_sparse(loaded.sparse),
+            );
             Box::into_raw(boxed)
         }
         Err(e) => {
-            let error_str = e.to_string().to_lowercase();
-            // Check if error indicates model not found
-            // HuggingFace returns 404 status code for non-existent models
-            if error_str.contains("404") {
-                set_last_error(&format!("MODEL_NOT_FOUND: {}", model_id_str));
-            } else {
-                set_last_error(&format!(
-                    "EMBEDDING_FAILED: Failed to load model '{}': {}",
-                    model_id_str, e
-                ));
-            }
+            set_last_error(&e);
             std::ptr::null_mut()
         }
     }
 }
 
-// ============================================================================
-// Text Embedding Functions
-// ============================================================================
-
-/// Embeds a single text query
+/// Creates an embedder from a local model directory (no network access)
+///
+/// The directory must contain `config.json`, `tokenizer.json` and
+/// `model.safetensors`. The architecture is detected from `config.json`.
 ///
 /// # Parameters
-/// - embedder: Pointer to CEmbedder
-/// - text: Text to embed
+/// - model_path: Path to the model directory
+/// - dtype: Data type for model weights (0=F32, 1=F16, -1=default)
+/// - options: Pointer to CEmbedderOptions, or NULL for defaults
 ///
 /// # Returns
-/// - Pointer to CTextEmbedding on success
+/// - Pointer to CEmbedder on success
 /// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FILE_NOT_FOUND:" - Directory or config.json does not exist
+/// - "TOKENIZER_NOT_FOUND:" - tokenizer.json is missing
+/// - "WEIGHTS_NOT_FOUND:" - model.safetensors is missing
+/// - "UNSUPPORTED_ARCHITECTURE:" - Architecture in config.json is not supported
+/// - "INVALID_CONFIG:" - Invalid argument
+/// - "EMBEDDING_FAILED:" - Model weights could not be loaded
 #[no_mangle]
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
-pub extern "C" fn embed_text(
-    embedder: *const CEmbedder,
-    text: *const c_char,
-) -> *mut CTextEmbedding {
+pub extern "C" fn embedder_from_local_path(
+    model_path: *const c_char,
+    dtype: i32,
+    options: *const CEmbedderOptions,
+) -> *mut CEmbedder {
     clear_last_error();
 
     // Validate inputs
-    if embedder.is_null() {
-        set_last_error("FFI_ERROR: embedder pointer is null");
-        return std::ptr::null_mut();
-    }
-    if text.is_null() {
-        set_last_error("INVALID_CONFIG: text: cannot be null");
+    if model_path.is_null() {
+        set_last_error("INVALID_CONFIG: model_path: cannot be null");
         return std::ptr::null_mut();
     }
 
-    let embedder = unsafe { &*embedder };
-
-    // Convert C string to Rust string
-    let text_str = unsafe {
-        match CStr::from_ptr(text).to_str() {
+    let model_path_str = unsafe {
+        match CStr::from_ptr(model_path).to_str() {
             Ok(s) => s,
             Err(_) => {
-                set_last_error("INVALID_CONFIG: text: invalid UTF-8 encoding");
+                set_last_error("INVALID_CONFIG: model_path: invalid UTF-8 encoding");
                 return std::ptr::null_mut();
             }
         }
     };
 
-    // Generate embedding - embed_query takes &[&str] and returns Vec<EmbedData>
-    let result = RUNTIME.block_on(async { embedder.inner.embed_query(&[text_str], None).await });
-
-    match result {
-        Ok(embed_data_vec) => {
-            // Extract the first (and only) EmbedData
-            if embed_data_vec.is_empty() {
-                set_last_error("EMBEDDING_FAILED: embed_query returned empty result");
-                return std::ptr::null_mut();
-            }
+    // Map dtype parameter to Dtype enum
+    let dtype_opt = match dtype {
+        0 => Some(Dtype::F32),
+        1 => Some(Dtype::F16),
+        -1 => None, // Use default
+        _ => {
+            set_last_error(&format!("INVALID_CONFIG: dtype: invalid value {}", dtype));
+            return std::ptr::null_mut();
+        }
+    };
 
-            let embed_data = &embed_data_vec[0];
+    let options = match EmbedderOptions::from_c(unsafe { options.as_ref() }) {
+        Ok(o) => o,
+        Err(e) => {
+            set_last_error(&e);
+            return std::ptr::null_mut();
+        }
+    };
 
-            // Extract vector from EmbeddingResult enum
+    match model_loader::load_from_local_dir(
+        &PathBuf::from(model_path_str),
+        dtype_opt,
+        options.pooling,
+        options.max_sequence_length,
+    ) {
+        Ok(embedder) => {
+            let content_hash = model_loader::local_dir_content_hash(Path::new(model_path_str));
+            let boxed = Box::new(CEmbedder::new(
+                Arc::new(embedder),
+                options,
+                ModelFingerprint::new(model_path_str, None, dtype).with_content_hash(content_hash),
+            ));
+            Box::into_raw(boxed)
+        }
+        Err(e) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Creates an embedder backed by ONNX Runtime
+///
+/// The returned handle works with every embed function (sync and async),
+/// exactly like a candle-based embedder. Requires the `ort` cargo feature.
+///
+/// # Parameters
+/// - model_path: Path to the exported `.onnx` model
+/// - tokenizer_path: Path to the matching `tokenizer.json`
+/// - options: Pointer to CEmbedderOptions, or NULL for defaults. Pooling
+///   defaults to mean and is ignored when the model already outputs
+///   sentence embeddings; max_sequence_length defaults to 512
+///
+/// # Returns
+/// - Pointer to CEmbedder on success
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FILE_NOT_FOUND:" - Model or tokenizer file does not exist
+/// - "INVALID_CONFIG:" - Invalid argument, or library built without `ort`
+/// - "EMBEDDING_FAILED:" - ONNX Runtime session could not be created
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn embedder_from_onnx(
+    model_path: *const c_char,
+    tokenizer_path: *const c_char,
+    options: *const CEmbedderOptions,
+) -> *mut CEmbedder {
+    clear_last_error();
+
+    // Validate inputs
+    if model_path.is_null() {
+        set_last_error("INVALID_CONFIG: model_path: cannot be null");
+        return std::ptr::null_mut();
+    }
+    if tokenizer_path.is_null() {
+        set_last_error("INVALID_CONFIG: tokenizer_path: cannot be null");
+        return std::ptr::null_mut();
+    }
+
+    let model_path_str = unsafe {
+        match CStr::from_ptr(model_path).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: model_path: invalid UTF-8 encoding");
+                return std::ptr::null_mut();
+            }
+        }
+    };
+
+    let tokenizer_path_str = unsafe {
+        match CStr::from_ptr(tokenizer_path).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: tokenizer_path: invalid UTF-8 encoding");
+                return std::ptr::null_mut();
+            }
+        }
+    };
+
+    let options = match EmbedderOptions::from_c(unsafe { options.as_ref() }) {
+        Ok(o) => o,
+        Err(e) => {
+            set_last_error(&e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    #[cfg(feature = "ort")]
+    {
+        let pooling = options.pooling.unwrap_or(crate::options::Pooling::Mean);
+
+        let model_path = PathBuf::from(model_path_str);
+        if !model_path.is_file() {
+            set_last_error(&format!("FILE_NOT_FOUND: {}", model_path_str));
+            return std::ptr::null_mut();
+        }
+        let tokenizer_path = PathBuf::from(tokenizer_path_str);
+        if !tokenizer_path.is_file() {
+            set_last_error(&format!("FILE_NOT_FOUND: {}", tokenizer_path_str));
+            return std::ptr::null_mut();
+        }
+
+        match onnx::OnnxEmbedder::new(
+            &model_path,
+            &tokenizer_path,
+            pooling,
+            options.max_sequence_length,
+        ) {
+            Ok(embedder) => {
+                let content_hash = cache::content_hash(&[&tokenizer_path], &[&model_path]);
+                let boxed = Box::new(CEmbedder::new(
+                    Arc::new(embedder.into_embedder()),
+                    options,
+                    ModelFingerprint::new(model_path_str, None, -1).with_content_hash(content_hash),
+                ));
+                Box::into_raw(boxed)
+            }
+            Err(e) => {
+                set_last_error(&format!(
+                    "EMBEDDING_FAILED: Failed to load ONNX model '{}': {:#}",
+                    model_path_str, e
+                ));
+                std::ptr::null_mut()
+            }
+        }
+    }
+
+    #[cfg(not(feature = "ort"))]
+    {
+        let _ = (model_path_str, tokenizer_path_str, options);
+        set_last_error("INVALID_CONFIG: backend: library was built without the 'ort' feature");
+        std::ptr::null_mut()
+    }
+}
+
+/// Creates an image embedder from a CLIP or SigLIP checkpoint on the HuggingFace Hub
+///
+/// The returned handle embeds images (embed_image_*) and text queries into
+/// the same space (embed_image_query).
+///
+/// # Parameters
+/// - model_id: Model identifier (e.g., "openai/clip-vit-base-patch32")
+/// - revision: Git revision (e.g., "main"), or NULL for default
+/// - token: HuggingFace auth token, or NULL to use the token set via
+///   set_hf_token (if any)
+/// - dtype: Data type for model weights (0=F32, 1=F16, -1=default)
+/// - options: Pointer to CEmbedderOptions, or NULL for defaults
+///
+/// # Returns
+/// - Pointer to CEmbedder on success
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "INVALID_CONFIG:" - Invalid argument, an option the backend cannot
+///   honor, or the checkpoint is not a vision model
+/// - "UNAUTHORIZED:" - Hub returned 401/403 (private or gated model)
+/// - "MODEL_NOT_FOUND:" - Model does not exist on the Hub
+/// - "EMBEDDING_FAILED:" - Model could not be loaded
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn vision_embedder_from_pretrained_hf(
+    model_id: *const c_char,
+    revision: *const c_char,
+    token: *const c_char,
+    dtype: i32,
+    options: *const CEmbedderOptions,
+) -> *mut CEmbedder {
+    clear_last_error();
+
+    // Validate inputs
+    if model_id.is_null() {
+        set_last_error("INVALID_CONFIG: model_id: cannot be null");
+        return std::ptr::null_mut();
+    }
+
+    let model_id_str = unsafe {
+        match CStr::from_ptr(model_id).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: model_id: invalid UTF-8 encoding");
+                return std::ptr::null_mut();
+            }
+        }
+    };
+
+    let revision_opt = if revision.is_null() {
+        None
+    } else {
+        unsafe {
+            match CStr::from_ptr(revision).to_str() {
+                Ok(s) => Some(s),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: revision: invalid UTF-8 encoding");
+                    return std::ptr::null_mut();
+                }
+            }
+        }
+    };
+
+    let token_opt = if token.is_null() {
+        None
+    } else {
+        unsafe {
+            match CStr::from_ptr(token).to_str() {
+                Ok(s) => Some(s),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: token: invalid UTF-8 encoding");
+                    return std::ptr::null_mut();
+                }
+            }
+        }
+    };
+
+    let dtype_opt = match dtype {
+        0 => Some(Dtype::F32),
+        1 => Some(Dtype::F16),
+        -1 => None,
+        _ => {
+            set_last_error(&format!("INVALID_CONFIG: dtype: invalid value {}", dtype));
+            return std::ptr::null_mut();
+        }
+    };
+
+    let options = match EmbedderOptions::from_c(unsafe { options.as_ref() })
+        .and_then(|o| o.ensure_model_defaults().map(|_| o))
+    {
+        Ok(o) => o,
+        Err(e) => {
+            set_last_error(&e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    match vision::load_from_hf(model_id_str, revision_opt, token_opt, dtype_opt) {
+        Ok(embedder) => Box::into_raw(Box::new(CEmbedder::new(
+            Arc::new(embedder),
+            options,
+            ModelFingerprint::new(model_id_str, revision_opt, dtype),
+        ))),
+        Err(e) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Sets the process-wide HuggingFace auth token
+///
+/// Used by every model load that does not pass its own token.
+///
+/// # Parameters
+/// - token: HuggingFace auth token, or NULL to clear it
+///
+/// # Returns
+/// - 0 on success
+/// - -1 on failure (check get_last_error)
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn set_hf_token(token: *const c_char) -> i32 {
+    clear_last_error();
+
+    if token.is_null() {
+        model_loader::set_default_token(None);
+        return 0;
+    }
+
+    let token_str = unsafe {
+        match CStr::from_ptr(token).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: token: invalid UTF-8 encoding");
+                return -1;
+            }
+        }
+    };
+
+    model_loader::set_default_token(Some(token_str));
+    0
+}
+
+/// Lists the model architectures compiled into this library
+///
+/// # Returns
+/// - JSON string: `[{"model_type": 0, "name": "bert", "architectures": [...]}, ...]`
+///   (caller must free with free_string)
+/// - NULL on failure (check get_last_error)
+#[no_mangle]
+pub extern "C" fn list_supported_architectures() -> *mut c_char {
+    clear_last_error();
+
+    match CString::new(model_loader::supported_architectures_json()) {
+        Ok(cstr) => cstr.into_raw(),
+        Err(_) => {
+            set_last_error("FFI_ERROR: Failed to encode architecture list");
+            std::ptr::null_mut()
+        }
+    }
+}
+
+// ============================================================================
+// Text Embedding Functions
+// ============================================================================
+
+/// Embeds a single text query
+///
+/// # Parameters
+/// - embedder: Pointer to CEmbedder
+/// - text: Text to embed
+///
+/// # Returns
+/// - Pointer to CTextEmbedding on success
+/// - NULL on failure (check get_last_error)
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn embed_text(
+    embedder: *const CEmbedder,
+    text: *const c_char,
+) -> *mut CTextEmbedding {
+    clear_last_error();
+
+    if !ensure_outside_runtime() {
+        return std::ptr::null_mut();
+    }
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return std::ptr::null_mut();
+    }
+    if text.is_null() {
+        set_last_error("INVALID_CONFIG: text: cannot be null");
+        return std::ptr::null_mut();
+    }
+
+    let embedder = unsafe { &*embedder };
+
+    // Convert C string to Rust string
+    let text_str = unsafe {
+        match CStr::from_ptr(text).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: text: invalid UTF-8 encoding");
+                return std::ptr::null_mut();
+            }
+        }
+    };
+
+    // Serve repeated texts from the cache
+    let cache = embedder.text_cache();
+    if let Some(values) = cache.get(CacheKind::Query, text_str) {
+        return Box::into_raw(Box::new(dense_to_c(values)));
+    }
+
+    // Generate embedding - embed_query takes &[&str] and returns Vec<EmbedData>
+    let result = RUNTIME
+        .block_on(async { embedder.inner.embed_query(&[text_str], None).await })
+        .map(|data| embedder.options.apply_to_data(data));
+
+    match result {
+        Ok(embed_data_vec) => {
+            // Extract the first (and only) EmbedData
+            if embed_data_vec.is_empty() {
+                set_last_error("EMBEDDING_FAILED: embed_query returned empty result");
+                return std::ptr::null_mut();
+            }
+
+            let embed_data = &embed_data_vec[0];
+
+            // Extract vector from EmbeddingResult enum
             let embedding_vec = match &embed_data.embedding {
                 EmbeddingResult::DenseVector(vec) => vec,
                 EmbeddingResult::MultiVector(_) => {
@@ -475,6 +1394,8 @@ pub extern "C" fn embed_text(
                 return std::ptr::null_mut();
             }
 
+            cache.insert(CacheKind::Query, text_str, embedding_vec);
+
             let len = embedding_vec.len();
             let mut boxed = embedding_vec.clone().into_boxed_slice();
             let ptr = boxed.as_mut_ptr();
@@ -513,6 +1434,10 @@ pub extern "C" fn embed_texts_batch(
 ) -> *mut CTextEmbeddingBatch {
     clear_last_error();
 
+    if !ensure_outside_runtime() {
+        return std::ptr::null_mut();
+    }
+
     // Validate inputs
     if embedder.is_null() {
         set_last_error("FFI_ERROR: embedder pointer is null");
@@ -554,12 +1479,28 @@ pub extern "C" fn embed_texts_batch(
     // Convert to Vec<&str> for embed function
     let text_refs: Vec<&str> = text_strings.iter().map(|s| s.as_str()).collect();
 
+    // Only texts missing from the cache go to the model
+    let cache = embedder.text_cache();
+    let lookup = cache.lookup_batch(CacheKind::Document, &text_refs);
+    let miss_refs = lookup.miss_texts(&text_refs);
+
     // Generate embeddings - embed() returns Vec<EmbeddingResult> directly
-    let result = RUNTIME.block_on(async { embedder.inner.embed(&text_refs, None, None).await });
+    let result = if miss_refs.is_empty() {
+        Ok(Vec::new())
+    } else {
+        RUNTIME
+            .block_on(async {
+                embedder
+                    .inner
+                    .embed(&miss_refs, embedder.options.batch_size, None)
+                    .await
+            })
+            .map(|results| embedder.options.apply_to_results(results))
+    };
 
     match result {
         Ok(embedding_results) => {
-            let mut c_embeddings = Vec::with_capacity(embedding_results.len());
+            let mut embedded = Vec::with_capacity(embedding_results.len());
 
             for embedding_result in embedding_results {
                 // Extract vector from EmbeddingResult enum
@@ -577,25 +1518,21 @@ pub extern "C" fn embed_texts_batch(
                     return std::ptr::null_mut();
                 }
 
-                let len = embedding_vec.len();
-                let mut boxed = embedding_vec.into_boxed_slice();
-                let ptr = boxed.as_mut_ptr();
-                std::mem::forget(boxed);
-
-                c_embeddings.push(CTextEmbedding { values: ptr, len });
+                embedded.push(embedding_vec);
             }
 
-            let batch_len = c_embeddings.len();
-            let mut boxed_embeddings = c_embeddings.into_boxed_slice();
-            let embeddings_ptr = boxed_embeddings.as_mut_ptr();
-            std::mem::forget(boxed_embeddings);
-
-            let batch = Box::new(CTextEmbeddingBatch {
-                embeddings: embeddings_ptr,
-                count: batch_len,
-            });
-
-            Box::into_raw(batch)
+            let returned = embedded.len();
+            match lookup.fill(&cache, CacheKind::Document, &text_refs, embedded) {
+                Some(vectors) => dense_vec_to_batch(vectors),
+                None => {
+                    set_last_error(&format!(
+                        "EMBEDDING_FAILED: embed returned {} results for {} texts",
+                        returned,
+                        miss_refs.len()
+                    ));
+                    std::ptr::null_mut()
+                }
+            }
         }
         Err(e) => {
             set_last_error(&format!(
@@ -608,356 +1545,2047 @@ pub extern "C" fn embed_texts_batch(
 }
 
 // ============================================================================
-// File/Directory Embedding Functions (Phase 3)
+// Sparse Embedding Functions
+// ============================================================================
+
+/// Embeds a single text query with a sparse (SPLADE) model
+///
+/// # Parameters
+/// - embedder: Pointer to CEmbedder (loaded from a sparse checkpoint)
+/// - text: Text to embed
+///
+/// # Returns
+/// - Pointer to CSparseEmbedding on success
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "INVALID_CONFIG: embedder:" - The embedder is not a sparse model
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn embed_text_sparse(
+    embedder: *const CEmbedder,
+    text: *const c_char,
+) -> *mut CSparseEmbedding {
+    clear_last_error();
+
+    if !ensure_outside_runtime() {
+        return std::ptr::null_mut();
+    }
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return std::ptr::null_mut();
+    }
+    if text.is_null() {
+        set_last_error("INVALID_CONFIG: text: cannot be null");
+        return std::ptr::null_mut();
+    }
+
+    let embedder = unsafe { &*embedder };
+    if let Err(e) = embedder.ensure_sparse() {
+        set_last_error(&e);
+        return std::ptr::null_mut();
+    }
+
+    let text_str = unsafe {
+        match CStr::from_ptr(text).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: text: invalid UTF-8 encoding");
+                return std::ptr::null_mut();
+            }
+        }
+    };
+
+    let result = RUNTIME
+        .block_on(async { embedder.inner.embed_query(&[text_str], None).await })
+        .map(|data| embedder.options.apply_to_data(data));
+
+    match result {
+        Ok(embed_data_vec) => {
+            let embed_data = match embed_data_vec.first() {
+                Some(data) => data,
+                None => {
+                    set_last_error("EMBEDDING_FAILED: embed_query returned empty result");
+                    return std::ptr::null_mut();
+                }
+            };
+
+            match &embed_data.embedding {
+                EmbeddingResult::DenseVector(vec) => {
+                    let (indices, values) = dense_to_sparse(vec);
+                    Box::into_raw(Box::new(sparse_to_c(indices, values)))
+                }
+                EmbeddingResult::MultiVector(_) => {
+                    set_last_error(
+                        "MULTI_VECTOR: Multi-vector embeddings cannot be returned as sparse",
+                    );
+                    std::ptr::null_mut()
+                }
+            }
+        }
+        Err(e) => {
+            set_last_error(&format!(
+                "EMBEDDING_FAILED: Sparse embedding generation failed: {}",
+                e
+            ));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Embeds a batch of texts with a sparse (SPLADE) model
+///
+/// # Parameters
+/// - embedder: Pointer to CEmbedder (loaded from a sparse checkpoint)
+/// - texts: Array of text pointers
+/// - count: Number of texts
+///
+/// # Returns
+/// - Pointer to CSparseEmbeddingBatch on success
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "INVALID_CONFIG: embedder:" - The embedder is not a sparse model
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn embed_texts_batch_sparse(
+    embedder: *const CEmbedder,
+    texts: *const *const c_char,
+    count: usize,
+) -> *mut CSparseEmbeddingBatch {
+    clear_last_error();
+
+    if !ensure_outside_runtime() {
+        return std::ptr::null_mut();
+    }
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return std::ptr::null_mut();
+    }
+    if texts.is_null() {
+        set_last_error("INVALID_CONFIG: texts: cannot be null");
+        return std::ptr::null_mut();
+    }
+    if count == 0 {
+        set_last_error("INVALID_CONFIG: count: must be greater than 0");
+        return std::ptr::null_mut();
+    }
+
+    let embedder = unsafe { &*embedder };
+    if let Err(e) = embedder.ensure_sparse() {
+        set_last_error(&e);
+        return std::ptr::null_mut();
+    }
+
+    // Convert C string array to Rust Vec<String>
+    let texts_slice = unsafe { std::slice::from_raw_parts(texts, count) };
+    let mut text_strings = Vec::with_capacity(count);
+
+    for &text_ptr in texts_slice {
+        if text_ptr.is_null() {
+            set_last_error("INVALID_CONFIG: texts: array contains null pointer");
+            return std::ptr::null_mut();
+        }
+
+        let text_str = unsafe {
+            match CStr::from_ptr(text_ptr).to_str() {
+                Ok(s) => s.to_string(),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: texts: array contains invalid UTF-8");
+                    return std::ptr::null_mut();
+                }
+            }
+        };
+        text_strings.push(text_str);
+    }
+
+    let text_refs: Vec<&str> = text_strings.iter().map(|s| s.as_str()).collect();
+
+    let result = RUNTIME
+        .block_on(async {
+            embedder
+                .inner
+                .embed(&text_refs, embedder.options.batch_size, None)
+                .await
+        })
+        .map(|results| embedder.options.apply_to_results(results));
+
+    match result {
+        Ok(embedding_results) => {
+            let mut sparse = Vec::with_capacity(embedding_results.len());
+
+            for embedding_result in embedding_results {
+                match embedding_result {
+                    EmbeddingResult::DenseVector(vec) => sparse.push(dense_to_sparse(&vec)),
+                    EmbeddingResult::MultiVector(_) => {
+                        set_last_error(
+                            "MULTI_VECTOR: Multi-vector embeddings cannot be returned as sparse",
+                        );
+                        return std::ptr::null_mut();
+                    }
+                }
+            }
+
+            sparse_vec_to_batch(sparse)
+        }
+        Err(e) => {
+            set_last_error(&format!(
+                "EMBEDDING_FAILED: Sparse batch embedding generation failed for {} texts: {}",
+                count, e
+            ));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+// ============================================================================
+// Multi-Vector Embedding Functions
+// ============================================================================
+
+/// Embeds a single text query, keeping one vector per token
+///
+/// Works with late-interaction models (ColBERT); dense models return a
+/// single row.
+///
+/// # Parameters
+/// - embedder: Pointer to CEmbedder
+/// - text: Text to embed
+///
+/// # Returns
+/// - Pointer to CMultiVectorEmbedding on success
+/// - NULL on failure (check get_last_error)
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn embed_text_multi_vector(
+    embedder: *const CEmbedder,
+    text: *const c_char,
+) -> *mut CMultiVectorEmbedding {
+    clear_last_error();
+
+    if !ensure_outside_runtime() {
+        return std::ptr::null_mut();
+    }
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return std::ptr::null_mut();
+    }
+    if text.is_null() {
+        set_last_error("INVALID_CONFIG: text: cannot be null");
+        return std::ptr::null_mut();
+    }
+
+    let embedder = unsafe { &*embedder };
+
+    let text_str = unsafe {
+        match CStr::from_ptr(text).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: text: invalid UTF-8 encoding");
+                return std::ptr::null_mut();
+            }
+        }
+    };
+
+    let result = RUNTIME
+        .block_on(async { embedder.inner.embed_query(&[text_str], None).await })
+        .map(|data| embedder.options.apply_to_data(data));
+
+    match result {
+        Ok(mut embed_data_vec) => {
+            if embed_data_vec.is_empty() {
+                set_last_error("EMBEDDING_FAILED: embed_query returned empty result");
+                return std::ptr::null_mut();
+            }
+
+            match flatten_embedding(embed_data_vec.swap_remove(0).embedding) {
+                Ok((values, rows, dim)) => {
+                    Box::into_raw(Box::new(multi_vector_to_c(values, rows, dim)))
+                }
+                Err(e) => {
+                    set_last_error(&e);
+                    std::ptr::null_mut()
+                }
+            }
+        }
+        Err(e) => {
+            set_last_error(&format!(
+                "EMBEDDING_FAILED: Multi-vector embedding generation failed: {}",
+                e
+            ));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Embeds a batch of texts, keeping one vector per token
+///
+/// # Parameters
+/// - embedder: Pointer to CEmbedder
+/// - texts: Array of text pointers
+/// - count: Number of texts
+///
+/// # Returns
+/// - Pointer to CMultiVectorEmbeddingBatch on success
+/// - NULL on failure (check get_last_error)
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn embed_texts_batch_multi_vector(
+    embedder: *const CEmbedder,
+    texts: *const *const c_char,
+    count: usize,
+) -> *mut CMultiVectorEmbeddingBatch {
+    clear_last_error();
+
+    if !ensure_outside_runtime() {
+        return std::ptr::null_mut();
+    }
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return std::ptr::null_mut();
+    }
+    if texts.is_null() {
+        set_last_error("INVALID_CONFIG: texts: cannot be null");
+        return std::ptr::null_mut();
+    }
+    if count == 0 {
+        set_last_error("INVALID_CONFIG: count: must be greater than 0");
+        return std::ptr::null_mut();
+    }
+
+    let embedder = unsafe { &*embedder };
+
+    // Convert C string array to Rust Vec<String>
+    let texts_slice = unsafe { std::slice::from_raw_parts(texts, count) };
+    let mut text_strings = Vec::with_capacity(count);
+
+    for &text_ptr in texts_slice {
+        if text_ptr.is_null() {
+            set_last_error("INVALID_CONFIG: texts: array contains null pointer");
+            return std::ptr::null_mut();
+        }
+
+        let text_str = unsafe {
+            match CStr::from_ptr(text_ptr).to_str() {
+                Ok(s) => s.to_string(),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: texts: array contains invalid UTF-8");
+                    return std::ptr::null_mut();
+                }
+            }
+        };
+        text_strings.push(text_str);
+    }
+
+    let text_refs: Vec<&str> = text_strings.iter().map(|s| s.as_str()).collect();
+
+    let result = RUNTIME
+        .block_on(async {
+            embedder
+                .inner
+                .embed(&text_refs, embedder.options.batch_size, None)
+                .await
+        })
+        .map(|results| embedder.options.apply_to_results(results));
+
+    match result {
+        Ok(embedding_results) => {
+            let flattened: Result<Vec<_>, String> = embedding_results
+                .into_iter()
+                .map(flatten_embedding)
+                .collect();
+
+            match flattened {
+                Ok(multi) => multi_vector_vec_to_batch(multi),
+                Err(e) => {
+                    set_last_error(&e);
+                    std::ptr::null_mut()
+                }
+            }
+        }
+        Err(e) => {
+            set_last_error(&format!(
+                "EMBEDDING_FAILED: Multi-vector batch embedding generation failed for {} texts: {}",
+                count, e
+            ));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+// ============================================================================
+// Reranking Functions
+// ============================================================================
+
+/// Creates a cross-encoder reranker from a HuggingFace model
+///
+/// The repo must contain `tokenizer.json` and an ONNX export at
+/// `onnx/model.onnx` or `model.onnx`. Requires the `ort` cargo feature.
+///
+/// # Parameters
+/// - model_id: Model identifier (e.g., "cross-encoder/ms-marco-MiniLM-L-6-v2")
+/// - revision: Git revision (e.g., "main"), or NULL for default
+/// - token: HuggingFace auth token, or NULL to use the token set via
+///   set_hf_token (if any)
+///
+/// # Returns
+/// - Pointer to CReranker on success (free with reranker_free)
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "INVALID_CONFIG:" - Invalid argument, or library built without `ort`
+/// - "UNAUTHORIZED:" - Hub returned 401/403 (private or gated model)
+/// - "RERANKER_NOT_FOUND:" - Model or its ONNX/tokenizer files do not exist
+/// - "RERANKER_LOAD_FAILED:" - Tokenizer or ONNX Runtime session could not be created
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn reranker_from_pretrained_hf(
+    model_id: *const c_char,
+    revision: *const c_char,
+    token: *const c_char,
+) -> *mut CReranker {
+    clear_last_error();
+
+    // Validate inputs
+    if model_id.is_null() {
+        set_last_error("INVALID_CONFIG: model_id: cannot be null");
+        return std::ptr::null_mut();
+    }
+
+    let model_id_str = unsafe {
+        match CStr::from_ptr(model_id).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: model_id: invalid UTF-8 encoding");
+                return std::ptr::null_mut();
+            }
+        }
+    };
+
+    let revision_opt = if revision.is_null() {
+        None
+    } else {
+        unsafe {
+            match CStr::from_ptr(revision).to_str() {
+                Ok(s) => Some(s),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: revision: invalid UTF-8 encoding");
+                    return std::ptr::null_mut();
+                }
+            }
+        }
+    };
+
+    let token_opt = if token.is_null() {
+        None
+    } else {
+        unsafe {
+            match CStr::from_ptr(token).to_str() {
+                Ok(s) => Some(s),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: token: invalid UTF-8 encoding");
+                    return std::ptr::null_mut();
+                }
+            }
+        }
+    };
+
+    match reranker::Reranker::from_pretrained_hf(model_id_str, revision_opt, token_opt) {
+        Ok(reranker) => Box::into_raw(Box::new(CReranker {
+            inner: Arc::new(reranker),
+        })),
+        Err(e) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Creates a cross-encoder reranker from a local directory (no network access)
+///
+/// The directory must contain `tokenizer.json` and `onnx/model.onnx` or
+/// `model.onnx`. Requires the `ort` cargo feature.
+///
+/// # Parameters
+/// - model_path: Path to the reranker directory
+///
+/// # Returns
+/// - Pointer to CReranker on success (free with reranker_free)
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "INVALID_CONFIG:" - Invalid argument, or library built without `ort`
+/// - "RERANKER_NOT_FOUND:" - Directory or its ONNX/tokenizer files do not exist
+/// - "RERANKER_LOAD_FAILED:" - Tokenizer or ONNX Runtime session could not be created
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn reranker_from_local_path(model_path: *const c_char) -> *mut CReranker {
+    clear_last_error();
+
+    // Validate inputs
+    if model_path.is_null() {
+        set_last_error("INVALID_CONFIG: model_path: cannot be null");
+        return std::ptr::null_mut();
+    }
+
+    let model_path_str = unsafe {
+        match CStr::from_ptr(model_path).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: model_path: invalid UTF-8 encoding");
+                return std::ptr::null_mut();
+            }
+        }
+    };
+
+    match reranker::Reranker::from_local_dir(&PathBuf::from(model_path_str)) {
+        Ok(reranker) => Box::into_raw(Box::new(CReranker {
+            inner: Arc::new(reranker),
+        })),
+        Err(e) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Reranks documents against a query
+///
+/// # Parameters
+/// - reranker: Pointer to CReranker
+/// - query: Query text
+/// - documents: Array of document text pointers
+/// - count: Number of documents
+/// - top_k: Number of results to return (0 = all)
+///
+/// # Returns
+/// - Pointer to CRerankResults on success, highest score first
+///   (free with free_rerank_results)
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FFI_ERROR:" - Reranker pointer is null
+/// - "INVALID_CONFIG:" - Invalid argument
+/// - "RERANK_FAILED:" - Scoring failed
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn rerank(
+    reranker: *const CReranker,
+    query: *const c_char,
+    documents: *const *const c_char,
+    count: usize,
+    top_k: usize,
+) -> *mut CRerankResults {
+    clear_last_error();
+
+    // Validate inputs
+    if reranker.is_null() {
+        set_last_error("FFI_ERROR: reranker pointer is null");
+        return std::ptr::null_mut();
+    }
+    if query.is_null() {
+        set_last_error("INVALID_CONFIG: query: cannot be null");
+        return std::ptr::null_mut();
+    }
+    if documents.is_null() {
+        set_last_error("INVALID_CONFIG: documents: cannot be null");
+        return std::ptr::null_mut();
+    }
+    if count == 0 {
+        set_last_error("INVALID_CONFIG: count: must be greater than 0");
+        return std::ptr::null_mut();
+    }
+
+    let reranker = unsafe { &*reranker };
+
+    let query_str = unsafe {
+        match CStr::from_ptr(query).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: query: invalid UTF-8 encoding");
+                return std::ptr::null_mut();
+            }
+        }
+    };
+
+    // Convert C string array to Rust Vec<&str>
+    let documents_slice = unsafe { std::slice::from_raw_parts(documents, count) };
+    let mut document_strs = Vec::with_capacity(count);
+
+    for &document_ptr in documents_slice {
+        if document_ptr.is_null() {
+            set_last_error("INVALID_CONFIG: documents: array contains null pointer");
+            return std::ptr::null_mut();
+        }
+
+        let document_str = unsafe {
+            match CStr::from_ptr(document_ptr).to_str() {
+                Ok(s) => s,
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: documents: array contains invalid UTF-8");
+                    return std::ptr::null_mut();
+                }
+            }
+        };
+        document_strs.push(document_str);
+    }
+
+    match reranker.inner.rerank(query_str, &document_strs, top_k) {
+        Ok(ranked) => rerank_results_to_c(ranked),
+        Err(e) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+// ============================================================================
+// Image Embedding Functions
+// ============================================================================
+
+/// Embeds an image file with a vision embedder
+///
+/// # Parameters
+/// - embedder: Pointer to CEmbedder loaded with vision_embedder_from_pretrained_hf
+/// - image_path: Path to a PNG, JPEG, WebP, GIF or BMP file
+///
+/// # Returns
+/// - Pointer to CTextEmbedding on success (free with free_embedding)
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FFI_ERROR:" - Embedder pointer is null
+/// - "INVALID_CONFIG:" - Invalid argument, or not a vision embedder
+/// - "FILE_NOT_FOUND:" - Image does not exist
+/// - "UNSUPPORTED_FORMAT:" - Not a supported image type
+/// - "EMBEDDING_FAILED:" - Decoding or inference failed
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn embed_image_file(
+    embedder: *const CEmbedder,
+    image_path: *const c_char,
+) -> *mut CTextEmbedding {
+    clear_last_error();
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return std::ptr::null_mut();
+    }
+    if image_path.is_null() {
+        set_last_error("INVALID_CONFIG: image_path: cannot be null");
+        return std::ptr::null_mut();
+    }
+
+    let embedder = unsafe { &*embedder };
+
+    let path = unsafe {
+        match CStr::from_ptr(image_path).to_str() {
+            Ok(s) => PathBuf::from(s),
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: image_path: invalid UTF-8 encoding");
+                return std::ptr::null_mut();
+            }
+        }
+    };
+
+    match vision::embed_image_files(&embedder.inner, &[path]) {
+        Ok(mut vectors) => {
+            let vector = embedder.options.apply_to_vector(vectors.swap_remove(0));
+            Box::into_raw(Box::new(dense_to_c(vector)))
+        }
+        Err(e) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Embeds an encoded image held in memory with a vision embedder
+///
+/// # Parameters
+/// - embedder: Pointer to CEmbedder loaded with vision_embedder_from_pretrained_hf
+/// - data: Encoded image bytes (PNG, JPEG, WebP, GIF or BMP)
+/// - len: Number of bytes
+///
+/// # Returns
+/// - Pointer to CTextEmbedding on success (free with free_embedding)
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FFI_ERROR:" - Embedder pointer is null
+/// - "INVALID_CONFIG:" - Invalid argument, or not a vision embedder
+/// - "UNSUPPORTED_FORMAT:" - Bytes are not a recognised image encoding
+/// - "EMBEDDING_FAILED:" - Decoding or inference failed
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn embed_image_bytes(
+    embedder: *const CEmbedder,
+    data: *const u8,
+    len: usize,
+) -> *mut CTextEmbedding {
+    clear_last_error();
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return std::ptr::null_mut();
+    }
+    if data.is_null() {
+        set_last_error("INVALID_CONFIG: data: cannot be null");
+        return std::ptr::null_mut();
+    }
+    if len == 0 {
+        set_last_error("INVALID_CONFIG: len: must be greater than 0");
+        return std::ptr::null_mut();
+    }
+
+    let embedder = unsafe { &*embedder };
+    let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+
+    match vision::embed_image_bytes(&embedder.inner, bytes) {
+        Ok(vector) => {
+            let vector = embedder.options.apply_to_vector(vector);
+            Box::into_raw(Box::new(dense_to_c(vector)))
+        }
+        Err(e) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Embeds a batch of image files with a vision embedder
+///
+/// # Parameters
+/// - embedder: Pointer to CEmbedder loaded with vision_embedder_from_pretrained_hf
+/// - image_paths: Array of image path pointers
+/// - count: Number of paths
+///
+/// # Returns
+/// - Pointer to CTextEmbeddingBatch on success, in input order
+///   (free with free_embedding_batch)
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FFI_ERROR:" - Embedder pointer is null
+/// - "INVALID_CONFIG:" - Invalid argument, or not a vision embedder
+/// - "FILE_NOT_FOUND:" - An image does not exist
+/// - "UNSUPPORTED_FORMAT:" - A path is not a supported image type
+/// - "EMBEDDING_FAILED:" - Decoding or inference failed
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn embed_image_files_batch(
+    embedder: *const CEmbedder,
+    image_paths: *const *const c_char,
+    count: usize,
+) -> *mut CTextEmbeddingBatch {
+    clear_last_error();
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return std::ptr::null_mut();
+    }
+    if image_paths.is_null() {
+        set_last_error("INVALID_CONFIG: image_paths: cannot be null");
+        return std::ptr::null_mut();
+    }
+    if count == 0 {
+        set_last_error("INVALID_CONFIG: count: must be greater than 0");
+        return std::ptr::null_mut();
+    }
+
+    let embedder = unsafe { &*embedder };
+
+    // Convert C string array to Rust Vec<PathBuf>
+    let paths_slice = unsafe { std::slice::from_raw_parts(image_paths, count) };
+    let mut paths = Vec::with_capacity(count);
+
+    for &path_ptr in paths_slice {
+        if path_ptr.is_null() {
+            set_last_error("INVALID_CONFIG: image_paths: array contains null pointer");
+            return std::ptr::null_mut();
+        }
+
+        let path = unsafe {
+            match CStr::from_ptr(path_ptr).to_str() {
+                Ok(s) => PathBuf::from(s),
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: image_paths: array contains invalid UTF-8");
+                    return std::ptr::null_mut();
+                }
+            }
+        };
+        paths.push(path);
+    }
+
+    match vision::embed_image_files(&embedder.inner, &paths) {
+        Ok(vectors) => dense_vec_to_batch(
+            vectors
+                .into_iter()
+                .map(|v| embedder.options.apply_to_vector(v))
+                .collect(),
+        ),
+        Err(e) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Embeds a text query into the image space of a vision embedder
+///
+/// The result is directly comparable with embed_image_* vectors from the
+/// same embedder (text-to-image search).
+///
+/// # Parameters
+/// - embedder: Pointer to CEmbedder loaded with vision_embedder_from_pretrained_hf
+/// - text: Query text
+///
+/// # Returns
+/// - Pointer to CTextEmbedding on success (free with free_embedding)
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FFI_ERROR:" - Embedder pointer is null
+/// - "INVALID_CONFIG:" - Invalid argument, or not a vision embedder
+/// - "EMBEDDING_FAILED:" - Inference failed
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn embed_image_query(
+    embedder: *const CEmbedder,
+    text: *const c_char,
+) -> *mut CTextEmbedding {
+    clear_last_error();
+
+    if !ensure_outside_runtime() {
+        return std::ptr::null_mut();
+    }
+
+    // Validate inputs
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return std::ptr::null_mut();
+    }
+    if text.is_null() {
+        set_last_error("INVALID_CONFIG: text: cannot be null");
+        return std::ptr::null_mut();
+    }
+
+    let embedder = unsafe { &*embedder };
+
+    let text_str = unsafe {
+        match CStr::from_ptr(text).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: text: invalid UTF-8 encoding");
+                return std::ptr::null_mut();
+            }
+        }
+    };
+
+    match RUNTIME.block_on(vision::embed_text_query(&embedder.inner, text_str)) {
+        Ok(vector) => {
+            let vector = embedder.options.apply_to_vector(vector);
+            Box::into_raw(Box::new(dense_to_c(vector)))
+        }
+        Err(e) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+// ============================================================================
+// File/Directory Embedding Functions (Phase 3)
 // ============================================================================
 
 /// Embed a single file
 ///
-/// Returns a batch of CEmbedData, one per chunk.
+/// Returns a batch of CEmbedData, one per chunk.
+///
+/// # Parameters
+/// - embedder: Embedder handle
+/// - file_path: Path to file (C string)
+/// - config: Pointer to CTextEmbedConfig
+///
+/// # Returns
+/// - Pointer to CEmbedDataBatch on success
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FILE_NOT_FOUND:" - File does not exist
+/// - "UNSUPPORTED_FORMAT:" - File format not supported
+/// - "FILE_READ_ERROR:" - Permission or I/O error reading file
+/// - "EMBEDDING_FAILED:" - Embedding generation failed
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn embed_file(
+    embedder: *const CEmbedder,
+    file_path: *const c_char,
+    config: *const CTextEmbedConfig,
+) -> *mut CEmbedDataBatch {
+    clear_last_error();
+
+    if !ensure_outside_runtime() {
+        return std::ptr::null_mut();
+    }
+// Validate pointers
+        if embedder.is_null() {
+            set_last_error("FFI_ERROR: embedder pointer is null");
+            return std::ptr::null_mut();
+        }
+        if file_path.is_null() {
+            set_last_error("INVALID_CONFIG: file_path: cannot be null");
+            return std::ptr::null_mut();
+        }
+        if config.is_null() {
+            set_last_error("INVALID_CONFIG: config: cannot be null");
+            return std::ptr::null_mut();
+        }
+
+        let embedder_ref = unsafe { &*embedder };
+        let config_ref = unsafe { &*config };
+
+        // Convert C string to Rust Path
+        let file_path_str = unsafe {
+            match CStr::from_ptr(file_path).to_str() {
+                Ok(s) => s,
+                Err(_) => {
+                    set_last_error("INVALID_CONFIG: file_path: invalid UTF-8 encoding");
+                    return std::ptr::null_mut();
+                }
+            }
+        };
+
+        let path = PathBuf::from(file_path_str);
+
+        // Check if file exists
+        if !path.exists() {
+            set_last_error(&format!("FILE_NOT_FOUND: {}", file_path_str));
+            return std::ptr::null_mut();
+        }
+
+        // Build TextEmbedConfig from CTextEmbedConfig
+        let text_config = TextEmbedConfig {
+            chunk_size: Some(config_ref.chunk_size),
+            overlap_ratio: Some(config_ref.overlap_ratio),
+            batch_size: embedder_ref.options.resolve_batch_size(config_ref.batch_size),
+            buffer_size: Some(config_ref.buffer_size),
+            ..Default::default()
+        };
+
+        // Extract metadata manually before calling embed_file
+        // This ensures we have metadata even if upstream function fails to extract it
+        eprintln!("DEBUG: Attempting to embed file: {:?}", path);
+        let extracted_metadata = match TextLoader::get_metadata(&path) {
+            Ok(mut metadata) => {
+                eprintln!("DEBUG: Metadata extracted successfully");
+                eprintln!("  file_name: {:?}", metadata.get("file_name"));
+                eprintln!("  created: {:?}", metadata.get("created"));
+                eprintln!("  modified: {:?}", metadata.get("modified"));
+
+                // Transform metadata: rename "file_name" to "file_path" for Dart API compatibility
+                if let Some(file_name) = metadata.remove("file_name") {
+                    metadata.insert("file_path".to_string(), file_name);
+                    eprintln!("DEBUG: Transformed file_name -> file_path");
+                }
+
+                Some(metadata)
+            }
+            Err(e) => {
+                eprintln!("DEBUG: Failed to extract metadata: {}", e);
+                None
+            }
+        };
+
+        // Call Arc<Embedder>::embed_file() using RUNTIME.block_on(), unless
+        // the disk cache already holds this content
+        let file_cache = embedder_ref.file_cache();
+        let embed_result = RUNTIME.block_on(disk_cache::embed_file_cached(
+            file_cache.as_ref(),
+            &path,
+            &text_config,
+            embedder_ref
+                .inner
+                .embed_file(path.clone(), Some(&text_config), None),
+        ));
+
+        match embed_result {
+            Ok(Some(mut embed_data_vec)) => {
+                eprintln!("DEBUG: Got {} EmbedData items from upstream", embed_data_vec.len());
+                embed_data_vec = embedder_ref.options.apply_to_data(embed_data_vec);
+
+                // Inject metadata into any EmbedData items that have None metadata
+                // Also add chunk_index and transform file_name to file_path for all items
+                if let Some(ref metadata) = extracted_metadata {
+                    eprintln!("DEBUG: extracted_metadata is Some, checking {} items", embed_data_vec.len());
+                    for (i, embed_data) in embed_data_vec.iter_mut().enumerate() {
+                        if embed_data.metadata.is_none() {
+                            eprintln!("DEBUG: Item {}: metadata is None, injecting", i);
+                            let mut chunk_metadata = metadata.clone();
+                            chunk_metadata.insert("chunk_index".to_string(), i.to_string());
+                            embed_data.metadata = Some(chunk_metadata);
+                        } else {
+                            eprintln!("DEBUG: Item {}: metadata already present", i);
+                            // Add chunk_index and transform file_name->file_path in existing metadata
+                            if let Some(ref mut existing_metadata) = embed_data.metadata {
+                                existing_metadata.insert("chunk_index".to_string(), i.to_string());
+                                // Transform file_name to file_path if present
+                                if let Some(file_name) = existing_metadata.remove("file_name") {
+                                    existing_metadata.insert("file_path".to_string(), file_name);
+                                    eprintln!("DEBUG: Item {}: Transformed file_name -> file_path", i);
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    eprintln!("DEBUG: extracted_metadata is None, no injection possible");
+                }
+
+                // Convert Vec<EmbedData> to CEmbedDataBatch
+                match embed_data_vec_to_batch(embed_data_vec) {
+                    Ok(batch_ptr) => batch_ptr,
+                    Err(e) => {
+                        set_last_error(&e);
+                        std::ptr::null_mut()
+                    }
+                }
+            }
+            Ok(None) => {
+                set_last_error("EMBEDDING_FAILED: embed_file returned None");
+                std::ptr::null_mut()
+            }
+            Err(e) => {
+                let error_str = e.to_string().to_lowercase();
+                if error_str.contains("not found") || error_str.contains("no such file") {
+                    set_last_error(&format!("FILE_NOT_FOUND: {}", file_path_str));
+                } else if error_str.contains("unsupported") || error_str.contains("format") {
+                    set_last_error(&format!("UNSUPPORTED_FORMAT: {}", file_path_str));
+                } else if error_str.contains("permission") || error_str.contains("access denied") {
+                    set_last_error(&format!("FILE_READ_ERROR: {}", e));
+                } else {
+                    set_last_error(&format!("EMBEDDING_FAILED: {}", e));
+                }
+                std::ptr::null_mut()
+            }
+        }
+    
+}
+
+/// Embed directory with streaming callback
+///
+/// Files are embedded one at a time and the callback receives exactly
+/// `buffer_size` chunks at a time (whatever each file produced if 0), plus
+/// once for the final partial buffer. A file is embedded in one piece, so
+/// memory peaks at the largest file's chunks plus one partial buffer. The
+/// callback receives ownership of each batch and returns 0 to continue or
+/// non-zero to stop the walk.
+///
+/// When `image_embedder` is set, `.png/.jpg/.jpeg/.webp/.gif/.bmp` files
+/// are embedded with it (one entry per image) while text documents go
+/// through `embedder`; every entry's metadata then carries a `modality`
+/// field ("text" or "image").
+///
+/// # Parameters
+/// - embedder: Embedder handle
+/// - image_embedder: Vision embedder handle for images, or NULL for text only
+/// - directory_path: Path to directory (C string)
+/// - extensions: NULL-terminated array of extension strings, or NULL for the
+///   default text formats (PDF, TXT, MD, DOCX, HTML)
+/// - extensions_count: Number of extensions (0 if extensions is NULL)
+/// - config: Pointer to CTextEmbedConfig
+/// - callback: Function to call with each batch
+/// - callback_context: User data passed to callback
+///
+/// # Returns
+/// - 0 when every file was processed
+/// - 1 when the callback stopped the walk
+/// - -1 on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FILE_NOT_FOUND:" - Directory or a file does not exist
+/// - "UNSUPPORTED_FORMAT:" - A file format is not supported
+/// - "FILE_READ_ERROR:" - Permission or I/O error reading the tree
+/// - "EMBEDDING_FAILED:" - Embedding generation failed
+///
+/// # Safety
+/// The callback pointer and context must remain valid for the duration of this call.
+/// This function blocks until the walk finishes; the callback runs on the
+/// calling thread.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn embed_directory_stream(
+    embedder: *const CEmbedder,
+    image_embedder: *const CEmbedder,
+    directory_path: *const c_char,
+    extensions: *const *const c_char,
+    extensions_count: usize,
+    config: *const CTextEmbedConfig,
+    callback: StreamCallback,
+    callback_context: *mut c_void,
+) -> i32 {
+    clear_last_error();
+
+    if !ensure_outside_runtime() {
+        return -1;
+    }
+
+    // Validate pointers
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return -1;
+    }
+    if directory_path.is_null() {
+        set_last_error("INVALID_CONFIG: directory_path: cannot be null");
+        return -1;
+    }
+    if config.is_null() {
+        set_last_error("INVALID_CONFIG: config: cannot be null");
+        return -1;
+    }
+
+    let embedder_ref = unsafe { &*embedder };
+    let image_embedder_ref = unsafe { image_embedder.as_ref() };
+    let config_ref = unsafe { &*config };
+
+    if let Some(image_ref) = image_embedder_ref {
+        if !vision::is_vision(&image_ref.inner) {
+            set_last_error("INVALID_CONFIG: image_embedder: not a vision model");
+            return -1;
+        }
+    }
+
+    // Convert C string to Rust PathBuf
+    let dir_path_str = unsafe {
+        match CStr::from_ptr(directory_path).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: directory_path: invalid UTF-8 encoding");
+                return -1;
+            }
+        }
+    };
+
+    let dir_path = PathBuf::from(dir_path_str);
+
+    // Check if directory exists
+    if !dir_path.exists() {
+        set_last_error(&format!("FILE_NOT_FOUND: {}", dir_path_str));
+        return -1;
+    }
+
+    // Convert C string array to Vec<String> for extensions
+    let extensions_opt = if extensions.is_null() || extensions_count == 0 {
+        None
+    } else {
+        let ext_slice = unsafe { std::slice::from_raw_parts(extensions, extensions_count) };
+        let mut ext_vec = Vec::with_capacity(extensions_count);
+
+        for &ext_ptr in ext_slice {
+            if ext_ptr.is_null() {
+                set_last_error("INVALID_CONFIG: extensions: array contains null pointer");
+                return -1;
+            }
+
+            let ext_str = unsafe {
+                match CStr::from_ptr(ext_ptr).to_str() {
+                    Ok(s) => s.to_string(),
+                    Err(_) => {
+                        set_last_error("INVALID_CONFIG: extensions: invalid UTF-8");
+                        return -1;
+                    }
+                }
+            };
+            ext_vec.push(ext_str);
+        }
+
+        Some(ext_vec)
+    };
+
+    // Build TextEmbedConfig from CTextEmbedConfig
+    let text_config = TextEmbedConfig {
+        chunk_size: Some(config_ref.chunk_size),
+        overlap_ratio: Some(config_ref.overlap_ratio),
+        batch_size: embedder_ref
+            .options
+            .resolve_batch_size(config_ref.batch_size),
+        buffer_size: Some(config_ref.buffer_size),
+        ..Default::default()
+    };
+
+    let route_images = image_embedder_ref.is_some();
+    let plan = match directory::plan_directory(&dir_path, extensions_opt, route_images) {
+        Ok(plan) => plan,
+        Err(e) => {
+            set_last_error(&e);
+            return -1;
+        }
+    };
+
+    let image_route = image_embedder_ref.map(|image_ref| directory::ImageRoute {
+        embedder: &image_ref.inner,
+        options: image_ref.options,
+        batch_size: image_ref.options.resolve_batch_size(config_ref.batch_size),
+        slot: None,
+    });
+
+    // Hand each buffer to the callback; Dart owns (and frees) every batch
+    let sink = |items: Vec<EmbedData>| {
+        let batch_ptr = embed_data_vec_to_batch(items)?;
+        if (callback)(batch_ptr, callback_context) == 0 {
+            Ok(ControlFlow::Continue(()))
+        } else {
+            Ok(ControlFlow::Break(()))
+        }
+    };
+
+    let file_cache = embedder_ref.file_cache();
+    let result = RUNTIME.block_on(directory::stream_directory(
+        &plan,
+        &embedder_ref.inner,
+        embedder_ref.options,
+        &text_config,
+        file_cache.as_ref(),
+        image_route,
+        config_ref.buffer_size,
+        &directory::Progress::default(),
+        &CancellationToken::new(),
+        || std::future::ready(()),
+        sink,
+    ));
+
+    match result {
+        Ok(ControlFlow::Continue(())) => 0,
+        Ok(ControlFlow::Break(())) => 1,
+        Err(e) => {
+            set_last_error(&e);
+            -1
+        }
+    }
+}
+
+// ============================================================================
+// Embedding Cache
+// ============================================================================
+
+/// C-compatible cache statistics, returned by embedder_cache_stats
+#[repr(C)]
+pub struct CCacheStats {
+    /// 1 = cache enabled, 0 = no cache, -1 = error (check get_last_error)
+    pub status: i32,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+    pub bytes: usize,
+    /// Configured entry limit (0 = unbounded)
+    pub max_entries: usize,
+    /// Configured byte limit (0 = unbounded)
+    pub max_bytes: usize,
+}
+
+/// Enables, resizes or disables the in-memory embedding cache of an embedder
+///
+/// With a cache, embed_text, embed_texts_batch, start_embed_text and
+/// start_embed_texts_batch look each text up by model id, revision, dtype and
+/// whitespace-normalized text first; batch calls only send the misses to the
+/// model. Least recently used entries are evicted beyond the limits. Calling
+/// this again replaces the cache with a new, empty one.
 ///
 /// # Parameters
-/// - embedder: Embedder handle
-/// - file_path: Path to file (C string)
-/// - config: Pointer to CTextEmbedConfig
+/// - embedder: Pointer to CEmbedder
+/// - max_entries: Maximum number of cached vectors (0 = no entry limit)
+/// - max_bytes: Maximum bytes of keys and vectors (0 = no byte limit).
+///   Both limits 0 disables the cache
 ///
 /// # Returns
-/// - Pointer to CEmbedDataBatch on success
-/// - NULL on failure (check get_last_error)
+/// 0 on success, -1 on failure (check get_last_error)
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn embedder_set_cache(
+    embedder: *const CEmbedder,
+    max_entries: usize,
+    max_bytes: usize,
+) -> i32 {
+    clear_last_error();
+
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return -1;
+    }
+
+    let embedder = unsafe { &*embedder };
+    let cache = if max_entries == 0 && max_bytes == 0 {
+        None
+    } else {
+        Some(Arc::new(EmbeddingCache::new(
+            &embedder.model,
+            max_entries,
+            max_bytes,
+        )))
+    };
+    *embedder.cache.write().unwrap() = cache;
+    0
+}
+
+/// Reads the hit/miss counters and size of an embedder's cache
+///
+/// # Parameters
+/// - embedder: Pointer to CEmbedder
+///
+/// # Returns
+/// CCacheStats; all counters are 0 when no cache is enabled
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn embedder_cache_stats(embedder: *const CEmbedder) -> CCacheStats {
+    clear_last_error();
+
+    let mut result = CCacheStats {
+        status: -1,
+        hits: 0,
+        misses: 0,
+        evictions: 0,
+        entries: 0,
+        bytes: 0,
+        max_entries: 0,
+        max_bytes: 0,
+    };
+
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return result;
+    }
+
+    result.status = 0;
+    if let Some(cache) = unsafe { &*embedder }.cache() {
+        let stats = cache.stats();
+        result.status = 1;
+        result.hits = stats.hits;
+        result.misses = stats.misses;
+        result.evictions = stats.evictions;
+        result.entries = stats.entries;
+        result.bytes = stats.bytes;
+        result.max_entries = cache.max_entries();
+        result.max_bytes = cache.max_bytes();
+    }
+    result
+}
+
+/// Opens (or creates) the persistent embedding cache
+///
+/// The cache is a single file (`embeddings.cache`) under `directory`, shared
+/// by every embedder. embed_text, embed_texts_batch, embed_file,
+/// embed_directory_stream and their async variants consult it before
+/// running inference; records are keyed by a hash of the content (text, or
+/// file bytes plus chunking settings) and the model fingerprint (id,
+/// revision, dtype and output options). Least recently used records are
+/// evicted beyond `max_bytes`. Opening again replaces the current cache
+/// and closes the previous one, including for operations already running;
+/// if opening fails, no disk cache is left open.
+///
+/// # Parameters
+/// - directory: Directory holding the cache file (created if missing)
+/// - max_bytes: Size limit of the live records (0 = 512 MiB)
+///
+/// # Returns
+/// 0 on success, -1 on failure (check get_last_error)
 ///
 /// # Error Prefixes
-/// - "FILE_NOT_FOUND:" - File does not exist
-/// - "UNSUPPORTED_FORMAT:" - File format not supported
-/// - "FILE_READ_ERROR:" - Permission or I/O error reading file
-/// - "EMBEDDING_FAILED:" - Embedding generation failed
+/// - "INVALID_CONFIG:" - Invalid argument
+/// - "FILE_READ_ERROR:" - Directory or cache file cannot be opened, or the
+///   directory is already in use by another open cache
 #[no_mangle]
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
-pub extern "C" fn embed_file(
-    embedder: *const CEmbedder,
-    file_path: *const c_char,
-    config: *const CTextEmbedConfig,
-) -> *mut CEmbedDataBatch {
+pub extern "C" fn cache_open(directory: *const c_char, max_bytes: u64) -> i32 {
     clear_last_error();
-// Validate pointers
-        if embedder.is_null() {
-            set_last_error("FFI_ERROR: embedder pointer is null");
-            return std::ptr::null_mut();
+
+    if directory.is_null() {
+        set_last_error("INVALID_CONFIG: directory: cannot be null");
+        return -1;
+    }
+
+    let directory_str = unsafe {
+        match CStr::from_ptr(directory).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: directory: invalid UTF-8 encoding");
+                return -1;
+            }
         }
-        if file_path.is_null() {
-            set_last_error("INVALID_CONFIG: file_path: cannot be null");
+    };
+
+    let max_bytes = if max_bytes == 0 {
+        disk_cache::DEFAULT_MAX_BYTES
+    } else {
+        max_bytes
+    };
+
+    // Release the current store first so its file is not open twice
+    disk_cache::install(None);
+    match DiskCache::open(&PathBuf::from(directory_str), max_bytes) {
+        Ok(cache) => {
+            disk_cache::install(Some(Arc::new(cache)));
+            0
+        }
+        Err(e) => {
+            set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Closes the persistent embedding cache (no-op if none is open)
+#[no_mangle]
+pub extern "C" fn cache_close() {
+    disk_cache::install(None);
+}
+
+/// Removes every record from the persistent embedding cache
+///
+/// # Returns
+/// 0 on success, -1 on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "INVALID_CONFIG:" - No cache is open
+/// - "FILE_READ_ERROR:" - Cache file cannot be truncated
+#[no_mangle]
+pub extern "C" fn cache_clear() -> i32 {
+    clear_last_error();
+
+    let Some(cache) = disk_cache::current() else {
+        set_last_error("INVALID_CONFIG: cache: no disk cache is open");
+        return -1;
+    };
+    match cache.clear() {
+        Ok(()) => 0,
+        Err(e) => {
+            set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Rewrites the persistent embedding cache without its evicted records
+///
+/// Compaction also runs automatically once evicted records take more space
+/// than live ones; call this to reclaim space right away.
+///
+/// # Returns
+/// 0 on success, -1 on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "INVALID_CONFIG:" - No cache is open
+/// - "FILE_READ_ERROR:" - Cache file cannot be rewritten
+#[no_mangle]
+pub extern "C" fn cache_compact() -> i32 {
+    clear_last_error();
+
+    let Some(cache) = disk_cache::current() else {
+        set_last_error("INVALID_CONFIG: cache: no disk cache is open");
+        return -1;
+    };
+    match cache.compact() {
+        Ok(()) => 0,
+        Err(e) => {
+            set_last_error(&e);
+            -1
+        }
+    }
+}
+
+// ============================================================================
+// Vector Index Functions
+// ============================================================================
+
+/// Creates an empty flat vector index
+///
+/// Search is exact: every query is compared with every stored vector. For
+/// large collections see vector_index_create_hnsw.
+///
+/// # Parameters
+/// - dimension: Length of every vector in the index
+/// - metric: 0 = cosine, 1 = dot product, 2 = Euclidean (L2)
+///
+/// # Returns
+/// - Pointer to CVectorIndex on success (free with vector_index_free)
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "INVALID_CONFIG:" - Invalid argument
+#[no_mangle]
+pub extern "C" fn vector_index_create(dimension: usize, metric: i32) -> *mut CVectorIndex {
+    clear_last_error();
+
+    let metric = match index_metric(dimension, metric) {
+        Ok(metric) => metric,
+        Err(e) => {
+            set_last_error(&e);
             return std::ptr::null_mut();
         }
-        if config.is_null() {
-            set_last_error("INVALID_CONFIG: config: cannot be null");
+    };
+
+    let flat = index::FlatIndex::new(dimension, metric);
+    Box::into_raw(Box::new(CVectorIndex {
+        inner: RwLock::new(index::VectorIndex::Flat(flat)),
+    }))
+}
+
+/// Creates an empty approximate (HNSW) vector index
+///
+/// Searches walk a layered proximity graph instead of scanning every vector,
+/// so they stay fast on large collections at the cost of occasionally
+/// missing a true neighbour. Inserts and removals are incremental.
+///
+/// # Parameters
+/// - dimension: Length of every vector in the index
+/// - metric: 0 = cosine, 1 = dot product, 2 = Euclidean (L2)
+/// - m: Links per node (0 = default of 16); higher improves recall and
+///   uses more memory
+/// - ef_construction: Candidates considered per insert (0 = default of
+///   200); higher builds a better graph more slowly
+/// - ef_search: Candidates considered per search (0 = default of 64);
+///   higher improves recall and slows searches. Can be changed later with
+///   vector_index_set_ef_search
+///
+/// # Returns
+/// - Pointer to CVectorIndex on success (free with vector_index_free)
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "INVALID_CONFIG:" - Invalid argument
+#[no_mangle]
+pub extern "C" fn vector_index_create_hnsw(
+    dimension: usize,
+    metric: i32,
+    m: usize,
+    ef_construction: usize,
+    ef_search: usize,
+) -> *mut CVectorIndex {
+    clear_last_error();
+
+    let metric = match index_metric(dimension, metric) {
+        Ok(metric) => metric,
+        Err(e) => {
+            set_last_error(&e);
             return std::ptr::null_mut();
         }
+    };
 
-        let embedder_ref = unsafe { &*embedder };
-        let config_ref = unsafe { &*config };
+    let defaults = index::HnswParams::default();
+    let params = index::HnswParams {
+        m: if m == 0 { defaults.m } else { m },
+        ef_construction: if ef_construction == 0 {
+            defaults.ef_construction
+        } else {
+            ef_construction
+        },
+        ef_search: if ef_search == 0 {
+            defaults.ef_search
+        } else {
+            ef_search
+        },
+    };
 
-        // Convert C string to Rust Path
-        let file_path_str = unsafe {
-            match CStr::from_ptr(file_path).to_str() {
+    match index::HnswIndex::new(dimension, metric, params) {
+        Ok(hnsw) => Box::into_raw(Box::new(CVectorIndex {
+            inner: RwLock::new(index::VectorIndex::Hnsw(hnsw)),
+        })),
+        Err(e) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Changes the search candidate list size of an HNSW index
+///
+/// # Parameters
+/// - index: Pointer to CVectorIndex created by vector_index_create_hnsw
+/// - ef_search: Candidates considered per search (must be greater than 0)
+///
+/// # Returns
+/// 0 on success, -1 on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FFI_ERROR:" - Index pointer is null
+/// - "INVALID_CONFIG:" - Invalid argument, or the index is not an HNSW index
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn vector_index_set_ef_search(index: *const CVectorIndex, ef_search: usize) -> i32 {
+    clear_last_error();
+
+    if index.is_null() {
+        set_last_error("FFI_ERROR: index pointer is null");
+        return -1;
+    }
+    if ef_search == 0 {
+        set_last_error("INVALID_CONFIG: ef_search: must be greater than 0");
+        return -1;
+    }
+
+    let index = unsafe { &*index };
+    match &mut *index.inner.write().unwrap() {
+        index::VectorIndex::Hnsw(hnsw) => {
+            hnsw.set_ef_search(ef_search);
+            0
+        }
+        index::VectorIndex::Flat(_) => {
+            set_last_error("INVALID_CONFIG: index: ef_search only applies to HNSW indexes");
+            -1
+        }
+    }
+}
+
+/// Adds one vector to an index, replacing any entry with the same id
+///
+/// # Parameters
+/// - index: Pointer to CVectorIndex
+/// - id: Caller-chosen id, returned by searches
+/// - values: Vector of `len` floats (e.g. CTextEmbedding.values)
+/// - len: Number of floats; must equal the index dimension
+/// - metadata_json: JSON returned with search hits, or NULL for none
+///
+/// # Returns
+/// 0 on success, -1 on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FFI_ERROR:" - Index pointer is null
+/// - "INVALID_CONFIG:" - Invalid argument or wrong dimension
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn vector_index_add(
+    index: *const CVectorIndex,
+    id: u64,
+    values: *const f32,
+    len: usize,
+    metadata_json: *const c_char,
+) -> i32 {
+    clear_last_error();
+
+    if index.is_null() {
+        set_last_error("FFI_ERROR: index pointer is null");
+        return -1;
+    }
+    if values.is_null() {
+        set_last_error("INVALID_CONFIG: values: cannot be null");
+        return -1;
+    }
+
+    let metadata = if metadata_json.is_null() {
+        None
+    } else {
+        let json = unsafe {
+            match CStr::from_ptr(metadata_json).to_str() {
                 Ok(s) => s,
                 Err(_) => {
-                    set_last_error("INVALID_CONFIG: file_path: invalid UTF-8 encoding");
-                    return std::ptr::null_mut();
+                    set_last_error("INVALID_CONFIG: metadata_json: invalid UTF-8 encoding");
+                    return -1;
                 }
             }
         };
-
-        let path = PathBuf::from(file_path_str);
-
-        // Check if file exists
-        if !path.exists() {
-            set_last_error(&format!("FILE_NOT_FOUND: {}", file_path_str));
-            return std::ptr::null_mut();
+        if serde_json::from_str::<serde_json::Value>(json).is_err() {
+            set_last_error("INVALID_CONFIG: metadata_json: not valid JSON");
+            return -1;
         }
+        Some(json.to_string())
+    };
 
-        // Build TextEmbedConfig from CTextEmbedConfig
-        let text_config = TextEmbedConfig {
-            chunk_size: Some(config_ref.chunk_size),
-            overlap_ratio: Some(config_ref.overlap_ratio),
-            batch_size: Some(config_ref.batch_size),
-            buffer_size: Some(config_ref.buffer_size),
-            ..Default::default()
-        };
-
-        // Extract metadata manually before calling embed_file
-        // This ensures we have metadata even if upstream function fails to extract it
-        eprintln!("DEBUG: Attempting to embed file: {:?}", path);
-        let extracted_metadata = match TextLoader::get_metadata(&path) {
-            Ok(mut metadata) => {
-                eprintln!("DEBUG: Metadata extracted successfully");
-                eprintln!("  file_name: {:?}", metadata.get("file_name"));
-                eprintln!("  created: {:?}", metadata.get("created"));
-                eprintln!("  modified: {:?}", metadata.get("modified"));
+    let index = unsafe { &*index };
+    let vector = unsafe { std::slice::from_raw_parts(values, len) };
+    match index.inner.write().unwrap().add(id, vector, metadata) {
+        Ok(()) => 0,
+        Err(e) => {
+            set_last_error(&e);
+            -1
+        }
+    }
+}
 
-                // Transform metadata: rename "file_name" to "file_path" for Dart API compatibility
-                if let Some(file_name) = metadata.remove("file_name") {
-                    metadata.insert("file_path".to_string(), file_name);
-                    eprintln!("DEBUG: Transformed file_name -> file_path");
-                }
+/// Adds every embedding of a text batch to an index, without metadata
+///
+/// The batch is read in place, so results of embed_texts_batch (or its
+/// async variant) can be indexed without copying them into Dart. Nothing is
+/// added if any embedding has the wrong dimension. The batch is not freed.
+///
+/// # Parameters
+/// - index: Pointer to CVectorIndex
+/// - ids: Array of `batch.count` ids, one per embedding
+/// - batch: Pointer to CTextEmbeddingBatch
+///
+/// # Returns
+/// 0 on success, -1 on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FFI_ERROR:" - Index pointer is null
+/// - "INVALID_CONFIG:" - Invalid argument or wrong dimension
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn vector_index_add_text_batch(
+    index: *const CVectorIndex,
+    ids: *const u64,
+    batch: *const CTextEmbeddingBatch,
+) -> i32 {
+    clear_last_error();
 
-                Some(metadata)
-            }
-            Err(e) => {
-                eprintln!("DEBUG: Failed to extract metadata: {}", e);
-                None
-            }
-        };
+    if index.is_null() {
+        set_last_error("FFI_ERROR: index pointer is null");
+        return -1;
+    }
+    if batch.is_null() {
+        set_last_error("INVALID_CONFIG: batch: cannot be null");
+        return -1;
+    }
 
-        // Call Arc<Embedder>::embed_file() using RUNTIME.block_on()
-        let embed_result = RUNTIME.block_on(async {
-            embedder_ref.inner.embed_file(path, Some(&text_config), None).await
-        });
+    let index = unsafe { &*index };
+    let batch = unsafe { &*batch };
+    if batch.count == 0 {
+        return 0;
+    }
+    if ids.is_null() || batch.embeddings.is_null() {
+        set_last_error("INVALID_CONFIG: ids: cannot be null");
+        return -1;
+    }
 
-        match embed_result {
-            Ok(Some(mut embed_data_vec)) => {
-                eprintln!("DEBUG: Got {} EmbedData items from upstream", embed_data_vec.len());
+    let ids = unsafe { std::slice::from_raw_parts(ids, batch.count) };
+    let embeddings = unsafe { std::slice::from_raw_parts(batch.embeddings, batch.count) };
 
-                // Inject metadata into any EmbedData items that have None metadata
-                // Also add chunk_index and transform file_name to file_path for all items
-                if let Some(ref metadata) = extracted_metadata {
-                    eprintln!("DEBUG: extracted_metadata is Some, checking {} items", embed_data_vec.len());
-                    for (i, embed_data) in embed_data_vec.iter_mut().enumerate() {
-                        if embed_data.metadata.is_none() {
-                            eprintln!("DEBUG: Item {}: metadata is None, injecting", i);
-                            let mut chunk_metadata = metadata.clone();
-                            chunk_metadata.insert("chunk_index".to_string(), i.to_string());
-                            embed_data.metadata = Some(chunk_metadata);
-                        } else {
-                            eprintln!("DEBUG: Item {}: metadata already present", i);
-                            // Add chunk_index and transform file_name->file_path in existing metadata
-                            if let Some(ref mut existing_metadata) = embed_data.metadata {
-                                existing_metadata.insert("chunk_index".to_string(), i.to_string());
-                                // Transform file_name to file_path if present
-                                if let Some(file_name) = existing_metadata.remove("file_name") {
-                                    existing_metadata.insert("file_path".to_string(), file_name);
-                                    eprintln!("DEBUG: Item {}: Transformed file_name -> file_path", i);
-                                }
-                            }
-                        }
-                    }
-                } else {
-                    eprintln!("DEBUG: extracted_metadata is None, no injection possible");
-                }
+    let mut inner = index.inner.write().unwrap();
+    let dimension = inner.dimension();
+    let mut vectors = Vec::with_capacity(embeddings.len());
+    for (i, embedding) in embeddings.iter().enumerate() {
+        if embedding.values.is_null() || embedding.len != dimension {
+            set_last_error(&format!(
+                "INVALID_CONFIG: batch: embedding {} has dimension {}, expected {}",
+                i, embedding.len, dimension
+            ));
+            return -1;
+        }
+        vectors.push(unsafe { std::slice::from_raw_parts(embedding.values, embedding.len) });
+    }
 
-                // Convert Vec<EmbedData> to CEmbedDataBatch
-                match embed_data_vec_to_batch(embed_data_vec) {
-                    Ok(batch_ptr) => batch_ptr,
-                    Err(e) => {
-                        set_last_error(&e);
-                        std::ptr::null_mut()
-                    }
-                }
-            }
-            Ok(None) => {
-                set_last_error("EMBEDDING_FAILED: embed_file returned None");
-                std::ptr::null_mut()
-            }
-            Err(e) => {
-                let error_str = e.to_string().to_lowercase();
-                if error_str.contains("not found") || error_str.contains("no such file") {
-                    set_last_error(&format!("FILE_NOT_FOUND: {}", file_path_str));
-                } else if error_str.contains("unsupported") || error_str.contains("format") {
-                    set_last_error(&format!("UNSUPPORTED_FORMAT: {}", file_path_str));
-                } else if error_str.contains("permission") || error_str.contains("access denied") {
-                    set_last_error(&format!("FILE_READ_ERROR: {}", e));
-                } else {
-                    set_last_error(&format!("EMBEDDING_FAILED: {}", e));
-                }
-                std::ptr::null_mut()
-            }
+    for (&id, vector) in ids.iter().zip(vectors) {
+        if let Err(e) = inner.add(id, vector, None) {
+            set_last_error(&e);
+            return -1;
         }
-    
+    }
+    0
 }
 
-/// Embed directory with streaming callback
+/// Adds every item of an embed data batch to an index
 ///
-/// Calls callback multiple times with batches of embeddings.
-/// Returns 0 on success, -1 on failure.
+/// Each item's `text_and_metadata_json` is stored as its metadata. The batch
+/// is read in place, so chunks from embed_file or embed_directory_stream can
+/// be indexed without copying them into Dart. Nothing is added if any item
+/// has the wrong dimension, is a multi-vector embedding or has metadata that
+/// is not valid UTF-8. The batch is not freed.
 ///
 /// # Parameters
-/// - embedder: Embedder handle
-/// - directory_path: Path to directory (C string)
-/// - extensions: NULL-terminated array of extension strings, or NULL for all files
-/// - extensions_count: Number of extensions (0 if extensions is NULL)
-/// - config: Pointer to CTextEmbedConfig
-/// - callback: Function to call with each batch
-/// - callback_context: User data passed to callback
+/// - index: Pointer to CVectorIndex
+/// - ids: Array of `batch.count` ids, one per item
+/// - batch: Pointer to CEmbedDataBatch
 ///
 /// # Returns
-/// - 0 on success
-/// - -1 on failure (check get_last_error)
+/// 0 on success, -1 on failure (check get_last_error)
 ///
-/// # Safety
-/// The callback pointer and context must remain valid for the duration of this call.
-/// This function blocks until all files are processed.
+/// # Error Prefixes
+/// - "FFI_ERROR:" - Index pointer is null
+/// - "INVALID_CONFIG:" - Invalid argument, wrong dimension, multi-vector item
+///   or invalid UTF-8 metadata
 #[no_mangle]
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
-pub extern "C" fn embed_directory_stream(
-    embedder: *const CEmbedder,
-    directory_path: *const c_char,
-    extensions: *const *const c_char,
-    extensions_count: usize,
-    config: *const CTextEmbedConfig,
-    callback: StreamCallback,
-    callback_context: *mut c_void,
+pub extern "C" fn vector_index_add_embed_data_batch(
+    index: *const CVectorIndex,
+    ids: *const u64,
+    batch: *const CEmbedDataBatch,
 ) -> i32 {
     clear_last_error();
-// Validate pointers
-        if embedder.is_null() {
-            set_last_error("FFI_ERROR: embedder pointer is null");
-            return -1;
-        }
-        if directory_path.is_null() {
-            set_last_error("INVALID_CONFIG: directory_path: cannot be null");
+
+    if index.is_null() {
+        set_last_error("FFI_ERROR: index pointer is null");
+        return -1;
+    }
+    if batch.is_null() {
+        set_last_error("INVALID_CONFIG: batch: cannot be null");
+        return -1;
+    }
+
+    let index = unsafe { &*index };
+    let batch = unsafe { &*batch };
+    if batch.count == 0 {
+        return 0;
+    }
+    if ids.is_null() || batch.items.is_null() {
+        set_last_error("INVALID_CONFIG: ids: cannot be null");
+        return -1;
+    }
+
+    let ids = unsafe { std::slice::from_raw_parts(ids, batch.count) };
+    let items = unsafe { std::slice::from_raw_parts(batch.items, batch.count) };
+
+    let mut inner = index.inner.write().unwrap();
+    let dimension = inner.dimension();
+    let mut entries = Vec::with_capacity(items.len());
+    for (i, item) in items.iter().enumerate() {
+        if item.embedding_rows != 1 {
+            set_last_error(&format!(
+                "INVALID_CONFIG: batch: item {} is a multi-vector embedding ({} rows)",
+                i, item.embedding_rows
+            ));
             return -1;
         }
-        if config.is_null() {
-            set_last_error("INVALID_CONFIG: config: cannot be null");
+        if item.embedding_values.is_null() || item.embedding_len != dimension {
+            set_last_error(&format!(
+                "INVALID_CONFIG: batch: item {} has dimension {}, expected {}",
+                i, item.embedding_len, dimension
+            ));
             return -1;
         }
-
-        let embedder_ref = unsafe { &*embedder };
-        let config_ref = unsafe { &*config };
-
-        // Convert C string to Rust PathBuf
-        let dir_path_str = unsafe {
-            match CStr::from_ptr(directory_path).to_str() {
-                Ok(s) => s,
+        let vector =
+            unsafe { std::slice::from_raw_parts(item.embedding_values, item.embedding_len) };
+        let metadata = if item.text_and_metadata_json.is_null() {
+            None
+        } else {
+            match unsafe { CStr::from_ptr(item.text_and_metadata_json) }.to_str() {
+                Ok(json) => Some(json.to_string()),
                 Err(_) => {
-                    set_last_error("INVALID_CONFIG: directory_path: invalid UTF-8 encoding");
+                    set_last_error(&format!(
+                        "INVALID_CONFIG: batch: item {} metadata is not valid UTF-8",
+                        i
+                    ));
                     return -1;
                 }
             }
         };
+        entries.push((vector, metadata));
+    }
 
-        let dir_path = PathBuf::from(dir_path_str);
-
-        // Check if directory exists
-        if !dir_path.exists() {
-            set_last_error(&format!("FILE_NOT_FOUND: {}", dir_path_str));
+    for (&id, (vector, metadata)) in ids.iter().zip(entries) {
+        if let Err(e) = inner.add(id, vector, metadata) {
+            set_last_error(&e);
             return -1;
         }
+    }
+    0
+}
 
-        // Convert C string array to Vec<String> for extensions
-        let extensions_opt = if extensions.is_null() || extensions_count == 0 {
-            None
-        } else {
-            let ext_slice = unsafe { std::slice::from_raw_parts(extensions, extensions_count) };
-            let mut ext_vec = Vec::with_capacity(extensions_count);
+/// Removes the entry stored under an id
+///
+/// # Returns
+/// 1 if an entry was removed, 0 if there was none, -1 on failure (check
+/// get_last_error)
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn vector_index_remove(index: *const CVectorIndex, id: u64) -> i32 {
+    clear_last_error();
 
-            for &ext_ptr in ext_slice {
-                if ext_ptr.is_null() {
-                    set_last_error("INVALID_CONFIG: extensions: array contains null pointer");
-                    return -1;
-                }
+    if index.is_null() {
+        set_last_error("FFI_ERROR: index pointer is null");
+        return -1;
+    }
 
-                let ext_str = unsafe {
-                    match CStr::from_ptr(ext_ptr).to_str() {
-                        Ok(s) => s.to_string(),
-                        Err(_) => {
-                            set_last_error("INVALID_CONFIG: extensions: invalid UTF-8");
-                            return -1;
-                        }
-                    }
-                };
-                ext_vec.push(ext_str);
-            }
+    let index = unsafe { &*index };
+    if index.inner.write().unwrap().remove(id) {
+        1
+    } else {
+        0
+    }
+}
 
-            Some(ext_vec)
-        };
+/// Number of entries in an index (0 if the pointer is null)
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn vector_index_count(index: *const CVectorIndex) -> usize {
+    if index.is_null() {
+        return 0;
+    }
+    unsafe { &*index }.inner.read().unwrap().len()
+}
 
-        // Build TextEmbedConfig from CTextEmbedConfig
-        let text_config = TextEmbedConfig {
-            chunk_size: Some(config_ref.chunk_size),
-            overlap_ratio: Some(config_ref.overlap_ratio),
-            batch_size: Some(config_ref.batch_size),
-            buffer_size: Some(config_ref.buffer_size),
-            ..Default::default()
-        };
+/// Finds the entries closest to a query vector
+///
+/// # Parameters
+/// - index: Pointer to CVectorIndex
+/// - query: Query vector of `len` floats (e.g. CTextEmbedding.values from
+///   embed_text)
+/// - len: Number of floats; must equal the index dimension
+/// - k: Maximum number of hits
+///
+/// # Returns
+/// - Pointer to CSearchResults, closest first (free with free_search_results)
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FFI_ERROR:" - Index pointer is null
+/// - "INVALID_CONFIG:" - Invalid argument or wrong dimension
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn vector_index_search(
+    index: *const CVectorIndex,
+    query: *const f32,
+    len: usize,
+    k: usize,
+) -> *mut CSearchResults {
+    clear_last_error();
 
-        // Call embed_directory_stream without adapter to collect all results
-        // When adapter is None, the function returns all embeddings in the result
-        eprintln!("DEBUG: Embedding directory: {:?}", dir_path);
-        eprintln!("DEBUG: Extensions filter: {:?}", extensions_opt);
-        eprintln!("DEBUG: Config - chunk_size: {}, overlap_ratio: {}",
-                  config_ref.chunk_size, config_ref.overlap_ratio);
-
-        let embed_result = RUNTIME.block_on(async {
-            embedder_ref.inner.embed_directory_stream(
-                dir_path,
-                extensions_opt,
-                Some(&text_config),
-                None,  // No adapter - collect all results instead of streaming
-            ).await
-        });
+    if index.is_null() {
+        set_last_error("FFI_ERROR: index pointer is null");
+        return std::ptr::null_mut();
+    }
+    if query.is_null() {
+        set_last_error("INVALID_CONFIG: query: cannot be null");
+        return std::ptr::null_mut();
+    }
+    if k == 0 {
+        set_last_error("INVALID_CONFIG: k: must be greater than 0");
+        return std::ptr::null_mut();
+    }
+
+    let index = unsafe { &*index };
+    let query = unsafe { std::slice::from_raw_parts(query, len) };
+    match index.inner.read().unwrap().search(query, k) {
+        Ok(hits) => search_hits_to_c(hits),
+        Err(e) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
+}
 
-        eprintln!("DEBUG: embed_directory_stream completed");
+/// Saves an index to a single file
+///
+/// The file records the format version, the model id, revision and dtype of
+/// `embedder` (which should be the embedder that produced the vectors) and
+/// its output-affecting options (normalize, pooling, max_sequence_length),
+/// the dimension and metric, and every entry with its metadata. HNSW indexes
+/// also store their graph, so loading does not rebuild it. An existing file
+/// is replaced.
+///
+/// # Parameters
+/// - index: Pointer to CVectorIndex
+/// - embedder: Pointer to the CEmbedder the vectors were embedded with
+/// - path: Destination file path
+///
+/// # Returns
+/// 0 on success, -1 on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FFI_ERROR:" - Index or embedder pointer is null
+/// - "INVALID_CONFIG:" - Invalid argument
+/// - "FILE_READ_ERROR:" - File cannot be written
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn vector_index_save(
+    index: *const CVectorIndex,
+    embedder: *const CEmbedder,
+    path: *const c_char,
+) -> i32 {
+    clear_last_error();
 
-        match embed_result {
-            Ok(Some(mut embed_data_vec)) => {
-                eprintln!("DEBUG: Got {} embeddings from directory", embed_data_vec.len());
-
-                // Log first few results if available
-                for (i, data) in embed_data_vec.iter().take(3).enumerate() {
-                    if let Some(ref metadata) = data.metadata {
-                        eprintln!("DEBUG: Result {}: metadata present with {} keys",
-                                 i, metadata.len());
-                    } else {
-                        eprintln!("DEBUG: Result {}: NO metadata", i);
-                    }
-                }
+    if index.is_null() {
+        set_last_error("FFI_ERROR: index pointer is null");
+        return -1;
+    }
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return -1;
+    }
+    if path.is_null() {
+        set_last_error("INVALID_CONFIG: path: cannot be null");
+        return -1;
+    }
 
-                // Transform file_name to file_path in all metadata
-                for embed_data in embed_data_vec.iter_mut() {
-                    if let Some(ref mut metadata) = embed_data.metadata {
-                        if let Some(file_name) = metadata.remove("file_name") {
-                            metadata.insert("file_path".to_string(), file_name);
-                        }
-                    }
-                }
+    let path_str = unsafe {
+        match CStr::from_ptr(path).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: path: invalid UTF-8 encoding");
+                return -1;
+            }
+        }
+    };
 
-                // Convert Vec<EmbedData> to CEmbedDataBatch
-                match embed_data_vec_to_batch(embed_data_vec) {
-                    Ok(batch_ptr) => {
-                        eprintln!("DEBUG: Converted to CEmbedDataBatch successfully");
-                        eprintln!("DEBUG: batch.count = {}", unsafe { (*batch_ptr).count });
-                        eprintln!("DEBUG: Calling Dart callback...");
+    let index = unsafe { &*index };
+    let embedder = unsafe { &*embedder };
+    let inner = index.inner.read().unwrap();
+    match inner.save(&PathBuf::from(path_str), &embedder.model, &embedder.options) {
+        Ok(()) => 0,
+        Err(e) => {
+            set_last_error(&e);
+            -1
+        }
+    }
+}
 
-                        // Call the callback once with all results
-                        (callback)(batch_ptr, callback_context);
+/// Loads an index saved by vector_index_save
+///
+/// Loading is refused unless `embedder` was loaded with the same model id,
+/// revision, dtype, normalize, pooling and max_sequence_length as the
+/// embedder the index was saved with, so queries are never compared with
+/// vectors produced differently.
+///
+/// # Parameters
+/// - path: File written by vector_index_save
+/// - embedder: Pointer to the CEmbedder that will produce query vectors
+///
+/// # Returns
+/// - Pointer to CVectorIndex on success (free with vector_index_free)
+/// - NULL on failure (check get_last_error)
+///
+/// # Error Prefixes
+/// - "FFI_ERROR:" - Embedder pointer is null
+/// - "INVALID_CONFIG:" - Invalid argument
+/// - "FILE_NOT_FOUND:" - File does not exist
+/// - "FILE_READ_ERROR:" - File cannot be read, has an unsupported version
+///   or is corrupt
+/// - "MODEL_MISMATCH:" - Index was built with a different model, revision,
+///   dtype or output-affecting option
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn vector_index_load(
+    path: *const c_char,
+    embedder: *const CEmbedder,
+) -> *mut CVectorIndex {
+    clear_last_error();
 
-                        eprintln!("DEBUG: Dart callback returned");
-                        // Note: Dart side is responsible for freeing the batch
-                        0  // Success
-                    }
-                    Err(e) => {
-                        eprintln!("DEBUG: Failed to convert to CEmbedDataBatch: {}", e);
-                        set_last_error(&e);
-                        -1
-                    }
-                }
-            }
-            Ok(None) => {
-                // This shouldn't happen when adapter is None, but handle it gracefully
-                set_last_error("EMBEDDING_FAILED: embed_directory_stream returned None");
-                -1
-            }
-            Err(e) => {
-                let error_str = e.to_string().to_lowercase();
-                if error_str.contains("not found") || error_str.contains("no such file") {
-                    set_last_error(&format!("FILE_NOT_FOUND: {}", dir_path_str));
-                } else if error_str.contains("permission") || error_str.contains("access denied") {
-                    set_last_error(&format!("FILE_READ_ERROR: {}", e));
-                } else {
-                    set_last_error(&format!("EMBEDDING_FAILED: Directory embedding failed - {}", e));
-                }
-                -1
+    if embedder.is_null() {
+        set_last_error("FFI_ERROR: embedder pointer is null");
+        return std::ptr::null_mut();
+    }
+    if path.is_null() {
+        set_last_error("INVALID_CONFIG: path: cannot be null");
+        return std::ptr::null_mut();
+    }
+
+    let path_str = unsafe {
+        match CStr::from_ptr(path).to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error("INVALID_CONFIG: path: invalid UTF-8 encoding");
+                return std::ptr::null_mut();
             }
         }
-    
+    };
+
+    let embedder = unsafe { &*embedder };
+    match index::VectorIndex::load(&PathBuf::from(path_str), &embedder.model, &embedder.options) {
+        Ok(inner) => Box::into_raw(Box::new(CVectorIndex {
+            inner: RwLock::new(inner),
+        })),
+        Err(e) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
 }
 
 // ============================================================================
@@ -969,7 +3597,8 @@ pub extern "C" fn embed_directory_stream(
 pub extern "C" fn embedder_free(embedder: *mut CEmbedder) {
     if !embedder.is_null() {
         unsafe {
-            drop(Box::from_raw(embedder));
+            let embedder = Box::from_raw(embedder);
+            async_embed::forget_lane(async_embed::lane_of(&embedder.inner));
         }
     }
 }
@@ -1013,6 +3642,132 @@ pub extern "C" fn free_embedding_batch(batch: *mut CTextEmbeddingBatch) {
     }
 }
 
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn reranker_free(reranker: *mut CReranker) {
+    if !reranker.is_null() {
+        unsafe {
+            drop(Box::from_raw(reranker));
+        }
+    }
+}
+
+/// Free a CRerankResults instance
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn free_rerank_results(results: *mut CRerankResults) {
+    if !results.is_null() {
+        unsafe {
+            let results = Box::from_raw(results);
+            if !results.results.is_null() {
+                drop(Vec::from_raw_parts(
+                    results.results,
+                    results.count,
+                    results.count,
+                ));
+            }
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn vector_index_free(index: *mut CVectorIndex) {
+    if !index.is_null() {
+        unsafe {
+            drop(Box::from_raw(index));
+        }
+    }
+}
+
+/// Free a CSearchResults instance
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn free_search_results(results: *mut CSearchResults) {
+    if !results.is_null() {
+        unsafe {
+            let results = Box::from_raw(results);
+            if !results.results.is_null() {
+                let hits = Vec::from_raw_parts(results.results, results.count, results.count);
+                for hit in hits {
+                    if !hit.metadata_json.is_null() {
+                        drop(CString::from_raw(hit.metadata_json));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Free a CMultiVectorEmbedding instance
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn free_multi_vector_embedding(embedding: *mut CMultiVectorEmbedding) {
+    if !embedding.is_null() {
+        unsafe {
+            let embedding = Box::from_raw(embedding);
+            free_multi_vector_embedding_single(*embedding);
+        }
+    }
+}
+
+/// Free a CMultiVectorEmbeddingBatch instance
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn free_multi_vector_embedding_batch(batch: *mut CMultiVectorEmbeddingBatch) {
+    if !batch.is_null() {
+        unsafe {
+            let batch = Box::from_raw(batch);
+            if !batch.embeddings.is_null() {
+                let embeddings = Vec::from_raw_parts(batch.embeddings, batch.count, batch.count);
+                for embedding in embeddings {
+                    free_multi_vector_embedding_single(embedding);
+                }
+            }
+        }
+    }
+}
+
+/// Free a CSparseEmbedding instance
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn free_sparse_embedding(embedding: *mut CSparseEmbedding) {
+    if !embedding.is_null() {
+        unsafe {
+            let embedding = Box::from_raw(embedding);
+            free_sparse_embedding_single(*embedding);
+        }
+    }
+}
+
+/// Free a CSparseEmbeddingBatch instance
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn free_sparse_embedding_batch(batch: *mut CSparseEmbeddingBatch) {
+    if !batch.is_null() {
+        unsafe {
+            let batch = Box::from_raw(batch);
+            if !batch.embeddings.is_null() {
+                let embeddings = Vec::from_raw_parts(batch.embeddings, batch.count, batch.count);
+                for embedding in embeddings {
+                    free_sparse_embedding_single(embedding);
+                }
+            }
+        }
+    }
+}
+
+/// Free a string returned by Rust (e.g. list_supported_architectures)
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn free_string(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        unsafe {
+            drop(CString::from_raw(ptr));
+        }
+    }
+}
+
 /// Free a CEmbedData instance
 #[no_mangle]
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
@@ -1094,6 +3849,39 @@ mod tests {
     use super::*;
     use std::collections::HashMap;
 
+    #[test]
+    fn test_ensure_outside_runtime() {
+        assert!(ensure_outside_runtime());
+
+        let inside = RUNTIME.block_on(async { ensure_outside_runtime() });
+
+        assert!(!inside);
+        let error_ptr = get_last_error();
+        let error = unsafe { CStr::from_ptr(error_ptr) }.to_str().unwrap();
+        assert!(error.starts_with("FFI_ERROR:"));
+        free_error_string(error_ptr);
+    }
+
+    #[test]
+    fn test_init_runtime_with_options_too_late() {
+        assert_eq!(init_runtime(), 0);
+
+        assert_eq!(init_runtime_with_options(2, 2), -1);
+    }
+
+    #[test]
+    fn test_embedder_cache_null_embedder() {
+        assert_eq!(embedder_set_cache(std::ptr::null(), 100, 0), -1);
+
+        let stats = embedder_cache_stats(std::ptr::null());
+
+        assert_eq!(stats.status, -1);
+        let error_ptr = get_last_error();
+        let error = unsafe { CStr::from_ptr(error_ptr) }.to_str().unwrap();
+        assert!(error.starts_with("FFI_ERROR:"));
+        free_error_string(error_ptr);
+    }
+
     #[test]
     fn test_embed_data_to_c_dense_vector() {
         // Arrange
@@ -1127,7 +3915,7 @@ mod tests {
     }
 
     #[test]
-    fn test_embed_data_to_c_multi_vector_error() {
+    fn test_embed_data_to_c_multi_vector() {
         // Arrange
         let embedding = EmbeddingResult::MultiVector(vec![vec![0.1, 0.2], vec![0.3, 0.4]]);
         let embed_data = EmbedData {
@@ -1140,12 +3928,203 @@ mod tests {
         let result = embed_data_to_c(embed_data);
 
         // Assert
-        assert!(result.is_err());
-        if let Err(err) = result {
-            assert!(err.contains("MULTI_VECTOR_NOT_SUPPORTED"));
-        } else {
-            panic!("Expected error but got Ok");
+        assert!(result.is_ok());
+        let c_data = result.unwrap();
+        assert_eq!(c_data.embedding_rows, 2);
+        assert_eq!(c_data.embedding_len, 4);
+        let values = unsafe { std::slice::from_raw_parts(c_data.embedding_values, 4) };
+        assert_eq!(values, &[0.1, 0.2, 0.3, 0.4]);
+
+        // Cleanup
+        unsafe {
+            free_embed_data_single(c_data);
+        }
+    }
+
+    #[test]
+    fn test_rerank_results_roundtrip() {
+        // Arrange
+        let ranked = vec![(2, 0.9), (0, 0.4)];
+
+        // Act
+        let batch_ptr = rerank_results_to_c(ranked);
+
+        // Assert
+        let batch = unsafe { &*batch_ptr };
+        assert_eq!(batch.count, 2);
+        let results = unsafe { std::slice::from_raw_parts(batch.results, batch.count) };
+        assert_eq!(results[0].index, 2);
+        assert_eq!(results[0].score, 0.9);
+        assert_eq!(results[1].index, 0);
+
+        // Cleanup
+        free_rerank_results(batch_ptr);
+    }
+
+    #[test]
+    fn test_rerank_null_reranker() {
+        let result = rerank(std::ptr::null(), std::ptr::null(), std::ptr::null(), 0, 0);
+
+        assert!(result.is_null());
+        let error_ptr = get_last_error();
+        let error = unsafe { CStr::from_ptr(error_ptr).to_str().unwrap().to_string() };
+        free_error_string(error_ptr);
+        assert!(error.starts_with("FFI_ERROR:"));
+    }
+
+    #[test]
+    fn test_vector_index_add_and_search() {
+        // Arrange
+        let index = vector_index_create(2, 0);
+        let metadata = CString::new("{\"text\":\"a\"}").unwrap();
+        let ids = [7u64, 8];
+        let mut values = [vec![0.0f32, 1.0], vec![1.0, 1.0]];
+        let mut embeddings: Vec<CTextEmbedding> = values
+            .iter_mut()
+            .map(|v| CTextEmbedding {
+                values: v.as_mut_ptr(),
+                len: v.len(),
+            })
+            .collect();
+        let batch = CTextEmbeddingBatch {
+            embeddings: embeddings.as_mut_ptr(),
+            count: embeddings.len(),
+        };
+
+        // Act
+        let added = vector_index_add(index, 1, [1.0f32, 0.0].as_ptr(), 2, metadata.as_ptr());
+        let added_batch = vector_index_add_text_batch(index, ids.as_ptr(), &batch);
+        let results_ptr = vector_index_search(index, [1.0f32, 0.2].as_ptr(), 2, 2);
+
+        // Assert
+        assert_eq!((added, added_batch), (0, 0));
+        assert_eq!(vector_index_count(index), 3);
+        let results = unsafe { &*results_ptr };
+        let hits = unsafe { std::slice::from_raw_parts(results.results, results.count) };
+        assert_eq!(hits.len(), 2);
+        assert_eq!((hits[0].id, hits[1].id), (1, 8));
+        let hit_metadata = unsafe { CStr::from_ptr(hits[0].metadata_json) };
+        assert_eq!(hit_metadata.to_str().unwrap(), "{\"text\":\"a\"}");
+        assert!(hits[1].metadata_json.is_null());
+        assert_eq!(vector_index_remove(index, 1), 1);
+        assert_eq!(vector_index_remove(index, 1), 0);
+
+        // Cleanup
+        free_search_results(results_ptr);
+        vector_index_free(index);
+    }
+
+    #[test]
+    fn test_vector_index_hnsw_add_and_search() {
+        // Arrange
+        let index = vector_index_create_hnsw(2, 2, 0, 0, 0);
+        for id in 0..20u64 {
+            let values = [id as f32, 0.0];
+            let added = vector_index_add(index, id, values.as_ptr(), 2, std::ptr::null());
+            assert_eq!(added, 0);
         }
+
+        // Act
+        let set_ef = vector_index_set_ef_search(index, 8);
+        let removed = vector_index_remove(index, 5);
+        let results_ptr = vector_index_search(index, [5.2f32, 0.0].as_ptr(), 2, 2);
+
+        // Assert
+        assert_eq!((set_ef, removed), (0, 1));
+        assert_eq!(vector_index_count(index), 19);
+        let results = unsafe { &*results_ptr };
+        let hits = unsafe { std::slice::from_raw_parts(results.results, results.count) };
+        assert_eq!((hits[0].id, hits[1].id), (6, 4));
+
+        // Cleanup
+        free_search_results(results_ptr);
+        vector_index_free(index);
+    }
+
+    #[test]
+    fn test_vector_index_save_load_null_embedder() {
+        let index = vector_index_create(2, 0);
+        let path = CString::new("unused.index").unwrap();
+
+        let saved = vector_index_save(index, std::ptr::null(), path.as_ptr());
+        let loaded = vector_index_load(path.as_ptr(), std::ptr::null());
+
+        assert_eq!(saved, -1);
+        assert!(loaded.is_null());
+        let error_ptr = get_last_error();
+        let error = unsafe { CStr::from_ptr(error_ptr).to_str().unwrap().to_string() };
+        free_error_string(error_ptr);
+        assert!(error.starts_with("FFI_ERROR:"));
+        vector_index_free(index);
+    }
+
+    #[test]
+    fn test_vector_index_rejects_wrong_dimension() {
+        let index = vector_index_create(3, 2);
+
+        let added = vector_index_add(index, 1, [1.0f32].as_ptr(), 1, std::ptr::null());
+
+        assert_eq!(added, -1);
+        let error_ptr = get_last_error();
+        let error = unsafe { CStr::from_ptr(error_ptr).to_str().unwrap().to_string() };
+        free_error_string(error_ptr);
+        assert!(error.starts_with("INVALID_CONFIG:"));
+        assert_eq!(vector_index_count(index), 0);
+        vector_index_free(index);
+    }
+
+    #[test]
+    fn test_vector_index_embed_data_batch_rejects_invalid_utf8_metadata() {
+        // Arrange: the second item's metadata is not valid UTF-8
+        let index = vector_index_create(2, 0);
+        let mut values = [vec![1.0f32, 0.0], vec![0.0, 1.0]];
+        let valid = CString::new("{\"text\":\"a\"}").unwrap();
+        let invalid = CString::new(vec![b'{', 0xff, b'}']).unwrap();
+        let mut items: Vec<CEmbedData> = values
+            .iter_mut()
+            .zip([&valid, &invalid])
+            .map(|(v, json)| CEmbedData {
+                embedding_values: v.as_mut_ptr(),
+                embedding_len: v.len(),
+                text_and_metadata_json: json.as_ptr() as *mut c_char,
+                embedding_rows: 1,
+            })
+            .collect();
+        let batch = CEmbedDataBatch {
+            items: items.as_mut_ptr(),
+            count: items.len(),
+        };
+
+        // Act
+        let added = vector_index_add_embed_data_batch(index, [1u64, 2].as_ptr(), &batch);
+
+        // Assert
+        assert_eq!(added, -1);
+        let error_ptr = get_last_error();
+        let error = unsafe { CStr::from_ptr(error_ptr).to_str().unwrap().to_string() };
+        free_error_string(error_ptr);
+        assert!(error.starts_with("INVALID_CONFIG: batch: item 1"));
+        assert_eq!(vector_index_count(index), 0);
+        vector_index_free(index);
+    }
+
+    #[test]
+    fn test_flatten_embedding_ragged_multi_vector_error() {
+        let embedding = EmbeddingResult::MultiVector(vec![vec![0.1, 0.2], vec![0.3]]);
+
+        let result = flatten_embedding(embedding);
+
+        assert!(result.unwrap_err().starts_with("EMBEDDING_FAILED:"));
+    }
+
+    #[test]
+    fn test_flatten_embedding_dense_is_single_row() {
+        let (values, rows, dim) =
+            flatten_embedding(EmbeddingResult::DenseVector(vec![1.0, 2.0, 3.0])).unwrap();
+
+        assert_eq!(values, vec![1.0, 2.0, 3.0]);
+        assert_eq!(rows, 1);
+        assert_eq!(dim, 3);
     }
 
     #[test]
@@ -1175,6 +4154,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_dense_to_sparse_keeps_non_zero_entries() {
+        let (indices, values) = dense_to_sparse(&[0.0, 1.5, 0.0, 0.0, 0.25]);
+
+        assert_eq!(indices, vec![1, 4]);
+        assert_eq!(values, vec![1.5, 0.25]);
+    }
+
+    #[test]
+    fn test_sparse_vec_to_batch_roundtrip() {
+        // Arrange
+        let sparse = vec![(vec![3, 7], vec![0.5, 1.0]), (vec![], vec![])];
+
+        // Act
+        let batch_ptr = sparse_vec_to_batch(sparse);
+
+        // Assert
+        let batch = unsafe { &*batch_ptr };
+        assert_eq!(batch.count, 2);
+        let first = unsafe { &*batch.embeddings };
+        assert_eq!(first.len, 2);
+        let indices = unsafe { std::slice::from_raw_parts(first.indices, first.len) };
+        assert_eq!(indices, &[3, 7]);
+
+        // Cleanup
+        free_sparse_embedding_batch(batch_ptr);
+    }
+
     #[test]
     fn test_free_embed_data_batch_null_safe() {
         // Act - should not crash
@@ -0,0 +1,371 @@
+//! Candle text embedder built directly from a local model directory.
+//!
+//! The encoder is constructed from `config.json`, `tokenizer.json` and
+//! `model.safetensors` without going through the HuggingFace Hub: nothing
+//! is written outside the model directory and nothing is fetched over the
+//! network, so models can be loaded from read-only app bundles. Like the
+//! ONNX backend, the result is wrapped as an upstream `TextEmbedder::Bert`
+//! so every embed path works unchanged.
+
+use crate::device;
+use crate::model_loader::ModelArchitecture;
+use crate::options::Pooling;
+use anyhow::{anyhow, Context};
+use candle_core::{DType, Device, IndexOp, Module, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::{bert, jina_bert, modernbert, qwen3, xlm_roberta};
+use embed_anything::embeddings::embed::{Embedder, EmbeddingResult, TextEmbedder};
+use embed_anything::embeddings::local::bert::BertEmbed;
+use embed_anything::Dtype;
+use std::fs;
+use std::path::Path;
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+/// Default batch size when the caller does not provide one.
+const DEFAULT_BATCH_SIZE: usize = 32;
+
+/// Upper bound on tokens per input for long-context models, to keep the
+/// attention memory of a single input bounded.
+const MAX_SEQUENCE_LENGTH_CAP: usize = 8192;
+
+/// Encoder network of a local model.
+enum Encoder {
+    Bert(bert::BertModel),
+    Jina(jina_bert::BertModel),
+    ModernBert(modernbert::ModernBert),
+    // Run on a fresh clone per call so no KV cache carries over between inputs
+    Qwen3(qwen3::Model),
+    XlmRoberta(xlm_roberta::XLMRobertaModel),
+}
+
+impl Encoder {
+    /// Whether the encoder takes an attention mask. Encoders that do not
+    /// are run one input at a time so padding never affects the output.
+    fn supports_padding(&self) -> bool {
+        matches!(
+            self,
+            Encoder::Bert(_) | Encoder::ModernBert(_) | Encoder::XlmRoberta(_)
+        )
+    }
+
+    /// Run the encoder and return token states `[batch, seq, hidden]`.
+    fn forward(
+        &self,
+        ids: &Tensor,
+        type_ids: &Tensor,
+        mask: &Tensor,
+    ) -> candle_core::Result<Tensor> {
+        match self {
+            Encoder::Bert(model) => model.forward(ids, type_ids, Some(mask)),
+            Encoder::Jina(model) => model.forward(ids),
+            Encoder::ModernBert(model) => model.forward(ids, mask),
+            Encoder::Qwen3(model) => model.clone().forward(ids, 0),
+            Encoder::XlmRoberta(model) => model.forward(ids, mask, type_ids, None, None, None),
+        }
+    }
+}
+
+/// Text embedder running a candle encoder loaded from local files.
+pub struct LocalEmbedder {
+    encoder: Encoder,
+    tokenizer: Tokenizer,
+    pooling: LocalPooling,
+    device: Device,
+}
+
+/// Pooling applied to the encoder output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LocalPooling {
+    /// Mean or [CLS] pooling (sentence-transformers encoders)
+    Encoder(Pooling),
+    /// Last token (decoder-based embedding models such as Qwen3)
+    LastToken,
+}
+
+impl LocalEmbedder {
+    /// Load the model found in `root` as the given architecture.
+    ///
+    /// `root` must contain `config.json`, `tokenizer.json` and
+    /// `model.safetensors` (see `model_loader::inspect_local_dir`).
+    ///
+    /// `pooling` overrides the pooling of the checkpoint. Inputs are
+    /// truncated to `max_sequence_length` tokens, capped at the model's own
+    /// limit (the model's limit if None).
+    pub fn load(
+        root: &Path,
+        architecture: ModelArchitecture,
+        dtype: Option<Dtype>,
+        pooling: Option<Pooling>,
+        max_sequence_length: Option<usize>,
+    ) -> anyhow::Result<Self> {
+        let config =
+            fs::read_to_string(root.join("config.json")).context("failed to read config.json")?;
+        let device = device::select_device();
+        let dtype = match dtype {
+            Some(Dtype::F16) => DType::F16,
+            _ => DType::F32,
+        };
+        // SAFETY: the weights file is memory-mapped read-only and must not be
+        // modified while the model is loaded, as with any mmap-based loader
+        let vb = unsafe {
+            VarBuilder::from_mmaped_safetensors(&[root.join("model.safetensors")], dtype, &device)?
+        };
+
+        let encoder = match architecture {
+            ModelArchitecture::Bert => {
+                let config: bert::Config = serde_json::from_str(&config)?;
+                Encoder::Bert(bert::BertModel::load(vb, &config)?)
+            }
+            ModelArchitecture::Jina => {
+                let config: jina_bert::Config = serde_json::from_str(&config)?;
+                Encoder::Jina(jina_bert::BertModel::new(vb, &config)?)
+            }
+            ModelArchitecture::ModernBert => {
+                let config: modernbert::Config = serde_json::from_str(&config)?;
+                // Bare `ModernBertModel` checkpoints lack the "model." prefix
+                let model = match modernbert::ModernBert::load(vb.clone(), &config) {
+                    Ok(model) => model,
+                    Err(_) => modernbert::ModernBert::load(
+                        vb.rename_f(|name| name.trim_start_matches("model.").to_string()),
+                        &config,
+                    )?,
+                };
+                Encoder::ModernBert(model)
+            }
+            ModelArchitecture::Qwen3 => {
+                let config: qwen3::Config = serde_json::from_str(&config)?;
+                Encoder::Qwen3(qwen3::Model::new(&config, vb)?)
+            }
+            ModelArchitecture::XlmRoberta => {
+                let config: xlm_roberta::Config = serde_json::from_str(&config)?;
+                // `XLMRobertaForMaskedLM` checkpoints nest the encoder
+                let model = match xlm_roberta::XLMRobertaModel::new(&config, vb.clone()) {
+                    Ok(model) => model,
+                    Err(_) => xlm_roberta::XLMRobertaModel::new(&config, vb.pp("roberta"))?,
+                };
+                Encoder::XlmRoberta(model)
+            }
+            ModelArchitecture::SparseBert => {
+                return Err(anyhow!(
+                    "sparse models are not supported by the local backend"
+                ));
+            }
+        };
+
+        let pooling = match (pooling, &encoder) {
+            (Some(pooling), _) => LocalPooling::Encoder(pooling),
+            (None, Encoder::Qwen3(_)) => LocalPooling::LastToken,
+            (None, _) => LocalPooling::Encoder(read_pooling(root)),
+        };
+
+        let mut tokenizer = Tokenizer::from_file(root.join("tokenizer.json"))
+            .map_err(|e| anyhow!("failed to load tokenizer: {}", e))?;
+        tokenizer.with_padding(Some(PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..Default::default()
+        }));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: resolve_max_sequence_length(
+                    max_sequence_length,
+                    model_max_sequence_length(&config, architecture),
+                ),
+                ..Default::default()
+            }))
+            .map_err(|e| anyhow!("failed to configure tokenizer: {}", e))?;
+
+        Ok(Self {
+            encoder,
+            tokenizer,
+            pooling,
+            device,
+        })
+    }
+
+    /// Wrap this embedder as an upstream `Embedder`.
+    pub fn into_embedder(self) -> Embedder {
+        Embedder::Text(TextEmbedder::Bert(Box::new(self)))
+    }
+
+    /// Run one batch through the encoder and return pooled vectors.
+    fn embed_batch(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts.to_vec(), true)
+            .map_err(|e| anyhow!("tokenization failed: {}", e))?;
+
+        let batch = encodings.len();
+        let seq_len = encodings.first().map(|e| e.get_ids().len()).unwrap_or(0);
+
+        let mut ids = Vec::with_capacity(batch * seq_len);
+        let mut mask = Vec::with_capacity(batch * seq_len);
+        let mut type_ids = Vec::with_capacity(batch * seq_len);
+        for encoding in &encodings {
+            ids.extend_from_slice(encoding.get_ids());
+            mask.extend_from_slice(encoding.get_attention_mask());
+            type_ids.extend_from_slice(encoding.get_type_ids());
+        }
+
+        let shape = (batch, seq_len);
+        let ids = Tensor::from_vec(ids, shape, &self.device)?;
+        let type_ids = Tensor::from_vec(type_ids, shape, &self.device)?;
+        let mask = Tensor::from_vec(mask, shape, &self.device)?;
+
+        let hidden = self
+            .encoder
+            .forward(&ids, &type_ids, &mask)?
+            .to_dtype(DType::F32)?;
+        let pooled = match self.pooling {
+            LocalPooling::Encoder(Pooling::Cls) => hidden.i((.., 0))?,
+            LocalPooling::Encoder(Pooling::Mean) => mean_pool(&hidden, &mask)?,
+            LocalPooling::LastToken => hidden.i((.., seq_len.saturating_sub(1)))?,
+        };
+        Ok(pooled.to_vec2::<f32>()?)
+    }
+}
+
+impl BertEmbed for LocalEmbedder {
+    fn embed(
+        &self,
+        text_batch: &[&str],
+        batch_size: Option<usize>,
+        _late_chunking: Option<bool>,
+    ) -> anyhow::Result<Vec<EmbeddingResult>> {
+        let batch_size = if self.encoder.supports_padding() {
+            batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1)
+        } else {
+            1
+        };
+        let mut results = Vec::with_capacity(text_batch.len());
+        for chunk in text_batch.chunks(batch_size) {
+            results.extend(
+                self.embed_batch(chunk)?
+                    .into_iter()
+                    .map(EmbeddingResult::DenseVector),
+            );
+        }
+        Ok(results)
+    }
+}
+
+/// Attention-mask weighted mean of token states `[batch, seq, hidden]`.
+fn mean_pool(hidden: &Tensor, mask: &Tensor) -> candle_core::Result<Tensor> {
+    let mask = mask.to_dtype(DType::F32)?.unsqueeze(2)?;
+    let summed = hidden.broadcast_mul(&mask)?.sum(1)?;
+    let counts = mask.sum(1)?.clamp(1.0, f64::MAX)?;
+    summed.broadcast_div(&counts)
+}
+
+/// Pooling declared by a sentence-transformers `1_Pooling/config.json`.
+///
+/// Defaults to mean pooling when the file is absent or unreadable.
+fn read_pooling(root: &Path) -> Pooling {
+    let config = fs::read_to_string(root.join("1_Pooling").join("config.json"))
+        .ok()
+        .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok());
+    match config {
+        Some(config) if config["pooling_mode_cls_token"].as_bool() == Some(true) => Pooling::Cls,
+        _ => Pooling::Mean,
+    }
+}
+
+/// Tokens per input: the requested length, never more than the model allows.
+fn resolve_max_sequence_length(requested: Option<usize>, model_max: usize) -> usize {
+    requested.map_or(model_max, |requested| requested.min(model_max))
+}
+
+/// Tokens per input: the model's position limit, capped at
+/// `MAX_SEQUENCE_LENGTH_CAP` (512 when the config does not declare one).
+fn model_max_sequence_length(config: &str, architecture: ModelArchitecture) -> usize {
+    let config = serde_json::from_str::<serde_json::Value>(config).unwrap_or_default();
+    let Some(positions) = config["max_position_embeddings"].as_u64() else {
+        return 512;
+    };
+    // RoBERTa position ids start after the padding index
+    let offset = match architecture {
+        ModelArchitecture::XlmRoberta => config["pad_token_id"].as_u64().unwrap_or(1) + 1,
+        _ => 0,
+    };
+    (positions.saturating_sub(offset) as usize).min(MAX_SEQUENCE_LENGTH_CAP)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Create a fresh scratch directory under the system temp dir.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join("embedanything_dart_tests")
+            .join(format!("local_model_{}_{}", name, std::process::id()));
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_read_pooling() {
+        let dir = scratch_dir("pooling");
+        assert_eq!(read_pooling(&dir), Pooling::Mean);
+
+        fs::create_dir_all(dir.join("1_Pooling")).unwrap();
+        fs::write(
+            dir.join("1_Pooling").join("config.json"),
+            r#"{"pooling_mode_cls_token": true, "pooling_mode_mean_tokens": false}"#,
+        )
+        .unwrap();
+        assert_eq!(read_pooling(&dir), Pooling::Cls);
+
+        fs::write(
+            dir.join("1_Pooling").join("config.json"),
+            r#"{"pooling_mode_cls_token": false, "pooling_mode_mean_tokens": true}"#,
+        )
+        .unwrap();
+        assert_eq!(read_pooling(&dir), Pooling::Mean);
+    }
+
+    #[test]
+    fn test_model_max_sequence_length() {
+        let bert = ModelArchitecture::Bert;
+        assert_eq!(
+            model_max_sequence_length(r#"{"max_position_embeddings": 512}"#, bert),
+            512
+        );
+        assert_eq!(
+            model_max_sequence_length(r#"{"max_position_embeddings": 32768}"#, bert),
+            MAX_SEQUENCE_LENGTH_CAP
+        );
+        assert_eq!(model_max_sequence_length("{}", bert), 512);
+
+        let xlm_roberta = r#"{"max_position_embeddings": 514, "pad_token_id": 1}"#;
+        assert_eq!(
+            model_max_sequence_length(xlm_roberta, ModelArchitecture::XlmRoberta),
+            512
+        );
+    }
+
+    #[test]
+    fn test_resolve_max_sequence_length() {
+        assert_eq!(resolve_max_sequence_length(None, 512), 512);
+        assert_eq!(resolve_max_sequence_length(Some(128), 512), 128);
+        // Longer than the position embeddings allow
+        assert_eq!(resolve_max_sequence_length(Some(4096), 512), 512);
+    }
+
+    #[test]
+    fn test_mean_pool_ignores_padding() {
+        // 1 input, 3 tokens x 2 hidden, last token is padding
+        let hidden = Tensor::new(&[[[1f32, 2.], [3., 4.], [100., 100.]]], &Device::Cpu).unwrap();
+        let mask = Tensor::new(&[[1u32, 1, 0]], &Device::Cpu).unwrap();
+
+        let pooled = mean_pool(&hidden, &mask).unwrap();
+        assert_eq!(pooled.to_vec2::<f32>().unwrap(), vec![vec![2.0, 3.0]]);
+    }
+}
@@ -0,0 +1,642 @@
+//! Model loading helpers shared by the sync and async FFI entry points.
+//!
+//! Hub models go through the upstream loaders. Models that ship inside an
+//! app bundle are built directly from their files by `local_model`, which
+//! never touches the hub cache or the network.
+//!
+//! ## Local directory layout
+//! - `config.json` (required, used for architecture detection)
+//! - `tokenizer.json` (required)
+//! - `model.safetensors` (required)
+//! - `1_Pooling/config.json` (optional, selects [CLS] instead of mean pooling)
+
+use crate::cache;
+use crate::local_model::LocalEmbedder;
+use crate::options::Pooling;
+use embed_anything::embeddings::embed::{Embedder, TextEmbedder};
+use embed_anything::Dtype;
+use hf_hub::api::sync::{ApiBuilder, ApiError};
+use hf_hub::{Repo, RepoType};
+use once_cell::sync::Lazy;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Model architecture families selectable through the `model_type` FFI
+/// argument.
+///
+/// The numeric values correspond to the Dart `EmbeddingModel` enum values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ModelArchitecture {
+    /// BERT / sentence-transformers models
+    Bert = 0,
+    /// Jina BERT models (ALiBi attention)
+    Jina = 1,
+    /// ModernBERT models
+    ModernBert = 2,
+    /// Qwen3 embedding models
+    Qwen3 = 3,
+    /// XLM-RoBERTa models (multilingual BERT variant)
+    XlmRoberta = 4,
+    /// Sparse BERT models with a masked-LM head (SPLADE)
+    SparseBert = 5,
+}
+
+impl ModelArchitecture {
+    /// All architectures compiled into this library.
+    pub const ALL: [ModelArchitecture; 6] = [
+        ModelArchitecture::Bert,
+        ModelArchitecture::Jina,
+        ModelArchitecture::ModernBert,
+        ModelArchitecture::Qwen3,
+        ModelArchitecture::XlmRoberta,
+        ModelArchitecture::SparseBert,
+    ];
+
+    /// `model_type` value requesting architecture auto-detection.
+    pub const AUTO: u8 = 255;
+
+    /// Map the `model_type` FFI argument to an architecture.
+    ///
+    /// Returns `Ok(None)` for auto-detection.
+    pub fn from_model_type(model_type: u8) -> Result<Option<Self>, String> {
+        if model_type == Self::AUTO {
+            return Ok(None);
+        }
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|arch| *arch as u8 == model_type)
+            .map(Some)
+            .ok_or_else(|| format!("INVALID_CONFIG: model_type: invalid value {}", model_type))
+    }
+
+    /// Identify the architecture family of a `config.json` architecture name.
+    pub fn from_config_architecture(architecture: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|arch| arch.config_architectures().contains(&architecture))
+    }
+
+    /// Short architecture name, as listed by `supported_architectures_json`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ModelArchitecture::Bert => "bert",
+            ModelArchitecture::Jina => "jina",
+            ModelArchitecture::ModernBert => "modernbert",
+            ModelArchitecture::Qwen3 => "qwen3",
+            ModelArchitecture::XlmRoberta => "xlm-roberta",
+            ModelArchitecture::SparseBert => "sparse-bert",
+        }
+    }
+
+    /// Whether models of this family produce sparse (SPLADE) output.
+    pub fn is_sparse(self) -> bool {
+        self == ModelArchitecture::SparseBert
+    }
+
+    /// Name understood by upstream `TextEmbedder::from_pretrained_hf`.
+    ///
+    /// None for architectures that upstream only loads through
+    /// `Embedder::from_pretrained_hf` auto-detection.
+    fn upstream_name(self) -> Option<&'static str> {
+        match self {
+            ModelArchitecture::XlmRoberta => None,
+            _ => Some(self.name()),
+        }
+    }
+
+    /// Architecture names (as found in `config.json` "architectures" or
+    /// "model_type") that belong to this family.
+    pub fn config_architectures(self) -> &'static [&'static str] {
+        match self {
+            ModelArchitecture::Bert => &["BertModel", "bert"],
+            ModelArchitecture::Jina => &["JinaBertModel", "JinaBertForMaskedLM"],
+            ModelArchitecture::ModernBert => {
+                &["ModernBertModel", "ModernBertForMaskedLM", "modernbert"]
+            }
+            ModelArchitecture::Qwen3 => &["Qwen3Model", "Qwen3ForCausalLM", "qwen3"],
+            ModelArchitecture::XlmRoberta => {
+                &["XLMRobertaModel", "XLMRobertaForMaskedLM", "xlm-roberta"]
+            }
+            ModelArchitecture::SparseBert => &["BertForMaskedLM"],
+        }
+    }
+}
+
+/// JSON description of every architecture compiled into this library.
+///
+/// Format: `[{"model_type": 0, "name": "bert", "architectures": [...]}, ...]`
+pub fn supported_architectures_json() -> String {
+    let list: Vec<serde_json::Value> = ModelArchitecture::ALL
+        .iter()
+        .map(|arch| {
+            serde_json::json!({
+                "model_type": *arch as u8,
+                "name": arch.name(),
+                "architectures": arch.config_architectures(),
+            })
+        })
+        .collect();
+    serde_json::Value::Array(list).to_string()
+}
+
+/// Process-wide HuggingFace token used when a load call passes none.
+static DEFAULT_HF_TOKEN: Lazy<RwLock<Option<String>>> = Lazy::new(|| RwLock::new(None));
+
+/// Set (or clear with `None`) the process-wide fallback HuggingFace token.
+pub fn set_default_token(token: Option<String>) {
+    *DEFAULT_HF_TOKEN.write().unwrap() = token;
+}
+
+/// Resolve the token for a load call: explicit token first, then the
+/// process-wide fallback.
+pub fn resolve_token(explicit: Option<&str>) -> Option<String> {
+    explicit
+        .map(|t| t.to_string())
+        .or_else(|| DEFAULT_HF_TOKEN.read().unwrap().clone())
+}
+
+/// Validated contents of a local model directory.
+#[derive(Debug)]
+pub struct LocalModelDir {
+    pub root: PathBuf,
+    pub architecture: ModelArchitecture,
+}
+
+/// Validate a local model directory and detect its architecture.
+///
+/// # Error Prefixes
+/// - "FILE_NOT_FOUND:" - Directory or config.json does not exist
+/// - "TOKENIZER_NOT_FOUND:" - tokenizer.json is missing
+/// - "WEIGHTS_NOT_FOUND:" - model.safetensors is missing
+/// - "UNSUPPORTED_ARCHITECTURE:" - config.json names an unknown architecture
+/// - "INVALID_CONFIG:" - config.json could not be parsed
+pub fn inspect_local_dir(path: &Path) -> Result<LocalModelDir, String> {
+    if !path.is_dir() {
+        return Err(format!("FILE_NOT_FOUND: {}", path.display()));
+    }
+
+    let config_path = path.join("config.json");
+    if !config_path.is_file() {
+        return Err(format!("FILE_NOT_FOUND: {}", config_path.display()));
+    }
+
+    let tokenizer_path = path.join("tokenizer.json");
+    if !tokenizer_path.is_file() {
+        return Err(format!("TOKENIZER_NOT_FOUND: {}", tokenizer_path.display()));
+    }
+
+    let weights_path = path.join("model.safetensors");
+    if !weights_path.is_file() {
+        return Err(format!("WEIGHTS_NOT_FOUND: {}", weights_path.display()));
+    }
+
+    let architecture_name = read_architecture(&config_path)?;
+    let architecture = ModelArchitecture::from_config_architecture(&architecture_name)
+        .ok_or_else(|| format!("UNSUPPORTED_ARCHITECTURE: {}", architecture_name))?;
+
+    Ok(LocalModelDir {
+        root: path.to_path_buf(),
+        architecture,
+    })
+}
+
+/// Read the architecture name from a `config.json` file.
+///
+/// Uses the first entry of "architectures", falling back to "model_type".
+fn read_architecture(config_path: &Path) -> Result<String, String> {
+    let contents = fs::read_to_string(config_path)
+        .map_err(|e| format!("FILE_READ_ERROR: {}: {}", config_path.display(), e))?;
+    let config: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|e| format!("INVALID_CONFIG: config.json: {}", e))?;
+
+    config["architectures"][0]
+        .as_str()
+        .or_else(|| config["model_type"].as_str())
+        .map(|s| s.to_string())
+        .ok_or_else(|| {
+            "UNSUPPORTED_ARCHITECTURE: config.json does not declare an architecture".to_string()
+        })
+}
+
+/// Kind of model being fetched, which selects the error prefixes of
+/// `hub_error_message`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HubModel {
+    Embedder,
+    Reranker,
+}
+
+impl HubModel {
+    fn not_found_prefix(self) -> &'static str {
+        match self {
+            HubModel::Embedder => "MODEL_NOT_FOUND",
+            HubModel::Reranker => "RERANKER_NOT_FOUND",
+        }
+    }
+
+    fn failed_message(self, model_id: &str, error: &anyhow::Error) -> String {
+        match self {
+            HubModel::Embedder => format!(
+                "EMBEDDING_FAILED: Failed to load model '{}': {}",
+                model_id, error
+            ),
+            HubModel::Reranker => format!(
+                "RERANKER_LOAD_FAILED: Failed to load reranker '{}': {}",
+                model_id, error
+            ),
+        }
+    }
+}
+
+/// Map a failure to load `model_id` to a prefixed error message.
+///
+/// The prefix follows the HTTP status of the failed hub request: 401/403
+/// (private or gated model accessed without a valid token) maps to
+/// "UNAUTHORIZED:", 404 to the kind's not-found prefix. Anything else,
+/// including errors that carry no status, is a load failure.
+pub fn hub_error_message<E: Into<anyhow::Error>>(
+    kind: HubModel,
+    model_id: &str,
+    error: E,
+) -> String {
+    let error = error.into();
+    match hub_status(&error) {
+        Some(401 | 403) => format!(
+            "UNAUTHORIZED: Access to '{}' was denied; a valid HuggingFace token is required: {}",
+            model_id, error
+        ),
+        Some(404) => format!("{}: {}", kind.not_found_prefix(), model_id),
+        _ => kind.failed_message(model_id, &error),
+    }
+}
+
+/// HTTP status returned by the hub, looked up along the error chain.
+fn hub_status(error: &anyhow::Error) -> Option<u16> {
+    error.chain().find_map(|cause| {
+        if let Some(api_error) = cause.downcast_ref::<ApiError>() {
+            return api_error_status(api_error);
+        }
+        match cause.downcast_ref::<ureq::Error>() {
+            Some(ureq::Error::Status(code, _)) => Some(*code),
+            _ => None,
+        }
+    })
+}
+
+fn api_error_status(error: &ApiError) -> Option<u16> {
+    match error {
+        ApiError::RequestError(request_error) => match request_error.as_ref() {
+            ureq::Error::Status(code, _) => Some(*code),
+            _ => None,
+        },
+        ApiError::TooManyRetries(inner) => api_error_status(inner),
+        _ => None,
+    }
+}
+
+/// Embedder built by a loader.
+pub struct LoadedModel {
+    pub embedder: Embedder,
+    /// Whether the embedder was built as a sparse (SPLADE) model
+    pub sparse: bool,
+}
+
+/// Load an embedder from the HuggingFace Hub.
+///
+/// With `architecture == None` the architecture is detected from the
+/// checkpoint's `config.json`; checkpoints of an unknown family are left to
+/// upstream auto-detection. Otherwise the requested architecture is used,
+/// and loading fails with "INVALID_CONFIG:" if `config.json` declares an
+/// architecture from a different family.
+///
+/// `token` falls back to the process-wide token set via `set_default_token`.
+pub fn load_from_hf(
+    model_id: &str,
+    revision: Option<&str>,
+    token: Option<&str>,
+    architecture: Option<ModelArchitecture>,
+    dtype: Option<Dtype>,
+) -> Result<LoadedModel, String> {
+    let token = resolve_token(token);
+
+    // Fetch config.json (served from the hub cache when already downloaded)
+    let api = ApiBuilder::new()
+        .with_token(token.clone())
+        .build()
+        .map_err(|e| hub_error_message(HubModel::Embedder, model_id, e))?;
+    let repo = match revision {
+        Some(rev) => Repo::with_revision(model_id.to_string(), RepoType::Model, rev.to_string()),
+        None => Repo::model(model_id.to_string()),
+    };
+    let config_path = api
+        .repo(repo)
+        .get("config.json")
+        .map_err(|e| hub_error_message(HubModel::Embedder, model_id, e))?;
+    let declared = read_architecture(&config_path).ok();
+    let detected = declared
+        .as_deref()
+        .and_then(ModelArchitecture::from_config_architecture);
+
+    // Unknown architectures are trusted to the explicit selection; only a
+    // checkpoint that belongs to another known family is a contradiction
+    if let (Some(requested), Some(detected)) = (architecture, detected) {
+        if detected != requested {
+            return Err(format!(
+                "INVALID_CONFIG: model_type: requested '{}' but '{}' declares '{}'",
+                requested.name(),
+                model_id,
+                declared.unwrap_or_default()
+            ));
+        }
+    }
+
+    let requested = architecture.or(detected);
+    let sparse = requested.is_some_and(ModelArchitecture::is_sparse);
+
+    let embedder = match requested.and_then(ModelArchitecture::upstream_name) {
+        // Sparse checkpoints are always loaded by name, so the sparse flag
+        // matches what upstream builds
+        Some(name) if architecture.is_some() || sparse => {
+            TextEmbedder::from_pretrained_hf(name, model_id, revision, token.as_deref(), dtype)
+                .map(Embedder::Text)
+        }
+        // Auto-detection, or a family upstream only loads that way (and
+        // config.json was checked above not to declare another family)
+        _ => Embedder::from_pretrained_hf(model_id, revision, token.as_deref(), dtype),
+    }
+    .map_err(|e| hub_error_message(HubModel::Embedder, model_id, e))?;
+
+    Ok(LoadedModel { embedder, sparse })
+}
+
+/// Content hash of a local model directory, for its `ModelFingerprint`.
+///
+/// Covers the config, tokenizer and pooling files in full and the weights
+/// by size and modification time (see `cache::content_hash`).
+pub fn local_dir_content_hash(path: &Path) -> String {
+    cache::content_hash(
+        &[
+            path.join("config.json"),
+            path.join("tokenizer.json"),
+            path.join("1_Pooling").join("config.json"),
+        ],
+        &[path.join("model.safetensors")],
+    )
+}
+
+/// Load an embedder from a local model directory.
+///
+/// The directory is validated and the model is built directly from its
+/// files; nothing is read from or written to the hub cache. Sparse
+/// (SPLADE) checkpoints are only supported through the hub loader.
+///
+/// `pooling` and `max_sequence_length` override the checkpoint's pooling and
+/// truncation length (see `LocalEmbedder::load`).
+pub fn load_from_local_dir(
+    path: &Path,
+    dtype: Option<Dtype>,
+    pooling: Option<Pooling>,
+    max_sequence_length: Option<usize>,
+) -> Result<Embedder, String> {
+    let local = inspect_local_dir(path)?;
+    if local.architecture.is_sparse() {
+        return Err(format!(
+            "UNSUPPORTED_ARCHITECTURE: {} (sparse models cannot be loaded from a local directory)",
+            local.architecture.name()
+        ));
+    }
+
+    LocalEmbedder::load(
+        &local.root,
+        local.architecture,
+        dtype,
+        pooling,
+        max_sequence_length,
+    )
+    .map(LocalEmbedder::into_embedder)
+    .map_err(|e| {
+        format!(
+            "EMBEDDING_FAILED: Failed to load local model '{}': {}",
+            local.root.display(),
+            e
+        )
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    /// Create a fresh scratch directory under the system temp dir.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join("embedanything_dart_tests")
+            .join(format!("{}_{}", name, std::process::id()));
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_model_dir(dir: &Path, architecture: &str) {
+        fs::write(
+            dir.join("config.json"),
+            format!(r#"{{"architectures": ["{}"]}}"#, architecture),
+        )
+        .unwrap();
+        fs::write(dir.join("tokenizer.json"), "{}").unwrap();
+        fs::write(dir.join("model.safetensors"), [0u8; 8]).unwrap();
+    }
+
+    #[test]
+    fn test_inspect_local_dir_valid() {
+        let dir = scratch_dir("valid");
+        write_model_dir(&dir, "BertModel");
+
+        let local = inspect_local_dir(&dir).unwrap();
+        assert_eq!(local.architecture, ModelArchitecture::Bert);
+    }
+
+    #[test]
+    fn test_inspect_local_dir_missing_directory() {
+        let dir = scratch_dir("missing").join("does_not_exist");
+
+        let err = inspect_local_dir(&dir).unwrap_err();
+        assert!(err.starts_with("FILE_NOT_FOUND:"));
+    }
+
+    #[test]
+    fn test_inspect_local_dir_missing_tokenizer() {
+        let dir = scratch_dir("no_tokenizer");
+        write_model_dir(&dir, "BertModel");
+        fs::remove_file(dir.join("tokenizer.json")).unwrap();
+
+        let err = inspect_local_dir(&dir).unwrap_err();
+        assert!(err.starts_with("TOKENIZER_NOT_FOUND:"));
+    }
+
+    #[test]
+    fn test_inspect_local_dir_missing_weights() {
+        let dir = scratch_dir("no_weights");
+        write_model_dir(&dir, "BertModel");
+        fs::remove_file(dir.join("model.safetensors")).unwrap();
+
+        let err = inspect_local_dir(&dir).unwrap_err();
+        assert!(err.starts_with("WEIGHTS_NOT_FOUND:"));
+    }
+
+    #[test]
+    fn test_inspect_local_dir_unsupported_architecture() {
+        let dir = scratch_dir("unsupported");
+        write_model_dir(&dir, "GPT2LMHeadModel");
+
+        let err = inspect_local_dir(&dir).unwrap_err();
+        assert_eq!(err, "UNSUPPORTED_ARCHITECTURE: GPT2LMHeadModel");
+    }
+
+    #[test]
+    fn test_load_from_local_dir_reports_invalid_weights() {
+        let dir = scratch_dir("invalid_weights");
+        write_model_dir(&dir, "BertModel");
+
+        let err = match load_from_local_dir(&dir, None, None, None) {
+            Ok(_) => panic!("invalid weights should not load"),
+            Err(e) => e,
+        };
+        assert!(err.starts_with("EMBEDDING_FAILED: Failed to load local model"));
+    }
+
+    #[test]
+    fn test_model_type_mapping() {
+        assert_eq!(
+            ModelArchitecture::from_model_type(0),
+            Ok(Some(ModelArchitecture::Bert))
+        );
+        assert_eq!(
+            ModelArchitecture::from_model_type(1),
+            Ok(Some(ModelArchitecture::Jina))
+        );
+        assert_eq!(
+            ModelArchitecture::from_model_type(4),
+            Ok(Some(ModelArchitecture::XlmRoberta))
+        );
+        assert_eq!(
+            ModelArchitecture::from_model_type(ModelArchitecture::AUTO),
+            Ok(None)
+        );
+        assert!(ModelArchitecture::from_model_type(42)
+            .unwrap_err()
+            .starts_with("INVALID_CONFIG: model_type:"));
+    }
+
+    #[test]
+    fn test_from_config_architecture() {
+        assert_eq!(
+            ModelArchitecture::from_config_architecture("JinaBertModel"),
+            Some(ModelArchitecture::Jina)
+        );
+        assert_eq!(
+            ModelArchitecture::from_config_architecture("Qwen3Model"),
+            Some(ModelArchitecture::Qwen3)
+        );
+        assert_eq!(
+            ModelArchitecture::from_config_architecture("XLMRobertaModel"),
+            Some(ModelArchitecture::XlmRoberta)
+        );
+        assert_eq!(
+            ModelArchitecture::from_config_architecture("BertForMaskedLM"),
+            Some(ModelArchitecture::SparseBert)
+        );
+        assert_eq!(
+            ModelArchitecture::from_config_architecture("GPT2LMHeadModel"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_load_from_local_dir_rejects_sparse() {
+        let dir = scratch_dir("sparse");
+        write_model_dir(&dir, "BertForMaskedLM");
+
+        let err = match load_from_local_dir(&dir, None, None, None) {
+            Ok(_) => panic!("sparse checkpoints should not load locally"),
+            Err(e) => e,
+        };
+        assert!(err.starts_with("UNSUPPORTED_ARCHITECTURE: sparse-bert"));
+    }
+
+    #[test]
+    fn test_supported_architectures_json() {
+        let parsed: serde_json::Value =
+            serde_json::from_str(&supported_architectures_json()).unwrap();
+        let list = parsed.as_array().unwrap();
+        assert_eq!(list.len(), ModelArchitecture::ALL.len());
+        assert_eq!(list[0]["model_type"], 0);
+        assert_eq!(list[0]["name"], "bert");
+        assert_eq!(list[1]["name"], "jina");
+    }
+
+    /// Hub error for a request answered with `code`.
+    fn status_error(code: u16) -> ApiError {
+        let response = ureq::Response::new(code, "status", "").unwrap();
+        ApiError::RequestError(Box::new(ureq::Error::Status(code, response)))
+    }
+
+    #[test]
+    fn test_hub_error_message() {
+        let not_found = hub_error_message(HubModel::Embedder, "org/missing", status_error(404));
+        assert_eq!(not_found, "MODEL_NOT_FOUND: org/missing");
+
+        let unauthorized = hub_error_message(HubModel::Embedder, "org/private", status_error(401));
+        assert!(unauthorized.starts_with("UNAUTHORIZED:"));
+        let gated = hub_error_message(HubModel::Embedder, "org/gated", status_error(403));
+        assert!(gated.starts_with("UNAUTHORIZED:"));
+
+        let other = hub_error_message(HubModel::Embedder, "org/model", anyhow!("out of memory"));
+        assert!(other.starts_with("EMBEDDING_FAILED: Failed to load model 'org/model'"));
+    }
+
+    #[test]
+    fn test_hub_error_message_uses_status_not_text() {
+        // A 404 whose URL mentions "401" is still a missing model
+        let not_found = hub_error_message(HubModel::Embedder, "org/model-401", status_error(404));
+        assert_eq!(not_found, "MODEL_NOT_FOUND: org/model-401");
+
+        // Numbers in the message of a status-less error are not statuses
+        let other = hub_error_message(
+            HubModel::Embedder,
+            "org/model-403",
+            anyhow!("connection refused: org/model-403"),
+        );
+        assert!(other.starts_with("EMBEDDING_FAILED:"));
+
+        // Retried requests keep their status, also behind context
+        let retried = ApiError::TooManyRetries(Box::new(status_error(401)));
+        let wrapped = anyhow::Error::new(retried).context("loading weights");
+        let unauthorized = hub_error_message(HubModel::Embedder, "org/private", wrapped);
+        assert!(unauthorized.starts_with("UNAUTHORIZED:"));
+    }
+
+    #[test]
+    fn test_resolve_token_prefers_explicit() {
+        set_default_token(Some("hf_default".to_string()));
+        assert_eq!(
+            resolve_token(Some("hf_explicit")).as_deref(),
+            Some("hf_explicit")
+        );
+        assert_eq!(resolve_token(None).as_deref(), Some("hf_default"));
+
+        set_default_token(None);
+        assert_eq!(resolve_token(None), None);
+    }
+}
@@ -0,0 +1,205 @@
+//! ONNX Runtime backend for text embedders (requires the `ort` feature).
+//!
+//! Loads an exported `.onnx` encoder plus its `tokenizer.json` from disk and
+//! wraps it as an upstream `TextEmbedder::Bert`, so every existing embed
+//! path (`embed_text`, `embed_texts_batch`, `embed_file`, the async tasks)
+//! works unchanged regardless of which backend produced the vectors.
+
+use crate::options::Pooling;
+use anyhow::{anyhow, Context};
+use embed_anything::embeddings::embed::{Embedder, EmbeddingResult, TextEmbedder};
+use embed_anything::embeddings::local::bert::BertEmbed;
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::Session;
+use ort::value::Tensor;
+use std::path::Path;
+use std::sync::Mutex;
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+/// Default batch size when the caller does not provide one.
+const DEFAULT_BATCH_SIZE: usize = 32;
+
+/// Default maximum number of tokens per input (BERT-style position limit).
+const DEFAULT_MAX_SEQUENCE_LENGTH: usize = 512;
+
+/// Text embedder backed by an ONNX Runtime session.
+pub struct OnnxEmbedder {
+    // ort 2.0 sessions need `&mut self` to run
+    session: Mutex<Session>,
+    tokenizer: Tokenizer,
+    pooling: Pooling,
+    has_token_type_ids: bool,
+}
+
+impl OnnxEmbedder {
+    /// Create an ONNX embedder from a model file and a `tokenizer.json`.
+    ///
+    /// Inputs are truncated to `max_sequence_length` tokens (512 if None).
+    pub fn new(
+        model_path: &Path,
+        tokenizer_path: &Path,
+        pooling: Pooling,
+        max_sequence_length: Option<usize>,
+    ) -> anyhow::Result<Self> {
+        let mut tokenizer = Tokenizer::from_file(tokenizer_path)
+            .map_err(|e| anyhow!("failed to load tokenizer: {}", e))?;
+        tokenizer.with_padding(Some(PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..Default::default()
+        }));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: max_sequence_length.unwrap_or(DEFAULT_MAX_SEQUENCE_LENGTH),
+                ..Default::default()
+            }))
+            .map_err(|e| anyhow!("failed to configure tokenizer: {}", e))?;
+
+        let session = Session::builder()?
+            .with_optimization_level(GraphOptimizationLevel::Level3)?
+            .commit_from_file(model_path)
+            .context("failed to create ONNX Runtime session")?;
+
+        let has_token_type_ids = session
+            .inputs
+            .iter()
+            .any(|input| input.name == "token_type_ids");
+
+        Ok(Self {
+            session: Mutex::new(session),
+            tokenizer,
+            pooling,
+            has_token_type_ids,
+        })
+    }
+
+    /// Wrap this embedder as an upstream `Embedder`.
+    pub fn into_embedder(self) -> Embedder {
+        Embedder::Text(TextEmbedder::Bert(Box::new(self)))
+    }
+
+    /// Run one batch through the session and return pooled vectors.
+    fn embed_batch(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts.to_vec(), true)
+            .map_err(|e| anyhow!("tokenization failed: {}", e))?;
+
+        let batch = encodings.len();
+        let seq_len = encodings.first().map(|e| e.get_ids().len()).unwrap_or(0);
+
+        let mut ids = Vec::with_capacity(batch * seq_len);
+        let mut mask = Vec::with_capacity(batch * seq_len);
+        let mut type_ids = Vec::with_capacity(batch * seq_len);
+        for encoding in &encodings {
+            ids.extend(encoding.get_ids().iter().map(|&v| v as i64));
+            mask.extend(encoding.get_attention_mask().iter().map(|&v| v as i64));
+            type_ids.extend(encoding.get_type_ids().iter().map(|&v| v as i64));
+        }
+
+        let shape = [batch, seq_len];
+        let mut session = self.session.lock().unwrap();
+        let outputs = if self.has_token_type_ids {
+            session.run(ort::inputs![
+                "input_ids" => Tensor::from_array((shape, ids))?,
+                "attention_mask" => Tensor::from_array((shape, mask.clone()))?,
+                "token_type_ids" => Tensor::from_array((shape, type_ids))?,
+            ])?
+        } else {
+            session.run(ort::inputs![
+                "input_ids" => Tensor::from_array((shape, ids))?,
+                "attention_mask" => Tensor::from_array((shape, mask.clone()))?,
+            ])?
+        };
+
+        let (output_shape, data) = outputs[0].try_extract_tensor::<f32>()?;
+        let dims: Vec<usize> = output_shape.iter().map(|&d| d as usize).collect();
+
+        match dims.as_slice() {
+            // Token-level output: [batch, seq, hidden]
+            [b, s, hidden] if *b == batch && *s == seq_len => Ok((0..batch)
+                .map(|i| {
+                    let tokens = &data[i * s * hidden..(i + 1) * s * hidden];
+                    let row_mask = &mask[i * s..(i + 1) * s];
+                    pool(tokens, row_mask, *hidden, self.pooling)
+                })
+                .collect()),
+            // Already pooled output: [batch, hidden]
+            [b, hidden] if *b == batch => {
+                Ok(data.chunks(*hidden).map(|row| row.to_vec()).collect())
+            }
+            _ => Err(anyhow!("unexpected ONNX output shape {:?}", dims)),
+        }
+    }
+}
+
+impl BertEmbed for OnnxEmbedder {
+    fn embed(
+        &self,
+        text_batch: &[&str],
+        batch_size: Option<usize>,
+        _late_chunking: Option<bool>,
+    ) -> anyhow::Result<Vec<EmbeddingResult>> {
+        let batch_size = batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1);
+        let mut results = Vec::with_capacity(text_batch.len());
+        for chunk in text_batch.chunks(batch_size) {
+            results.extend(
+                self.embed_batch(chunk)?
+                    .into_iter()
+                    .map(EmbeddingResult::DenseVector),
+            );
+        }
+        Ok(results)
+    }
+}
+
+/// Pool token embeddings (`seq_len * hidden`, row-major) into one vector.
+fn pool(tokens: &[f32], mask: &[i64], hidden: usize, pooling: Pooling) -> Vec<f32> {
+    match pooling {
+        Pooling::Cls => tokens[..hidden].to_vec(),
+        Pooling::Mean => {
+            let mut sum = vec![0.0f32; hidden];
+            let mut count = 0.0f32;
+            for (token, &m) in tokens.chunks(hidden).zip(mask) {
+                if m == 0 {
+                    continue;
+                }
+                for (acc, v) in sum.iter_mut().zip(token) {
+                    *acc += v;
+                }
+                count += 1.0;
+            }
+            if count > 0.0 {
+                sum.iter_mut().for_each(|v| *v /= count);
+            }
+            sum
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mean_pooling_ignores_padding() {
+        // 3 tokens x 2 hidden, last token is padding
+        let tokens = [1.0, 2.0, 3.0, 4.0, 100.0, 100.0];
+        let mask = [1, 1, 0];
+
+        let pooled = pool(&tokens, &mask, 2, Pooling::Mean);
+        assert_eq!(pooled, vec![2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_cls_pooling_takes_first_token() {
+        let tokens = [1.0, 2.0, 3.0, 4.0];
+        let mask = [1, 1];
+
+        let pooled = pool(&tokens, &mask, 2, Pooling::Cls);
+        assert_eq!(pooled, vec![1.0, 2.0]);
+    }
+}
@@ -0,0 +1,285 @@
+//! Per-embedder options supplied at load time.
+//!
+//! Options are stored on `CEmbedder` and applied by every embed function
+//! (sync and async): batch size is forwarded to the model, normalization is
+//! applied to the returned vectors. Sequence length and pooling are backend
+//! settings and are consumed by the loader; the ONNX and local backends
+//! honor them, upstream hub models reject them.
+
+use crate::CEmbedderOptions;
+use embed_anything::embeddings::embed::{EmbedData, EmbeddingResult};
+
+/// Pooling strategy applied to token-level model outputs.
+///
+/// The numeric values correspond to the `pooling` field of `CEmbedderOptions`
+/// (-1 keeps the model default).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum Pooling {
+    /// Attention-mask weighted mean over all tokens
+    Mean = 0,
+    /// First ([CLS]) token
+    Cls = 1,
+}
+
+impl Pooling {
+    /// Map the `pooling` FFI argument to a pooling strategy.
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Pooling::Mean),
+            1 => Some(Pooling::Cls),
+            _ => None,
+        }
+    }
+}
+
+/// Rust-side view of `CEmbedderOptions`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EmbedderOptions {
+    /// L2-normalize every returned vector (each row for multi-vector output)
+    pub normalize: bool,
+    /// Batch size passed to the model (None = model default)
+    pub batch_size: Option<usize>,
+    /// Maximum tokens per input (None = model default)
+    pub max_sequence_length: Option<usize>,
+    /// Pooling override (None = model default)
+    pub pooling: Option<Pooling>,
+}
+
+impl EmbedderOptions {
+    /// Convert options received over FFI. NULL yields the defaults.
+    ///
+    /// # Errors
+    /// - "INVALID_CONFIG:" if `pooling` is not -1, 0 or 1
+    pub fn from_c(options: Option<&CEmbedderOptions>) -> Result<Self, String> {
+        let Some(options) = options else {
+            return Ok(Self::default());
+        };
+
+        let pooling = match options.pooling {
+            -1 => None,
+            value => match Pooling::from_i32(value) {
+                Some(p) => Some(p),
+                None => return Err(format!("INVALID_CONFIG: pooling: invalid value {}", value)),
+            },
+        };
+
+        Ok(Self {
+            normalize: options.normalize != 0,
+            batch_size: (options.batch_size > 0).then_some(options.batch_size),
+            max_sequence_length: (options.max_sequence_length > 0)
+                .then_some(options.max_sequence_length),
+            pooling,
+        })
+    }
+
+    /// Reject settings that upstream hub models cannot honor.
+    ///
+    /// Upstream models fix their pooling and truncation internally, so only
+    /// the ONNX and local backends accept these overrides.
+    pub fn ensure_model_defaults(&self) -> Result<(), String> {
+        if self.pooling.is_some() {
+            return Err(
+                "INVALID_CONFIG: pooling: override is only supported by the ONNX and local backends"
+                    .to_string(),
+            );
+        }
+        if self.max_sequence_length.is_some() {
+            return Err(
+                "INVALID_CONFIG: max_sequence_length: override is only supported by the ONNX and local backends"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Batch size for file/directory embedding: the per-call value wins,
+    /// the embedder default is used when the call passes 0.
+    pub fn resolve_batch_size(&self, requested: usize) -> Option<usize> {
+        if requested > 0 {
+            Some(requested)
+        } else {
+            self.batch_size
+        }
+    }
+
+    /// Apply post-processing to a single embedding.
+    pub fn apply(&self, mut embedding: EmbeddingResult) -> EmbeddingResult {
+        if self.normalize {
+            match &mut embedding {
+                EmbeddingResult::DenseVector(vec) => l2_normalize(vec),
+                EmbeddingResult::MultiVector(rows) => {
+                    rows.iter_mut().for_each(|row| l2_normalize(row))
+                }
+            }
+        }
+        embedding
+    }
+
+    /// Apply post-processing to a plain dense vector.
+    pub fn apply_to_vector(&self, mut vector: Vec<f32>) -> Vec<f32> {
+        if self.normalize {
+            l2_normalize(&mut vector);
+        }
+        vector
+    }
+
+    /// Apply post-processing to raw model outputs.
+    pub fn apply_to_results(&self, results: Vec<EmbeddingResult>) -> Vec<EmbeddingResult> {
+        results.into_iter().map(|e| self.apply(e)).collect()
+    }
+
+    /// Apply post-processing to embedded chunks.
+    pub fn apply_to_data(&self, mut items: Vec<EmbedData>) -> Vec<EmbedData> {
+        if self.normalize {
+            for item in items.iter_mut() {
+                let embedding = std::mem::replace(
+                    &mut item.embedding,
+                    EmbeddingResult::DenseVector(Vec::new()),
+                );
+                item.embedding = self.apply(embedding);
+            }
+        }
+        items
+    }
+}
+
+/// Scale a vector to unit L2 norm in place (zero vectors are left unchanged).
+pub fn l2_normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c_options(
+        normalize: u8,
+        batch_size: usize,
+        max_len: usize,
+        pooling: i32,
+    ) -> CEmbedderOptions {
+        CEmbedderOptions {
+            normalize,
+            batch_size,
+            max_sequence_length: max_len,
+            pooling,
+        }
+    }
+
+    #[test]
+    fn test_pooling_from_i32() {
+        assert_eq!(Pooling::from_i32(0), Some(Pooling::Mean));
+        assert_eq!(Pooling::from_i32(1), Some(Pooling::Cls));
+        assert_eq!(Pooling::from_i32(7), None);
+    }
+
+    #[test]
+    fn test_from_c_null_is_default() {
+        assert_eq!(
+            EmbedderOptions::from_c(None),
+            Ok(EmbedderOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_from_c_maps_zero_to_model_default() {
+        // Arrange
+        let c = c_options(1, 0, 0, -1);
+
+        // Act
+        let options = EmbedderOptions::from_c(Some(&c)).unwrap();
+
+        // Assert
+        assert!(options.normalize);
+        assert_eq!(options.batch_size, None);
+        assert_eq!(options.max_sequence_length, None);
+        assert_eq!(options.pooling, None);
+    }
+
+    #[test]
+    fn test_from_c_invalid_pooling() {
+        let c = c_options(0, 16, 256, 9);
+
+        let err = EmbedderOptions::from_c(Some(&c)).unwrap_err();
+        assert!(err.starts_with("INVALID_CONFIG: pooling:"));
+    }
+
+    #[test]
+    fn test_ensure_model_defaults_rejects_backend_overrides() {
+        let pooled = EmbedderOptions::from_c(Some(&c_options(0, 0, 0, 1))).unwrap();
+        let truncated = EmbedderOptions::from_c(Some(&c_options(0, 0, 128, -1))).unwrap();
+        let plain = EmbedderOptions::from_c(Some(&c_options(1, 64, 0, -1))).unwrap();
+
+        assert!(pooled.ensure_model_defaults().is_err());
+        assert!(truncated.ensure_model_defaults().is_err());
+        assert!(plain.ensure_model_defaults().is_ok());
+    }
+
+    #[test]
+    fn test_resolve_batch_size() {
+        let options = EmbedderOptions {
+            batch_size: Some(64),
+            ..Default::default()
+        };
+
+        assert_eq!(options.resolve_batch_size(8), Some(8));
+        assert_eq!(options.resolve_batch_size(0), Some(64));
+        assert_eq!(EmbedderOptions::default().resolve_batch_size(0), None);
+    }
+
+    #[test]
+    fn test_apply_normalizes_dense_and_multi_vector() {
+        let options = EmbedderOptions {
+            normalize: true,
+            ..Default::default()
+        };
+
+        let dense = options.apply(EmbeddingResult::DenseVector(vec![3.0, 4.0]));
+        let multi = options.apply(EmbeddingResult::MultiVector(vec![
+            vec![0.0, 2.0],
+            vec![5.0, 0.0],
+        ]));
+
+        match dense {
+            EmbeddingResult::DenseVector(v) => assert_eq!(v, vec![0.6, 0.8]),
+            _ => panic!("expected dense vector"),
+        }
+        match multi {
+            EmbeddingResult::MultiVector(rows) => {
+                assert_eq!(rows, vec![vec![0.0, 1.0], vec![1.0, 0.0]])
+            }
+            _ => panic!("expected multi-vector"),
+        }
+    }
+
+    #[test]
+    fn test_apply_without_normalize_is_identity() {
+        let options = EmbedderOptions::default();
+
+        match options.apply(EmbeddingResult::DenseVector(vec![3.0, 4.0])) {
+            EmbeddingResult::DenseVector(v) => assert_eq!(v, vec![3.0, 4.0]),
+            _ => panic!("expected dense vector"),
+        }
+    }
+
+    #[test]
+    fn test_l2_normalize() {
+        let mut vector = vec![3.0, 4.0];
+        l2_normalize(&mut vector);
+        assert!((vector[0] - 0.6).abs() < 1e-6);
+        assert!((vector[1] - 0.8).abs() < 1e-6);
+
+        // Zero vector stays zero
+        let mut zero = vec![0.0, 0.0];
+        l2_normalize(&mut zero);
+        assert_eq!(zero, vec![0.0, 0.0]);
+    }
+}
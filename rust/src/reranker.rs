@@ -0,0 +1,403 @@
+//! Cross-encoder reranking.
+//!
+//! A cross-encoder scores each `(query, document)` pair jointly, which is
+//! slower than comparing embeddings but considerably more precise, so it is
+//! used to reorder the top hits of a vector search. Inference runs on ONNX
+//! Runtime: loading requires the `ort` feature, without it every loader
+//! fails with "INVALID_CONFIG:".
+//!
+//! # Error Prefixes
+//! - "RERANKER_NOT_FOUND:" - Model repo/directory or its ONNX/tokenizer files are missing
+//! - "UNAUTHORIZED:" - Hub returned 401/403 (private or gated model)
+//! - "RERANKER_LOAD_FAILED:" - Tokenizer or ONNX Runtime session could not be created
+//! - "RERANK_FAILED:" - Scoring failed
+
+use crate::model_loader::{self, hub_error_message, HubModel};
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "ort")]
+use anyhow::anyhow;
+#[cfg(feature = "ort")]
+use ort::{session::builder::GraphOptimizationLevel, session::Session, value::Tensor};
+#[cfg(feature = "ort")]
+use std::sync::Mutex;
+#[cfg(feature = "ort")]
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+/// Locations of the exported model, in lookup order.
+const MODEL_FILE_CANDIDATES: [&str; 2] = ["onnx/model.onnx", "model.onnx"];
+
+/// Tokenizer file expected next to the model.
+const TOKENIZER_FILE: &str = "tokenizer.json";
+
+/// Number of pairs scored per ONNX Runtime call.
+#[cfg(feature = "ort")]
+const SCORE_BATCH_SIZE: usize = 32;
+
+/// Maximum number of tokens per (query, document) pair.
+#[cfg(feature = "ort")]
+const MAX_SEQUENCE_LENGTH: usize = 512;
+
+/// Error returned by every loader when the library lacks the `ort` feature.
+const BACKEND_UNAVAILABLE: &str =
+    "INVALID_CONFIG: backend: library was built without the 'ort' feature";
+
+/// Cross-encoder reranker backed by an ONNX Runtime session.
+pub struct Reranker {
+    // ort 2.0 sessions need `&mut self` to run
+    #[cfg(feature = "ort")]
+    session: Mutex<Session>,
+    #[cfg(feature = "ort")]
+    tokenizer: Tokenizer,
+    #[cfg(feature = "ort")]
+    has_token_type_ids: bool,
+}
+
+impl Reranker {
+    /// Download (or reuse from the hub cache) a reranker from the HuggingFace Hub.
+    ///
+    /// The repo must contain `tokenizer.json` and an ONNX export at
+    /// `onnx/model.onnx` or `model.onnx`. `token` falls back to the
+    /// process-wide token set via `set_hf_token`.
+    pub fn from_pretrained_hf(
+        model_id: &str,
+        revision: Option<&str>,
+        token: Option<&str>,
+    ) -> Result<Self, String> {
+        use hf_hub::api::sync::ApiBuilder;
+        use hf_hub::{Repo, RepoType};
+
+        // Fail before downloading anything the library cannot run
+        if !cfg!(feature = "ort") {
+            return Err(BACKEND_UNAVAILABLE.to_string());
+        }
+
+        let api = ApiBuilder::new()
+            .with_token(model_loader::resolve_token(token))
+            .build()
+            .map_err(|e| hub_error_message(HubModel::Reranker, model_id, e))?;
+        let repo = match revision {
+            Some(rev) => {
+                Repo::with_revision(model_id.to_string(), RepoType::Model, rev.to_string())
+            }
+            None => Repo::model(model_id.to_string()),
+        };
+        let repo = api.repo(repo);
+
+        let tokenizer_path = repo
+            .get(TOKENIZER_FILE)
+            .map_err(|e| hub_error_message(HubModel::Reranker, model_id, e))?;
+
+        let mut last_error = None;
+        for candidate in MODEL_FILE_CANDIDATES {
+            match repo.get(candidate) {
+                Ok(model_path) => return Self::from_files(&model_path, &tokenizer_path),
+                Err(e) => last_error = Some(hub_error_message(HubModel::Reranker, model_id, e)),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| format!("RERANKER_NOT_FOUND: {}", model_id)))
+    }
+
+    /// Load a reranker from a local directory (no network access).
+    pub fn from_local_dir(root: &Path) -> Result<Self, String> {
+        if !root.is_dir() {
+            return Err(format!("RERANKER_NOT_FOUND: {}", root.display()));
+        }
+
+        let tokenizer_path = root.join(TOKENIZER_FILE);
+        if !tokenizer_path.is_file() {
+            return Err(format!(
+                "RERANKER_NOT_FOUND: {} is missing",
+                tokenizer_path.display()
+            ));
+        }
+
+        let model_path = find_model_file(root).ok_or_else(|| {
+            format!(
+                "RERANKER_NOT_FOUND: no ONNX model in {} (expected {})",
+                root.display(),
+                MODEL_FILE_CANDIDATES.join(" or ")
+            )
+        })?;
+
+        Self::from_files(&model_path, &tokenizer_path)
+    }
+
+    #[cfg(feature = "ort")]
+    fn from_files(model_path: &Path, tokenizer_path: &Path) -> Result<Self, String> {
+        let load = || -> anyhow::Result<Self> {
+            let mut tokenizer = Tokenizer::from_file(tokenizer_path)
+                .map_err(|e| anyhow!("failed to load tokenizer: {}", e))?;
+            tokenizer.with_padding(Some(PaddingParams {
+                strategy: PaddingStrategy::BatchLongest,
+                ..Default::default()
+            }));
+            tokenizer
+                .with_truncation(Some(TruncationParams {
+                    max_length: MAX_SEQUENCE_LENGTH,
+                    ..Default::default()
+                }))
+                .map_err(|e| anyhow!("failed to configure tokenizer: {}", e))?;
+
+            let session = Session::builder()?
+                .with_optimization_level(GraphOptimizationLevel::Level3)?
+                .commit_from_file(model_path)?;
+
+            let has_token_type_ids = session
+                .inputs
+                .iter()
+                .any(|input| input.name == "token_type_ids");
+
+            Ok(Self {
+                session: Mutex::new(session),
+                tokenizer,
+                has_token_type_ids,
+            })
+        };
+
+        load().map_err(|e| {
+            format!(
+                "RERANKER_LOAD_FAILED: Failed to load reranker '{}': {:#}",
+                model_path.display(),
+                e
+            )
+        })
+    }
+
+    #[cfg(not(feature = "ort"))]
+    fn from_files(_model_path: &Path, _tokenizer_path: &Path) -> Result<Self, String> {
+        Err(BACKEND_UNAVAILABLE.to_string())
+    }
+
+    /// Score every document against the query and return the `top_k` best
+    /// as `(index, score)` pairs, highest score first (`top_k == 0` keeps all).
+    pub fn rerank(
+        &self,
+        query: &str,
+        documents: &[&str],
+        top_k: usize,
+    ) -> Result<Vec<(usize, f32)>, String> {
+        let scores = self
+            .score(query, documents)
+            .map_err(|e| format!("RERANK_FAILED: {}", e))?;
+        Ok(rank(scores, top_k))
+    }
+
+    /// Relevance score for each document, in input order.
+    #[cfg(feature = "ort")]
+    fn score(&self, query: &str, documents: &[&str]) -> anyhow::Result<Vec<f32>> {
+        let mut scores = Vec::with_capacity(documents.len());
+        for chunk in documents.chunks(SCORE_BATCH_SIZE) {
+            scores.extend(self.score_batch(query, chunk)?);
+        }
+        Ok(scores)
+    }
+
+    #[cfg(not(feature = "ort"))]
+    fn score(&self, _query: &str, _documents: &[&str]) -> anyhow::Result<Vec<f32>> {
+        anyhow::bail!("library was built without the 'ort' feature")
+    }
+
+    #[cfg(feature = "ort")]
+    fn score_batch(&self, query: &str, documents: &[&str]) -> anyhow::Result<Vec<f32>> {
+        let pairs: Vec<(String, String)> = documents
+            .iter()
+            .map(|doc| (query.to_string(), doc.to_string()))
+            .collect();
+        let encodings = self
+            .tokenizer
+            .encode_batch(pairs, true)
+            .map_err(|e| anyhow!("tokenization failed: {}", e))?;
+
+        let batch = encodings.len();
+        let seq_len = encodings.first().map(|e| e.get_ids().len()).unwrap_or(0);
+
+        let mut ids = Vec::with_capacity(batch * seq_len);
+        let mut mask = Vec::with_capacity(batch * seq_len);
+        let mut type_ids = Vec::with_capacity(batch * seq_len);
+        for encoding in &encodings {
+            ids.extend(encoding.get_ids().iter().map(|&v| v as i64));
+            mask.extend(encoding.get_attention_mask().iter().map(|&v| v as i64));
+            type_ids.extend(encoding.get_type_ids().iter().map(|&v| v as i64));
+        }
+
+        let shape = [batch, seq_len];
+        let mut session = self.session.lock().unwrap();
+        let outputs = if self.has_token_type_ids {
+            session.run(ort::inputs![
+                "input_ids" => Tensor::from_array((shape, ids))?,
+                "attention_mask" => Tensor::from_array((shape, mask))?,
+                "token_type_ids" => Tensor::from_array((shape, type_ids))?,
+            ])?
+        } else {
+            session.run(ort::inputs![
+                "input_ids" => Tensor::from_array((shape, ids))?,
+                "attention_mask" => Tensor::from_array((shape, mask))?,
+            ])?
+        };
+
+        let (output_shape, data) = outputs[0].try_extract_tensor::<f32>()?;
+        let dims: Vec<usize> = output_shape.iter().map(|&d| d as usize).collect();
+
+        logits_to_scores(data, &dims, batch)
+            .ok_or_else(|| anyhow!("unexpected reranker output shape {:?}", dims))
+    }
+}
+
+/// First ONNX model file found under `root`, in `MODEL_FILE_CANDIDATES` order.
+fn find_model_file(root: &Path) -> Option<PathBuf> {
+    MODEL_FILE_CANDIDATES
+        .iter()
+        .map(|candidate| root.join(candidate))
+        .find(|path| path.is_file())
+}
+
+/// Convert raw model logits into one relevance score per pair.
+///
+/// `[batch]` and `[batch, 1]` outputs are used as-is (regression head);
+/// `[batch, n]` classification outputs yield the softmax probability of the
+/// last ("relevant") class. Returns None for any other shape.
+#[cfg_attr(not(feature = "ort"), allow(dead_code))]
+fn logits_to_scores(data: &[f32], dims: &[usize], batch: usize) -> Option<Vec<f32>> {
+    match dims {
+        [b] | [b, 1] if *b == batch => Some(data.to_vec()),
+        [b, classes] if *b == batch && *classes > 1 => Some(
+            data.chunks(*classes)
+                .map(|row| {
+                    let max = row.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+                    let sum: f32 = row.iter().map(|v| (v - max).exp()).sum();
+                    (row[classes - 1] - max).exp() / sum
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Sort `(index, score)` pairs by descending score and keep the first
+/// `top_k` (`0` keeps all). Ties keep input order.
+fn rank(scores: Vec<f32>, top_k: usize) -> Vec<(usize, f32)> {
+    let mut ranked: Vec<(usize, f32)> = scores.into_iter().enumerate().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    if top_k > 0 {
+        ranked.truncate(top_k);
+    }
+    ranked
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "embedanything_reranker_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_rank_orders_by_score_and_truncates() {
+        // Arrange
+        let scores = vec![0.1, 0.9, 0.5, 0.7];
+
+        // Act
+        let ranked = rank(scores, 2);
+
+        // Assert
+        assert_eq!(ranked, vec![(1, 0.9), (3, 0.7)]);
+    }
+
+    #[test]
+    fn test_rank_top_k_zero_keeps_all_and_ties_are_stable() {
+        let ranked = rank(vec![0.5, 0.8, 0.5], 0);
+
+        assert_eq!(ranked, vec![(1, 0.8), (0, 0.5), (2, 0.5)]);
+    }
+
+    #[test]
+    fn test_rank_top_k_larger_than_input() {
+        assert_eq!(rank(vec![0.3], 10), vec![(0, 0.3)]);
+    }
+
+    #[test]
+    fn test_logits_to_scores_regression_head() {
+        assert_eq!(
+            logits_to_scores(&[1.5, -2.0], &[2, 1], 2),
+            Some(vec![1.5, -2.0])
+        );
+        assert_eq!(logits_to_scores(&[0.3], &[1], 1), Some(vec![0.3]));
+    }
+
+    #[test]
+    fn test_logits_to_scores_classification_head() {
+        let scores = logits_to_scores(&[0.0, 0.0, 0.0, 10.0], &[2, 2], 2).unwrap();
+
+        assert!((scores[0] - 0.5).abs() < 1e-6);
+        assert!(scores[1] > 0.99);
+    }
+
+    #[test]
+    fn test_logits_to_scores_unexpected_shape() {
+        assert_eq!(logits_to_scores(&[0.0; 6], &[2, 3, 1], 2), None);
+        assert_eq!(logits_to_scores(&[0.0; 2], &[2, 1], 3), None);
+    }
+
+    #[test]
+    fn test_from_local_dir_missing_directory() {
+        let err = Reranker::from_local_dir(Path::new("/nonexistent/reranker"))
+            .err()
+            .unwrap();
+
+        assert!(err.starts_with("RERANKER_NOT_FOUND:"));
+    }
+
+    #[test]
+    fn test_from_local_dir_missing_model_file() {
+        // Arrange
+        let dir = scratch_dir("missing_model");
+        fs::write(dir.join(TOKENIZER_FILE), "{}").unwrap();
+
+        // Act
+        let err = Reranker::from_local_dir(&dir).err().unwrap();
+
+        // Assert
+        assert!(err.starts_with("RERANKER_NOT_FOUND:"));
+        assert!(err.contains("model.onnx"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_find_model_file_prefers_onnx_subdirectory() {
+        let dir = scratch_dir("find_model");
+        fs::create_dir_all(dir.join("onnx")).unwrap();
+        fs::write(dir.join("model.onnx"), b"").unwrap();
+        fs::write(dir.join("onnx/model.onnx"), b"").unwrap();
+
+        assert_eq!(find_model_file(&dir), Some(dir.join("onnx/model.onnx")));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_hub_error_message() {
+        let status_error = |code| {
+            let response = ureq::Response::new(code, "status", "").unwrap();
+            hf_hub::api::sync::ApiError::RequestError(Box::new(ureq::Error::Status(code, response)))
+        };
+        let message = |error: anyhow::Error| hub_error_message(HubModel::Reranker, "a/b", error);
+
+        assert!(message(status_error(404).into()).starts_with("RERANKER_NOT_FOUND: a/b"));
+        assert!(message(status_error(401).into()).starts_with("UNAUTHORIZED:"));
+        assert!(message(anyhow::anyhow!("timeout")).starts_with("RERANKER_LOAD_FAILED:"));
+    }
+}
@@ -0,0 +1,327 @@
+//! Image embedding with CLIP-style vision models (CLIP, SigLIP).
+//!
+//! Vision checkpoints load as upstream `Embedder::Vision`. Images are
+//! embedded through the image tower; text queries go through the text tower
+//! (`embed_query`) so they land in the same space and can be compared
+//! directly against image vectors.
+
+use embed_anything::embeddings::embed::{EmbedData, EmbedImage, Embedder, EmbeddingResult};
+use embed_anything::Dtype;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::directory::normalize_extension;
+use crate::model_loader::{self, HubModel};
+use crate::options::EmbedderOptions;
+
+/// Image file extensions routed to the vision pipeline (lowercase).
+pub const IMAGE_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "webp", "gif", "bmp"];
+
+/// Images per inference call when walking a directory without a batch size.
+pub const DEFAULT_IMAGE_BATCH_SIZE: usize = 32;
+
+/// Counter for unique scratch file names in `embed_image_bytes`.
+static NEXT_SCRATCH_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Load a CLIP/SigLIP checkpoint from the HuggingFace Hub.
+///
+/// Fails with "INVALID_CONFIG:" if the checkpoint is not a vision model.
+pub fn load_from_hf(
+    model_id: &str,
+    revision: Option<&str>,
+    token: Option<&str>,
+    dtype: Option<Dtype>,
+) -> Result<Embedder, String> {
+    let token = model_loader::resolve_token(token);
+    let embedder = Embedder::from_pretrained_hf(model_id, revision, token.as_deref(), dtype)
+        .map_err(|e| model_loader::hub_error_message(HubModel::Embedder, model_id, e))?;
+
+    if !is_vision(&embedder) {
+        return Err(format!(
+            "INVALID_CONFIG: model_id: '{}' is not a CLIP/SigLIP vision checkpoint",
+            model_id
+        ));
+    }
+    Ok(embedder)
+}
+
+/// Whether an embedder can embed images.
+pub fn is_vision(embedder: &Embedder) -> bool {
+    matches!(embedder, Embedder::Vision(_))
+}
+
+/// Whether a path has one of the supported image extensions.
+pub fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Embed image files, one vector per path (input order).
+///
+/// # Errors
+/// - "INVALID_CONFIG:" if the embedder is not a vision model
+/// - "FILE_NOT_FOUND:" if a path does not exist
+/// - "UNSUPPORTED_FORMAT:" if a path is not a supported image type
+/// - "MULTI_VECTOR:" if the model returns multi-vector embeddings
+/// - "EMBEDDING_FAILED:" if decoding or inference fails
+pub fn embed_image_files(embedder: &Embedder, paths: &[PathBuf]) -> Result<Vec<Vec<f32>>, String> {
+    let Embedder::Vision(vision) = embedder else {
+        return Err(not_vision_error());
+    };
+
+    for path in paths {
+        if !path.exists() {
+            return Err(format!("FILE_NOT_FOUND: {}", path.display()));
+        }
+        if !is_image_path(path) {
+            let extension = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("unknown");
+            return Err(format!(
+                "UNSUPPORTED_FORMAT: {} for {}",
+                extension,
+                path.display()
+            ));
+        }
+    }
+
+    let embed_data = vision
+        .embed_image_batch(paths)
+        .map_err(|e| format!("EMBEDDING_FAILED: Image embedding failed: {}", e))?;
+
+    embed_data
+        .into_iter()
+        .map(|data| dense_vector(data.embedding))
+        .collect()
+}
+
+/// Embed an encoded image held in memory (PNG, JPEG, WebP, GIF or BMP).
+///
+/// The bytes are written to a scratch file because the upstream image
+/// pipeline decodes from disk; the file is removed afterwards.
+///
+/// # Errors
+/// Same as `embed_image_files`, plus "UNSUPPORTED_FORMAT:" if the bytes
+/// are not a recognised image encoding.
+pub fn embed_image_bytes(embedder: &Embedder, bytes: &[u8]) -> Result<Vec<f32>, String> {
+    if !is_vision(embedder) {
+        return Err(not_vision_error());
+    }
+
+    let extension = sniff_image_extension(bytes)
+        .ok_or_else(|| "UNSUPPORTED_FORMAT: unknown for <image bytes>".to_string())?;
+
+    let scratch = std::env::temp_dir().join(format!(
+        "embedanything_image_{}_{}.{}",
+        std::process::id(),
+        NEXT_SCRATCH_ID.fetch_add(1, Ordering::Relaxed),
+        extension
+    ));
+    std::fs::write(&scratch, bytes)
+        .map_err(|e| format!("FILE_READ_ERROR: {}: {}", scratch.display(), e))?;
+
+    let result = embed_image_files(embedder, std::slice::from_ref(&scratch));
+    let _ = std::fs::remove_file(&scratch);
+
+    result.map(|mut vectors| vectors.swap_remove(0))
+}
+
+/// Embed a text query into the shared text-image space.
+///
+/// # Errors
+/// - "INVALID_CONFIG:" if the embedder is not a vision model
+/// - "MULTI_VECTOR:" if the model returns multi-vector embeddings
+/// - "EMBEDDING_FAILED:" if inference fails
+pub async fn embed_text_query(embedder: &Embedder, text: &str) -> Result<Vec<f32>, String> {
+    if !is_vision(embedder) {
+        return Err(not_vision_error());
+    }
+
+    let embed_data = embedder
+        .embed_query(&[text], None)
+        .await
+        .map_err(|e| format!("EMBEDDING_FAILED: Image query embedding failed: {}", e))?;
+
+    match embed_data.into_iter().next() {
+        Some(data) => dense_vector(data.embedding),
+        None => Err("EMBEDDING_FAILED: embed_query returned empty result".to_string()),
+    }
+}
+
+/// Extension filter of a directory walk, split between the two pipelines.
+#[derive(Debug, PartialEq)]
+pub struct ExtensionRouting {
+    /// Filter for the text pipeline (`None` keeps the upstream defaults).
+    pub text: Option<Vec<String>>,
+    /// Whether the text pipeline has anything to do.
+    pub run_text: bool,
+    /// Normalized image extensions to collect for the vision pipeline.
+    pub images: Vec<String>,
+}
+
+/// Route a directory extension filter (e.g. `[".pdf", ".png"]`).
+///
+/// Image extensions go to the vision pipeline and everything else stays
+/// with the text pipeline. Without a filter every image type is collected.
+pub fn route_extensions(extensions: Option<Vec<String>>) -> ExtensionRouting {
+    let Some(extensions) = extensions else {
+        return ExtensionRouting {
+            text: None,
+            run_text: true,
+            images: IMAGE_EXTENSIONS.iter().map(|ext| ext.to_string()).collect(),
+        };
+    };
+
+    let (images, text): (Vec<String>, Vec<String>) = extensions
+        .into_iter()
+        .partition(|ext| IMAGE_EXTENSIONS.contains(&normalize_extension(ext).as_str()));
+
+    ExtensionRouting {
+        run_text: !text.is_empty(),
+        text: Some(text),
+        images: images.iter().map(|ext| normalize_extension(ext)).collect(),
+    }
+}
+
+/// Embed image files as directory entries.
+///
+/// Each image becomes one entry without text, with `file_path` and
+/// `modality: image` metadata. `options` are the vision embedder's options.
+pub fn embed_images_as_data(
+    embedder: &Embedder,
+    options: EmbedderOptions,
+    paths: &[PathBuf],
+) -> Result<Vec<EmbedData>, String> {
+    let vectors = embed_image_files(embedder, paths)?;
+
+    Ok(paths
+        .iter()
+        .zip(vectors)
+        .map(|(path, vector)| {
+            let metadata = HashMap::from([
+                ("file_path".to_string(), path.display().to_string()),
+                ("modality".to_string(), "image".to_string()),
+            ]);
+            EmbedData::new(
+                EmbeddingResult::DenseVector(options.apply_to_vector(vector)),
+                None,
+                Some(metadata),
+            )
+        })
+        .collect())
+}
+
+/// Detect the image encoding from its magic bytes.
+pub fn sniff_image_extension(bytes: &[u8]) -> Option<&'static str> {
+    match bytes {
+        [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some("png"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("jpg"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("webp"),
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some("gif"),
+        [b'B', b'M', ..] => Some("bmp"),
+        _ => None,
+    }
+}
+
+fn dense_vector(embedding: EmbeddingResult) -> Result<Vec<f32>, String> {
+    match embedding {
+        EmbeddingResult::DenseVector(vec) => Ok(vec),
+        EmbeddingResult::MultiVector(_) => {
+            Err("MULTI_VECTOR: Multi-vector image embeddings are not supported".to_string())
+        }
+    }
+}
+
+fn not_vision_error() -> String {
+    "INVALID_CONFIG: embedder: not a vision model (load it with vision_embedder_from_pretrained_hf)"
+        .to_string()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_image_extension() {
+        assert_eq!(
+            sniff_image_extension(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0]),
+            Some("png")
+        );
+        assert_eq!(
+            sniff_image_extension(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some("jpg")
+        );
+        assert_eq!(sniff_image_extension(b"RIFF\0\0\0\0WEBPVP8 "), Some("webp"));
+        assert_eq!(sniff_image_extension(b"GIF89a"), Some("gif"));
+        assert_eq!(sniff_image_extension(b"BM\0\0"), Some("bmp"));
+    }
+
+    #[test]
+    fn test_sniff_image_extension_rejects_other_data() {
+        assert_eq!(sniff_image_extension(b""), None);
+        assert_eq!(sniff_image_extension(b"%PDF-1.7"), None);
+        assert_eq!(sniff_image_extension(b"RIFF\0\0\0\0WAVE"), None);
+    }
+
+    #[test]
+    fn test_is_image_path() {
+        assert!(is_image_path(Path::new("photo.PNG")));
+        assert!(is_image_path(Path::new("/a/b/scan.jpeg")));
+        assert!(is_image_path(Path::new("x.webp")));
+        assert!(!is_image_path(Path::new("notes.txt")));
+        assert!(!is_image_path(Path::new("no_extension")));
+    }
+
+    #[test]
+    fn test_route_extensions_without_filter() {
+        let routing = route_extensions(None);
+
+        assert_eq!(routing.text, None);
+        assert!(routing.run_text);
+        assert_eq!(routing.images.len(), IMAGE_EXTENSIONS.len());
+    }
+
+    #[test]
+    fn test_route_extensions_splits_filter() {
+        let routing = route_extensions(Some(vec![
+            ".pdf".to_string(),
+            ".PNG".to_string(),
+            "jpg".to_string(),
+            ".md".to_string(),
+        ]));
+
+        assert_eq!(
+            routing.text,
+            Some(vec![".pdf".to_string(), ".md".to_string()])
+        );
+        assert!(routing.run_text);
+        assert_eq!(routing.images, vec!["png".to_string(), "jpg".to_string()]);
+    }
+
+    #[test]
+    fn test_route_extensions_images_only_skips_text() {
+        let routing = route_extensions(Some(vec![".webp".to_string()]));
+
+        assert!(!routing.run_text);
+        assert_eq!(routing.images, vec!["webp".to_string()]);
+    }
+
+    #[test]
+    fn test_dense_vector_rejects_multi_vector() {
+        assert_eq!(
+            dense_vector(EmbeddingResult::DenseVector(vec![1.0])),
+            Ok(vec![1.0])
+        );
+        assert!(dense_vector(EmbeddingResult::MultiVector(vec![vec![1.0]]))
+            .unwrap_err()
+            .starts_with("MULTI_VECTOR:"));
+    }
+}
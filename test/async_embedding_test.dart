@@ -1,6 +1,7 @@
 import 'dart:io';
 import 'package:test/test.dart';
 import 'package:embedanythingindart/embedanythingindart.dart';
+import 'package:embedanythingindart/src/ffi/async_bindings.dart' as async_ffi;
 
 /// Tests for async embedding operations.
 ///
@@ -32,6 +33,28 @@ void main() {
         throwsA(isA<EmbedAnythingError>()),
       );
     });
+
+    test('fromPretrainedHfAsync honors the requested architecture', () async {
+      // all-MiniLM-L6-v2 declares BertModel, so requesting Jina must fail
+      expect(
+        () => EmbedAnything.fromPretrainedHfAsync(
+          modelId: 'sentence-transformers/all-MiniLM-L6-v2',
+          model: EmbeddingModel.jina,
+        ),
+        throwsA(isA<InvalidConfigError>()),
+      );
+    });
+
+    test('fromPretrainedHfAsync loads with an explicit architecture', () async {
+      final embedder = await EmbedAnything.fromPretrainedHfAsync(
+        modelId: 'sentence-transformers/all-MiniLM-L6-v2',
+        model: EmbeddingModel.bert,
+      );
+
+      expect(embedder.config!.modelType, equals(EmbeddingModel.bert));
+
+      embedder.dispose();
+    });
   });
 
   group('Async Text Embedding', () {
@@ -187,6 +210,75 @@ void main() {
     });
   });
 
+  group('Async Registry Maintenance', () {
+    late EmbedAnything embedder;
+
+    setUpAll(() async {
+      embedder = await EmbedAnything.fromPretrainedHfAsync(
+        modelId: 'sentence-transformers/all-MiniLM-L6-v2',
+      );
+    });
+
+    tearDownAll(() {
+      embedder.dispose();
+    });
+
+    test('discarding a pending operation completes its future', () async {
+      final operation = embedder.startEmbedTextAsync('Test text');
+
+      expect(async_ffi.discardAsyncOperation(operation.operationId), equals(0));
+
+      // The waiter is notified instead of hanging; depending on timing the
+      // result was discarded while running (cancelled) or after finishing
+      await expectLater(
+        operation.future.timeout(const Duration(seconds: 30)),
+        throwsA(isA<EmbedAnythingError>()),
+      );
+    });
+  });
+
+  group('Async Priority', () {
+    late EmbedAnything embedder;
+
+    setUpAll(() async {
+      embedder = await EmbedAnything.fromPretrainedHfAsync(
+        modelId: 'sentence-transformers/all-MiniLM-L6-v2',
+      );
+    });
+
+    tearDownAll(() {
+      embedder.dispose();
+    });
+
+    test('embedTextAsync accepts a priority', () async {
+      final result = await embedder.embedTextAsync(
+        'Background query',
+        priority: OperationPriority.background,
+      );
+
+      expect(result.dimension, equals(384));
+    });
+
+    test('setPriority is rejected once the operation finished', () async {
+      final operation = embedder.startEmbedTextAsync(
+        'Test text',
+        priority: OperationPriority.interactive,
+      );
+      await operation.future;
+
+      expect(operation.setPriority(OperationPriority.background), isFalse);
+    });
+
+    test('embedTextAsync finishes within a generous timeout', () async {
+      final result = await embedder.embedTextAsync(
+        'Bounded query',
+        timeout: const Duration(minutes: 5),
+      );
+
+      expect(result.dimension, equals(384));
+    });
+  });
+
   group('Error Handling', () {
     test('embedTextAsync throws after dispose', () async {
       final embedder = await EmbedAnything.fromPretrainedHfAsync(
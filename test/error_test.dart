@@ -76,6 +76,8 @@ void main() {
           EmbeddingFailedError(reason: 'test'), isA<EmbedAnythingError>());
       expect(MultiVectorNotSupportedError(), isA<EmbedAnythingError>());
       expect(FFIError(operation: 'test'), isA<EmbedAnythingError>());
+      expect(UnauthorizedError(modelId: 'test', reason: 'test'),
+          isA<EmbedAnythingError>());
     });
 
     test('Phase 3 errors implement Exception', () {
@@ -115,6 +117,9 @@ void main() {
         UnsupportedFileFormatError() => 'Unsupported file format error',
         FileReadError() => 'File read error',
         EmbeddingCancelledError() => 'Embedding cancelled error',
+        UnauthorizedError() => 'Unauthorized error',
+        ModelMismatchError() => 'Model mismatch error',
+        OperationTimeoutError() => 'Operation timeout error',
       };
 
       expect(message, equals('Model not found error'));
@@ -1,4 +1,5 @@
 import 'dart:ffi';
+import 'package:embedanythingindart/src/errors.dart';
 import 'package:embedanythingindart/src/ffi/native_types.dart';
 import 'package:embedanythingindart/src/ffi/ffi_utils.dart';
 import 'package:ffi/ffi.dart';
@@ -143,5 +144,46 @@ void main() {
 
       freeStringArray(arrayPtr, strings.length);
     });
+
+    test('throwErrorMessage maps async error prefixes to typed errors', () {
+      expect(
+        () => throwErrorMessage('INVALID_CONFIG: model_type: expected bert'),
+        throwsA(isA<InvalidConfigError>()
+            .having((e) => e.field, 'field', 'model_type')),
+      );
+      expect(
+        () => throwErrorMessage('FILE_NOT_FOUND: /missing.txt'),
+        throwsA(isA<FileNotFoundError>()),
+      );
+    });
+
+    test('throwErrorMessage maps UNAUTHORIZED to UnauthorizedError', () {
+      expect(
+        () => throwErrorMessage(
+            "UNAUTHORIZED: Access to 'my-org/private' was denied; a valid "
+            'HuggingFace token is required: status code 401'),
+        throwsA(isA<UnauthorizedError>()
+            .having((e) => e.modelId, 'modelId', 'my-org/private')
+            .having((e) => e.reason, 'reason', contains('401'))),
+      );
+    });
+
+    test('throwErrorMessage maps MODEL_MISMATCH to ModelMismatchError', () {
+      expect(
+        () => throwErrorMessage(
+            'MODEL_MISMATCH: index was built with org/a, embedder is org/b'),
+        throwsA(isA<ModelMismatchError>()
+            .having((e) => e.reason, 'reason', startsWith('index was built'))),
+      );
+    });
+
+    test('throwErrorMessage maps TIMEOUT to OperationTimeoutError', () {
+      expect(
+        () => throwErrorMessage(
+            'TIMEOUT: operation 7 did not finish within 50 ms'),
+        throwsA(isA<OperationTimeoutError>()
+            .having((e) => e.reason, 'reason', contains('50 ms'))),
+      );
+    });
   });
 }
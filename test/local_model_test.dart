@@ -0,0 +1,137 @@
+import 'dart:io';
+
+import 'package:embedanythingindart/embedanythingindart.dart';
+import 'package:test/test.dart';
+
+const _modelId = 'sentence-transformers/all-MiniLM-L6-v2';
+
+/// Snapshot directory of [modelId] in the local HuggingFace cache, or null
+/// if no complete snapshot has been downloaded.
+String? _cachedSnapshot(String modelId) {
+  final hfHome = Platform.environment['HF_HOME'] ??
+      '${Platform.environment['HOME']}/.cache/huggingface';
+  final snapshots = Directory(
+    '$hfHome/hub/models--${modelId.replaceAll('/', '--')}/snapshots',
+  );
+  if (!snapshots.existsSync()) {
+    return null;
+  }
+
+  for (final dir in snapshots.listSync().whereType<Directory>()) {
+    final complete = ['config.json', 'tokenizer.json', 'model.safetensors']
+        .every((name) => File('${dir.path}/$name').existsSync());
+    if (complete) {
+      return dir.path;
+    }
+  }
+  return null;
+}
+
+void main() {
+  group('Local Model Loading', () {
+    late EmbedAnything hubEmbedder;
+    String? snapshot;
+
+    setUpAll(() {
+      // Loading from the hub fills the cache the local tests read from
+      hubEmbedder = EmbedAnything.fromPretrainedHf(
+        model: EmbeddingModel.bert,
+        modelId: _modelId,
+      );
+      snapshot = _cachedSnapshot(_modelId);
+    });
+
+    tearDownAll(() {
+      hubEmbedder.dispose();
+    });
+
+    test('fromLocalPath matches the hub model', () {
+      if (snapshot == null) {
+        markTestSkipped('No cached snapshot of $_modelId');
+        return;
+      }
+
+      final embedder = EmbedAnything.fromLocalPath(snapshot!);
+      try {
+        final local = embedder.embedText('Hello, world!');
+        final hub = hubEmbedder.embedText('Hello, world!');
+
+        expect(local.dimension, equals(384));
+        expect(local.cosineSimilarity(hub), greaterThan(0.999));
+      } finally {
+        embedder.dispose();
+      }
+    });
+
+    test('fromLocalPathAsync loads the same model', () async {
+      if (snapshot == null) {
+        markTestSkipped('No cached snapshot of $_modelId');
+        return;
+      }
+
+      final embedder = await EmbedAnything.fromLocalPathAsync(snapshot!);
+      try {
+        final result = await embedder.embedTextAsync('Hello, world!');
+
+        expect(result.dimension, equals(384));
+      } finally {
+        embedder.dispose();
+      }
+    });
+
+    test('fromLocalPath honors pooling and max sequence length', () {
+      if (snapshot == null) {
+        markTestSkipped('No cached snapshot of $_modelId');
+        return;
+      }
+
+      final embedder = EmbedAnything.fromLocalPath(
+        snapshot!,
+        pooling: PoolingStrategy.cls,
+        maxSequenceLength: 4,
+      );
+      try {
+        final long = 'one two three four five six seven eight nine ten';
+        final local = embedder.embedText(long);
+        final hub = hubEmbedder.embedText(long);
+
+        // Truncated [CLS] vectors differ from the full mean-pooled ones
+        expect(local.dimension, equals(384));
+        expect(local.cosineSimilarity(hub), lessThan(0.99));
+        // Only the first tokens are seen
+        expect(
+          embedder.embedText('one two three four').cosineSimilarity(local),
+          greaterThan(0.999),
+        );
+      } finally {
+        embedder.dispose();
+      }
+    });
+
+    test('missing directory throws FileNotFoundError', () {
+      expect(
+        () => EmbedAnything.fromLocalPath('/nonexistent/model/dir'),
+        throwsA(isA<FileNotFoundError>()),
+      );
+    });
+
+    test('directory without a tokenizer throws FileNotFoundError', () async {
+      final dir = Directory.systemTemp.createTempSync('local_model_test');
+      try {
+        File('${dir.path}/config.json')
+            .writeAsStringSync('{"architectures": ["BertModel"]}');
+
+        await expectLater(
+          EmbedAnything.fromLocalPathAsync(dir.path),
+          throwsA(isA<FileNotFoundError>().having(
+            (e) => e.path,
+            'path',
+            endsWith('tokenizer.json'),
+          )),
+        );
+      } finally {
+        dir.deleteSync(recursive: true);
+      }
+    });
+  });
+}
@@ -0,0 +1,114 @@
+import 'dart:io';
+
+import 'package:embedanythingindart/embedanythingindart.dart';
+import 'package:test/test.dart';
+
+void main() {
+  group('MultiVectorEmbedding', () {
+    test('fromRowMajor splits the buffer into token rows', () {
+      final embedding = MultiVectorEmbedding.fromRowMajor(
+        [1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+        3,
+      );
+
+      expect(embedding.length, equals(3));
+      expect(embedding.dimension, equals(2));
+      expect(embedding.rows[1].values, equals([3.0, 4.0]));
+    });
+
+    test('fromRowMajor rejects a buffer that does not divide evenly', () {
+      expect(
+        () => MultiVectorEmbedding.fromRowMajor([1.0, 2.0, 3.0], 2),
+        throwsArgumentError,
+      );
+    });
+
+    test('mean averages the token vectors', () {
+      final embedding = MultiVectorEmbedding.fromRowMajor(
+        [1.0, 0.0, 3.0, 4.0],
+        2,
+      );
+
+      expect(embedding.mean().values, equals([2.0, 2.0]));
+    });
+
+    test('maxSim sums the best match of each query token', () {
+      const query = MultiVectorEmbedding([
+        EmbeddingResult([1.0, 0.0]),
+        EmbeddingResult([0.0, 1.0]),
+      ]);
+      const doc = MultiVectorEmbedding([
+        EmbeddingResult([0.5, 0.0]),
+        EmbeddingResult([0.0, 0.25]),
+        EmbeddingResult([0.1, 0.1]),
+      ]);
+
+      expect(query.maxSim(doc), closeTo(0.5 + 0.25, 1e-9));
+    });
+
+    test('maxSim rejects mismatched dimensions', () {
+      const a = MultiVectorEmbedding([
+        EmbeddingResult([1.0, 0.0]),
+      ]);
+      const b = MultiVectorEmbedding([
+        EmbeddingResult([1.0, 0.0, 0.0]),
+      ]);
+
+      expect(() => a.maxSim(b), throwsArgumentError);
+    });
+  });
+
+  group('Multi-Vector Embedding on a dense model', () {
+    late EmbedAnything embedder;
+
+    setUpAll(() {
+      embedder = EmbedAnything.fromConfig(ModelConfig.bertMiniLML6());
+    });
+
+    tearDownAll(() {
+      embedder.dispose();
+    });
+
+    test('embedTextMultiVector returns a single row', () {
+      final multi = embedder.embedTextMultiVector('Hello, world!');
+      final dense = embedder.embedText('Hello, world!');
+
+      expect(multi.length, equals(1));
+      expect(multi.dimension, equals(384));
+      expect(multi.rows.first.cosineSimilarity(dense), greaterThan(0.999));
+    });
+
+    test('sync and async batch results agree', () async {
+      final texts = ['First text', 'Second text'];
+      final sync = embedder.embedTextsBatchMultiVector(texts);
+      final async = await embedder.embedTextsBatchMultiVectorAsync(texts);
+
+      expect(sync, hasLength(2));
+      expect(async, hasLength(2));
+      for (int i = 0; i < texts.length; i++) {
+        expect(async[i].length, equals(sync[i].length));
+        expect(
+          async[i].rows.first.cosineSimilarity(sync[i].rows.first),
+          greaterThan(0.999),
+        );
+      }
+    });
+
+    test('embedTextMultiVectorAsync returns a single row', () async {
+      final multi = await embedder.embedTextMultiVectorAsync('Hello');
+
+      expect(multi.length, equals(1));
+    });
+
+    test('file chunks from a dense model have no multiVector', () async {
+      final path = '${Directory.current.path}/test/fixtures/sample.txt';
+      final chunks = await embedder.embedFile(path);
+
+      expect(chunks, isNotEmpty);
+      for (final chunk in chunks) {
+        expect(chunk.multiVector, isNull);
+        expect(chunk.embedding.dimension, equals(384));
+      }
+    });
+  });
+}
@@ -0,0 +1,24 @@
+import 'package:embedanythingindart/embedanythingindart.dart';
+import 'package:test/test.dart';
+
+void main() {
+  group('ONNX Embedder', () {
+    test('PoolingStrategy values match the native options', () {
+      expect(PoolingStrategy.modelDefault.value, equals(-1));
+      expect(PoolingStrategy.mean.value, equals(0));
+      expect(PoolingStrategy.cls.value, equals(1));
+    });
+
+    test('fromOnnx with a missing model throws a typed error', () {
+      // FileNotFoundError with the `ort` feature, InvalidConfigError without
+      expect(
+        () => EmbedAnything.fromOnnx(
+          '/nonexistent/model.onnx',
+          '/nonexistent/tokenizer.json',
+          pooling: PoolingStrategy.cls,
+        ),
+        throwsA(anyOf(isA<FileNotFoundError>(), isA<InvalidConfigError>())),
+      );
+    });
+  });
+}
@@ -217,6 +217,21 @@ void main() {
           reason: 'Should process at least 2 .md files');
     });
 
+    test('cancelling the subscription stops the walk', () async {
+      // Arrange
+      final dirPath = '$fixturesPath${Platform.pathSeparator}sample_dir';
+
+      // Act: take the first chunk, which cancels the subscription
+      final first = await embedder.embedDirectory(dirPath, chunkSize: 500).first;
+
+      // Assert: the embedder is free for the next call
+      expect(first.embedding.dimension, equals(384));
+      final chunks = await embedder
+          .embedDirectory(dirPath, extensions: ['.md'], chunkSize: 500)
+          .toList();
+      expect(chunks, isNotEmpty);
+    });
+
     test('throws FileNotFoundError for non-existent directory', () async {
       // Arrange
       final nonExistentDir =
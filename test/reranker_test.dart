@@ -0,0 +1,74 @@
+import 'package:embedanythingindart/embedanythingindart.dart';
+import 'package:test/test.dart';
+
+const _documents = [
+  'A recipe for tomato pasta',
+  'Machine learning is a subset of artificial intelligence',
+  'The weather in Paris is mild in spring',
+];
+
+void main() {
+  group('Reranker loading', () {
+    test('fromLocalPath with a missing directory throws a typed error', () {
+      // ModelNotFoundError with the `ort` feature, InvalidConfigError without
+      expect(
+        () => Reranker.fromLocalPath('/nonexistent/reranker'),
+        throwsA(anyOf(isA<ModelNotFoundError>(), isA<InvalidConfigError>())),
+      );
+    });
+
+    test('fromLocalPathAsync with a missing directory throws a typed error',
+        () async {
+      await expectLater(
+        Reranker.fromLocalPathAsync('/nonexistent/reranker'),
+        throwsA(anyOf(isA<ModelNotFoundError>(), isA<InvalidConfigError>())),
+      );
+    });
+  });
+
+  group('Reranker scoring', () {
+    late Reranker reranker;
+
+    setUpAll(() async {
+      reranker = await Reranker.fromPretrainedHfAsync(
+        modelId: 'cross-encoder/ms-marco-MiniLM-L-6-v2',
+      );
+    });
+
+    tearDownAll(() {
+      reranker.dispose();
+    });
+
+    test('ranks the relevant document first', () {
+      final results = reranker.rerank('What is machine learning?', _documents);
+
+      expect(results, hasLength(_documents.length));
+      expect(results.first.index, equals(1));
+      for (int i = 1; i < results.length; i++) {
+        expect(results[i - 1].score, greaterThanOrEqualTo(results[i].score));
+      }
+    });
+
+    test('topK limits the number of results', () {
+      final results = reranker.rerank(
+        'What is machine learning?',
+        _documents,
+        topK: 2,
+      );
+
+      expect(results, hasLength(2));
+    });
+
+    test('sync and async results agree', () async {
+      final sync = reranker.rerank('Paris weather', _documents);
+      final async = await reranker.rerankAsync('Paris weather', _documents);
+
+      expect(async.map((r) => r.index), equals(sync.map((r) => r.index)));
+      expect(async.first.score, closeTo(sync.first.score, 1e-5));
+    });
+
+    test('empty document list returns without calling native code', () {
+      expect(reranker.rerank('query', []), isEmpty);
+    });
+  }, tags: ['slow']);
+}
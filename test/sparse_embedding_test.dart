@@ -0,0 +1,100 @@
+import 'package:embedanythingindart/embedanythingindart.dart';
+import 'package:test/test.dart';
+
+void main() {
+  group('SparseEmbedding', () {
+    test('dot sums the products of shared indices', () {
+      const a = SparseEmbedding([3, 7, 42], [1.0, 2.0, 0.5]);
+      const b = SparseEmbedding([7, 42, 100], [3.0, 4.0, 9.0]);
+
+      expect(a.dot(b), closeTo(2.0 * 3.0 + 0.5 * 4.0, 1e-9));
+      expect(a.dot(b), closeTo(b.dot(a), 1e-9));
+      expect(a.length, equals(3));
+    });
+
+    test('dot of disjoint embeddings is zero', () {
+      const a = SparseEmbedding([1], [1.0]);
+      const b = SparseEmbedding([2], [1.0]);
+
+      expect(a.dot(b), equals(0.0));
+    });
+  });
+
+  group('Sparse Embedding on a dense model', () {
+    late EmbedAnything embedder;
+
+    setUpAll(() {
+      embedder = EmbedAnything.fromConfig(ModelConfig.bertMiniLML6());
+    });
+
+    tearDownAll(() {
+      embedder.dispose();
+    });
+
+    test('embedTextSparse throws InvalidConfigError', () {
+      expect(
+        () => embedder.embedTextSparse('Hello, world!'),
+        throwsA(isA<InvalidConfigError>()),
+      );
+    });
+
+    test('embedTextsBatchSparseAsync throws InvalidConfigError', () async {
+      await expectLater(
+        embedder.embedTextsBatchSparseAsync(['First', 'Second']),
+        throwsA(isA<InvalidConfigError>()),
+      );
+    });
+
+    test('empty batch returns without calling native code', () {
+      expect(embedder.embedTextsBatchSparse([]), isEmpty);
+    });
+  });
+
+  group('Sparse Embedding on a SPLADE model', () {
+    late EmbedAnything embedder;
+
+    setUpAll(() async {
+      embedder = await EmbedAnything.fromPretrainedHfAsync(
+        modelId: 'prithivida/Splade_PP_en_v1',
+      );
+    });
+
+    tearDownAll(() {
+      embedder.dispose();
+    });
+
+    test('explicit sparseBert model type loads a sparse model', () {
+      final explicit = EmbedAnything.fromPretrainedHf(
+        model: EmbeddingModel.sparseBert,
+        modelId: 'prithivida/Splade_PP_en_v1',
+      );
+      addTearDown(explicit.dispose);
+
+      expect(explicit.embedTextSparse('machine learning').length,
+          greaterThan(0));
+    });
+
+    test('sync and async results agree', () async {
+      final sync = embedder.embedTextSparse('machine learning');
+      final async = await embedder.embedTextSparseAsync('machine learning');
+
+      expect(sync.length, greaterThan(0));
+      expect(async.indices, equals(sync.indices));
+      expect(sync.values.every((v) => v > 0), isTrue);
+    });
+
+    test('related texts score higher than unrelated ones', () {
+      final results = embedder.embedTextsBatchSparse([
+        'machine learning',
+        'training neural networks',
+        'a recipe for pasta',
+      ]);
+
+      expect(results, hasLength(3));
+      expect(
+        results[0].dot(results[1]),
+        greaterThan(results[0].dot(results[2])),
+      );
+    });
+  }, tags: ['slow']);
+}
@@ -0,0 +1,112 @@
+import 'dart:io';
+import 'dart:typed_data';
+
+import 'package:embedanythingindart/embedanythingindart.dart';
+import 'package:test/test.dart';
+
+/// A 2x2 red PNG
+final _redPng = Uint8List.fromList([
+  0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, //
+  0x49, 0x48, 0x44, 0x52, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x02,
+  0x08, 0x02, 0x00, 0x00, 0x00, 0xfd, 0xd4, 0x9a, 0x73, 0x00, 0x00, 0x00,
+  0x10, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0xf8, 0xcf, 0xc0, 0x00,
+  0x44, 0x0c, 0x10, 0x0a, 0x00, 0x1f, 0xee, 0x03, 0xfd, 0x8b, 0x5f, 0x14,
+  0xd4, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60,
+  0x82,
+]);
+
+void main() {
+  group('Image embedding on a text model', () {
+    late EmbedAnything embedder;
+
+    setUpAll(() {
+      embedder = EmbedAnything.fromConfig(ModelConfig.bertMiniLML6());
+    });
+
+    tearDownAll(() {
+      embedder.dispose();
+    });
+
+    test('embedImageQuery throws InvalidConfigError', () {
+      expect(
+        () => embedder.embedImageQuery('a red square'),
+        throwsA(isA<InvalidConfigError>()),
+      );
+    });
+
+    test('embedImageBytesAsync throws InvalidConfigError', () async {
+      await expectLater(
+        embedder.embedImageBytesAsync(_redPng),
+        throwsA(isA<InvalidConfigError>()),
+      );
+    });
+
+    test('empty image batch returns without calling native code', () {
+      expect(embedder.embedImageFilesBatch([]), isEmpty);
+    });
+  });
+
+  group('Image embedding on a CLIP model', () {
+    late EmbedAnything embedder;
+    late Directory tempDir;
+    late String imagePath;
+
+    setUpAll(() async {
+      embedder = await EmbedAnything.fromVisionModelAsync(
+        modelId: 'openai/clip-vit-base-patch32',
+      );
+      tempDir = Directory.systemTemp.createTempSync('vision_test');
+      imagePath = '${tempDir.path}/red.png';
+      File(imagePath).writeAsBytesSync(_redPng);
+    });
+
+    tearDownAll(() {
+      embedder.dispose();
+      tempDir.deleteSync(recursive: true);
+    });
+
+    test('file and bytes produce the same embedding', () {
+      final fromFile = embedder.embedImageFile(imagePath);
+      final fromBytes = embedder.embedImageBytes(_redPng);
+
+      expect(fromFile.dimension, equals(512));
+      expect(fromFile.cosineSimilarity(fromBytes), greaterThan(0.999));
+    });
+
+    test('sync and async results agree', () async {
+      final sync = embedder.embedImageFile(imagePath);
+      final async = await embedder.embedImageFileAsync(imagePath);
+      final batch = await embedder.embedImageFilesBatchAsync([imagePath]);
+
+      expect(async.cosineSimilarity(sync), greaterThan(0.999));
+      expect(batch, hasLength(1));
+      expect(batch.first.cosineSimilarity(sync), greaterThan(0.999));
+    });
+
+    test('text queries share the image space', () async {
+      final image = embedder.embedImageFile(imagePath);
+      final red = embedder.embedImageQuery('a plain red square');
+      final dog = await embedder.embedImageQueryAsync('a dog on a beach');
+
+      expect(red.dimension, equals(image.dimension));
+      expect(
+        red.cosineSimilarity(image),
+        greaterThan(dog.cosineSimilarity(image)),
+      );
+    });
+
+    test('missing image throws FileNotFoundError', () {
+      expect(
+        () => embedder.embedImageFile('${tempDir.path}/missing.png'),
+        throwsA(isA<FileNotFoundError>()),
+      );
+    });
+
+    test('non-image bytes throw UnsupportedFileFormatError', () {
+      expect(
+        () => embedder.embedImageBytes(Uint8List.fromList([1, 2, 3, 4])),
+        throwsA(isA<UnsupportedFileFormatError>()),
+      );
+    });
+  }, tags: ['slow']);
+}